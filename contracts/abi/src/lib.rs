@@ -1,6 +1,6 @@
-use async_graphql::{Enum, InputObject, Request, Response, SimpleObject};
+use async_graphql::{ComplexObject, Enum, InputObject, Request, Response, SimpleObject};
 use linera_sdk::graphql::GraphQLMutationRoot;
-use linera_sdk::linera_base_types::{ContractAbi, ServiceAbi};
+use linera_sdk::linera_base_types::{AccountOwner, Amount, BcsSignable, ContractAbi, CryptoHash, ServiceAbi};
 use serde::{Deserialize, Serialize};
 
 pub struct CheckersAbi;
@@ -57,6 +57,8 @@ pub enum GameStatus {
     Pending,
     Active,
     Finished,
+    /// Closed before anyone joined, or aborted before it could be scored; it has no result
+    Cancelled,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
@@ -74,6 +76,73 @@ pub enum PlayerType {
     AI,
 }
 
+/// Playing style of the AI opponent, biasing its evaluation weights
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, Default)]
+pub enum AiPersonality {
+    #[default]
+    Balanced,
+    /// Pushes men forward and values captures over keeping material
+    Aggressive,
+    /// Keeps the back rank intact and avoids trading pieces
+    Defensive,
+    /// Prefers central control over raw material
+    Positional,
+}
+
+/// Evaluation weights used by the AI search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AiWeights {
+    pub man_value: i32,
+    pub king_value: i32,
+    /// Bonus per row a man has advanced towards promotion
+    pub advancement: i32,
+    /// Bonus for a man still guarding its own back rank
+    pub back_rank: i32,
+    /// Bonus for a piece on one of the 16 central squares
+    pub center_control: i32,
+    /// How much the opponent's material counts, in percent of our own
+    pub opponent_material_pct: i32,
+}
+
+impl AiPersonality {
+    pub fn weights(&self) -> AiWeights {
+        match self {
+            AiPersonality::Balanced => AiWeights {
+                man_value: 100,
+                king_value: 160,
+                advancement: 4,
+                back_rank: 0,
+                center_control: 0,
+                opponent_material_pct: 100,
+            },
+            AiPersonality::Aggressive => AiWeights {
+                man_value: 100,
+                king_value: 170,
+                advancement: 10,
+                back_rank: 0,
+                center_control: 2,
+                opponent_material_pct: 115,
+            },
+            AiPersonality::Defensive => AiWeights {
+                man_value: 100,
+                king_value: 150,
+                advancement: 2,
+                back_rank: 15,
+                center_control: 0,
+                opponent_material_pct: 90,
+            },
+            AiPersonality::Positional => AiWeights {
+                man_value: 100,
+                king_value: 160,
+                advancement: 3,
+                back_rank: 6,
+                center_control: 8,
+                opponent_material_pct: 100,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, Default)]
 pub enum Turn {
     #[default]
@@ -127,9 +196,693 @@ impl CheckersMove {
         self.promoted = true;
         self
     }
+
+    /// Whether both moves go between the same squares
+    pub fn same_squares(&self, other: &CheckersMove) -> bool {
+        (self.from_row, self.from_col, self.to_row, self.to_col)
+            == (other.from_row, other.from_col, other.to_row, other.to_col)
+    }
+}
+
+/// Conditional moves a player may have registered on one game at a time
+pub const MAX_CONDITIONAL_MOVES: usize = 10;
+
+/// A reply registered in advance for a correspondence game: when the opponent
+/// plays `if_move`, the contract plays `reply` for `player`
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ConditionalMove {
+    pub player: String,
+    #[graphql(name = "ifMove")]
+    pub if_move: CheckersMove,
+    pub reply: CheckersMove,
+}
+
+/// Evaluation lost (in the AI's units, a man is worth 100) from which a move
+/// counts as an inaccuracy, a mistake and a blunder
+pub const INACCURACY_LOSS: i32 = 30;
+pub const MISTAKE_LOSS: i32 = 80;
+pub const BLUNDER_LOSS: i32 = 200;
+
+/// How a move compares with the engine's preferred move in the same position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum MoveClassification {
+    Best,
+    Good,
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl MoveClassification {
+    /// Classify a move by how much evaluation it gave up against the best move
+    pub fn from_loss(loss: i32) -> Self {
+        match loss {
+            l if l <= 0 => MoveClassification::Best,
+            l if l < INACCURACY_LOSS => MoveClassification::Good,
+            l if l < MISTAKE_LOSS => MoveClassification::Inaccuracy,
+            l if l < BLUNDER_LOSS => MoveClassification::Mistake,
+            _ => MoveClassification::Blunder,
+        }
+    }
+
+    /// Annotation symbol shown next to the move in a replay
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            MoveClassification::Best | MoveClassification::Good => "",
+            MoveClassification::Inaccuracy => "?!",
+            MoveClassification::Mistake => "?",
+            MoveClassification::Blunder => "??",
+        }
+    }
+}
+
+/// Engine verdict on one move of a game, kept alongside the move log
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MoveAnnotation {
+    /// Index of the move in the game's move list
+    #[graphql(name = "moveIndex")]
+    pub move_index: u32,
+    pub side: Turn,
+    pub classification: MoveClassification,
+    pub symbol: String,
+    /// Evaluation given up compared with `best_move`
+    #[graphql(name = "evalLoss")]
+    pub eval_loss: i32,
+    /// The engine's preferred move, when it differs from the one played
+    #[graphql(name = "bestMove")]
+    pub best_move: Option<CheckersMove>,
+}
+
+/// Fewest moves by a player before their game is checked for anomalies
+pub const ANOMALY_MIN_MOVES: usize = 10;
+/// Think times whose standard deviation is under this percentage of their mean look scripted
+pub const UNIFORM_TIMING_MAX_CV_PCT: u64 = 10;
+/// Share of engine-best moves, in percent, above which accuracy looks superhuman
+pub const ENGINE_MATCH_MIN_PCT: usize = 90;
+
+/// Why a game was flagged for moderator review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum SuspicionReason {
+    /// The player's think times barely varied
+    UniformMoveTimes,
+    /// Nearly every move matched the engine's best move
+    EngineAccuracy,
+}
+
+/// A signal worth a moderator's look; it never penalizes the player by itself
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct SuspicionFlag {
+    pub player_id: String,
+    pub reason: SuspicionReason,
+    pub flagged_at: u64,
+}
+
+/// Whether a player's think times, in microseconds, are suspiciously even
+pub fn is_uniform_timing(think_times: &[u64]) -> bool {
+    if think_times.len() < ANOMALY_MIN_MOVES {
+        return false;
+    }
+    let n = think_times.len() as u128;
+    let mean = think_times.iter().map(|&t| t as u128).sum::<u128>() / n;
+    if mean == 0 {
+        return true;
+    }
+    let variance = think_times
+        .iter()
+        .map(|&t| (t as u128).abs_diff(mean).pow(2))
+        .sum::<u128>()
+        / n;
+    // stddev / mean < CV%  <=>  variance * 100^2 < (mean * CV)^2
+    variance * 10_000 < (mean * UNIFORM_TIMING_MAX_CV_PCT as u128).pow(2)
+}
+
+/// Whether a player's analyzed moves match the engine too often to be human
+pub fn is_engine_like(classifications: &[MoveClassification]) -> bool {
+    if classifications.len() < ANOMALY_MIN_MOVES {
+        return false;
+    }
+    let best = classifications.iter().filter(|c| **c == MoveClassification::Best).count();
+    let errors = classifications
+        .iter()
+        .filter(|c| matches!(c, MoveClassification::Mistake | MoveClassification::Blunder))
+        .count();
+    errors == 0 && best * 100 >= classifications.len() * ENGINE_MATCH_MIN_PCT
+}
+
+/// Number of plies of each finished game recorded in the opening explorer
+pub const OPENING_EXPLORER_PLIES: usize = 12;
+
+/// Stable 64-bit FNV-1a hash of a position and the side to move, as 16 hex digits
+pub fn position_hash(board: &str, turn: Turn) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let side = match turn {
+        Turn::Red => b'r',
+        Turn::Black => b'b',
+    };
+    for byte in board.bytes().chain(std::iter::once(side)) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// A move played from an opening explorer position and how often it was chosen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct OpeningMove {
+    pub from_row: u8,
+    pub from_col: u8,
+    pub to_row: u8,
+    pub to_col: u8,
+    /// Hash of the position the move leads to
+    pub position_hash: String,
+    pub games: u32,
+}
+
+/// Results of the finished games that passed through a position
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct OpeningStats {
+    pub position_hash: String,
+    pub games: u32,
+    pub red_wins: u32,
+    pub black_wins: u32,
+    pub draws: u32,
+    /// Continuations played from this position, most popular first
+    pub moves: Vec<OpeningMove>,
+}
+
+impl OpeningStats {
+    pub fn new(position_hash: String) -> Self {
+        Self { position_hash, ..Default::default() }
+    }
+
+    /// Count one more game through this position, and the move it continued with
+    pub fn record(&mut self, result: GameResult, next: Option<OpeningMove>) {
+        self.games += 1;
+        match result {
+            GameResult::RedWins => self.red_wins += 1,
+            GameResult::BlackWins => self.black_wins += 1,
+            GameResult::Draw => self.draws += 1,
+            GameResult::InProgress => {}
+        }
+
+        let Some(next) = next else { return };
+        match self.moves.iter_mut().find(|m| m.position_hash == next.position_hash) {
+            Some(existing) => existing.games += 1,
+            None => self.moves.push(OpeningMove { games: 1, ..next }),
+        }
+        self.moves.sort_by_key(|m| std::cmp::Reverse(m.games));
+    }
+}
+
+/// Length of a puzzle rush session
+pub const PUZZLE_RUSH_DURATION_MICROS: u64 = 3 * 60 * 1_000_000;
+
+/// A position to find the best move in, taken from a blunder in an analyzed game
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Puzzle {
+    pub id: String,
+    pub board_state: String,
+    pub turn: Turn,
+    /// Game the position was taken from. Kept out of GraphQL, like `solution`:
+    /// the game's analysis gives the engine's best move, which is the answer.
+    /// The answer can still be found by searching analyzed games for the position.
+    #[graphql(skip)]
+    pub source_game_id: String,
+    #[graphql(skip)]
+    pub solution: CheckersMove,
+}
+
+impl Puzzle {
+    pub fn is_solved_by(&self, from_row: u8, from_col: u8, to_row: u8, to_col: u8) -> bool {
+        (self.solution.from_row, self.solution.from_col, self.solution.to_row, self.solution.to_col)
+            == (from_row, from_col, to_row, to_col)
+    }
+}
+
+/// A timed run of consecutive puzzles that ends on the first miss
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PuzzleRushSession {
+    pub player_id: String,
+    pub started_at: u64,
+    pub ends_at: u64,
+    /// Puzzles solved in a row so far
+    pub streak: u32,
+    /// Puzzle to solve next, None once the session is over
+    pub current_puzzle_id: Option<String>,
+}
+
+impl PuzzleRushSession {
+    pub fn is_active(&self, now: u64) -> bool {
+        self.current_puzzle_id.is_some() && now < self.ends_at
+    }
+}
+
+/// Most members a club can have, owner included
+pub const MAX_CLUB_MEMBERS: usize = 100;
+/// Longest club name
+pub const MAX_CLUB_NAME_LEN: usize = 40;
+/// Results shown on a club page, newest first
+pub const MAX_CLUB_RECENT_RESULTS: usize = 20;
+
+/// Who can become a member of a club
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, Default)]
+pub enum ClubJoinPolicy {
+    /// Anyone can join
+    #[default]
+    Open,
+    /// Joining puts the player on a waiting list for a club admin to approve
+    Approval,
+}
+
+/// A game a club member finished, as shown on the club page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject)]
+pub struct ClubResult {
+    pub game_id: String,
+    pub player_id: String,
+    pub opponent: Option<String>,
+    /// 2 for a win, 1 for a draw, 0 for a loss
+    pub points: u32,
+    pub finished_at: u64,
+}
+
+/// A group of players who can play team events together. A player belongs to at
+/// most one club.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Club {
+    pub id: String,
+    pub name: String,
+    pub owner: String,
+    /// Members who can approve and kick others, owner included
+    pub admins: Vec<String>,
+    /// Owner included
+    pub members: Vec<String>,
+    pub join_policy: ClubJoinPolicy,
+    /// Players waiting for approval to join
+    pub join_requests: Vec<String>,
+    pub recent_results: Vec<ClubResult>,
+    pub created_at: u64,
+}
+
+impl Club {
+    pub fn new(id: String, name: String, owner: String, join_policy: ClubJoinPolicy, now: u64) -> Self {
+        Self {
+            id,
+            name,
+            admins: vec![owner.clone()],
+            members: vec![owner.clone()],
+            owner,
+            join_policy,
+            join_requests: Vec::new(),
+            recent_results: Vec::new(),
+            created_at: now,
+        }
+    }
+
+    pub fn is_member(&self, player_id: &str) -> bool {
+        self.members.iter().any(|m| m == player_id)
+    }
+
+    pub fn is_admin(&self, player_id: &str) -> bool {
+        self.admins.iter().any(|a| a == player_id)
+    }
+
+    /// Take a player out of the club. If the owner leaves, the longest-serving admin
+    /// takes over, or failing that the longest-serving member.
+    pub fn remove_member(&mut self, player_id: &str) {
+        self.members.retain(|m| m != player_id);
+        self.admins.retain(|a| a != player_id);
+        if self.owner == player_id {
+            if let Some(next_owner) = self.admins.first().or(self.members.first()).cloned() {
+                if !self.is_admin(&next_owner) {
+                    self.admins.push(next_owner.clone());
+                }
+                self.owner = next_owner;
+            }
+        }
+    }
+
+    /// Add a finished game to the club page, dropping the oldest beyond the limit
+    pub fn record_result(&mut self, result: ClubResult) {
+        self.recent_results.insert(0, result);
+        self.recent_results.truncate(MAX_CLUB_RECENT_RESULTS);
+    }
+}
+
+/// Most boards a club match can be played over
+pub const MAX_CLUB_MATCH_BOARDS: u32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ClubMatchStatus {
+    /// Waiting for an admin of the challenged club to answer
+    Proposed,
+    InProgress,
+    Finished,
+    Declined,
+}
+
+/// One board of a club match. The home player has red on odd boards.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ClubMatchBoard {
+    pub board: u32,
+    pub home_player: String,
+    pub away_player: String,
+    pub game_id: String,
+    pub result: Option<GameResult>,
+}
+
+/// A team match between two clubs, their strongest members playing each other
+/// board by board
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ClubMatch {
+    pub id: String,
+    /// The challenging club
+    pub home_club: String,
+    pub away_club: String,
+    pub board_count: u32,
+    pub time_control: TimeControl,
+    pub status: ClubMatchStatus,
+    pub boards: Vec<ClubMatchBoard>,
+    /// Match points: 2 for each board won, 1 for each drawn
+    pub home_score: u32,
+    pub away_score: u32,
+    /// None for a drawn match, or one that is not over
+    pub winner_club: Option<String>,
+    pub created_at: u64,
+    pub finished_at: Option<u64>,
+}
+
+impl ClubMatch {
+    /// Record a board's result and update the match score, finishing the match
+    /// once every board is decided
+    pub fn record_board(&mut self, game_id: &str, result: GameResult, now: u64) {
+        let Some(board) = self.boards.iter_mut().find(|b| b.game_id == game_id) else { return };
+        if board.result.is_some() || result == GameResult::InProgress {
+            return;
+        }
+        board.result = Some(result);
+
+        let (mut home_score, mut away_score) = (0, 0);
+        for board in &self.boards {
+            let home_is_red = board.board % 2 == 1;
+            match (board.result, home_is_red) {
+                (Some(GameResult::RedWins), true) | (Some(GameResult::BlackWins), false) => home_score += 2,
+                (Some(GameResult::RedWins), false) | (Some(GameResult::BlackWins), true) => away_score += 2,
+                (Some(GameResult::Draw), _) => {
+                    home_score += 1;
+                    away_score += 1;
+                }
+                _ => {}
+            }
+        }
+        self.home_score = home_score;
+        self.away_score = away_score;
+
+        if self.boards.iter().all(|b| b.result.is_some()) {
+            self.status = ClubMatchStatus::Finished;
+            self.finished_at = Some(now);
+            self.winner_club = match home_score.cmp(&away_score) {
+                std::cmp::Ordering::Greater => Some(self.home_club.clone()),
+                std::cmp::Ordering::Less => Some(self.away_club.clone()),
+                std::cmp::Ordering::Equal => None,
+            };
+        }
+    }
+}
+
+/// Most members a study can have, owner included
+pub const MAX_STUDY_MEMBERS: usize = 20;
+/// Most positions a study's move tree can hold
+pub const MAX_STUDY_NODES: usize = 500;
+/// Longest comment that can be attached to a study position
+pub const MAX_STUDY_COMMENT_LEN: usize = 500;
+
+/// A position in a study's move tree
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct StudyNode {
+    /// Index of the node in the study's node list; the root is 0
+    pub id: u32,
+    pub parent: Option<u32>,
+    /// Move leading here from the parent, None for the root
+    pub checkers_move: Option<CheckersMove>,
+    pub board_state: String,
+    pub turn: Turn,
+    pub comment: Option<String>,
+    pub children: Vec<u32>,
+}
+
+/// A shared board for analyzing positions together, with a tree of explored moves
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Study {
+    pub id: String,
+    pub name: String,
+    pub owner: String,
+    /// Players allowed to edit the study, owner included
+    pub members: Vec<String>,
+    pub nodes: Vec<StudyNode>,
+    /// Node currently shown on the shared board
+    pub current_node: u32,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl Study {
+    pub fn new(id: String, name: String, owner: String, board_state: String, turn: Turn, now: u64) -> Self {
+        Self {
+            id,
+            name,
+            members: vec![owner.clone()],
+            owner,
+            nodes: vec![StudyNode {
+                id: 0,
+                parent: None,
+                checkers_move: None,
+                board_state,
+                turn,
+                comment: None,
+                children: Vec::new(),
+            }],
+            current_node: 0,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn is_member(&self, player_id: &str) -> bool {
+        self.members.iter().any(|m| m == player_id)
+    }
+
+    pub fn node(&self, node_id: u32) -> Option<&StudyNode> {
+        self.nodes.get(node_id as usize)
+    }
+
+    /// Add a position reached from `parent` and return its node ID.
+    /// A move already explored from the parent returns the existing node.
+    pub fn add_node(
+        &mut self,
+        parent: u32,
+        checkers_move: CheckersMove,
+        board_state: String,
+        turn: Turn,
+    ) -> Result<u32, OperationError> {
+        let parent_node = self.node(parent)
+            .ok_or_else(|| OperationError::new(ErrorCode::NotFound, "Study position not found"))?;
+        let same_move = |m: &CheckersMove| {
+            (m.from_row, m.from_col, m.to_row, m.to_col)
+                == (checkers_move.from_row, checkers_move.from_col, checkers_move.to_row, checkers_move.to_col)
+        };
+        if let Some(&existing) = parent_node.children.iter().find(|&&child| {
+            self.nodes[child as usize].checkers_move.as_ref().is_some_and(same_move)
+        }) {
+            return Ok(existing);
+        }
+        if self.nodes.len() >= MAX_STUDY_NODES {
+            return Err(OperationError::new(ErrorCode::LimitReached, "Study is full"));
+        }
+
+        let id = self.nodes.len() as u32;
+        self.nodes.push(StudyNode {
+            id,
+            parent: Some(parent),
+            checkers_move: Some(checkers_move),
+            board_state,
+            turn,
+            comment: None,
+            children: Vec::new(),
+        });
+        self.nodes[parent as usize].children.push(id);
+        Ok(id)
+    }
+}
+
+/// Number of games in a rating category before a rating stops being provisional
+pub const PROVISIONAL_GAMES: u32 = 10;
+
+/// Rating economy settings, fixed when the application is instantiated
+/// Omitted fields in the JSON argument fall back to their defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+#[serde(default)]
+pub struct RatingConfig {
+    #[graphql(name = "minRating")]
+    pub min_rating: u32,
+    #[graphql(name = "maxRating")]
+    pub max_rating: u32,
+    #[graphql(name = "startingRating")]
+    pub starting_rating: u32,
+    /// K-factor while a player has fewer than `k_factor_games` games in a category
+    #[graphql(name = "kFactorNew")]
+    pub k_factor_new: u32,
+    #[graphql(name = "kFactorGames")]
+    pub k_factor_games: u32,
+    /// K-factor once a player has `k_factor_games` games in a category
+    #[graphql(name = "kFactorEstablished")]
+    pub k_factor_established: u32,
+    /// Established players rated at or above this use `k_factor_high_rating` instead
+    #[graphql(name = "highRatingThreshold")]
+    pub high_rating_threshold: Option<u32>,
+    #[graphql(name = "kFactorHighRating")]
+    pub k_factor_high_rating: u32,
+}
+
+impl Default for RatingConfig {
+    fn default() -> Self {
+        Self {
+            min_rating: 100,
+            max_rating: 3000,
+            starting_rating: 1200,
+            k_factor_new: 32,
+            k_factor_games: 30,
+            k_factor_established: 16,
+            high_rating_threshold: None,
+            k_factor_high_rating: 10,
+        }
+    }
+}
+
+impl RatingConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_rating > self.starting_rating || self.starting_rating > self.max_rating {
+            return Err("Rating bounds must satisfy min <= starting <= max".to_string());
+        }
+        let k_factors = [self.k_factor_new, self.k_factor_established, self.k_factor_high_rating];
+        if k_factors.iter().any(|k| !(1..=100).contains(k)) {
+            return Err("K-factors must be between 1 and 100".to_string());
+        }
+        Ok(())
+    }
+
+    /// K-factor for a player with `games` games in a category and the given rating
+    pub fn k_factor(&self, games: u32, rating: u32) -> u32 {
+        if games < self.k_factor_games {
+            self.k_factor_new
+        } else if self.high_rating_threshold.is_some_and(|threshold| rating >= threshold) {
+            self.k_factor_high_rating
+        } else {
+            self.k_factor_established
+        }
+    }
+}
+
+/// Gameplay settings and limits, fixed when the application is instantiated
+/// Omitted fields in the JSON argument fall back to their defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+#[serde(default)]
+pub struct GameConfig {
+    /// Whether games are rated when the creator doesn't say
+    #[graphql(name = "defaultRated")]
+    pub default_rated: bool,
+    /// Fixed rating the AI plays at in rated games
+    #[graphql(name = "aiRating")]
+    pub ai_rating: u32,
+    /// Time controls games may use; empty allows all of them
+    #[graphql(name = "allowedTimeControls")]
+    pub allowed_time_controls: Vec<TimeControl>,
+    /// Chain that hosts shared matchmaking, when players run the app on their own chains
+    #[graphql(name = "hubChainId")]
+    pub hub_chain_id: Option<String>,
+    #[graphql(name = "maxSpectators")]
+    pub max_spectators: u32,
+    #[graphql(name = "maxTournamentPlayers")]
+    pub max_tournament_players: u32,
+    /// Percent of won wager pots and tournament prize pools kept for the treasury
+    #[graphql(name = "rakePercent")]
+    pub rake_percent: u32,
+    /// Account owner the treasury is withdrawn to; required when there is a rake
+    #[graphql(name = "treasuryOwner")]
+    pub treasury_owner: Option<String>,
+}
+
+/// Highest rake a deployment can configure, in percent
+pub const MAX_RAKE_PERCENT: u32 = 20;
+
+/// `percent` percent of an amount, rounded down
+pub fn percent_of(amount: Amount, percent: u32) -> Amount {
+    Amount::from_attos(u128::from(amount).saturating_mul(u128::from(percent)) / 100)
+}
+
+/// Split an amount into the rake and what is left to pay out
+pub fn split_rake(amount: Amount, rake_percent: u32) -> (Amount, Amount) {
+    let rake = percent_of(amount, rake_percent);
+    (rake, amount.saturating_sub(rake))
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            default_rated: true,
+            ai_rating: 1500,
+            allowed_time_controls: Vec::new(),
+            hub_chain_id: None,
+            max_spectators: 200,
+            max_tournament_players: 64,
+            rake_percent: 0,
+            treasury_owner: None,
+        }
+    }
+}
+
+impl GameConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_tournament_players < 2 {
+            return Err("Tournaments must allow at least 2 players".to_string());
+        }
+        if let Some(hub) = &self.hub_chain_id {
+            hub.parse::<linera_sdk::linera_base_types::ChainId>()
+                .map_err(|_| "Hub chain ID is not a valid chain ID".to_string())?;
+        }
+        if self.rake_percent > MAX_RAKE_PERCENT {
+            return Err(format!("Rake can be at most {}%", MAX_RAKE_PERCENT));
+        }
+        match &self.treasury_owner {
+            Some(owner) => {
+                owner.parse::<AccountOwner>()
+                    .map_err(|_| "Treasury owner is not a valid account owner".to_string())?;
+            }
+            None if self.rake_percent > 0 => return Err("A rake needs a treasury owner".to_string()),
+            None => {}
+        }
+        Ok(())
+    }
+
+    pub fn allows(&self, time_control: &TimeControl) -> bool {
+        self.allowed_time_controls.is_empty() || self.allowed_time_controls.contains(time_control)
+    }
+}
+
+/// Argument passed when the application is created
+/// Every field is optional in the JSON argument and falls back to its default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstantiationArgument {
+    #[serde(default)]
+    pub rating: RatingConfig,
+    #[serde(default)]
+    pub game: GameConfig,
+    /// Account owners allowed to moderate
+    #[serde(default)]
+    pub admins: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
 pub struct PlayerStats {
     #[graphql(name = "chainId")]
     pub chain_id: String,
@@ -157,6 +910,57 @@ pub struct PlayerStats {
     pub rapid_rating: u32,
     #[graphql(name = "rapidGames")]
     pub rapid_games: u32,
+    /// Longest puzzle rush streak
+    #[graphql(name = "puzzleRushBest")]
+    #[serde(default)]
+    pub puzzle_rush_best: u32,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub xp: u64,
+    #[serde(default)]
+    pub title: Option<Title>,
+    /// Rated games in a row played at the rating of a higher title
+    #[graphql(name = "titleQualifyingGames")]
+    #[serde(default)]
+    pub title_qualifying_games: u32,
+    #[graphql(name = "bulletWinStreak")]
+    #[serde(default)]
+    pub bullet_win_streak: u32,
+    #[graphql(name = "bulletBestStreak")]
+    #[serde(default)]
+    pub bullet_best_streak: u32,
+    #[graphql(name = "blitzWinStreak")]
+    #[serde(default)]
+    pub blitz_win_streak: u32,
+    #[graphql(name = "blitzBestStreak")]
+    #[serde(default)]
+    pub blitz_best_streak: u32,
+    #[graphql(name = "rapidWinStreak")]
+    #[serde(default)]
+    pub rapid_win_streak: u32,
+    #[graphql(name = "rapidBestStreak")]
+    #[serde(default)]
+    pub rapid_best_streak: u32,
+    /// Results split by the color played
+    #[graphql(name = "redWins")]
+    #[serde(default)]
+    pub red_wins: u32,
+    #[graphql(name = "redLosses")]
+    #[serde(default)]
+    pub red_losses: u32,
+    #[graphql(name = "redDraws")]
+    #[serde(default)]
+    pub red_draws: u32,
+    #[graphql(name = "blackWins")]
+    #[serde(default)]
+    pub black_wins: u32,
+    #[graphql(name = "blackLosses")]
+    #[serde(default)]
+    pub black_losses: u32,
+    #[graphql(name = "blackDraws")]
+    #[serde(default)]
+    pub black_draws: u32,
 }
 
 impl Default for PlayerStats {
@@ -175,6 +979,23 @@ impl Default for PlayerStats {
             blitz_games: 0,
             rapid_rating: 1200,
             rapid_games: 0,
+            puzzle_rush_best: 0,
+            username: None,
+            xp: 0,
+            title: None,
+            title_qualifying_games: 0,
+            bullet_win_streak: 0,
+            bullet_best_streak: 0,
+            blitz_win_streak: 0,
+            blitz_best_streak: 0,
+            rapid_win_streak: 0,
+            rapid_best_streak: 0,
+            red_wins: 0,
+            red_losses: 0,
+            red_draws: 0,
+            black_wins: 0,
+            black_losses: 0,
+            black_draws: 0,
         }
     }
 }
@@ -187,6 +1008,17 @@ impl PlayerStats {
         }
     }
 
+    /// New stats with every rating at the configured starting rating
+    pub fn with_config(chain_id: String, config: &RatingConfig) -> Self {
+        Self {
+            chain_id,
+            bullet_rating: config.starting_rating,
+            blitz_rating: config.starting_rating,
+            rapid_rating: config.starting_rating,
+            ..Default::default()
+        }
+    }
+
     pub fn record_win(&mut self) {
         self.games_played += 1;
         self.games_won += 1;
@@ -207,6 +1039,20 @@ impl PlayerStats {
         self.games_drawn += 1;
     }
 
+    /// Count a finished game's result in the split for the color played
+    pub fn record_color_result(&mut self, color: Turn, result: GameResult) {
+        let (wins, losses, draws) = match color {
+            Turn::Red => (&mut self.red_wins, &mut self.red_losses, &mut self.red_draws),
+            Turn::Black => (&mut self.black_wins, &mut self.black_losses, &mut self.black_draws),
+        };
+        match (color, result) {
+            (Turn::Red, GameResult::RedWins) | (Turn::Black, GameResult::BlackWins) => *wins += 1,
+            (Turn::Red, GameResult::BlackWins) | (Turn::Black, GameResult::RedWins) => *losses += 1,
+            (_, GameResult::Draw) => *draws += 1,
+            (_, GameResult::InProgress) => {}
+        }
+    }
+
     pub fn get_rating(&self, time_control: &TimeControl) -> u32 {
         match time_control {
             TimeControl::Bullet1_0 | TimeControl::Bullet2_1 => self.bullet_rating,
@@ -215,6 +1061,14 @@ impl PlayerStats {
         }
     }
 
+    pub fn set_rating(&mut self, time_control: &TimeControl, rating: u32) {
+        match time_control.category() {
+            RatingCategory::Bullet => self.bullet_rating = rating,
+            RatingCategory::Blitz => self.blitz_rating = rating,
+            RatingCategory::Rapid => self.rapid_rating = rating,
+        }
+    }
+
     pub fn get_games_in_category(&self, time_control: &TimeControl) -> u32 {
         match time_control {
             TimeControl::Bullet1_0 | TimeControl::Bullet2_1 => self.bullet_games,
@@ -223,20 +1077,82 @@ impl PlayerStats {
         }
     }
 
-    pub fn update_rating(&mut self, opponent_rating: u32, outcome: f64, time_control: &TimeControl) {
-        let my_rating = self.get_rating(time_control) as f64;
-        let opp_rating = opponent_rating as f64;
-        let games = self.get_games_in_category(time_control);
-        let k: f64 = if games < 30 { 32.0 } else { 16.0 };
-        let expected = 1.0 / (1.0 + 10_f64.powf((opp_rating - my_rating) / 400.0));
-        let change = k * (outcome - expected);
-        let new_rating = (my_rating + change).round() as i32;
-        let new_rating = new_rating.max(100).min(3000) as u32;
+    /// A rating is provisional until the player has enough games in that category
+    pub fn is_provisional(&self, time_control: &TimeControl) -> bool {
+        self.get_games_in_category(time_control) < PROVISIONAL_GAMES
+    }
 
-        match time_control {
-            TimeControl::Bullet1_0 | TimeControl::Bullet2_1 => {
-                self.bullet_rating = new_rating;
-                self.bullet_games += 1;
+    /// Share of games won, in basis points (0-10000)
+    pub fn win_rate_bps(&self) -> u32 {
+        if self.games_played == 0 {
+            return 0;
+        }
+        (self.games_won as u64 * 10_000 / self.games_played as u64) as u32
+    }
+
+    /// Whether the player has an established rating in at least one category
+    pub fn has_established_rating(&self) -> bool {
+        [TimeControl::Bullet1_0, TimeControl::Blitz3_0, TimeControl::Rapid10_0]
+            .iter()
+            .any(|tc| !self.is_provisional(tc))
+    }
+
+    /// Highest established rating across categories, if any
+    pub fn peak_established_rating(&self) -> Option<u32> {
+        [TimeControl::Bullet1_0, TimeControl::Blitz3_0, TimeControl::Rapid10_0]
+            .iter()
+            .filter(|tc| !self.is_provisional(tc))
+            .map(|tc| self.get_rating(tc))
+            .max()
+    }
+
+    /// Update the title after a rated game.
+    /// A title is granted after TITLE_QUALIFYING_GAMES games in a row at its rating,
+    /// and revoked once the rating falls TITLE_REVOCATION_MARGIN below its threshold.
+    pub fn update_title(&mut self) {
+        let Some(rating) = self.peak_established_rating() else {
+            return;
+        };
+        if self.title.is_some_and(|title| rating + TITLE_REVOCATION_MARGIN < title.min_rating()) {
+            self.title = Title::for_rating(rating + TITLE_REVOCATION_MARGIN);
+        }
+        let qualifying = Title::for_rating(rating);
+        if qualifying > self.title {
+            self.title_qualifying_games += 1;
+            if self.title_qualifying_games >= TITLE_QUALIFYING_GAMES {
+                self.title = qualifying;
+                self.title_qualifying_games = 0;
+            }
+        } else {
+            self.title_qualifying_games = 0;
+        }
+    }
+
+    pub fn update_rating(&mut self, opponent_rating: u32, outcome: f64, time_control: &TimeControl) {
+        self.update_rating_with_config(opponent_rating, outcome, time_control, &RatingConfig::default());
+    }
+
+    /// Update the rating for a result, keeping it within the configured bounds
+    pub fn update_rating_with_config(
+        &mut self,
+        opponent_rating: u32,
+        outcome: f64,
+        time_control: &TimeControl,
+        config: &RatingConfig,
+    ) {
+        let my_rating = self.get_rating(time_control) as f64;
+        let opp_rating = opponent_rating as f64;
+        let games = self.get_games_in_category(time_control);
+        let k = config.k_factor(games, self.get_rating(time_control)) as f64;
+        let expected = 1.0 / (1.0 + 10_f64.powf((opp_rating - my_rating) / 400.0));
+        let change = k * (outcome - expected);
+        let new_rating = (my_rating + change).round() as i32;
+        let new_rating = new_rating.clamp(config.min_rating as i32, config.max_rating as i32) as u32;
+
+        match time_control {
+            TimeControl::Bullet1_0 | TimeControl::Bullet2_1 => {
+                self.bullet_rating = new_rating;
+                self.bullet_games += 1;
             }
             TimeControl::Blitz3_0 | TimeControl::Blitz5_3 => {
                 self.blitz_rating = new_rating;
@@ -263,6 +1179,328 @@ impl PlayerStats {
         self.record_draw();
         self.update_rating(opponent_rating, 0.5, time_control);
     }
+
+    /// Record a rated result, where `outcome` is 1.0 for a win, 0.5 for a draw and 0.0 for a loss
+    pub fn record_result_with_config(
+        &mut self,
+        opponent_rating: u32,
+        outcome: f64,
+        time_control: &TimeControl,
+        config: &RatingConfig,
+    ) {
+        if outcome > 0.5 {
+            self.record_win();
+        } else if outcome < 0.5 {
+            self.record_loss();
+        } else {
+            self.record_draw();
+        }
+        self.record_category_streak(time_control, outcome);
+        self.update_rating_with_config(opponent_rating, outcome, time_control, config);
+    }
+
+    /// Update the win streaks of the time control's rating category.
+    /// Like the overall streak, a draw leaves them unchanged.
+    pub fn record_category_streak(&mut self, time_control: &TimeControl, outcome: f64) {
+        let (streak, best) = match time_control.category() {
+            RatingCategory::Bullet => (&mut self.bullet_win_streak, &mut self.bullet_best_streak),
+            RatingCategory::Blitz => (&mut self.blitz_win_streak, &mut self.blitz_best_streak),
+            RatingCategory::Rapid => (&mut self.rapid_win_streak, &mut self.rapid_best_streak),
+        };
+        if outcome > 0.5 {
+            *streak += 1;
+            *best = (*best).max(*streak);
+        } else if outcome < 0.5 {
+            *streak = 0;
+        }
+    }
+
+    pub fn get_win_streak(&self, time_control: &TimeControl) -> u32 {
+        match time_control.category() {
+            RatingCategory::Bullet => self.bullet_win_streak,
+            RatingCategory::Blitz => self.blitz_win_streak,
+            RatingCategory::Rapid => self.rapid_win_streak,
+        }
+    }
+
+    pub fn get_best_streak(&self, time_control: &TimeControl) -> u32 {
+        match time_control.category() {
+            RatingCategory::Bullet => self.bullet_best_streak,
+            RatingCategory::Blitz => self.blitz_best_streak,
+            RatingCategory::Rapid => self.rapid_best_streak,
+        }
+    }
+
+    /// Starting stats for a new season: counts are cleared and each rating
+    /// is pulled `decay_pct` percent of the way back toward the starting rating
+    pub fn for_new_season(&self, decay_pct: u32, config: &RatingConfig) -> Self {
+        let start = config.starting_rating as i64;
+        let decay = |rating: u32| -> u32 {
+            let offset = rating as i64 - start;
+            (start + offset * (100 - decay_pct.min(100)) as i64 / 100) as u32
+        };
+        Self {
+            chain_id: self.chain_id.clone(),
+            username: self.username.clone(),
+            xp: self.xp,
+            title: self.title,
+            bullet_rating: decay(self.bullet_rating),
+            blitz_rating: decay(self.blitz_rating),
+            rapid_rating: decay(self.rapid_rating),
+            ..Default::default()
+        }
+    }
+}
+
+pub const MIN_USERNAME_LEN: usize = 3;
+pub const MAX_USERNAME_LEN: usize = 20;
+
+/// Check that a username is 3-20 ASCII letters, digits, `_` or `-`
+pub fn validate_username(username: &str) -> Result<(), String> {
+    let len = username.len();
+    if !(MIN_USERNAME_LEN..=MAX_USERNAME_LEN).contains(&len) {
+        return Err(format!(
+            "Username must be {} to {} characters",
+            MIN_USERNAME_LEN, MAX_USERNAME_LEN
+        ));
+    }
+    if !username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err("Username may only contain letters, digits, _ and -".to_string());
+    }
+    Ok(())
+}
+
+pub const MAX_BIO_LEN: usize = 300;
+
+/// Self-description a player shows next to their stats
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct PlayerProfile {
+    #[graphql(name = "playerId")]
+    pub player_id: String,
+    pub bio: String,
+    /// ISO 3166-1 alpha-2 country code, uppercase
+    pub country: Option<String>,
+    /// Hash of the avatar image blob, as 64 hex characters
+    #[graphql(name = "avatarHash")]
+    pub avatar_hash: Option<String>,
+    #[graphql(name = "updatedAt")]
+    pub updated_at: u64,
+}
+
+impl PlayerProfile {
+    /// Check the length and format of each field
+    pub fn validate(&self) -> Result<(), String> {
+        if self.bio.chars().count() > MAX_BIO_LEN {
+            return Err(format!("Bio must be at most {} characters", MAX_BIO_LEN));
+        }
+        if let Some(country) = &self.country {
+            if country.len() != 2 || !country.chars().all(|c| c.is_ascii_uppercase()) {
+                return Err("Country must be a two-letter code".to_string());
+            }
+        }
+        if let Some(hash) = &self.avatar_hash {
+            if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err("Avatar must be a 64 character hex blob hash".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Preferences the contract applies on a player's behalf
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct PlayerSettings {
+    /// Decline every draw offer made to this player without showing it to them
+    #[graphql(name = "autoDeclineDraws")]
+    pub auto_decline_draws: bool,
+    /// Only accept challenges from friends
+    #[graphql(name = "friendsOnlyChallenges")]
+    pub friends_only_challenges: bool,
+    /// Whether the player's new games and queue entries are rated when they
+    /// don't say; None follows the deployment's default
+    #[graphql(name = "defaultRated")]
+    pub default_rated: Option<bool>,
+    /// Color the player takes in games they create without choosing one
+    #[graphql(name = "preferredColor")]
+    pub preferred_color: Option<ColorPreference>,
+}
+
+/// Ordering options for the leaderboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, Default)]
+pub enum LeaderboardSort {
+    #[default]
+    Wins,
+    Rating,
+    WinRate,
+    Streak,
+    PuzzleRush,
+    /// Current win streak in the time control's rating category
+    CategoryStreak,
+}
+
+#[ComplexObject]
+impl PlayerStats {
+    #[graphql(name = "bulletProvisional")]
+    async fn bullet_provisional(&self) -> bool {
+        self.is_provisional(&TimeControl::Bullet1_0)
+    }
+
+    #[graphql(name = "blitzProvisional")]
+    async fn blitz_provisional(&self) -> bool {
+        self.is_provisional(&TimeControl::Blitz3_0)
+    }
+
+    #[graphql(name = "rapidProvisional")]
+    async fn rapid_provisional(&self) -> bool {
+        self.is_provisional(&TimeControl::Rapid10_0)
+    }
+
+    async fn level(&self) -> u32 {
+        level_for_xp(self.xp)
+    }
+
+    /// Total XP needed to reach the next level
+    #[graphql(name = "nextLevelXp")]
+    async fn next_level_xp(&self) -> u64 {
+        xp_for_level(level_for_xp(self.xp) + 1)
+    }
+}
+
+/// Totals behind a player's play-style statistics, over all their finished games
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
+pub struct PlayStyleStats {
+    pub games: u32,
+    /// Turns played; a multi-jump capture counts once
+    pub moves: u32,
+    /// Turns that captured at least one piece
+    #[graphql(name = "capturingMoves")]
+    pub capturing_moves: u32,
+    /// Pieces captured
+    pub captures: u32,
+    pub promotions: u32,
+    /// Turns whose think time is known, all but a game's first turn
+    #[graphql(name = "timedMoves")]
+    pub timed_moves: u32,
+    #[graphql(name = "thinkTimeMicros")]
+    pub think_time_micros: u64,
+    #[graphql(name = "timeoutLosses")]
+    pub timeout_losses: u32,
+}
+
+impl PlayStyleStats {
+    /// Add a finished game played as `color`, given its moves
+    pub fn record_game(&mut self, color: Turn, moves: &[CheckersMove], lost_on_time: bool) {
+        self.games += 1;
+        if lost_on_time {
+            self.timeout_losses += 1;
+        }
+        let turns = group_turns(moves);
+        let mine = if color == Turn::Red { 0 } else { 1 };
+        for (i, turn) in turns.iter().enumerate().skip(mine).step_by(2) {
+            self.moves += 1;
+            let captures = turn.iter().filter(|mv| mv.captured_row.is_some()).count() as u32;
+            if captures > 0 {
+                self.capturing_moves += 1;
+                self.captures += captures;
+            }
+            if turn.iter().any(|mv| mv.promoted) {
+                self.promotions += 1;
+            }
+            if i > 0 {
+                let previous = turns[i - 1][turns[i - 1].len() - 1].timestamp;
+                self.timed_moves += 1;
+                self.think_time_micros += turn[0].timestamp.saturating_sub(previous);
+            }
+        }
+    }
+}
+
+#[ComplexObject]
+impl PlayStyleStats {
+    #[graphql(name = "averageMovesPerGame")]
+    async fn average_moves_per_game(&self) -> f64 {
+        if self.games == 0 { 0.0 } else { self.moves as f64 / self.games as f64 }
+    }
+
+    /// Share of turns that captured a piece
+    #[graphql(name = "captureRate")]
+    async fn capture_rate(&self) -> f64 {
+        if self.moves == 0 { 0.0 } else { self.capturing_moves as f64 / self.moves as f64 }
+    }
+
+    /// Promotions to king per game
+    #[graphql(name = "promotionRate")]
+    async fn promotion_rate(&self) -> f64 {
+        if self.games == 0 { 0.0 } else { self.promotions as f64 / self.games as f64 }
+    }
+
+    #[graphql(name = "averageMoveTimeMs")]
+    async fn average_move_time_ms(&self) -> u64 {
+        (self.think_time_micros / 1000).checked_div(self.timed_moves as u64).unwrap_or(0)
+    }
+}
+
+/// Titles earned by holding a high rating
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Enum)]
+pub enum Title {
+    Expert,
+    Master,
+    Grandmaster,
+}
+
+/// Rated games in a row at a title's rating needed to earn it
+pub const TITLE_QUALIFYING_GAMES: u32 = 10;
+/// How far below a title's threshold the rating may fall before it is revoked
+pub const TITLE_REVOCATION_MARGIN: u32 = 200;
+
+impl Title {
+    pub fn min_rating(&self) -> u32 {
+        match self {
+            Title::Expert => 1800,
+            Title::Master => 2000,
+            Title::Grandmaster => 2200,
+        }
+    }
+
+    /// Highest title whose threshold a rating reaches
+    pub fn for_rating(rating: u32) -> Option<Title> {
+        [Title::Grandmaster, Title::Master, Title::Expert]
+            .into_iter()
+            .find(|title| rating >= title.min_rating())
+    }
+}
+
+pub const XP_PER_GAME: u64 = 10;
+/// Extra XP for winning a game, on top of XP_PER_GAME
+pub const XP_PER_WIN: u64 = 15;
+pub const XP_PER_PUZZLE: u64 = 5;
+/// XP for each participant of a tournament that finishes
+pub const XP_PER_TOURNAMENT: u64 = 50;
+pub const XP_PER_MISSION: u64 = 25;
+
+/// Total XP needed to reach a level: 100 for level 2, 300 for level 3, 600 for level 4, ...
+pub fn xp_for_level(level: u32) -> u64 {
+    let level = level.max(1) as u64;
+    50 * level * (level - 1)
+}
+
+/// Level reached with an amount of XP, starting at level 1
+pub fn level_for_xp(xp: u64) -> u32 {
+    let mut level = 1;
+    while xp >= xp_for_level(level + 1) {
+        level += 1;
+    }
+    level
+}
+
+/// A level gained during an operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct LevelUp {
+    #[graphql(name = "playerId")]
+    pub player_id: String,
+    pub level: u32,
+    pub xp: u64,
 }
 
 pub const STARTING_BOARD: &str = " r r r r/r r r r / r r r r/        /        /b b b b / b b b b/b b b b ";
@@ -276,6 +1514,8 @@ pub struct CheckersGame {
     pub black_player_type: PlayerType,
     pub board_state: String,
     pub current_turn: Turn,
+    /// Moves stored on the game itself. New moves go to the game's move log
+    /// instead; the `game` query fills this in with the full history.
     pub moves: Vec<CheckersMove>,
     pub move_count: u32,
     pub status: GameStatus,
@@ -298,6 +1538,106 @@ pub struct CheckersGame {
     #[graphql(name = "tournamentMatchId")]
     #[serde(default)]
     pub tournament_match_id: Option<String>,
+    #[graphql(name = "aiTimeBudgetMs")]
+    #[serde(default)]
+    pub ai_time_budget_ms: Option<u64>,
+    #[graphql(name = "aiPersonality")]
+    #[serde(default)]
+    pub ai_personality: AiPersonality,
+    /// Players currently watching the game
+    #[serde(default)]
+    pub spectators: Vec<String>,
+    #[graphql(name = "spectatorCount")]
+    #[serde(default)]
+    pub spectator_count: u32,
+    /// When an open game stops accepting an opponent (microseconds)
+    #[graphql(name = "expiresAt")]
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    /// Usernames of the players, filled in by the service when the game is queried
+    #[graphql(name = "redUsername")]
+    #[serde(skip)]
+    pub red_username: Option<String>,
+    #[graphql(name = "blackUsername")]
+    #[serde(skip)]
+    pub black_username: Option<String>,
+    /// Private games are left out of open game listings and joined by invite code
+    #[graphql(name = "isPrivate")]
+    #[serde(default)]
+    pub is_private: bool,
+    #[graphql(name = "inviteCode")]
+    #[serde(default)]
+    pub invite_code: Option<String>,
+    /// Anti-cheat signals raised for moderator review
+    #[graphql(name = "suspicionFlags")]
+    #[serde(default)]
+    pub suspicion_flags: Vec<SuspicionFlag>,
+    /// Multi-chain games only: the chain holding the authoritative copy of the game.
+    /// Games played entirely on one hub chain leave this unset.
+    #[graphql(name = "hostChain")]
+    #[serde(default)]
+    pub host_chain: Option<String>,
+    /// Replicas only: the game's ID on its host chain. A replica is stored under
+    /// `replica_game_id`, so it cannot collide with this chain's own games.
+    #[graphql(name = "hostGameId")]
+    #[serde(default)]
+    pub host_game_id: Option<String>,
+    /// Multi-chain games only: the chain each player plays from
+    #[graphql(name = "redChain")]
+    #[serde(default)]
+    pub red_chain: Option<String>,
+    #[graphql(name = "blackChain")]
+    #[serde(default)]
+    pub black_chain: Option<String>,
+    /// Games started by another application through `CreateMatch`: the calling
+    /// application's ID and the reference it gave the match
+    #[graphql(name = "ownerApp")]
+    #[serde(default)]
+    pub owner_app: Option<String>,
+    #[graphql(name = "externalRef")]
+    #[serde(default)]
+    pub external_ref: Option<String>,
+    /// Tokens staked on the game, if it is a wager game
+    #[serde(default)]
+    pub wager: Option<Wager>,
+    /// Club match this game is a board of, if any
+    #[graphql(name = "clubMatchId")]
+    #[serde(default)]
+    pub club_match_id: Option<String>,
+    /// Hash of the creator's secret for a random-color game; the colors are
+    /// settled once the creator reveals it after an opponent joins
+    #[graphql(name = "colorCommitment")]
+    #[serde(default)]
+    pub color_commitment: Option<String>,
+    /// The joiner's contribution to the color toss, set while the game waits
+    /// for the creator's reveal
+    #[graphql(name = "colorSeed")]
+    #[serde(default)]
+    pub color_seed: Option<String>,
+    /// Replies registered in advance by the players, listed per player through
+    /// `conditionalMoves`. Like everything on chain they are public, so the
+    /// opponent can read them too.
+    #[graphql(skip)]
+    #[serde(default)]
+    pub conditional_moves: Vec<ConditionalMove>,
+    /// Draw offers made so far, to limit how often a player can offer
+    #[graphql(name = "drawOffers")]
+    #[serde(default)]
+    pub draw_offers: Vec<DrawOfferRecord>,
+}
+
+/// Draw offers each player may make in one game
+pub const MAX_DRAW_OFFERS_PER_GAME: usize = 3;
+/// Moves (by either player) that must be played between two offers by the same player
+pub const DRAW_OFFER_COOLDOWN_MOVES: u32 = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct DrawOfferRecord {
+    #[graphql(name = "offeredBy")]
+    pub offered_by: Turn,
+    /// Moves played when the offer was made
+    #[graphql(name = "moveCount")]
+    pub move_count: u32,
 }
 
 fn default_is_rated() -> bool {
@@ -305,6 +1645,14 @@ fn default_is_rated() -> bool {
 }
 
 impl CheckersGame {
+    /// Whether either side is played by the AI
+    pub fn has_ai_player(&self) -> bool {
+        self.red_player_type == PlayerType::AI
+            || self.black_player_type == PlayerType::AI
+            || self.red_player.as_deref() == Some("AI")
+            || self.black_player.as_deref() == Some("AI")
+    }
+
     pub fn new(id: String, red_player: Option<String>, red_type: PlayerType) -> Self {
         Self {
             id,
@@ -327,6 +1675,28 @@ impl CheckersGame {
             creator_wants_random: false,
             tournament_id: None,
             tournament_match_id: None,
+            ai_time_budget_ms: None,
+            ai_personality: AiPersonality::Balanced,
+            spectators: Vec::new(),
+            spectator_count: 0,
+            expires_at: None,
+            is_private: false,
+            invite_code: None,
+            red_username: None,
+            black_username: None,
+            suspicion_flags: Vec::new(),
+            host_chain: None,
+            host_game_id: None,
+            red_chain: None,
+            black_chain: None,
+            owner_app: None,
+            external_ref: None,
+            wager: None,
+            club_match_id: None,
+            color_commitment: None,
+            color_seed: None,
+            conditional_moves: Vec::new(),
+            draw_offers: Vec::new(),
         }
     }
 
@@ -364,6 +1734,28 @@ impl CheckersGame {
             creator_wants_random: false,
             tournament_id: None,
             tournament_match_id: None,
+            ai_time_budget_ms: None,
+            ai_personality: AiPersonality::Balanced,
+            spectators: Vec::new(),
+            spectator_count: 0,
+            expires_at: None,
+            is_private: false,
+            invite_code: None,
+            red_username: None,
+            black_username: None,
+            suspicion_flags: Vec::new(),
+            host_chain: None,
+            host_game_id: None,
+            red_chain: None,
+            black_chain: None,
+            owner_app: None,
+            external_ref: None,
+            wager: None,
+            club_match_id: None,
+            color_commitment: None,
+            color_seed: None,
+            conditional_moves: Vec::new(),
+            draw_offers: Vec::new(),
         };
 
         match color_pref {
@@ -378,6 +1770,62 @@ impl CheckersGame {
         game
     }
 
+    /// Whether an opponent has joined and the creator still has to reveal their color secret
+    pub fn awaiting_color_reveal(&self) -> bool {
+        self.status == GameStatus::Pending && self.color_seed.is_some()
+    }
+
+    /// Check that `color` may offer a draw now: they have offers left and enough
+    /// moves have been played since their last one
+    pub fn check_draw_offer_allowed(&self, color: Turn) -> Result<(), String> {
+        let mut own = self.draw_offers.iter().filter(|offer| offer.offered_by == color);
+        if own.clone().count() >= MAX_DRAW_OFFERS_PER_GAME {
+            return Err(format!("At most {} draw offers per game", MAX_DRAW_OFFERS_PER_GAME));
+        }
+        if let Some(last) = own.next_back() {
+            let next_allowed = last.move_count + DRAW_OFFER_COOLDOWN_MOVES;
+            if self.move_count < next_allowed {
+                return Err(format!("You can offer a draw again after move {}", next_allowed));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether this is an untimed game the player to move has left without a move
+    /// for `ABANDONED_GAME_MICROS`
+    pub fn is_abandoned(&self, now: u64) -> bool {
+        self.status == GameStatus::Active
+            && self.clock.is_none()
+            && now >= self.updated_at.saturating_add(ABANDONED_GAME_MICROS)
+    }
+
+    /// Whether the game can no longer be played, either finished or cancelled
+    pub fn is_over(&self) -> bool {
+        matches!(self.status, GameStatus::Finished | GameStatus::Cancelled)
+    }
+
+    /// Club match boards start unrated and become rated once both players have
+    /// moved, so a member seated while away is not rated on a game they never played
+    pub fn rate_club_board_once_played(&mut self) {
+        if self.club_match_id.is_some() && self.move_count >= 2 {
+            self.is_rated = true;
+        }
+    }
+
+    /// Whether this is an open game nobody joined before it expired
+    pub fn is_expired_seek(&self, now: u64) -> bool {
+        self.status == GameStatus::Pending && self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    /// The player who won, once the game has a decisive result
+    pub fn winner(&self) -> Option<&str> {
+        match self.result? {
+            GameResult::RedWins => self.red_player.as_deref(),
+            GameResult::BlackWins => self.black_player.as_deref(),
+            GameResult::Draw | GameResult::InProgress => None,
+        }
+    }
+
     pub fn can_player_move(&self, chain_id: &str) -> bool {
         if self.status != GameStatus::Active {
             return false;
@@ -387,93 +1835,546 @@ impl CheckersGame {
             Turn::Black => self.black_player.as_deref() == Some(chain_id),
         }
     }
+
+    /// The color a player has in this game
+    pub fn color_of(&self, player_id: &str) -> Option<Turn> {
+        if self.red_player.as_deref() == Some(player_id) {
+            Some(Turn::Red)
+        } else if self.black_player.as_deref() == Some(player_id) {
+            Some(Turn::Black)
+        } else {
+            None
+        }
+    }
+
+    /// After the opponent's `last` move, take the reply the player now to move
+    /// registered for it. Their other conditional moves no longer apply and
+    /// are dropped.
+    pub fn take_conditional_reply(&mut self, last: &CheckersMove) -> Option<CheckersMove> {
+        let to_move = match self.current_turn {
+            Turn::Red => self.red_player.clone()?,
+            Turn::Black => self.black_player.clone()?,
+        };
+        let mut reply = None;
+        self.conditional_moves.retain(|conditional| {
+            if conditional.player != to_move {
+                return true;
+            }
+            if reply.is_none() && conditional.if_move.same_squares(last) {
+                reply = Some(conditional.reply.clone());
+            }
+            false
+        });
+        reply
+    }
+
+    /// Whether this copy is a replica of a game hosted on another chain
+    pub fn is_hosted_elsewhere(&self, chain_id: &str) -> bool {
+        self.host_chain.as_deref().is_some_and(|host| host != chain_id)
+    }
+
+    /// The game's ID on its host chain, which relayed messages refer to it by
+    pub fn id_on_host(&self) -> &str {
+        self.host_game_id.as_deref().unwrap_or(&self.id)
+    }
+
+    /// The chain a player of a multi-chain game plays from
+    pub fn chain_of(&self, player_id: &str) -> Option<&str> {
+        if self.red_player.as_deref() == Some(player_id) {
+            self.red_chain.as_deref()
+        } else if self.black_player.as_deref() == Some(player_id) {
+            self.black_chain.as_deref()
+        } else {
+            None
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, GraphQLMutationRoot)]
-pub enum Operation {
-    CreateGame {
-        vs_ai: bool,
-        time_control: Option<TimeControl>,
-        color_preference: Option<ColorPreference>,
-        is_rated: Option<bool>,
-        player_id: String,
-    },
-    JoinGame {
-        game_id: String,
-        player_id: String,
-    },
-    MakeMove {
-        game_id: String,
-        from_row: u8,
-        from_col: u8,
-        to_row: u8,
-        to_col: u8,
-        player_id: String,
-    },
-    Resign {
-        game_id: String,
-        player_id: String,
-    },
-    RequestAiMove {
-        game_id: String,
-    },
-    JoinQueue {
-        time_control: TimeControl,
-        player_id: String,
-    },
-    LeaveQueue {
-        player_id: String,
-    },
-    OfferDraw {
-        game_id: String,
-    },
-    AcceptDraw {
-        game_id: String,
-    },
-    DeclineDraw {
-        game_id: String,
-    },
-    ClaimTimeWin {
-        game_id: String,
-    },
-    CreateTournament {
-        name: String,
-        time_control: TimeControl,
-        max_players: u32,
-        is_public: bool,
-        scheduled_start: Option<u64>,
-        player_id: String,
-    },
-    JoinTournament {
-        tournament_id: String,
-        player_id: String,
-    },
-    JoinTournamentByCode {
-        invite_code: String,
-        player_id: String,
-    },
-    LeaveTournament {
-        tournament_id: String,
-        player_id: String,
-    },
-    StartTournament {
-        tournament_id: String,
-        player_id: String,
-    },
-    StartTournamentMatch {
-        tournament_id: String,
-        match_id: String,
-        player_id: String,
-    },
-    ForfeitTournamentMatch {
-        tournament_id: String,
-        match_id: String,
-        player_id: String,
-    },
-    CancelTournament {
-        tournament_id: String,
-        player_id: String,
-    },
+/// The ID a chain stores its replica of another chain's game under. Game IDs are
+/// only unique on the chain that created them, so replicas are keyed by host too.
+pub fn replica_game_id(host_chain: &str, game_id: &str) -> String {
+    format!("{}@{}", game_id, host_chain)
+}
+
+// The GraphQLMutationRoot derive turns each operation field into a resolver argument,
+// so the generated mutation root has as many parameters as the largest operation.
+#[allow(clippy::too_many_arguments)]
+mod operation {
+    use super::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize, GraphQLMutationRoot)]
+    pub enum Operation {
+        CreateGame {
+            vs_ai: bool,
+            time_control: Option<TimeControl>,
+            color_preference: Option<ColorPreference>,
+            is_rated: Option<bool>,
+            ai_time_budget_ms: Option<u64>,
+            ai_personality: Option<AiPersonality>,
+            /// Keep the game out of public listings; opponents join with the returned code
+            private: Option<bool>,
+            /// Tokens each player stakes, taken from the signer's account. The winner
+            /// takes both stakes; a draw or an unjoined game gives them back.
+            wager: Option<Amount>,
+            /// For a random-color game, `colorCommitment` of a secret the creator
+            /// reveals through `RevealColor` once an opponent joins. Required when
+            /// the game is rated.
+            #[serde(default)]
+            color_commitment: Option<String>,
+            player_id: String,
+        },
+        JoinGame {
+            game_id: String,
+            player_id: String,
+        },
+        MakeMove {
+            game_id: String,
+            from_row: u8,
+            from_col: u8,
+            to_row: u8,
+            to_col: u8,
+            player_id: String,
+        },
+        Resign {
+            game_id: String,
+            player_id: String,
+        },
+        RequestAiMove {
+            game_id: String,
+        },
+        JoinQueue {
+            time_control: TimeControl,
+            /// Seek a casual game with `Some(false)`; defaults to the chain's rated setting
+            #[serde(default)]
+            is_rated: Option<bool>,
+            player_id: String,
+        },
+        /// Leave one pool, or every pool the player is waiting in when `time_control` is None
+        LeaveQueue {
+            #[serde(default)]
+            time_control: Option<TimeControl>,
+            player_id: String,
+        },
+        OfferDraw {
+            game_id: String,
+            player_id: String,
+        },
+        AcceptDraw {
+            game_id: String,
+            player_id: String,
+        },
+        DeclineDraw {
+            game_id: String,
+            player_id: String,
+        },
+        ClaimTimeWin {
+            game_id: String,
+            player_id: String,
+        },
+        CreateTournament {
+            name: String,
+            time_control: TimeControl,
+            max_players: u32,
+            is_public: bool,
+            scheduled_start: Option<u64>,
+            /// Tokens each player pays to enter, pooled and paid out as prizes
+            entry_fee: Option<Amount>,
+            /// Percent of the prize pool for each finishing place, e.g. [50, 30, 20].
+            /// Defaults to everything for the winner.
+            payout_schedule: Option<Vec<u32>>,
+            /// Swiss or single elimination. Defaults to Swiss.
+            format: Option<TournamentFormat>,
+            /// Minutes each round may take before unplayed matches are forfeited
+            round_time_limit_minutes: Option<u32>,
+            /// What the tournament is about, shown to players browsing it
+            description: Option<String>,
+            /// Swiss only: play off a tie for first that the tiebreaks can't split
+            playoff: Option<PlayoffMode>,
+            /// Elimination only: play the final as a best-of series of this many games,
+            /// with draws worth half a win
+            best_of: Option<u32>,
+            /// With `best_of`, play every round as a series, not just the final
+            best_of_all_rounds: Option<bool>,
+            /// Single elimination only: have the semifinal losers play for third place
+            third_place_match: Option<bool>,
+            player_id: String,
+        },
+        JoinTournament {
+            tournament_id: String,
+            player_id: String,
+        },
+        JoinTournamentByCode {
+            invite_code: String,
+            player_id: String,
+        },
+        LeaveTournament {
+            tournament_id: String,
+            player_id: String,
+        },
+        StartTournament {
+            tournament_id: String,
+            player_id: String,
+        },
+        StartTournamentMatch {
+            tournament_id: String,
+            match_id: String,
+            player_id: String,
+        },
+        ForfeitTournamentMatch {
+            tournament_id: String,
+            match_id: String,
+            player_id: String,
+        },
+        CancelTournament {
+            tournament_id: String,
+            player_id: String,
+        },
+        WatchGame {
+            game_id: String,
+            player_id: String,
+        },
+        UnwatchGame {
+            game_id: String,
+            player_id: String,
+        },
+        SendGameMessage {
+            game_id: String,
+            text: String,
+            player_id: String,
+        },
+        SendEmote {
+            game_id: String,
+            emote: Emote,
+            player_id: String,
+        },
+        /// Annotate every move of a finished game with an engine classification
+        AnalyzeGame { game_id: String },
+        /// Start a timed puzzle rush, ending any previous session
+        StartPuzzleRush { player_id: String },
+        /// Answer the current puzzle of a puzzle rush
+        SubmitPuzzleMove {
+            from_row: u8,
+            from_col: u8,
+            to_row: u8,
+            to_col: u8,
+            player_id: String,
+        },
+        /// Create a study from the starting position, or from a game's current position
+        CreateStudy {
+            name: String,
+            from_game_id: Option<String>,
+            player_id: String,
+        },
+        AddStudyMember { study_id: String, member_id: String, player_id: String },
+        RemoveStudyMember { study_id: String, member_id: String, player_id: String },
+        /// Play a move from a study position, adding it to the move tree
+        AddStudyMove {
+            study_id: String,
+            node_id: u32,
+            from_row: u8,
+            from_col: u8,
+            to_row: u8,
+            to_col: u8,
+            player_id: String,
+        },
+        CommentStudyNode { study_id: String, node_id: u32, text: String, player_id: String },
+        /// Show a position of the move tree on the shared board
+        SetStudyPosition { study_id: String, node_id: u32, player_id: String },
+        /// Ask another player to be friends; accepts their request if they already sent one
+        SendFriendRequest { friend_id: String, player_id: String },
+        AcceptFriendRequest { friend_id: String, player_id: String },
+        /// Remove a friend, or decline or withdraw a pending request
+        RemoveFriend { friend_id: String, player_id: String },
+        /// Invite a specific player to a game
+        ChallengePlayer {
+            opponent_id: String,
+            time_control: TimeControl,
+            rated: bool,
+            color: ColorPreference,
+            player_id: String,
+        },
+        AcceptChallenge { challenge_id: String, player_id: String },
+        /// Decline a challenge, or withdraw it when sent by the challenger
+        DeclineChallenge { challenge_id: String, player_id: String },
+        /// Close expired challenges, open games and seeks
+        CleanupExpired,
+        JoinGameByCode { invite_code: String, player_id: String },
+        /// Claim a unique username, releasing the player's previous one
+        RegisterUsername { username: String, player_id: String },
+        /// Post a seek to the seek board
+        PostSeek {
+            time_control: TimeControl,
+            rated: bool,
+            color: ColorPreference,
+            min_rating: Option<u32>,
+            max_rating: Option<u32>,
+            player_id: String,
+        },
+        AcceptSeek { seek_id: String, player_id: String },
+        CancelSeek { seek_id: String, player_id: String },
+        /// Stop a player from joining your games, challenging you or being matched with you
+        BlockPlayer { blocked_id: String, player_id: String },
+        UnblockPlayer { blocked_id: String, player_id: String },
+        /// Replace the player's bio, country and avatar
+        UpdateProfile {
+            bio: Option<String>,
+            country: Option<String>,
+            avatar_hash: Option<String>,
+            player_id: String,
+        },
+        /// Grant moderation rights to an account owner; only from the creator chain,
+        /// signed by an admin or an owner of that chain
+        AddAdmin { owner: String },
+        RemoveAdmin { owner: String },
+        /// Admin only: put a player's rating in a category back to the starting rating
+        ResetRating { target_id: String, category: RatingCategory },
+        /// Admin only: end an active game with the given result.
+        /// Stats and ratings are only updated if `apply_ratings` is set.
+        ForceEndGame { game_id: String, result: GameResult, apply_ratings: bool },
+        /// Report a player for moderators to review, optionally pointing at a game
+        ReportPlayer {
+            reported_id: String,
+            game_id: Option<String>,
+            reason: String,
+            player_id: String,
+        },
+        /// Admin only: close an open report, as resolved or dismissed
+        ResolveReport { report_id: String, dismiss: bool, note: String },
+        /// Admin only: stop a player from creating or joining games, queues and tournaments
+        BanPlayer { target_id: String, reason: String },
+        UnbanPlayer { target_id: String },
+        /// Follow the games of another chain through its event stream, so they can be
+        /// watched from this chain without the host sending messages to each spectator
+        SubscribeToGames { chain_id: String },
+        UnsubscribeFromGames { chain_id: String },
+        /// End a wager game that has had no move for `WAGER_INACTIVITY_REFUND_MICROS`, or
+        /// withdraw an unjoined one, giving each player their stake back. Once the
+        /// dispute window of a finished game has closed, pays its stakes out instead.
+        ClaimWagerRefund { game_id: String, player_id: String },
+        /// Hold a finished game's wager payout for an admin to decide; only within
+        /// `WAGER_DISPUTE_WINDOW_MICROS` of the game ending
+        DisputeWager { game_id: String, reason: String, player_id: String },
+        /// Admin only: pay a disputed wager to `winner`, or refund both stakes if there
+        /// is none. The game's result and ratings are not touched.
+        ResolveWagerDispute { game_id: String, winner: Option<String> },
+        /// Admin only: send treasury funds to the configured treasury owner,
+        /// everything if no amount is given
+        WithdrawTreasury { amount: Option<Amount> },
+        /// Cross-application calls only: start a game between two players on behalf of
+        /// the calling application. When the game ends an `AppGameEnded` event is
+        /// published, carrying the caller's application ID and `reference`.
+        /// The game is rated only if `rated` is set and both players have approved
+        /// the calling application with `ApproveMatchApp`; otherwise it is played unrated.
+        CreateMatch {
+            red_player: String,
+            black_player: String,
+            time_control: Option<TimeControl>,
+            rated: bool,
+            reference: Option<String>,
+        },
+        /// Look up the status and result of a game, for applications calling this one
+        GetGameResult { game_id: String },
+        /// Join a pending game hosted on another chain. The game is then played from
+        /// this chain: moves and resignations on the local copy are relayed to the host.
+        /// The local copy is stored under `replica_game_id(host_chain, game_id)`.
+        JoinRemoteGame { host_chain: String, game_id: String, player_id: String },
+        /// Found a club, with the creator as its owner and first admin
+        CreateClub { name: String, join_policy: ClubJoinPolicy, player_id: String },
+        /// Join an open club, or ask to join one that needs approval
+        JoinClub { club_id: String, player_id: String },
+        LeaveClub { club_id: String, player_id: String },
+        /// Club admins only: accept a player waiting to join
+        ApproveClubMember { club_id: String, member_id: String, player_id: String },
+        /// Club admins only: remove a member or turn down a join request. Admins can
+        /// only be removed by the owner.
+        KickClubMember { club_id: String, member_id: String, player_id: String },
+        /// Club owner only: make a member an admin, or take that back
+        SetClubAdmin { club_id: String, member_id: String, admin: bool, player_id: String },
+        /// Club admins only: challenge another club to a match over `boards` boards.
+        /// When accepted, each club's top-rated members for the time control are
+        /// paired board by board.
+        ChallengeClub {
+            club_id: String,
+            opponent_club_id: String,
+            boards: u32,
+            time_control: TimeControl,
+            player_id: String,
+        },
+        /// Admins of the challenged club only: accept a club match and start its games.
+        /// Each board is rated only once both of its players have moved.
+        AcceptClubMatch { match_id: String, player_id: String },
+        /// Admins of either club: turn down or withdraw a proposed club match
+        DeclineClubMatch { match_id: String, player_id: String },
+        /// Forfeit the matches of a tournament that are past their round deadline. A
+        /// match nobody started is lost by both players, or by the one who never said
+        /// they were ready; in a started game, the player on move loses. Anyone may
+        /// call this.
+        EnforceRoundDeadlines { tournament_id: String },
+        /// Say you are ready to play a tournament match. The game is created as soon
+        /// as both players are ready.
+        ReadyForMatch { tournament_id: String, match_id: String, player_id: String },
+        /// Leave a tournament that has started. Results so far stand; a game in
+        /// progress is lost and any match still to be played is forfeited.
+        WithdrawFromTournament { tournament_id: String, player_id: String },
+        /// Remove `kicked_id` from a tournament. Organizers or an admin may do so
+        /// during registration, refunding the entry fee; once it has started only
+        /// admins may, and the player is withdrawn with their results kept.
+        KickFromTournament { tournament_id: String, kicked_id: String, player_id: String },
+        /// Creator only: make a player a co-organizer, who can start, cancel and
+        /// remove players from the tournament, or take that role away
+        SetTournamentOrganizer { tournament_id: String, organizer_id: String, organizer: bool, player_id: String },
+        /// Organizers only: post a note, such as a schedule change, on a tournament
+        PostTournamentAnnouncement { tournament_id: String, text: String, player_id: String },
+        /// Admin only: drop index entries left pointing at finished, cancelled or
+        /// missing games and tournaments
+        PruneIndexes,
+        /// Settle the colors of a random-color game an opponent joined. The creator
+        /// reveals the secret behind their `colorCommitment`; once the reveal window
+        /// has passed, the joiner may claim red without it.
+        RevealColor { game_id: String, secret: Option<String>, player_id: String },
+        /// Correspondence games only: when the opponent plays `if_move`, play `reply`.
+        /// Registered replies are public, so the opponent can see them.
+        AddConditionalMove { game_id: String, if_move: CheckersMove, reply: CheckersMove, player_id: String },
+        ClearConditionalMoves { game_id: String, player_id: String },
+        /// End an untimed game that has had no move for `ABANDONED_GAME_MICROS`: the
+        /// player who stopped moving loses, or the game is aborted if it barely started.
+        /// Anyone may call it.
+        AdjudicateAbandonedGame { game_id: String },
+        /// Hand out the trophies of a season that has ended. Anyone may call it;
+        /// each season's trophies are awarded once. Seasons with many players are
+        /// scanned over several calls: repeat while it returns `SeasonTrophiesPending`.
+        AwardSeasonTrophies { season_id: u32 },
+        /// Replace the player's settings; omitted fields go back to their defaults
+        UpdateSettings {
+            auto_decline_draws: Option<bool>,
+            friends_only_challenges: Option<bool>,
+            default_rated: Option<bool>,
+            preferred_color: Option<ColorPreference>,
+            player_id: String,
+        },
+        /// Let an application create rated matches for the player through `CreateMatch`
+        ApproveMatchApp { app_id: String, player_id: String },
+        /// Withdraw an application's approval to create rated matches for the player
+        RevokeMatchApp { app_id: String, player_id: String },
+    }
+}
+
+pub use operation::*;
+
+impl Operation {
+    /// The player an operation acts for, if it names one.
+    /// Operations without one act for the chain that submits them.
+    pub fn player_id(&self) -> Option<&str> {
+        match self {
+            Operation::CreateGame { player_id, .. }
+            | Operation::JoinGame { player_id, .. }
+            | Operation::MakeMove { player_id, .. }
+            | Operation::Resign { player_id, .. }
+            | Operation::JoinQueue { player_id, .. }
+            | Operation::LeaveQueue { player_id, .. }
+            | Operation::CreateTournament { player_id, .. }
+            | Operation::JoinTournament { player_id, .. }
+            | Operation::JoinTournamentByCode { player_id, .. }
+            | Operation::LeaveTournament { player_id, .. }
+            | Operation::StartTournament { player_id, .. }
+            | Operation::StartTournamentMatch { player_id, .. }
+            | Operation::ForfeitTournamentMatch { player_id, .. }
+            | Operation::CancelTournament { player_id, .. }
+            | Operation::WatchGame { player_id, .. }
+            | Operation::UnwatchGame { player_id, .. }
+            | Operation::SendGameMessage { player_id, .. }
+            | Operation::SendEmote { player_id, .. }
+            | Operation::StartPuzzleRush { player_id, .. }
+            | Operation::SubmitPuzzleMove { player_id, .. }
+            | Operation::CreateStudy { player_id, .. }
+            | Operation::AddStudyMember { player_id, .. }
+            | Operation::RemoveStudyMember { player_id, .. }
+            | Operation::AddStudyMove { player_id, .. }
+            | Operation::CommentStudyNode { player_id, .. }
+            | Operation::SetStudyPosition { player_id, .. }
+            | Operation::SendFriendRequest { player_id, .. }
+            | Operation::AcceptFriendRequest { player_id, .. }
+            | Operation::RemoveFriend { player_id, .. }
+            | Operation::ChallengePlayer { player_id, .. }
+            | Operation::AcceptChallenge { player_id, .. }
+            | Operation::DeclineChallenge { player_id, .. }
+            | Operation::JoinGameByCode { player_id, .. }
+            | Operation::RegisterUsername { player_id, .. }
+            | Operation::PostSeek { player_id, .. }
+            | Operation::AcceptSeek { player_id, .. }
+            | Operation::CancelSeek { player_id, .. }
+            | Operation::BlockPlayer { player_id, .. }
+            | Operation::UnblockPlayer { player_id, .. }
+            | Operation::UpdateProfile { player_id, .. }
+            | Operation::ReportPlayer { player_id, .. }
+            | Operation::ClaimWagerRefund { player_id, .. }
+            | Operation::DisputeWager { player_id, .. }
+            | Operation::JoinRemoteGame { player_id, .. }
+            | Operation::CreateClub { player_id, .. }
+            | Operation::JoinClub { player_id, .. }
+            | Operation::LeaveClub { player_id, .. }
+            | Operation::ApproveClubMember { player_id, .. }
+            | Operation::KickClubMember { player_id, .. }
+            | Operation::SetClubAdmin { player_id, .. }
+            | Operation::ChallengeClub { player_id, .. }
+            | Operation::AcceptClubMatch { player_id, .. }
+            | Operation::DeclineClubMatch { player_id, .. }
+            | Operation::ReadyForMatch { player_id, .. }
+            | Operation::WithdrawFromTournament { player_id, .. }
+            | Operation::KickFromTournament { player_id, .. }
+            | Operation::SetTournamentOrganizer { player_id, .. }
+            | Operation::PostTournamentAnnouncement { player_id, .. }
+            | Operation::RevealColor { player_id, .. }
+            | Operation::AddConditionalMove { player_id, .. }
+            | Operation::ClearConditionalMoves { player_id, .. }
+            | Operation::OfferDraw { player_id, .. }
+            | Operation::AcceptDraw { player_id, .. }
+            | Operation::DeclineDraw { player_id, .. }
+            | Operation::ClaimTimeWin { player_id, .. }
+            | Operation::UpdateSettings { player_id, .. }
+            | Operation::ApproveMatchApp { player_id, .. }
+            | Operation::RevokeMatchApp { player_id, .. } => Some(player_id),
+            Operation::RequestAiMove { .. }
+            | Operation::AdjudicateAbandonedGame { .. }
+            | Operation::AwardSeasonTrophies { .. }
+            | Operation::AnalyzeGame { .. }
+            | Operation::CleanupExpired
+            | Operation::EnforceRoundDeadlines { .. }
+            | Operation::PruneIndexes
+            | Operation::AddAdmin { .. }
+            | Operation::RemoveAdmin { .. }
+            | Operation::ResetRating { .. }
+            | Operation::ForceEndGame { .. }
+            | Operation::ResolveReport { .. }
+            | Operation::BanPlayer { .. }
+            | Operation::UnbanPlayer { .. }
+            | Operation::WithdrawTreasury { .. }
+            | Operation::ResolveWagerDispute { .. }
+            | Operation::CreateMatch { .. }
+            | Operation::GetGameResult { .. }
+            | Operation::SubscribeToGames { .. }
+            | Operation::UnsubscribeFromGames { .. } => None,
+        }
+    }
+
+    /// Whether a banned player may still submit the operation.
+    /// Bans only keep players from starting things; they can always back out.
+    pub fn allowed_when_banned(&self) -> bool {
+        matches!(
+            self,
+            Operation::Resign { .. }
+                | Operation::LeaveQueue { .. }
+                | Operation::LeaveTournament { .. }
+                | Operation::WithdrawFromTournament { .. }
+                | Operation::CancelSeek { .. }
+                | Operation::DeclineChallenge { .. }
+                | Operation::DeclineDraw { .. }
+                | Operation::ClaimWagerRefund { .. }
+                | Operation::DisputeWager { .. }
+                | Operation::LeaveClub { .. }
+                | Operation::RevokeMatchApp { .. }
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -482,7 +2383,7 @@ pub enum OperationResult {
     GameJoined { game_id: String },
     MoveMade { game_id: String, game_over: bool },
     Resigned { game_id: String },
-    AiMoveMade { game_id: String, game_over: bool },
+    AiMoveMade { game_id: String, game_over: bool, search_depth: u32 },
     QueueJoined { time_control: TimeControl },
     QueueLeft,
     MatchFound { game_id: String, opponent: String },
@@ -508,12 +2409,157 @@ pub enum OperationResult {
     TournamentCancelled {
         tournament_id: String,
     },
-    Error { message: String },
+    GameWatched { game_id: String, spectator_count: u32 },
+    GameUnwatched { game_id: String, spectator_count: u32 },
+    GameMessageSent { game_id: String, message_count: u32 },
+    EmoteSent { game_id: String, emote: Emote },
+    GameAnalyzed { game_id: String, blunders: u32, mistakes: u32, inaccuracies: u32 },
+    PuzzleRushStarted { puzzle_id: String, ends_at: u64 },
+    PuzzleSolved { streak: u32, next_puzzle_id: String },
+    PuzzleRushEnded { streak: u32, best_streak: u32 },
+    StudyCreated { study_id: String },
+    StudyUpdated { study_id: String, node_id: u32 },
+    FriendRequestSent { friend_id: String },
+    FriendAdded { friend_id: String },
+    FriendRemoved { friend_id: String },
+    ChallengeSent { challenge_id: String },
+    ChallengeAccepted { challenge_id: String, game_id: String },
+    ChallengeDeclined { challenge_id: String },
+    ExpiredCleaned { challenges: u32, games: u32, seeks: u32, queue_entries: u32 },
+    PlayerBlocked { blocked_id: String },
+    PlayerUnblocked { blocked_id: String },
+    PrivateGameCreated { game_id: String, invite_code: String },
+    SeekPosted { seek_id: String },
+    SeekAccepted { seek_id: String, game_id: String },
+    SeekCancelled { seek_id: String },
+    UsernameRegistered { username: String },
+    ProfileUpdated,
+    AdminAdded { owner: String },
+    AdminRemoved { owner: String },
+    RatingReset { player_id: String, category: RatingCategory, rating: u32 },
+    GameForceEnded { game_id: String, result: GameResult },
+    PlayerReported { report_id: String },
+    ReportClosed { report_id: String, status: ReportStatus },
+    PlayerBanned { player_id: String },
+    PlayerUnbanned { player_id: String },
+    /// `game_id` is the ID the local copy will be stored under
+    RemoteJoinRequested { game_id: String },
+    /// A move sent to the host chain of a multi-chain game. `move_number` is the
+    /// move count of the local copy; the host drops the move if it has moved on.
+    MoveRelayed { game_id: String, move_number: u32 },
+    ResignationRelayed { game_id: String },
+    /// The join request was sent to the hub chain, which runs the shared queue
+    QueueJoinRequested { time_control: TimeControl },
+    TreasuryWithdrawn { amount: Amount },
+    WagerRefunded { game_id: String },
+    WagerSettled { game_id: String },
+    WagerDisputed { game_id: String },
+    WagerDisputeResolved { game_id: String, winner: Option<String> },
+    /// `rated` tells whether the game was created rated
+    MatchCreated { game_id: String, rated: bool },
+    GameResultInfo { game_id: String, status: GameStatus, result: Option<GameResult> },
+    GamesSubscribed { chain_id: String },
+    GamesUnsubscribed { chain_id: String },
+    ClubCreated { club_id: String },
+    ClubJoined { club_id: String },
+    /// The club needs approval; the player is on its waiting list
+    ClubJoinRequested { club_id: String },
+    ClubLeft { club_id: String },
+    ClubMemberApproved { club_id: String, member_id: String },
+    ClubMemberKicked { club_id: String, member_id: String },
+    ClubAdminSet { club_id: String, member_id: String, admin: bool },
+    ClubMatchProposed { match_id: String },
+    ClubMatchStarted { match_id: String, game_ids: Vec<String> },
+    ClubMatchDeclined { match_id: String },
+    RoundDeadlinesEnforced { tournament_id: String, forfeited: u32 },
+    /// Ready noted; the game starts when the opponent is ready too
+    MatchReadied { tournament_id: String, match_id: String },
+    TournamentWithdrawn { tournament_id: String },
+    TournamentPlayerKicked { tournament_id: String, player_id: String },
+    TournamentOrganizerSet { tournament_id: String, organizer_id: String, organizer: bool },
+    TournamentAnnouncementPosted { tournament_id: String, announcement_count: u32 },
+    IndexesPruned { invite_codes: u32, watched_games: u32 },
+    ColorsRevealed { game_id: String, red_player: String },
+    ConditionalMoveAdded { game_id: String, conditional_moves: u32 },
+    ConditionalMovesCleared { game_id: String },
+    /// `result` is None when the game was aborted
+    GameAdjudicated { game_id: String, result: Option<GameResult> },
+    SettingsUpdated,
+    SeasonTrophiesAwarded { season_id: u32, trophies: u32 },
+    /// More of the season's players remain to be scanned before trophies are awarded
+    SeasonTrophiesPending { season_id: u32 },
+    /// A draw action or time-win claim sent to the host chain of a multi-chain game
+    GameActionRelayed { game_id: String },
+    MatchAppApproved { app_id: String },
+    MatchAppRevoked { app_id: String },
+    Error { code: ErrorCode, message: String },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Message {
-    JoinRequest { game_id: String, player_chain: String },
+/// Machine-readable kind of an `OperationResult::Error`, so clients need not match on messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ErrorCode {
+    GameNotFound,
+    GameNotActive,
+    NotInGame,
+    NotYourTurn,
+    /// The move breaks the rules of checkers
+    IllegalMove,
+    /// A capture is available and must be taken
+    MustCapture,
+    TimeExpired,
+    TournamentNotFound,
+    TournamentFull,
+    RegistrationClosed,
+    /// Some other game object (study, seek, challenge, report, ...) does not exist
+    NotFound,
+    /// The operation is not signed by the owner of its player ID
+    Unauthenticated,
+    /// The player is not allowed to act on this object
+    NotAuthorized,
+    /// The rules do not allow this action
+    NotAllowed,
+    InvalidInput,
+    /// The object is not in a state where the operation applies
+    InvalidState,
+    AlreadyDone,
+    Expired,
+    LimitReached,
+    /// One of the players blocked the other
+    Blocked,
+    Banned,
+    /// Saving or loading state failed
+    Storage,
+    Rejected,
+}
+
+/// An error a helper passes up, along with the code it is reported under
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl OperationError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        OperationError { code, message: message.into() }
+    }
+
+    /// An error saving or loading state
+    pub fn storage(message: impl Into<String>) -> Self {
+        OperationError::new(ErrorCode::Storage, message)
+    }
+}
+
+impl From<OperationError> for OperationResult {
+    fn from(error: OperationError) -> Self {
+        OperationResult::Error { code: error.code, message: error.message }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Message {
+    /// Sent by a player's chain to the host chain of a pending game to take the open seat
+    JoinRequest { game_id: String, player_id: String },
     GameStarted { game_id: String, red_player: String, black_player: String },
     MoveMade {
         game_id: String,
@@ -524,12 +2570,16 @@ pub enum Message {
         game_result: Option<GameResult>,
     },
     GameEnded { game_id: String, result: GameResult, winner: Option<String> },
-    SyncGameState { game: CheckersGame },
+    SyncGameState { game: Box<CheckersGame> },
+    /// Sent by the hub chain to the chains of both matched players, after the game itself
     MatchFound {
         game_id: String,
         red_player: String,
         black_player: String,
         time_control: TimeControl,
+        /// The clock as it was started, so clients can show it before the first move
+        #[serde(default)]
+        clock: Option<Clock>,
     },
     DrawOffered {
         game_id: String,
@@ -541,6 +2591,411 @@ pub enum Message {
     DrawAccepted {
         game_id: String,
     },
+    /// A move made on a player's copy of a multi-chain game, sent with the
+    /// player's signature to the host chain. `move_number` is the number of moves
+    /// the player had seen, so moves made on an out-of-date copy are dropped.
+    RelayMove {
+        game_id: String,
+        player_id: String,
+        from_row: u8,
+        from_col: u8,
+        to_row: u8,
+        to_col: u8,
+        move_number: u32,
+    },
+    RelayResign { game_id: String, player_id: String },
+    /// Sent by a player's chain to the hub chain to queue for a match
+    JoinQueueRequest { player_id: String, time_control: TimeControl, #[serde(default = "default_is_rated")] is_rated: bool },
+    LeaveQueueRequest { player_id: String, #[serde(default)] time_control: Option<TimeControl> },
+    /// Sent to the chains of a cancelled tournament's registered players
+    TournamentCancelled { tournament_id: String },
+    /// Draw actions and time-win claims made on a player's copy of a
+    /// multi-chain game, sent with the player's signature to the host chain
+    RelayOfferDraw { game_id: String, player_id: String },
+    RelayAcceptDraw { game_id: String, player_id: String },
+    RelayDeclineDraw { game_id: String, player_id: String },
+    RelayClaimTimeWin { game_id: String, player_id: String },
+}
+
+/// A player's stake in a wager game, and the account it came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct Stake {
+    pub player_id: String,
+    pub owner: AccountOwner,
+}
+
+/// Tokens the players of a game stake, held by the application until the game ends
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct Wager {
+    /// What each player stakes
+    pub amount: Amount,
+    pub stakes: Vec<Stake>,
+    /// Whether the stakes have been paid out
+    pub settled: bool,
+    /// A player who disputed the wager. Disputed stakes are held until an admin rules.
+    #[serde(default)]
+    pub disputed_by: Option<String>,
+    #[serde(default)]
+    pub dispute_reason: Option<String>,
+    /// When a played game's stakes may be paid out, once its dispute window closes
+    #[graphql(name = "payableAt")]
+    #[serde(default)]
+    pub payable_at: Option<u64>,
+}
+
+/// How long a wager game can go without a move before either player may end it
+/// and take their stake back
+pub const WAGER_INACTIVITY_REFUND_MICROS: u64 = 7 * MICROS_PER_DAY;
+/// How long the stakes of a finished wager game are held, so either player can
+/// dispute the result before they are paid out
+pub const WAGER_DISPUTE_WINDOW_MICROS: u64 = MICROS_PER_DAY;
+
+impl Wager {
+    pub fn new(amount: Amount) -> Self {
+        Self { amount, stakes: Vec::new(), settled: false, disputed_by: None, dispute_reason: None, payable_at: None }
+    }
+
+    /// Whether the result of the finished game can still be disputed
+    pub fn disputable(&self, now: u64) -> bool {
+        !self.settled && self.disputed_by.is_none() && self.payable_at.is_some_and(|payable_at| now < payable_at)
+    }
+
+    /// Whether the stakes of the finished game can be paid out
+    pub fn payable(&self, now: u64) -> bool {
+        !self.settled && self.disputed_by.is_none() && self.payable_at.is_some_and(|payable_at| now >= payable_at)
+    }
+
+    /// Total held in escrow
+    pub fn pot(&self) -> Amount {
+        self.amount.saturating_mul(self.stakes.len() as u128)
+    }
+
+    pub fn has_stake(&self, player_id: &str) -> bool {
+        self.stakes.iter().any(|stake| stake.player_id == player_id)
+    }
+
+    /// Who is paid what when the game ends, and the rake kept for the treasury:
+    /// the pot less the rake to the winner, or each stake back to its owner in
+    /// full on a draw or when the game never started
+    pub fn payouts(&self, winner: Option<&str>, rake_percent: u32) -> (Vec<(AccountOwner, Amount)>, Amount) {
+        match winner.and_then(|winner| self.stakes.iter().find(|stake| stake.player_id == winner)) {
+            Some(stake) => {
+                let (rake, prize) = split_rake(self.pot(), rake_percent);
+                (vec![(stake.owner, prize)], rake)
+            }
+            None => (self.stakes.iter().map(|stake| (stake.owner, self.amount)).collect(), Amount::ZERO),
+        }
+    }
+}
+
+/// Longest reference an application can attach to a match it creates
+pub const MAX_MATCH_REFERENCE_LEN: usize = 64;
+
+/// Longest chat message, in characters
+pub const MAX_CHAT_MESSAGE_LEN: usize = 280;
+
+/// Most chat messages stored for a single game
+pub const MAX_CHAT_MESSAGES_PER_GAME: usize = 500;
+
+/// A chat message posted in a game
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ChatMessage {
+    pub sender: String,
+    pub text: String,
+    pub timestamp: u64,
+}
+
+/// Minimum time between two emotes from the same player in a game, in microseconds
+pub const EMOTE_COOLDOWN_MICROS: u64 = 3_000_000;
+
+/// Most emotes stored for a single game
+pub const MAX_EMOTES_PER_GAME: usize = 200;
+
+/// Quick messages players can send during a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum Emote {
+    GoodLuck,
+    WellPlayed,
+    Oops,
+    Thanks,
+    NiceMove,
+    Thinking,
+}
+
+/// An emote sent in a game, stored by side rather than player ID to keep it small
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct EmoteRecord {
+    pub sender: Turn,
+    pub emote: Emote,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ChallengeStatus {
+    Pending,
+    Accepted,
+    Declined,
+    /// Withdrawn by the challenger, or closed when they started another game
+    Cancelled,
+    Expired,
+}
+
+/// How long an untimed game can go without a move before anyone may have it adjudicated
+pub const ABANDONED_GAME_MICROS: u64 = 3 * MICROS_PER_DAY;
+/// Abandoned games with fewer moves than this are aborted instead of scored
+pub const MIN_MOVES_FOR_ADJUDICATION: u32 = 4;
+
+/// How long the creator of a random-color game has to reveal their secret
+/// once an opponent joins
+pub const COLOR_REVEAL_TIMEOUT_MICROS: u64 = 5 * 60 * 1_000_000;
+
+/// Hashed for color tosses
+#[derive(Serialize, Deserialize)]
+struct ColorToss(Vec<String>);
+
+impl BcsSignable<'_> for ColorToss {}
+
+/// The commitment a creator publishes for their color-toss secret
+pub fn color_commitment(secret: &str) -> String {
+    CryptoHash::new(&ColorToss(vec![secret.to_string()])).to_string()
+}
+
+/// A coin toss over `inputs`: true when the first player named in them takes
+/// red. The result depends only on the inputs, so nobody can steer it by timing
+/// a transaction.
+pub fn color_toss(inputs: &[&str]) -> bool {
+    let hash = CryptoHash::new(&ColorToss(inputs.iter().map(|input| input.to_string()).collect()));
+    hash.as_bytes()[0].is_multiple_of(2)
+}
+
+/// How long a challenge waits for an answer
+pub const CHALLENGE_TTL_MICROS: u64 = 10 * 60 * 1_000_000;
+/// How long an open game waits for an opponent
+pub const SEEK_TTL_MICROS: u64 = 30 * 60 * 1_000_000;
+
+/// An invitation to a game sent to one specific player
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Challenge {
+    pub id: String,
+    pub challenger: String,
+    pub opponent: String,
+    pub time_control: TimeControl,
+    pub is_rated: bool,
+    /// Color the challenger wants to play
+    pub color: ColorPreference,
+    pub status: ChallengeStatus,
+    /// Game created when the challenge was accepted
+    pub game_id: Option<String>,
+    pub created_at: u64,
+    #[serde(default)]
+    pub expires_at: u64,
+}
+
+impl Challenge {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.status == ChallengeStatus::Pending && now >= self.expires_at
+    }
+}
+
+/// Most open seeks a player can post at once
+pub const MAX_SEEKS_PER_PLAYER: usize = 3;
+
+/// A standing offer to play anyone who fits its constraints; the game is only
+/// created once someone accepts it
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Seek {
+    pub id: String,
+    pub player: String,
+    pub time_control: TimeControl,
+    pub is_rated: bool,
+    /// Color the poster wants to play
+    pub color: ColorPreference,
+    /// Rating range, in the seek's rating category, an opponent must fall in
+    pub min_rating: Option<u32>,
+    pub max_rating: Option<u32>,
+    pub created_at: u64,
+    pub expires_at: u64,
+}
+
+impl Seek {
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    pub fn accepts_rating(&self, rating: u32) -> bool {
+        self.min_rating.is_none_or(|min| rating >= min) && self.max_rating.is_none_or(|max| rating <= max)
+    }
+}
+
+/// Most friends a player can have
+pub const MAX_FRIENDS: usize = 200;
+
+/// A friend request waiting for an answer
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct FriendRequest {
+    pub from: String,
+    pub sent_at: u64,
+}
+
+/// A friend and the game they are playing right now
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct FriendActivity {
+    pub friend_id: String,
+    pub game: CheckersGame,
+}
+
+/// Longest reason accepted in a player report
+pub const MAX_REPORT_REASON_LEN: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ReportStatus {
+    Open,
+    /// An admin acted on the report
+    Resolved,
+    /// An admin found nothing to act on
+    Dismissed,
+}
+
+/// A player's report of another player, kept as a moderation record
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Report {
+    pub id: String,
+    pub reporter: String,
+    pub reported_id: String,
+    pub game_id: Option<String>,
+    pub reason: String,
+    pub status: ReportStatus,
+    pub created_at: u64,
+    /// Admin that closed the report, and what they noted
+    pub resolved_by: Option<String>,
+    pub resolution_note: Option<String>,
+    pub resolved_at: Option<u64>,
+}
+
+/// A player barred by an admin from starting or joining anything
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PlayerBan {
+    pub player_id: String,
+    pub reason: String,
+    pub banned_at: u64,
+}
+
+/// Name of the event stream the application publishes `GameEvent`s on
+pub const GAME_EVENT_STREAM: &str = "checkers";
+
+/// Events published on the application's event stream, for other chains and indexers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GameEvent {
+    GameCreated {
+        game_id: String,
+        creator: String,
+        time_control: Option<TimeControl>,
+        is_rated: bool,
+    },
+    MoveMade {
+        game_id: String,
+        player: String,
+        checkers_move: CheckersMove,
+        move_count: u32,
+    },
+    GameEnded {
+        game_id: String,
+        result: GameResult,
+    },
+    DrawOffered {
+        game_id: String,
+        offered_by: Turn,
+    },
+    MatchFound {
+        game_id: String,
+        red_player: String,
+        black_player: String,
+        time_control: TimeControl,
+        #[serde(default)]
+        clock: Option<Clock>,
+    },
+    TournamentUpdated {
+        tournament_id: String,
+        status: TournamentStatus,
+    },
+    /// A game created by another application through `CreateMatch` has ended.
+    /// Applications subscribe to this stream to react to their games' outcomes;
+    /// the fields of this variant will not change.
+    AppGameEnded {
+        game_id: String,
+        owner_app: String,
+        reference: Option<String>,
+        red_player: String,
+        black_player: String,
+        result: GameResult,
+        rated: bool,
+    },
+    /// A player reached a new level
+    LeveledUp {
+        player_id: String,
+        level: u32,
+        xp: u64,
+    },
+}
+
+impl GameEvent {
+    /// The game an event is about, if any
+    pub fn game_id(&self) -> Option<&str> {
+        match self {
+            GameEvent::GameCreated { game_id, .. }
+            | GameEvent::MoveMade { game_id, .. }
+            | GameEvent::GameEnded { game_id, .. }
+            | GameEvent::DrawOffered { game_id, .. }
+            | GameEvent::MatchFound { game_id, .. }
+            | GameEvent::AppGameEnded { game_id, .. } => Some(game_id),
+            GameEvent::TournamentUpdated { .. } | GameEvent::LeveledUp { .. } => None,
+        }
+    }
+}
+
+/// A game on another chain, followed through that chain's event stream
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct SpectatedGame {
+    pub chain_id: String,
+    pub game_id: String,
+    pub red_player: Option<String>,
+    pub black_player: Option<String>,
+    pub time_control: Option<TimeControl>,
+    pub moves: Vec<CheckersMove>,
+    pub move_count: u32,
+    pub result: Option<GameResult>,
+    pub updated_at: u64,
+}
+
+impl SpectatedGame {
+    pub fn new(chain_id: String, game_id: String) -> Self {
+        Self { chain_id, game_id, ..Self::default() }
+    }
+
+    /// Update the game from one of its events. Moves already seen are skipped.
+    pub fn apply(&mut self, event: &GameEvent, now: u64) {
+        match event {
+            GameEvent::GameCreated { time_control, .. } => self.time_control = *time_control,
+            GameEvent::MatchFound { red_player, black_player, time_control, .. } => {
+                self.red_player = Some(red_player.clone());
+                self.black_player = Some(black_player.clone());
+                self.time_control = Some(*time_control);
+            }
+            GameEvent::MoveMade { checkers_move, move_count, .. } => {
+                if *move_count <= self.move_count {
+                    return;
+                }
+                self.moves.push(checkers_move.clone());
+                self.move_count = *move_count;
+            }
+            GameEvent::GameEnded { result, .. } => self.result = Some(*result),
+            GameEvent::DrawOffered { .. }
+            | GameEvent::TournamentUpdated { .. }
+            | GameEvent::AppGameEnded { .. }
+            | GameEvent::LeveledUp { .. } => return,
+        }
+        self.updated_at = now;
+    }
 }
 
 pub fn get_piece(board_state: &str, row: u8, col: u8) -> Piece {
@@ -638,73 +3093,590 @@ impl TimeControl {
             TimeControl::Rapid10_0 => 0,
         }
     }
+
+    /// The time control a clock was created with, if it matches a standard one
+    pub fn from_clock(clock: &Clock) -> Option<TimeControl> {
+        TimeControl::all()
+            .into_iter()
+            .find(|tc| tc.initial_time_ms() == clock.initial_time_ms && tc.increment_ms() == clock.increment_ms)
+    }
+
+    /// The rating pool this time control counts towards
+    pub fn category(&self) -> RatingCategory {
+        match self {
+            TimeControl::Bullet1_0 | TimeControl::Bullet2_1 => RatingCategory::Bullet,
+            TimeControl::Blitz3_0 | TimeControl::Blitz5_3 => RatingCategory::Blitz,
+            TimeControl::Rapid10_0 => RatingCategory::Rapid,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, Default)]
-pub struct Clock {
-    pub initial_time_ms: u64,
-    pub increment_ms: u64,
-    pub red_time_ms: u64,
-    pub black_time_ms: u64,
-    pub last_move_at: u64,
-    pub active_player: Option<Turn>,
+/// Rating pools shared by time controls of similar length
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Enum)]
+pub enum RatingCategory {
+    Bullet,
+    Blitz,
+    Rapid,
 }
 
-impl Clock {
-    pub fn new(time_control: TimeControl) -> Self {
-        let initial = time_control.initial_time_ms();
-        let increment = time_control.increment_ms();
-        Self {
-            initial_time_ms: initial,
-            increment_ms: increment,
-            red_time_ms: initial,
-            black_time_ms: initial,
-            last_move_at: 0,
-            active_player: None,
+impl RatingCategory {
+    /// A time control rated in this category
+    pub fn time_control(&self) -> TimeControl {
+        match self {
+            RatingCategory::Bullet => TimeControl::Bullet1_0,
+            RatingCategory::Blitz => TimeControl::Blitz3_0,
+            RatingCategory::Rapid => TimeControl::Rapid10_0,
         }
     }
+}
 
-    pub fn start(&mut self, current_time_ms: u64) {
-        self.last_move_at = current_time_ms;
-        self.active_player = Some(Turn::Red);
-    }
+/// Compact record of a finished game, for archive listings
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct GameSummary {
+    pub id: String,
+    #[graphql(name = "redPlayer")]
+    pub red_player: Option<String>,
+    #[graphql(name = "blackPlayer")]
+    pub black_player: Option<String>,
+    pub result: Option<GameResult>,
+    #[graphql(name = "moveCount")]
+    pub move_count: u32,
+    #[graphql(name = "isRated")]
+    pub is_rated: bool,
+    #[graphql(name = "timeControl")]
+    pub time_control: Option<TimeControl>,
+    #[graphql(name = "tournamentId")]
+    pub tournament_id: Option<String>,
+    #[graphql(name = "createdAt")]
+    pub created_at: u64,
+    #[graphql(name = "finishedAt")]
+    pub finished_at: u64,
+}
 
-    pub fn timed_out(&self, current_time_ms: u64) -> Option<Turn> {
-        match self.active_player {
-            Some(Turn::Red) => {
-                let elapsed = current_time_ms.saturating_sub(self.last_move_at);
-                if elapsed >= self.red_time_ms {
-                    Some(Turn::Red)
-                } else {
-                    None
-                }
-            }
-            Some(Turn::Black) => {
-                let elapsed = current_time_ms.saturating_sub(self.last_move_at);
-                if elapsed >= self.black_time_ms {
-                    Some(Turn::Black)
-                } else {
-                    None
-                }
-            }
-            None => None,
+impl GameSummary {
+    pub fn from_game(game: &CheckersGame) -> Self {
+        Self {
+            id: game.id.clone(),
+            red_player: game.red_player.clone(),
+            black_player: game.black_player.clone(),
+            result: game.result,
+            move_count: game.move_count,
+            is_rated: game.is_rated,
+            time_control: game.clock.as_ref().and_then(TimeControl::from_clock),
+            tournament_id: game.tournament_id.clone(),
+            created_at: game.created_at,
+            finished_at: game.updated_at,
         }
     }
+}
 
-    pub fn make_move(&mut self, current_time_ms: u64) -> bool {
-        let Some(active) = self.active_player else {
-            return false;
-        };
+/// A finished game exported in Portable Draughts Notation
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct GamePdn {
+    #[graphql(name = "gameId")]
+    pub game_id: String,
+    pub pdn: String,
+}
 
-        let elapsed = current_time_ms.saturating_sub(self.last_move_at);
+/// PDN square number (1-32) of a dark square
+/// Red starts on rows 0-2, so Red's back row holds squares 1-4.
+pub fn pdn_square(row: u8, col: u8) -> u8 {
+    row * 4 + col / 2 + 1
+}
 
-        match active {
-            Turn::Red => {
-                if elapsed >= self.red_time_ms {
-                    self.red_time_ms = 0;
-                    return false;
-                }
-                self.red_time_ms = self.red_time_ms.saturating_sub(elapsed) + self.increment_ms;
+/// Civil date (year, month, day) of a timestamp in microseconds
+fn civil_date(micros: u64) -> (u64, u64, u64) {
+    let days = micros / MICROS_PER_DAY + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Split a game's moves into turns, Red's first and then alternating.
+/// The jumps of a capture sequence share a turn: the capturing piece always moves
+/// again from the square it just landed on.
+pub fn group_turns(moves: &[CheckersMove]) -> Vec<&[CheckersMove]> {
+    let mut turns = Vec::new();
+    let mut start = 0;
+    for i in 1..moves.len() {
+        let (previous, mv) = (&moves[i - 1], &moves[i]);
+        let continues = previous.captured_row.is_some()
+            && mv.captured_row.is_some()
+            && previous.to_row == mv.from_row
+            && previous.to_col == mv.from_col;
+        if !continues {
+            turns.push(&moves[start..i]);
+            start = i;
+        }
+    }
+    if start < moves.len() {
+        turns.push(&moves[start..]);
+    }
+    turns
+}
+
+/// A game in PDN, with its headers and the moves in `game.moves`
+/// Red moves first, so it is written as PDN's first-moving side, Black, and the
+/// result is given from Red's side. Successive jumps of one capture are joined
+/// into a single move (`9x18x27`).
+pub fn game_pdn(game: &CheckersGame) -> String {
+    let name = |username: &Option<String>, player: &Option<String>| {
+        username.clone().or_else(|| player.clone()).unwrap_or_else(|| "?".to_string())
+    };
+    let result = match game.result {
+        Some(GameResult::RedWins) => "1-0",
+        Some(GameResult::BlackWins) => "0-1",
+        Some(GameResult::Draw) => "1/2-1/2",
+        Some(GameResult::InProgress) | None => "*",
+    };
+    let (year, month, day) = civil_date(game.created_at);
+    let mut pdn = format!(
+        "[Event \"{}\"]\n[Date \"{:04}.{:02}.{:02}\"]\n[Black \"{}\"]\n[White \"{}\"]\n[Result \"{}\"]\n[GameType \"21\"]\n",
+        if game.is_rated { "Rated game" } else { "Casual game" },
+        year,
+        month,
+        day,
+        name(&game.red_username, &game.red_player),
+        name(&game.black_username, &game.black_player),
+        result,
+    );
+    if let Some(clock) = &game.clock {
+        pdn.push_str(&format!("[TimeControl \"{}+{}\"]\n", clock.initial_time_ms / 1000, clock.increment_ms / 1000));
+    }
+    pdn.push('\n');
+
+    for (i, turn) in group_turns(&game.moves).into_iter().enumerate() {
+        if i % 2 == 0 {
+            pdn.push_str(&format!("{}. ", i / 2 + 1));
+        }
+        let separator = if turn[0].captured_row.is_some() { 'x' } else { '-' };
+        pdn.push_str(&pdn_square(turn[0].from_row, turn[0].from_col).to_string());
+        for mv in turn {
+            pdn.push(separator);
+            pdn.push_str(&pdn_square(mv.to_row, mv.to_col).to_string());
+        }
+        pdn.push(' ');
+    }
+    pdn.push_str(result);
+    pdn.push('\n');
+    pdn
+}
+
+/// Filters for game search; every field is optional and they combine with AND
+#[derive(Debug, Clone, Default, Serialize, Deserialize, InputObject)]
+pub struct GameFilter {
+    pub status: Option<GameStatus>,
+    #[graphql(name = "isRated")]
+    pub is_rated: Option<bool>,
+    #[graphql(name = "timeControl")]
+    pub time_control: Option<TimeControl>,
+    /// Player chain ID, on either side
+    pub player: Option<String>,
+    pub result: Option<GameResult>,
+    /// Only games created at or after this time (microseconds)
+    #[graphql(name = "createdAfter")]
+    pub created_after: Option<u64>,
+    /// Only games created before this time (microseconds)
+    #[graphql(name = "createdBefore")]
+    pub created_before: Option<u64>,
+    #[graphql(name = "tournamentId")]
+    pub tournament_id: Option<String>,
+}
+
+impl GameFilter {
+    pub fn matches(&self, game: &CheckersGame) -> bool {
+        let seated = |player: &String| {
+            game.red_player.as_ref() == Some(player) || game.black_player.as_ref() == Some(player)
+        };
+        self.status.is_none_or(|status| game.status == status)
+            && self.is_rated.is_none_or(|rated| game.is_rated == rated)
+            && self.time_control.is_none_or(|tc| {
+                game.clock.as_ref().and_then(TimeControl::from_clock) == Some(tc)
+            })
+            && self.player.as_ref().is_none_or(seated)
+            && self.result.is_none_or(|result| game.result == Some(result))
+            && self.created_after.is_none_or(|after| game.created_at >= after)
+            && self.created_before.is_none_or(|before| game.created_at < before)
+            && self.tournament_id.as_ref().is_none_or(|id| game.tournament_id.as_ref() == Some(id))
+    }
+}
+
+/// Filters for browsing tournaments; every field is optional and they combine with AND
+#[derive(Debug, Clone, Default, Serialize, Deserialize, InputObject)]
+pub struct TournamentFilter {
+    pub status: Option<TournamentStatus>,
+    #[graphql(name = "timeControl")]
+    pub time_control: Option<TimeControl>,
+    pub format: Option<TournamentFormat>,
+    /// Only tournaments scheduled to start before this time (microseconds),
+    /// e.g. now plus an hour for the ones starting soon
+    #[graphql(name = "startingBefore")]
+    pub starting_before: Option<u64>,
+}
+
+impl TournamentFilter {
+    pub fn matches(&self, tournament: &Tournament) -> bool {
+        self.status.is_none_or(|status| tournament.status == status)
+            && self.time_control.is_none_or(|tc| tournament.time_control == tc)
+            && self.format.is_none_or(|format| tournament.format == format)
+            && self.starting_before.is_none_or(|before| {
+                tournament.scheduled_start.is_some_and(|start| start < before)
+            })
+    }
+}
+
+/// Microseconds in a day, used to bucket daily counters
+pub const MICROS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000;
+
+/// Application-wide totals for dashboards
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct GlobalStats {
+    /// Games that have finished
+    #[graphql(name = "gamesPlayed")]
+    pub games_played: u64,
+    #[graphql(name = "gamesActive")]
+    pub games_active: u64,
+    pub players: u64,
+    pub tournaments: u64,
+    #[graphql(name = "movesToday")]
+    pub moves_today: u64,
+}
+
+/// Counts kept up to date as games, players and the queue change
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct Totals {
+    #[graphql(name = "totalGames")]
+    pub total_games: u64,
+    #[graphql(name = "pendingGames")]
+    pub pending_games: u64,
+    #[graphql(name = "activeGames")]
+    pub active_games: u64,
+    #[graphql(name = "registeredPlayers")]
+    pub registered_players: u64,
+    #[graphql(name = "queuedPlayers")]
+    pub queued_players: u64,
+}
+
+/// A player's position in one rating category
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PlayerRank {
+    pub category: RatingCategory,
+    pub rating: u32,
+    /// 1-based; players with equal ratings share a rank
+    pub rank: u32,
+    #[graphql(name = "totalPlayers")]
+    pub total_players: u32,
+    /// Share of ranked players with a lower rating, in basis points (0-10000)
+    #[graphql(name = "percentileBps")]
+    pub percentile_bps: u32,
+}
+
+/// A single rating update, recorded for rating graphs
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RatingChange {
+    #[graphql(name = "gameId")]
+    pub game_id: String,
+    #[graphql(name = "oldRating")]
+    pub old_rating: u32,
+    #[graphql(name = "newRating")]
+    pub new_rating: u32,
+    #[graphql(name = "timeControl")]
+    pub time_control: TimeControl,
+    pub timestamp: u64,
+}
+
+/// Length of a rating season, in microseconds (28 days)
+pub const SEASON_LENGTH_MICROS: u64 = 28 * 24 * 60 * 60 * 1_000_000;
+
+/// How far ratings move back toward the starting rating when a new season starts, in percent
+pub const SEASON_RATING_DECAY_PCT: u32 = 50;
+
+/// A rating season. Seasons are fixed windows of SEASON_LENGTH_MICROS,
+/// so the season id is derived directly from a timestamp.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Season {
+    pub id: u32,
+    #[graphql(name = "startsAt")]
+    pub starts_at: u64,
+    #[graphql(name = "endsAt")]
+    pub ends_at: u64,
+    #[graphql(name = "ratingDecayPct")]
+    pub rating_decay_pct: u32,
+    /// Set once the season's trophies have been handed out
+    #[graphql(name = "trophiesAwarded")]
+    #[serde(default)]
+    pub trophies_awarded: bool,
+}
+
+impl Season {
+    /// The season containing the given timestamp (microseconds)
+    pub fn for_timestamp(timestamp: u64) -> Self {
+        let id = (timestamp / SEASON_LENGTH_MICROS) as u32;
+        let starts_at = id as u64 * SEASON_LENGTH_MICROS;
+        Self {
+            id,
+            starts_at,
+            ends_at: starts_at + SEASON_LENGTH_MICROS,
+            rating_decay_pct: SEASON_RATING_DECAY_PCT,
+            trophies_awarded: false,
+        }
+    }
+
+    /// Whether the season is over at `now` and its trophies can be awarded
+    pub fn can_award_trophies(&self, now: u64) -> bool {
+        now >= self.ends_at && !self.trophies_awarded
+    }
+}
+
+/// Number of top-rated players per rating category awarded a season trophy
+pub const SEASON_TROPHY_PLACES: usize = 10;
+
+/// Progress of a season's trophy award. Seasons with more players than one
+/// operation can scan are awarded over several `AwardSeasonTrophies` calls.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SeasonTrophyScan {
+    /// Season stats entries scanned so far, in key order
+    pub scanned: u64,
+    /// Best-rated players so far in each trophy time control, best first
+    pub top_rated: Vec<Vec<PlayerStats>>,
+    /// Most games and longest streak so far, with the player who has them
+    pub most_games: Option<(u32, String)>,
+    pub best_streak: Option<(u32, String)>,
+}
+
+/// What a season trophy was awarded for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum TrophyKind {
+    TopRated,
+    MostGames,
+    BestStreak,
+}
+
+/// A trophy awarded at the end of a season
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Trophy {
+    #[graphql(name = "seasonId")]
+    pub season_id: u32,
+    pub kind: TrophyKind,
+    /// Final placing, 1-based (always 1 for MostGames and BestStreak)
+    pub rank: u32,
+    /// Rating category, for TopRated trophies
+    pub category: Option<RatingCategory>,
+    #[graphql(name = "awardedAt")]
+    pub awarded_at: u64,
+}
+
+/// Milestones a player earns once and keeps as a badge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Enum)]
+pub enum Achievement {
+    FirstWin,
+    TenWins,
+    /// Ten wins in a row
+    WinStreak10,
+    /// A win without losing a single piece
+    FlawlessWin,
+    HundredGames,
+    TournamentVictory,
+    /// Finishing in the top three of a tournament of three or more
+    TournamentPodium,
+}
+
+impl Achievement {
+    /// Achievements a player qualifies for after a game, given their updated stats,
+    /// whether they won and how many of their pieces were left on the board
+    pub fn earned_after_game(stats: &PlayerStats, won: bool, pieces_left: u8) -> Vec<Achievement> {
+        let mut earned = Vec::new();
+        if won {
+            earned.push(Achievement::FirstWin);
+            if pieces_left == 12 {
+                earned.push(Achievement::FlawlessWin);
+            }
+        }
+        if stats.games_won >= 10 {
+            earned.push(Achievement::TenWins);
+        }
+        if stats.win_streak >= 10 {
+            earned.push(Achievement::WinStreak10);
+        }
+        if stats.games_played >= 100 {
+            earned.push(Achievement::HundredGames);
+        }
+        earned
+    }
+}
+
+/// An achievement as awarded to a player
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Badge {
+    pub achievement: Achievement,
+    /// Game or tournament the achievement was earned in
+    #[graphql(name = "sourceId")]
+    pub source_id: String,
+    #[graphql(name = "awardedAt")]
+    pub awarded_at: u64,
+}
+
+/// How often a set of missions rotates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum MissionPeriod {
+    Daily,
+    Weekly,
+}
+
+impl MissionPeriod {
+    pub fn length_micros(&self) -> u64 {
+        match self {
+            MissionPeriod::Daily => MICROS_PER_DAY,
+            MissionPeriod::Weekly => 7 * MICROS_PER_DAY,
+        }
+    }
+
+    /// Number of the period containing a timestamp, counted from the epoch
+    pub fn index_at(&self, timestamp: u64) -> u64 {
+        timestamp / self.length_micros()
+    }
+}
+
+/// What a mission asks the player to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum MissionGoal {
+    PlayGames,
+    WinGames,
+    WinBulletGames,
+    WinBlitzGames,
+    SolvePuzzles,
+    PlayTournament,
+}
+
+const DAILY_MISSIONS: [(MissionGoal, u32); 5] = [
+    (MissionGoal::PlayGames, 3),
+    (MissionGoal::WinGames, 1),
+    (MissionGoal::WinBlitzGames, 2),
+    (MissionGoal::SolvePuzzles, 5),
+    (MissionGoal::WinBulletGames, 2),
+];
+const WEEKLY_MISSIONS: [(MissionGoal, u32); 3] = [
+    (MissionGoal::PlayTournament, 1),
+    (MissionGoal::WinGames, 10),
+    (MissionGoal::PlayGames, 20),
+];
+/// Missions offered at once in each period
+pub const DAILY_MISSION_SLOTS: usize = 2;
+pub const WEEKLY_MISSION_SLOTS: usize = 1;
+
+/// A player's progress on one of the current missions
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Mission {
+    pub period: MissionPeriod,
+    #[graphql(name = "periodIndex")]
+    pub period_index: u64,
+    pub goal: MissionGoal,
+    pub target: u32,
+    pub progress: u32,
+    #[graphql(name = "completedAt")]
+    pub completed_at: Option<u64>,
+}
+
+impl Mission {
+    /// The missions on offer in a period; each period rotates through the templates
+    pub fn rotation(period: MissionPeriod, period_index: u64) -> Vec<Mission> {
+        let (templates, slots): (&[(MissionGoal, u32)], usize) = match period {
+            MissionPeriod::Daily => (&DAILY_MISSIONS, DAILY_MISSION_SLOTS),
+            MissionPeriod::Weekly => (&WEEKLY_MISSIONS, WEEKLY_MISSION_SLOTS),
+        };
+        (0..slots)
+            .map(|slot| {
+                let (goal, target) = templates[(period_index as usize + slot) % templates.len()];
+                Mission { period, period_index, goal, target, progress: 0, completed_at: None }
+            })
+            .collect()
+    }
+
+    /// Count progress towards the mission; returns true if this completed it
+    pub fn advance(&mut self, amount: u32, now: u64) -> bool {
+        if self.completed_at.is_some() {
+            return false;
+        }
+        self.progress = (self.progress + amount).min(self.target);
+        if self.progress == self.target {
+            self.completed_at = Some(now);
+            return true;
+        }
+        false
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, Default)]
+pub struct Clock {
+    pub initial_time_ms: u64,
+    pub increment_ms: u64,
+    pub red_time_ms: u64,
+    pub black_time_ms: u64,
+    pub last_move_at: u64,
+    pub active_player: Option<Turn>,
+}
+
+impl Clock {
+    pub fn new(time_control: TimeControl) -> Self {
+        let initial = time_control.initial_time_ms();
+        let increment = time_control.increment_ms();
+        Self {
+            initial_time_ms: initial,
+            increment_ms: increment,
+            red_time_ms: initial,
+            black_time_ms: initial,
+            last_move_at: 0,
+            active_player: None,
+        }
+    }
+
+    pub fn start(&mut self, current_time_ms: u64) {
+        self.last_move_at = current_time_ms;
+        self.active_player = Some(Turn::Red);
+    }
+
+    pub fn timed_out(&self, current_time_ms: u64) -> Option<Turn> {
+        match self.active_player {
+            Some(Turn::Red) => {
+                let elapsed = current_time_ms.saturating_sub(self.last_move_at);
+                if elapsed >= self.red_time_ms {
+                    Some(Turn::Red)
+                } else {
+                    None
+                }
+            }
+            Some(Turn::Black) => {
+                let elapsed = current_time_ms.saturating_sub(self.last_move_at);
+                if elapsed >= self.black_time_ms {
+                    Some(Turn::Black)
+                } else {
+                    None
+                }
+            }
+            None => None,
+        }
+    }
+
+    pub fn make_move(&mut self, current_time_ms: u64) -> bool {
+        let Some(active) = self.active_player else {
+            return false;
+        };
+
+        let elapsed = current_time_ms.saturating_sub(self.last_move_at);
+
+        match active {
+            Turn::Red => {
+                if elapsed >= self.red_time_ms {
+                    self.red_time_ms = 0;
+                    return false;
+                }
+                self.red_time_ms = self.red_time_ms.saturating_sub(elapsed) + self.increment_ms;
                 self.active_player = Some(Turn::Black);
             }
             Turn::Black => {
@@ -752,11 +3724,74 @@ pub enum ColorPreference {
     Random,
 }
 
+/// Rating difference accepted when a queue entry is fresh
+pub const QUEUE_INITIAL_RATING_BAND: u32 = 100;
+/// How much the accepted rating difference grows per widening interval
+pub const QUEUE_RATING_BAND_STEP: u32 = 50;
+/// How long a queue entry waits before its rating band widens
+pub const QUEUE_BAND_WIDEN_INTERVAL_MICROS: u64 = 15 * 1_000_000;
+/// How long a queue entry stays matchable unless the player joins again
+pub const QUEUE_ENTRY_TTL_MICROS: u64 = 10 * 60 * 1_000_000;
+
+/// How many of a player's recent colors are remembered for balancing
+pub const COLOR_HISTORY_LEN: usize = 10;
+
+/// The colors a player was given in their recent queue and tournament games
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ColorHistory {
+    /// Most recent last
+    pub recent: Vec<Turn>,
+}
+
+impl ColorHistory {
+    pub fn record(&mut self, color: Turn) {
+        self.recent.push(color);
+        if self.recent.len() > COLOR_HISTORY_LEN {
+            self.recent.remove(0);
+        }
+    }
+
+    /// Red games minus black games
+    pub fn balance(&self) -> i32 {
+        self.recent
+            .iter()
+            .map(|color| if *color == Turn::Red { 1 } else { -1 })
+            .sum()
+    }
+}
+
+/// Whether `a` should play red against `b` to even out both players' colors.
+/// None when neither is owed a color.
+pub fn balanced_red(a: &ColorHistory, b: &ColorHistory) -> Option<bool> {
+    match a.balance().cmp(&b.balance()) {
+        std::cmp::Ordering::Less => Some(true),
+        std::cmp::Ordering::Greater => Some(false),
+        // Same balance: avoid giving anyone the same color twice in a row
+        std::cmp::Ordering::Equal => match (a.recent.last(), b.recent.last()) {
+            (Some(Turn::Red), Some(Turn::Black)) | (Some(Turn::Red), None) | (None, Some(Turn::Black)) => Some(false),
+            (Some(Turn::Black), Some(Turn::Red)) | (Some(Turn::Black), None) | (None, Some(Turn::Red)) => Some(true),
+            _ => None,
+        },
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct QueueEntry {
     pub chain_id: String,
     pub time_control: TimeControl,
     pub joined_at: u64,
+    /// Chain the player queued from, when it is not the hub chain
+    #[graphql(name = "homeChain")]
+    #[serde(default)]
+    pub home_chain: Option<String>,
+    /// When the entry stops being matched; joining the same pool again renews it
+    #[graphql(name = "expiresAt")]
+    #[serde(default)]
+    pub expires_at: u64,
+    /// Unrated entries wait in a separate casual pool
+    #[graphql(name = "isRated")]
+    #[serde(default = "default_is_rated")]
+    pub is_rated: bool,
 }
 
 impl QueueEntry {
@@ -765,17 +3800,93 @@ impl QueueEntry {
             chain_id,
             time_control,
             joined_at,
+            home_chain: None,
+            expires_at: joined_at + QUEUE_ENTRY_TTL_MICROS,
+            is_rated: true,
+        }
+    }
+
+    /// The pool this entry waits in
+    pub fn pool(&self) -> QueuePool {
+        QueuePool { time_control: self.time_control, is_rated: self.is_rated }
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Rating difference this entry accepts at `now`, widening the longer it has waited
+    pub fn rating_band(&self, now: u64) -> u32 {
+        rating_band_after(now.saturating_sub(self.joined_at))
+    }
+}
+
+/// Rating difference accepted after waiting `waited` microseconds in the queue
+pub fn rating_band_after(waited: u64) -> u32 {
+    let steps = (waited / QUEUE_BAND_WIDEN_INTERVAL_MICROS).min(u32::MAX as u64) as u32;
+    QUEUE_INITIAL_RATING_BAND.saturating_add(steps.saturating_mul(QUEUE_RATING_BAND_STEP))
+}
+
+/// One matchmaking pool: players with the same time control seeking rated
+/// or casual games
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QueuePool {
+    pub time_control: TimeControl,
+    pub is_rated: bool,
+}
+
+impl QueuePool {
+    pub fn all() -> Vec<Self> {
+        TimeControl::all()
+            .into_iter()
+            .flat_map(|time_control| {
+                [true, false].map(|is_rated| QueuePool { time_control, is_rated })
+            })
+            .collect()
+    }
+}
+
+/// How many recent queue opponents are remembered per player
+pub const QUEUE_RECENT_OPPONENTS: usize = 3;
+/// How long two players are kept apart in the queue after being paired
+pub const QUEUE_REMATCH_COOLDOWN_MICROS: u64 = 5 * 60 * 1_000_000;
+
+/// A player's last few queue pairings, used to avoid forced rematches
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecentOpponents {
+    /// (opponent, paired at), most recent last
+    pub entries: Vec<(String, u64)>,
+}
+
+impl RecentOpponents {
+    pub fn record(&mut self, opponent: &str, now: u64) {
+        self.entries.retain(|(id, _)| id != opponent);
+        self.entries.push((opponent.to_string(), now));
+        if self.entries.len() > QUEUE_RECENT_OPPONENTS {
+            let excess = self.entries.len() - QUEUE_RECENT_OPPONENTS;
+            self.entries.drain(..excess);
         }
     }
+
+    /// Whether `opponent` was paired with this player within the cooldown window
+    pub fn in_cooldown(&self, opponent: &str, now: u64) -> bool {
+        self.entries.iter().any(|(id, at)| {
+            id == opponent && now.saturating_sub(*at) < QUEUE_REMATCH_COOLDOWN_MICROS
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct QueueStatus {
     pub time_control: TimeControl,
+    /// Players waiting for a rated game
     pub player_count: u32,
+    /// Players waiting for a casual game
+    #[graphql(name = "casualPlayerCount")]
+    pub casual_player_count: u32,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Enum, Default)]
 pub enum TournamentStatus {
     #[default]
     Registration,
@@ -799,6 +3910,42 @@ pub enum TournamentFormat {
     #[default]
     Swiss,
     SingleElimination,
+    DoubleElimination,
+    RoundRobin,
+}
+
+impl TournamentFormat {
+    /// Knockout formats, where every match needs a winner
+    pub fn is_elimination(&self) -> bool {
+        matches!(self, TournamentFormat::SingleElimination | TournamentFormat::DoubleElimination)
+    }
+}
+
+/// How a Swiss tournament settles a tie for first that the tiebreaks can't split
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum PlayoffMode {
+    /// A playoff game at the tournament's time control, replayed until decisive
+    Game,
+    /// One game where red has more time but black has draw odds
+    Armageddon,
+}
+
+/// Black's share of the initial time in an Armageddon playoff, in percent
+pub const ARMAGEDDON_BLACK_TIME_PCT: u64 = 80;
+
+/// Longest best-of series an elimination match can be played as
+pub const MAX_SERIES_GAMES: u32 = 7;
+
+/// Which part of a bracket a tournament match belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum, Default)]
+pub enum BracketSide {
+    /// The winners bracket, which is the only bracket outside double elimination
+    #[default]
+    Winners,
+    Losers,
+    GrandFinal,
+    /// Single elimination's consolation match between the semifinal losers
+    ThirdPlace,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, Default)]
@@ -807,6 +3954,10 @@ pub struct SwissParticipant {
     pub score: u32,
     pub opponents: Vec<String>,
     pub has_bye: bool,
+    /// Left the tournament while it was running; keeps their results but gets no
+    /// more pairings
+    #[serde(default)]
+    pub withdrawn: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, Default)]
@@ -816,7 +3967,7 @@ pub struct TournamentRound {
     pub completed: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject, Default)]
 pub struct TournamentMatch {
     pub id: String,
     pub round: u32,
@@ -826,6 +3977,80 @@ pub struct TournamentMatch {
     pub game_id: Option<String>,
     pub winner: Option<String>,
     pub status: MatchStatus,
+    #[serde(default)]
+    pub bracket: BracketSide,
+    /// When the match must be finished by, stamped when it becomes ready to play
+    #[serde(default)]
+    pub deadline: Option<u64>,
+    /// Neither player started the match before its deadline, so both lose it
+    #[serde(default)]
+    pub double_forfeit: bool,
+    /// A playoff for first place, which doesn't count towards scores
+    #[serde(default)]
+    pub playoff: bool,
+    /// Games player1 has won in a best-of series
+    #[graphql(name = "player1Wins")]
+    #[serde(default)]
+    pub player1_wins: u32,
+    /// Games player2 has won in a best-of series
+    #[graphql(name = "player2Wins")]
+    #[serde(default)]
+    pub player2_wins: u32,
+    /// Players who have said they are ready; the game starts once both have
+    #[serde(default)]
+    pub ready_players: Vec<String>,
+    /// Drawn games in a best-of series, each worth half a win to both players
+    #[graphql(name = "seriesDraws")]
+    #[serde(default)]
+    pub series_draws: u32,
+}
+
+impl TournamentMatch {
+    /// Put the match back to be played again, e.g. after a drawn elimination
+    /// game. Both players have to ready up again, and it gets a fresh deadline.
+    pub fn replay(&mut self) {
+        self.game_id = None;
+        self.status = MatchStatus::Ready;
+        self.ready_players.clear();
+        self.deadline = None;
+    }
+
+    /// Count a finished game of a best-of series; `winner` is None for a draw
+    pub fn record_series_game(&mut self, winner: Option<&str>) {
+        match winner {
+            Some(winner) if self.player1.as_deref() == Some(winner) => self.player1_wins += 1,
+            Some(_) => self.player2_wins += 1,
+            None => self.series_draws += 1,
+        }
+    }
+
+    /// Each player's series score in half points
+    fn series_half_points(&self) -> (u32, u32) {
+        (2 * self.player1_wins + self.series_draws, 2 * self.player2_wins + self.series_draws)
+    }
+
+    /// The player who has won a best-of series by taking more than half of its
+    /// points. A series level after all its games goes to the next decisive game.
+    pub fn series_winner(&self, best_of: u32) -> Option<String> {
+        let (player1, player2) = self.series_half_points();
+        if player1 > best_of {
+            self.player1.clone()
+        } else if player2 > best_of {
+            self.player2.clone()
+        } else {
+            None
+        }
+    }
+
+    /// The player ahead in a best-of series, if either is
+    pub fn series_leader(&self) -> Option<String> {
+        let (player1, player2) = self.series_half_points();
+        match player1.cmp(&player2) {
+            std::cmp::Ordering::Greater => self.player1.clone(),
+            std::cmp::Ordering::Less => self.player2.clone(),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject, Default)]
@@ -861,12 +4086,691 @@ pub struct Tournament {
     pub rounds: Vec<TournamentRound>,
     #[serde(default)]
     pub num_rounds: u32,
-}
-
-fn default_is_public() -> bool {
-    true
-}
-
+    /// Tokens each player pays to enter, if any
+    #[graphql(name = "entryFee")]
+    #[serde(default)]
+    pub entry_fee: Option<Amount>,
+    /// Entry fees collected and not yet paid out or refunded
+    #[graphql(name = "prizePool")]
+    #[serde(default)]
+    pub prize_pool: Amount,
+    /// Percent of the prize pool for each finishing place
+    #[graphql(name = "payoutSchedule")]
+    #[serde(default)]
+    pub payout_schedule: Vec<u32>,
+    /// Who paid the entry fee, and the accounts prizes and refunds go to
+    #[serde(default)]
+    pub entries: Vec<Stake>,
+    /// Minutes players get to play each match once it is ready; past that the
+    /// match can be forfeited with EnforceRoundDeadlines
+    #[graphql(name = "roundTimeLimitMinutes")]
+    #[serde(default)]
+    pub round_time_limit_minutes: Option<u32>,
+    /// Players the creator has trusted to run the tournament alongside them
+    #[serde(default)]
+    pub organizers: Vec<String>,
+    /// What the tournament is about, set by the creator
+    #[serde(default)]
+    pub description: String,
+    /// How a Swiss tie for first is played off, if at all
+    #[serde(default)]
+    pub playoff: Option<PlayoffMode>,
+    /// Elimination only: games in a best-of series, for the final or every round
+    #[graphql(name = "bestOf")]
+    #[serde(default)]
+    pub best_of: Option<u32>,
+    /// Whether every round is a best-of series rather than just the final
+    #[graphql(name = "bestOfAllRounds")]
+    #[serde(default)]
+    pub best_of_all_rounds: bool,
+    /// Single elimination only: the semifinal losers play for third place
+    #[graphql(name = "thirdPlaceMatch")]
+    #[serde(default)]
+    pub third_place_match: bool,
+    /// Notes from the organizers, oldest first
+    #[serde(default)]
+    pub announcements: Vec<TournamentAnnouncement>,
+}
+
+/// A note the organizers posted on a tournament, e.g. a schedule change
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct TournamentAnnouncement {
+    pub author: String,
+    pub text: String,
+    pub timestamp: u64,
+}
+
+fn default_is_public() -> bool {
+    true
+}
+
+/// Most co-organizers a tournament can have, creator not included
+pub const MAX_TOURNAMENT_ORGANIZERS: usize = 10;
+/// Longest tournament description
+pub const MAX_TOURNAMENT_DESCRIPTION_LEN: usize = 1000;
+/// Longest tournament announcement
+pub const MAX_ANNOUNCEMENT_LEN: usize = 500;
+/// Announcements kept on a tournament; the oldest are dropped past this
+pub const MAX_TOURNAMENT_ANNOUNCEMENTS: usize = 50;
+
+/// Round-robin pairings from the Berger tables, as indexes into the player list.
+/// Every player meets every other once over `players - 1` rounds (or `players`
+/// rounds for an odd count, where whoever would meet the missing player sits out,
+/// shown as a None opponent). The first of each pair has the white pieces in the
+/// printed tables.
+pub fn berger_pairings(players: usize) -> Vec<Vec<(usize, Option<usize>)>> {
+    if players < 2 {
+        return Vec::new();
+    }
+    let table_size = players + players % 2;
+    let rotating = table_size - 1;
+    let fixed = table_size - 1;
+    let seat = |index: usize| (index < players).then_some(index);
+    (0..rotating)
+        .map(|round| {
+            let first = round * table_size / 2 % rotating;
+            let mut pairs = vec![if round % 2 == 0 { (first, fixed) } else { (fixed, first) }];
+            for i in 1..table_size / 2 {
+                pairs.push(((first + i) % rotating, (first + rotating - i) % rotating));
+            }
+            pairs
+                .into_iter()
+                .filter_map(|(white, black)| match (seat(white), seat(black)) {
+                    (Some(white), black) => Some((white, black)),
+                    (None, Some(black)) => Some((black, None)),
+                    (None, None) => None,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Tiebreaks separating tournament players on the same score, compared in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, SimpleObject, Default)]
+pub struct Tiebreaks {
+    /// Sum of the scores of everyone the player has met
+    pub buchholz: u32,
+    /// Score of every opponent beaten plus half the score of every opponent drawn
+    /// with, doubled so it stays a whole number
+    pub sonneborn_berger: u32,
+}
+
+/// A player's place in a tournament, with how they got there
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject)]
+pub struct TournamentStanding {
+    /// 1 for the leader
+    pub rank: u32,
+    pub player_id: String,
+    pub score: u32,
+    pub tiebreaks: Tiebreaks,
+    /// The player's matches, in the order they were paired
+    pub rounds: Vec<StandingRound>,
+}
+
+/// How a player finished a tournament, kept in their tournament history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject)]
+pub struct TournamentRecord {
+    #[graphql(name = "tournamentId")]
+    pub tournament_id: String,
+    pub name: String,
+    pub format: TournamentFormat,
+    #[graphql(name = "timeControl")]
+    pub time_control: TimeControl,
+    /// 1 for the winner
+    pub placement: u32,
+    /// Number of players who took part
+    pub players: u32,
+    pub score: u32,
+    #[graphql(name = "startedAt")]
+    pub started_at: Option<u64>,
+    #[graphql(name = "finishedAt")]
+    pub finished_at: u64,
+}
+
+/// One match in a player's tournament standing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject)]
+pub struct StandingRound {
+    pub round: u32,
+    pub match_id: String,
+    /// None for a bye
+    pub opponent: Option<String>,
+    /// Points scored in the match, None while it is still to be played
+    pub points: Option<u32>,
+}
+
+/// One player's line in a round-robin crosstable
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject)]
+pub struct CrosstableRow {
+    pub player_id: String,
+    pub score: u32,
+    /// Result against each player, in the same order as the rows
+    pub results: Vec<CrosstableCell>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SimpleObject)]
+pub struct CrosstableCell {
+    pub opponent: String,
+    /// 2 for a win, 1 for a draw, 0 for a loss, or None if not played yet
+    pub points: Option<u32>,
+}
+
+/// Most places a tournament payout schedule can pay
+pub const MAX_PAYOUT_PLACES: usize = 10;
+
+/// Check that a payout schedule pays at least one place and shares out exactly 100%
+pub fn validate_payout_schedule(schedule: &[u32]) -> Result<(), String> {
+    if schedule.is_empty() || schedule.len() > MAX_PAYOUT_PLACES {
+        return Err(format!("Payout schedule must pay between 1 and {} places", MAX_PAYOUT_PLACES));
+    }
+    if schedule.contains(&0) {
+        return Err("Every paid place needs a share above 0%".to_string());
+    }
+    if schedule.iter().sum::<u32>() != 100 {
+        return Err("Payout schedule must add up to 100%".to_string());
+    }
+    Ok(())
+}
+
+/// Bracket positions for each seed, e.g. [0, 3, 1, 2] for four players, so
+/// seed 1 plays seed 4 and can only meet seed 2 in the final
+pub fn seed_order(bracket_size: usize) -> Vec<usize> {
+    let mut order = vec![0];
+    while order.len() < bracket_size {
+        let size = order.len() * 2;
+        order = order.iter().flat_map(|&seed| [seed, size - 1 - seed]).collect();
+    }
+    order
+}
+
+impl Tournament {
+    /// Whether the player may run the tournament: its creator or a co-organizer
+    pub fn is_organizer(&self, player_id: &str) -> bool {
+        self.creator == player_id || self.organizers.iter().any(|o| o == player_id)
+    }
+
+    /// Matches on one side of the bracket, in round order
+    pub fn bracket(&self, side: BracketSide) -> Vec<TournamentMatch> {
+        let mut matches: Vec<TournamentMatch> =
+            self.matches.iter().filter(|m| m.bracket == side).cloned().collect();
+        matches.sort_by_key(|m| (m.round, m.match_number));
+        matches
+    }
+
+    /// Every player's result against every other, best score first
+    pub fn crosstable(&self) -> Vec<CrosstableRow> {
+        let mut players: Vec<&String> = self.participants.iter().map(|p| &p.player_id).collect();
+        if players.is_empty() {
+            players = self.registered_players.iter().collect();
+        }
+        let points = |player: &String, opponent: &String| {
+            self.matches
+                .iter()
+                .filter(|m| m.status == MatchStatus::Finished && !m.playoff)
+                .filter(|m| {
+                    (m.player1.as_ref() == Some(player) && m.player2.as_ref() == Some(opponent))
+                        || (m.player1.as_ref() == Some(opponent) && m.player2.as_ref() == Some(player))
+                })
+                .map(|m| match &m.winner {
+                    _ if m.double_forfeit => 0,
+                    Some(winner) if winner == player => 2,
+                    Some(_) => 0,
+                    None => 1,
+                })
+                .reduce(|total, points| total + points)
+        };
+        let mut rows: Vec<CrosstableRow> = players
+            .iter()
+            .map(|player| {
+                let results: Vec<CrosstableCell> = players
+                    .iter()
+                    .filter(|opponent| *opponent != player)
+                    .map(|opponent| CrosstableCell { opponent: (*opponent).clone(), points: points(player, opponent) })
+                    .collect();
+                let score = results.iter().filter_map(|cell| cell.points).sum();
+                CrosstableRow { player_id: (*player).clone(), score, results }
+            })
+            .collect();
+        rows.sort_by_key(|row| std::cmp::Reverse((row.score, self.tiebreaks(&row.player_id))));
+        let order: Vec<String> = rows.iter().map(|row| row.player_id.clone()).collect();
+        for row in &mut rows {
+            row.results.sort_by_key(|cell| order.iter().position(|player| *player == cell.opponent));
+        }
+        rows
+    }
+
+    /// Single-elimination places decided by the final and the third-place match:
+    /// 1 for the runner-up, 2 and 3 for the third-place winner and loser, and 4
+    /// for everyone else. 0 for every player in other formats.
+    fn podium_place(&self, player_id: &str) -> u32 {
+        if self.format != TournamentFormat::SingleElimination {
+            return 0;
+        }
+        let loser = |m: &TournamentMatch| {
+            [&m.player1, &m.player2].into_iter().flatten().find(|p| m.winner.as_ref() != Some(*p)).cloned()
+        };
+        let decided = |side: BracketSide, round: u32| {
+            self.matches.iter().find(|m| {
+                m.bracket == side && m.round == round && m.status == MatchStatus::Finished && !m.double_forfeit
+            })
+        };
+        let player = Some(player_id.to_string());
+        if decided(BracketSide::Winners, self.num_rounds).is_some_and(|m| loser(m) == player) {
+            return 1;
+        }
+        match decided(BracketSide::ThirdPlace, self.num_rounds) {
+            Some(m) if m.winner == player => 2,
+            Some(m) if loser(m) == player => 3,
+            _ => 4,
+        }
+    }
+
+    fn score_of(&self, player_id: &str) -> u32 {
+        self.participants.iter().find(|p| p.player_id == player_id).map_or(0, |p| p.score)
+    }
+
+    /// Buchholz from the player's opponents list and Sonneborn–Berger from their
+    /// finished games
+    pub fn tiebreaks(&self, player_id: &str) -> Tiebreaks {
+        let buchholz = self.participants
+            .iter()
+            .find(|p| p.player_id == player_id)
+            .map_or(0, |p| p.opponents.iter().map(|opponent| self.score_of(opponent)).sum());
+        let sonneborn_berger = self.matches
+            .iter()
+            .filter(|m| m.status == MatchStatus::Finished && !m.double_forfeit && !m.playoff)
+            .filter_map(|m| match (&m.player1, &m.player2) {
+                (Some(p1), Some(p2)) if p1 == player_id => Some((p2, &m.winner)),
+                (Some(p1), Some(p2)) if p2 == player_id => Some((p1, &m.winner)),
+                _ => None,
+            })
+            .map(|(opponent, winner)| match winner {
+                Some(winner) if winner == player_id => 2 * self.score_of(opponent),
+                Some(_) => 0,
+                None => self.score_of(opponent),
+            })
+            .sum();
+        Tiebreaks { buchholz, sonneborn_berger }
+    }
+
+    /// Games in the match's best-of series; 1 for a single game. The final is the
+    /// last winners-bracket round, or the grand final in double elimination.
+    pub fn series_length(&self, m: &TournamentMatch) -> u32 {
+        let Some(best_of) = self.best_of.filter(|_| self.format.is_elimination()) else { return 1 };
+        let is_final = match self.format {
+            TournamentFormat::DoubleElimination => m.bracket == BracketSide::GrandFinal,
+            _ => m.bracket == BracketSide::Winners && m.round == self.num_rounds,
+        };
+        if self.best_of_all_rounds || is_final { best_of } else { 1 }
+    }
+
+    /// The two players level on score and tiebreaks at the top of the standings,
+    /// who would need a playoff to separate them
+    pub fn tied_for_first(&self) -> Option<(String, String)> {
+        let standings = self.final_standings();
+        let [first, second, ..] = standings.as_slice() else { return None };
+        let key = |player: &str| (self.score_of(player), self.tiebreaks(player));
+        (key(first) == key(second)).then(|| (first.clone(), second.clone()))
+    }
+
+    /// What each participant's tournament history records for this tournament
+    pub fn records(&self, finished_at: u64) -> Vec<(String, TournamentRecord)> {
+        let players = self.participants.len() as u32;
+        self.final_standings()
+            .into_iter()
+            .enumerate()
+            .map(|(i, player_id)| {
+                let record = TournamentRecord {
+                    tournament_id: self.id.clone(),
+                    name: self.name.clone(),
+                    format: self.format,
+                    time_control: self.time_control,
+                    placement: i as u32 + 1,
+                    players,
+                    score: self.score_of(&player_id),
+                    started_at: self.started_at,
+                    finished_at,
+                };
+                (player_id, record)
+            })
+            .collect()
+    }
+
+    /// Every participant in finishing order, with their tiebreaks and match results
+    pub fn standings(&self) -> Vec<TournamentStanding> {
+        // Round-robin byes come round for everyone, so they score nothing
+        let bye_points = if self.format == TournamentFormat::RoundRobin { 0 } else { 2 };
+        self.final_standings()
+            .into_iter()
+            .enumerate()
+            .map(|(i, player_id)| {
+                let rounds = self.matches
+                    .iter()
+                    .filter(|m| !m.playoff)
+                    .filter(|m| [&m.player1, &m.player2].into_iter().any(|p| p.as_deref() == Some(player_id.as_str())))
+                    .map(|m| {
+                        let opponent = [&m.player1, &m.player2]
+                            .into_iter()
+                            .flatten()
+                            .find(|p| **p != player_id)
+                            .cloned();
+                        let decided = matches!(m.status, MatchStatus::Finished | MatchStatus::Bye);
+                        let points = decided.then(|| match (&opponent, &m.winner) {
+                            _ if m.double_forfeit => 0,
+                            (None, _) => bye_points,
+                            (Some(_), Some(winner)) if *winner == player_id => 2,
+                            (Some(_), Some(_)) => 0,
+                            (Some(_), None) => 1,
+                        });
+                        StandingRound { round: m.round, match_id: m.id.clone(), opponent, points }
+                    })
+                    .collect();
+                TournamentStanding {
+                    rank: i as u32 + 1,
+                    score: self.score_of(&player_id),
+                    tiebreaks: self.tiebreaks(&player_id),
+                    player_id,
+                    rounds,
+                }
+            })
+            .collect()
+    }
+
+    /// Players in finishing order: the winner, then by score and tiebreaks, keeping
+    /// registration order for anyone still level
+    pub fn final_standings(&self) -> Vec<String> {
+        let mut participants: Vec<(&SwissParticipant, Tiebreaks)> =
+            self.participants.iter().map(|p| (p, self.tiebreaks(&p.player_id))).collect();
+        participants.sort_by_key(|(participant, tiebreaks)| {
+            (
+                self.winner.as_ref() != Some(&participant.player_id),
+                self.podium_place(&participant.player_id),
+                std::cmp::Reverse(participant.score),
+                std::cmp::Reverse(*tiebreaks),
+            )
+        });
+        participants.into_iter().map(|(participant, _)| participant.player_id.clone()).collect()
+    }
+
+    /// Prizes for a finished tournament, and the rake kept for the treasury. After
+    /// the rake, each paid place gets its share of the pool, and whatever is left
+    /// by rounding or by unfilled places goes to first place. A tournament that
+    /// finished without a winner, or whose paid places hold nobody who paid an
+    /// entry fee, gives the entry fees back instead.
+    pub fn prize_payouts(&self, rake_percent: u32) -> (Vec<(AccountOwner, Amount)>, Amount) {
+        if self.winner.is_none() {
+            return (self.entry_refunds(), Amount::ZERO);
+        }
+        let owner_of = |player_id: &str| {
+            self.entries.iter().find(|entry| entry.player_id == player_id).map(|entry| entry.owner)
+        };
+        let (rake, pool) = split_rake(self.prize_pool, rake_percent);
+        let mut payouts: Vec<(AccountOwner, Amount)> = Vec::new();
+        let mut paid = Amount::ZERO;
+        for (player_id, percent) in self.final_standings().iter().zip(&self.payout_schedule) {
+            let Some(owner) = owner_of(player_id) else { continue };
+            let share = percent_of(pool, *percent);
+            paid = paid.saturating_add(share);
+            payouts.push((owner, share));
+        }
+        let rest = pool.saturating_sub(paid);
+        match payouts.first_mut() {
+            Some((_, first)) => *first = first.saturating_add(rest),
+            None => return (self.entry_refunds(), Amount::ZERO),
+        }
+        payouts.retain(|(_, amount)| !amount.is_zero());
+        (payouts, rake)
+    }
+
+    /// Each paid entry fee back to its owner, as far as the prize pool covers them
+    pub fn entry_refunds(&self) -> Vec<(AccountOwner, Amount)> {
+        let fee = self.entry_fee.unwrap_or(Amount::ZERO);
+        let mut left = self.prize_pool;
+        let mut refunds = Vec::new();
+        for entry in &self.entries {
+            let amount = fee.min(left);
+            if amount.is_zero() {
+                break;
+            }
+            left = left.saturating_sub(amount);
+            refunds.push((entry.owner, amount));
+        }
+        refunds
+    }
+
+    /// Builds the whole single-elimination bracket up front. `seeds` lists the
+    /// players best first; they are spread with the standard seed order, so the top
+    /// seeds meet last and any byes go to them in round 1. Later rounds start as
+    /// Pending and are filled in as results come in.
+    pub fn generate_elimination_bracket(&mut self, seeds: &[String]) {
+        let bracket_size = seeds.len().next_power_of_two().max(2);
+
+        self.participants = self.registered_players
+            .iter()
+            .map(|pid| SwissParticipant {
+                player_id: pid.clone(),
+                ..SwissParticipant::default()
+            })
+            .collect();
+        self.num_rounds = bracket_size.trailing_zeros();
+        self.total_rounds = self.num_rounds;
+
+        let mut matches = Vec::new();
+        for (i, pair) in seed_order(bracket_size).chunks(2).enumerate() {
+            let p1 = seeds.get(pair[0]).cloned();
+            let p2 = seeds.get(pair[1]).cloned();
+            let is_bye = p1.is_none() || p2.is_none();
+            matches.push(TournamentMatch {
+                id: format!("{}_r1_m{}", self.id, i + 1),
+                round: 1,
+                match_number: i as u32 + 1,
+                winner: if is_bye { p1.clone().or(p2.clone()) } else { None },
+                player1: p1,
+                player2: p2,
+                status: if is_bye { MatchStatus::Bye } else { MatchStatus::Ready },
+                bracket: BracketSide::Winners,
+                ..TournamentMatch::default()
+            });
+        }
+        for round in 2..=self.num_rounds {
+            let match_count = bracket_size >> round;
+            for i in 0..match_count {
+                matches.push(TournamentMatch {
+                    id: format!("{}_r{}_m{}", self.id, round, i + 1),
+                    round,
+                    match_number: i as u32 + 1,
+                    ..TournamentMatch::default()
+                });
+            }
+        }
+
+        if self.third_place_match && self.num_rounds > 1 {
+            matches.push(TournamentMatch {
+                id: format!("{}_tp_m1", self.id),
+                round: self.num_rounds,
+                match_number: 1,
+                bracket: BracketSide::ThirdPlace,
+                ..TournamentMatch::default()
+            });
+        }
+
+        self.matches = matches;
+        self.sync_elimination_rounds();
+    }
+
+    /// Rebuild the per-round view of an elimination bracket from its winners
+    /// bracket matches
+    pub fn sync_elimination_rounds(&mut self) {
+        self.rounds = (1..=self.num_rounds)
+            .map(|round_number| {
+                let matches: Vec<TournamentMatch> = self.matches
+                    .iter()
+                    .filter(|m| m.bracket == BracketSide::Winners && m.round == round_number)
+                    .cloned()
+                    .collect();
+                let completed = matches.iter().all(|m| matches!(m.status, MatchStatus::Finished | MatchStatus::Bye));
+                TournamentRound { round_number, matches, completed }
+            })
+            .collect();
+    }
+
+    /// Builds a double-elimination bracket: a winners bracket seeded as in single
+    /// elimination, a losers bracket of 2 * (rounds - 1) rounds, and a grand final.
+    /// Everything past winners round 1 starts as Pending and is filled in by
+    /// advance_double_elimination() from the matches that feed it.
+    pub fn generate_double_elimination_bracket(&mut self, seeds: &[String]) {
+        self.generate_elimination_bracket(seeds);
+        let bracket_size = 1usize << self.num_rounds;
+
+        for round in 1..=2 * (self.num_rounds - 1) {
+            let match_count = bracket_size >> (round.div_ceil(2) + 1);
+            for i in 0..match_count {
+                self.matches.push(TournamentMatch {
+                    id: format!("{}_l{}_m{}", self.id, round, i + 1),
+                    round,
+                    match_number: i as u32 + 1,
+                    bracket: BracketSide::Losers,
+                    ..TournamentMatch::default()
+                });
+            }
+        }
+        self.matches.push(TournamentMatch {
+            id: format!("{}_gf_m1", self.id),
+            round: 1,
+            match_number: 1,
+            bracket: BracketSide::GrandFinal,
+            ..TournamentMatch::default()
+        });
+    }
+
+    /// The matches whose result fills each slot of an elimination match, and
+    /// whether the slot takes that match's winner (true) or loser (false). Winners
+    /// bracket losers drop in reversed order to put off rematches.
+    pub fn bracket_feeders(&self, m: &TournamentMatch) -> Vec<(String, bool)> {
+        let rounds = self.num_rounds;
+        let n = m.match_number;
+        let winners = |round: u32, n: u32| format!("{}_r{}_m{}", self.id, round, n);
+        let losers = |round: u32, n: u32| format!("{}_l{}_m{}", self.id, round, n);
+        match (m.bracket, m.round) {
+            (BracketSide::Winners, 1) => Vec::new(),
+            (BracketSide::Winners, round) => vec![(winners(round - 1, 2 * n - 1), true), (winners(round - 1, 2 * n), true)],
+            (BracketSide::Losers, 1) => vec![(winners(1, 2 * n - 1), false), (winners(1, 2 * n), false)],
+            (BracketSide::Losers, round) if round % 2 == 0 => {
+                let dropping_round = round / 2 + 1;
+                let match_count = (1u32 << rounds) >> (dropping_round);
+                vec![(losers(round - 1, n), true), (winners(dropping_round, match_count + 1 - n), false)]
+            }
+            (BracketSide::Losers, round) => vec![(losers(round - 1, 2 * n - 1), true), (losers(round - 1, 2 * n), true)],
+            (BracketSide::GrandFinal, 1) if rounds > 1 => {
+                vec![(winners(rounds, 1), true), (losers(2 * (rounds - 1), 1), true)]
+            }
+            (BracketSide::GrandFinal, 1) => vec![(winners(1, 1), true), (winners(1, 1), false)],
+            (BracketSide::GrandFinal, _) => Vec::new(),
+            (BracketSide::ThirdPlace, _) => vec![(winners(rounds - 1, 1), false), (winners(rounds - 1, 2), false)],
+        }
+    }
+
+    /// The player a finished match sends on through one of its slots, or None if
+    /// the match is not decided yet. A decided slot can be empty, e.g. the loser of
+    /// a bye, or either side of a double forfeit.
+    fn bracket_slot(&self, match_id: &str, takes_winner: bool) -> Option<Option<String>> {
+        let m = self.matches.iter().find(|m| m.id == match_id)?;
+        match m.status {
+            MatchStatus::Finished if m.double_forfeit => Some(None),
+            MatchStatus::Finished if takes_winner => Some(m.winner.clone()),
+            MatchStatus::Finished => Some(
+                [&m.player1, &m.player2].into_iter().flatten().find(|p| m.winner.as_ref() != Some(*p)).cloned(),
+            ),
+            MatchStatus::Bye => Some(if takes_winner { m.winner.clone() } else { None }),
+            _ => None,
+        }
+    }
+
+    /// Fill Pending bracket matches whose feeders are decided, playing out
+    /// walkovers where one side is empty. Returns whether anything changed.
+    pub fn fill_bracket_matches(&mut self) -> bool {
+        let mut changed = false;
+        loop {
+            let mut filled = false;
+            for idx in 0..self.matches.len() {
+                if self.matches[idx].status != MatchStatus::Pending {
+                    continue;
+                }
+                let feeders = self.bracket_feeders(&self.matches[idx]);
+                if feeders.is_empty() {
+                    continue;
+                }
+                let slots: Vec<Option<Option<String>>> = feeders
+                    .iter()
+                    .map(|(match_id, takes_winner)| self.bracket_slot(match_id, *takes_winner))
+                    .collect();
+                let m = &mut self.matches[idx];
+                m.player1 = slots[0].clone().flatten();
+                m.player2 = slots[1].clone().flatten();
+                if slots.iter().any(|slot| slot.is_none()) {
+                    continue;
+                }
+                if m.player1.is_some() && m.player2.is_some() {
+                    m.status = MatchStatus::Ready;
+                } else {
+                    m.status = MatchStatus::Bye;
+                    m.winner = m.player1.clone().or(m.player2.clone());
+                }
+                filled = true;
+            }
+            if !filled {
+                break;
+            }
+            changed = true;
+        }
+        changed
+    }
+
+    /// Fill the bracket, add the reset when the losers bracket champion wins the
+    /// grand final, and finish the tournament once the grand final is settled
+    pub fn advance_double_elimination(&mut self) -> bool {
+        let changed = self.fill_bracket_matches();
+
+        let final_id = format!("{}_gf_m1", self.id);
+        let reset_id = format!("{}_gf_m2", self.id);
+        let Some(grand_final) = self.matches.iter().find(|m| m.id == final_id).cloned() else {
+            return changed;
+        };
+        let reset = self.matches.iter().find(|m| m.id == reset_id).cloned();
+        if grand_final.double_forfeit || reset.as_ref().is_some_and(|reset| reset.double_forfeit) {
+            self.status = TournamentStatus::Finished;
+            return true;
+        }
+        let champion = match (&reset, grand_final.status) {
+            (Some(reset), _) if reset.status == MatchStatus::Finished => reset.winner.clone(),
+            (Some(_), _) => None,
+            (None, MatchStatus::Bye) => grand_final.winner.clone(),
+            (None, MatchStatus::Finished) if grand_final.winner == grand_final.player1 => grand_final.winner.clone(),
+            (None, MatchStatus::Finished) => {
+                // The losers bracket champion has handed the winners bracket champion
+                // their first loss, so the final is played again
+                self.matches.push(TournamentMatch {
+                    id: reset_id,
+                    round: 2,
+                    match_number: 1,
+                    player1: grand_final.player1,
+                    player2: grand_final.player2,
+                    status: MatchStatus::Ready,
+                    bracket: BracketSide::GrandFinal,
+                    ..TournamentMatch::default()
+                });
+                return true;
+            }
+            (None, _) => None,
+        };
+        if let Some(champion) = champion {
+            self.status = TournamentStatus::Finished;
+            self.winner = Some(champion);
+            return true;
+        }
+        changed
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -926,141 +4830,1076 @@ mod tests {
     #[test]
     fn test_count_pieces_after_capture() {
         let board = STARTING_BOARD;
-        let board = set_piece(&board, 0, 1, Piece::Empty);
+        let board = set_piece(board, 0, 1, Piece::Empty);
         let (red, black) = count_pieces(&board);
         assert_eq!(red, 11);
         assert_eq!(black, 12);
     }
 
     #[test]
-    fn test_count_pieces_with_kings() {
-        let board = " R R R R/        /        /        /        /        /        /B B B B ";
-        let (red, black) = count_pieces(board);
-        assert_eq!(red, 4);
-        assert_eq!(black, 4);
+    fn test_count_pieces_with_kings() {
+        let board = " R R R R/        /        /        /        /        /        /B B B B ";
+        let (red, black) = count_pieces(board);
+        assert_eq!(red, 4);
+        assert_eq!(black, 4);
+    }
+
+    #[test]
+    fn test_is_valid_square_dark_squares() {
+        assert!(is_valid_square(0, 1));
+        assert!(is_valid_square(0, 3));
+        assert!(is_valid_square(1, 0));
+        assert!(is_valid_square(7, 6));
+    }
+
+    #[test]
+    fn test_is_valid_square_light_squares() {
+        assert!(!is_valid_square(0, 0));
+        assert!(!is_valid_square(0, 2));
+        assert!(!is_valid_square(1, 1));
+        assert!(!is_valid_square(7, 7));
+    }
+
+    #[test]
+    fn test_is_valid_square_out_of_bounds() {
+        assert!(!is_valid_square(8, 0));
+        assert!(!is_valid_square(0, 8));
+        assert!(!is_valid_square(8, 8));
+    }
+
+    // ========================================================================
+    // PIECE TESTS
+    // ========================================================================
+
+    #[test]
+    fn test_piece_is_red() {
+        assert!(Piece::Red.is_red());
+        assert!(Piece::RedKing.is_red());
+        assert!(!Piece::Black.is_red());
+        assert!(!Piece::BlackKing.is_red());
+        assert!(!Piece::Empty.is_red());
+    }
+
+    #[test]
+    fn test_piece_is_black() {
+        assert!(Piece::Black.is_black());
+        assert!(Piece::BlackKing.is_black());
+        assert!(!Piece::Red.is_black());
+        assert!(!Piece::RedKing.is_black());
+        assert!(!Piece::Empty.is_black());
+    }
+
+    #[test]
+    fn test_piece_is_king() {
+        assert!(Piece::RedKing.is_king());
+        assert!(Piece::BlackKing.is_king());
+        assert!(!Piece::Red.is_king());
+        assert!(!Piece::Black.is_king());
+        assert!(!Piece::Empty.is_king());
+    }
+
+    #[test]
+    fn test_piece_is_empty() {
+        assert!(Piece::Empty.is_empty());
+        assert!(!Piece::Red.is_empty());
+        assert!(!Piece::Black.is_empty());
+        assert!(!Piece::RedKing.is_empty());
+        assert!(!Piece::BlackKing.is_empty());
+    }
+
+    #[test]
+    fn test_piece_to_king() {
+        assert_eq!(Piece::Red.to_king(), Piece::RedKing);
+        assert_eq!(Piece::Black.to_king(), Piece::BlackKing);
+        assert_eq!(Piece::RedKing.to_king(), Piece::RedKing);
+        assert_eq!(Piece::BlackKing.to_king(), Piece::BlackKing);
+        assert_eq!(Piece::Empty.to_king(), Piece::Empty);
+    }
+
+    // ========================================================================
+    // TURN TESTS
+    // ========================================================================
+
+    #[test]
+    fn test_turn_opposite() {
+        assert_eq!(Turn::Red.opposite(), Turn::Black);
+        assert_eq!(Turn::Black.opposite(), Turn::Red);
+    }
+
+    #[test]
+    fn test_turn_double_opposite() {
+        assert_eq!(Turn::Red.opposite().opposite(), Turn::Red);
+        assert_eq!(Turn::Black.opposite().opposite(), Turn::Black);
+    }
+
+    // ========================================================================
+    // CHECKERS MOVE TESTS
+    // ========================================================================
+
+    #[test]
+    fn test_checkers_move_new() {
+        let m = CheckersMove::new(2, 1, 3, 2);
+        assert_eq!(m.from_row, 2);
+        assert_eq!(m.from_col, 1);
+        assert_eq!(m.to_row, 3);
+        assert_eq!(m.to_col, 2);
+        assert!(m.captured_row.is_none());
+        assert!(!m.promoted);
+    }
+
+    #[test]
+    fn test_checkers_move_with_capture() {
+        let m = CheckersMove::new(2, 1, 4, 3).with_capture(3, 2);
+        assert_eq!(m.captured_row, Some(3));
+        assert_eq!(m.captured_col, Some(2));
+    }
+
+    #[test]
+    fn test_checkers_move_with_promotion() {
+        let m = CheckersMove::new(6, 1, 7, 2).with_promotion();
+        assert!(m.promoted);
+    }
+
+    #[test]
+    fn test_checkers_move_capture_and_promotion() {
+        let m = CheckersMove::new(5, 2, 7, 4).with_capture(6, 3).with_promotion();
+        assert!(m.promoted);
+        assert_eq!(m.captured_row, Some(6));
+    }
+
+    #[test]
+    fn test_move_classification_from_loss() {
+        assert_eq!(MoveClassification::from_loss(-5), MoveClassification::Best);
+        assert_eq!(MoveClassification::from_loss(0), MoveClassification::Best);
+        assert_eq!(MoveClassification::from_loss(INACCURACY_LOSS - 1), MoveClassification::Good);
+        assert_eq!(MoveClassification::from_loss(INACCURACY_LOSS), MoveClassification::Inaccuracy);
+        assert_eq!(MoveClassification::from_loss(MISTAKE_LOSS), MoveClassification::Mistake);
+        assert_eq!(MoveClassification::from_loss(BLUNDER_LOSS), MoveClassification::Blunder);
+        assert_eq!(MoveClassification::Blunder.symbol(), "??");
+        assert_eq!(MoveClassification::Good.symbol(), "");
+    }
+
+    #[test]
+    fn test_position_hash_depends_on_side_to_move() {
+        let red = position_hash(STARTING_BOARD, Turn::Red);
+        assert_eq!(red.len(), 16);
+        assert_eq!(red, position_hash(STARTING_BOARD, Turn::Red));
+        assert_ne!(red, position_hash(STARTING_BOARD, Turn::Black));
+    }
+
+    #[test]
+    fn test_opening_stats_record() {
+        let start = position_hash(STARTING_BOARD, Turn::Red);
+        let next = |hash: &str| OpeningMove {
+            from_row: 2, from_col: 1, to_row: 3, to_col: 2,
+            position_hash: hash.to_string(),
+            games: 0,
+        };
+        let mut stats = OpeningStats::new(start);
+        stats.record(GameResult::RedWins, Some(next("a")));
+        stats.record(GameResult::Draw, Some(next("b")));
+        stats.record(GameResult::BlackWins, Some(next("b")));
+        stats.record(GameResult::Draw, None);
+
+        assert_eq!(stats.games, 4);
+        assert_eq!((stats.red_wins, stats.black_wins, stats.draws), (1, 1, 2));
+        assert_eq!(stats.moves.len(), 2);
+        assert_eq!(stats.moves[0].position_hash, "b");
+        assert_eq!(stats.moves[0].games, 2);
+    }
+
+    #[test]
+    fn test_puzzle_solution_and_rush_session() {
+        let puzzle = Puzzle {
+            id: "puzzle_000001".to_string(),
+            board_state: STARTING_BOARD.to_string(),
+            turn: Turn::Red,
+            source_game_id: "game_000001".to_string(),
+            solution: CheckersMove::new(2, 1, 3, 2),
+        };
+        assert!(puzzle.is_solved_by(2, 1, 3, 2));
+        assert!(!puzzle.is_solved_by(2, 1, 3, 0));
+
+        let mut session = PuzzleRushSession {
+            player_id: "player".to_string(),
+            started_at: 0,
+            ends_at: PUZZLE_RUSH_DURATION_MICROS,
+            streak: 0,
+            current_puzzle_id: Some(puzzle.id.clone()),
+        };
+        assert!(session.is_active(PUZZLE_RUSH_DURATION_MICROS - 1));
+        assert!(!session.is_active(PUZZLE_RUSH_DURATION_MICROS));
+        session.current_puzzle_id = None;
+        assert!(!session.is_active(0));
+    }
+
+    #[test]
+    fn test_study_move_tree() {
+        let mut study = Study::new(
+            "study_000001".to_string(),
+            "Openings".to_string(),
+            "owner".to_string(),
+            STARTING_BOARD.to_string(),
+            Turn::Red,
+            0,
+        );
+        assert!(study.is_member("owner"));
+        assert!(!study.is_member("guest"));
+
+        let first = study.add_node(0, CheckersMove::new(2, 1, 3, 2), "a".to_string(), Turn::Black).unwrap();
+        let again = study.add_node(0, CheckersMove::new(2, 1, 3, 2), "a".to_string(), Turn::Black).unwrap();
+        let other = study.add_node(0, CheckersMove::new(2, 3, 3, 4), "b".to_string(), Turn::Black).unwrap();
+        assert_eq!(first, again);
+        assert_ne!(first, other);
+        assert_eq!(study.node(0).unwrap().children, vec![first, other]);
+        assert_eq!(study.node(other).unwrap().parent, Some(0));
+        assert!(study.add_node(99, CheckersMove::new(5, 0, 4, 1), "c".to_string(), Turn::Red).is_err());
+    }
+
+    #[test]
+    fn test_challenge_and_seek_expiry() {
+        let mut challenge = Challenge {
+            id: "challenge_000001".to_string(),
+            challenger: "a".to_string(),
+            opponent: "b".to_string(),
+            time_control: TimeControl::Blitz5_3,
+            is_rated: true,
+            color: ColorPreference::Random,
+            status: ChallengeStatus::Pending,
+            game_id: None,
+            created_at: 0,
+            expires_at: CHALLENGE_TTL_MICROS,
+        };
+        assert!(!challenge.is_expired(CHALLENGE_TTL_MICROS - 1));
+        assert!(challenge.is_expired(CHALLENGE_TTL_MICROS));
+        challenge.status = ChallengeStatus::Accepted;
+        assert!(!challenge.is_expired(CHALLENGE_TTL_MICROS));
+
+        let mut game = CheckersGame::new_with_options("game_000001".to_string(), "a".to_string(), ColorPreference::Red, true, None);
+        assert!(!game.is_expired_seek(u64::MAX));
+        game.expires_at = Some(SEEK_TTL_MICROS);
+        assert!(game.is_expired_seek(SEEK_TTL_MICROS));
+        game.status = GameStatus::Active;
+        assert!(!game.is_expired_seek(SEEK_TTL_MICROS));
+    }
+
+    #[test]
+    fn test_seek_rating_range() {
+        let mut seek = Seek {
+            id: "seek_000001".to_string(),
+            player: "a".to_string(),
+            time_control: TimeControl::Blitz5_3,
+            is_rated: true,
+            color: ColorPreference::Random,
+            min_rating: None,
+            max_rating: None,
+            created_at: 0,
+            expires_at: SEEK_TTL_MICROS,
+        };
+        assert!(seek.accepts_rating(100));
+        seek.min_rating = Some(1100);
+        seek.max_rating = Some(1300);
+        assert!(seek.accepts_rating(1100));
+        assert!(seek.accepts_rating(1300));
+        assert!(!seek.accepts_rating(1099));
+        assert!(!seek.accepts_rating(1301));
+        assert!(seek.is_expired(SEEK_TTL_MICROS));
+    }
+
+    #[test]
+    fn test_validate_username() {
+        assert!(validate_username("abc").is_ok());
+        assert!(validate_username("Player_One-2").is_ok());
+        assert!(validate_username("ab").is_err());
+        assert!(validate_username(&"a".repeat(MAX_USERNAME_LEN + 1)).is_err());
+        assert!(validate_username("has space").is_err());
+        assert!(validate_username("émile").is_err());
+    }
+
+    #[test]
+    fn test_profile_validation() {
+        let mut profile = PlayerProfile {
+            bio: "I like kings".to_string(),
+            country: Some("FR".to_string()),
+            avatar_hash: Some("ab".repeat(32)),
+            ..Default::default()
+        };
+        assert!(profile.validate().is_ok());
+        profile.country = Some("fr".to_string());
+        assert!(profile.validate().is_err());
+        profile.country = None;
+        profile.avatar_hash = Some("xyz".to_string());
+        assert!(profile.validate().is_err());
+        profile.avatar_hash = None;
+        profile.bio = "a".repeat(MAX_BIO_LEN + 1);
+        assert!(profile.validate().is_err());
+    }
+
+    #[test]
+    fn test_achievements_earned_after_game() {
+        let mut stats = PlayerStats::new("p".to_string());
+        stats.record_win();
+        assert_eq!(
+            Achievement::earned_after_game(&stats, true, 12),
+            vec![Achievement::FirstWin, Achievement::FlawlessWin]
+        );
+        assert_eq!(Achievement::earned_after_game(&stats, true, 5), vec![Achievement::FirstWin]);
+
+        for _ in 0..9 {
+            stats.record_win();
+        }
+        let earned = Achievement::earned_after_game(&stats, true, 3);
+        assert!(earned.contains(&Achievement::TenWins));
+        assert!(earned.contains(&Achievement::WinStreak10));
+
+        stats.record_loss();
+        let earned = Achievement::earned_after_game(&stats, false, 0);
+        assert_eq!(earned, vec![Achievement::TenWins]);
+    }
+
+    #[test]
+    fn test_level_for_xp() {
+        assert_eq!(level_for_xp(0), 1);
+        assert_eq!(level_for_xp(99), 1);
+        assert_eq!(level_for_xp(100), 2);
+        assert_eq!(level_for_xp(299), 2);
+        assert_eq!(level_for_xp(300), 3);
+        assert_eq!(xp_for_level(1), 0);
+        assert!(xp_for_level(level_for_xp(12_345) + 1) > 12_345);
+    }
+
+    #[test]
+    fn test_mission_rotation_and_progress() {
+        let today = Mission::rotation(MissionPeriod::Daily, 7);
+        let tomorrow = Mission::rotation(MissionPeriod::Daily, 8);
+        assert_eq!(today.len(), DAILY_MISSION_SLOTS);
+        assert_eq!(today[1].goal, tomorrow[0].goal);
+        assert_ne!(today[0].goal, tomorrow[0].goal);
+        assert_eq!(MissionPeriod::Weekly.index_at(7 * MICROS_PER_DAY), 1);
+
+        let mut mission = Mission::rotation(MissionPeriod::Weekly, 0).remove(0);
+        assert_eq!(mission.goal, MissionGoal::PlayTournament);
+        assert!(mission.advance(1, 42));
+        assert_eq!(mission.completed_at, Some(42));
+        assert!(!mission.advance(1, 43));
+        assert_eq!(mission.progress, 1);
+    }
+
+    #[test]
+    fn test_title_granted_and_revoked() {
+        let mut stats = PlayerStats::new("p".to_string());
+        stats.blitz_games = 50;
+        stats.blitz_rating = 2050;
+        for _ in 0..TITLE_QUALIFYING_GAMES - 1 {
+            stats.update_title();
+        }
+        assert_eq!(stats.title, None);
+        stats.update_title();
+        assert_eq!(stats.title, Some(Title::Master));
+
+        // A dip below the threshold but within the margin keeps the title
+        stats.blitz_rating = 1850;
+        stats.update_title();
+        assert_eq!(stats.title, Some(Title::Master));
+
+        stats.blitz_rating = 1650;
+        stats.update_title();
+        assert_eq!(stats.title, Some(Title::Expert));
+    }
+
+    #[test]
+    fn test_category_streaks_are_independent() {
+        let mut stats = PlayerStats::new("p".to_string());
+        let config = RatingConfig::default();
+        stats.record_result_with_config(1200, 1.0, &TimeControl::Bullet1_0, &config);
+        stats.record_result_with_config(1200, 1.0, &TimeControl::Bullet2_1, &config);
+        stats.record_result_with_config(1200, 0.0, &TimeControl::Rapid10_0, &config);
+        assert_eq!(stats.win_streak, 0);
+        assert_eq!(stats.get_win_streak(&TimeControl::Bullet1_0), 2);
+        assert_eq!(stats.get_best_streak(&TimeControl::Bullet1_0), 2);
+        assert_eq!(stats.get_win_streak(&TimeControl::Rapid10_0), 0);
+
+        stats.record_result_with_config(1200, 0.5, &TimeControl::Bullet1_0, &config);
+        assert_eq!(stats.get_win_streak(&TimeControl::Bullet1_0), 2);
+        stats.record_result_with_config(1200, 0.0, &TimeControl::Bullet1_0, &config);
+        assert_eq!(stats.get_win_streak(&TimeControl::Bullet1_0), 0);
+        assert_eq!(stats.get_best_streak(&TimeControl::Bullet1_0), 2);
+    }
+
+    #[test]
+    fn test_operation_player_id() {
+        let op = Operation::Resign { game_id: "game_000001".to_string(), player_id: "alice".to_string() };
+        assert_eq!(op.player_id(), Some("alice"));
+        assert_eq!(Operation::RequestAiMove { game_id: "game_000001".to_string() }.player_id(), None);
+        assert_eq!(Operation::CleanupExpired.player_id(), None);
+    }
+
+    #[test]
+    fn test_uniform_timing_detection() {
+        let steady = vec![2_000_000; ANOMALY_MIN_MOVES];
+        assert!(is_uniform_timing(&steady));
+        assert!(!is_uniform_timing(&steady[1..]));
+
+        let human: Vec<u64> = (0..ANOMALY_MIN_MOVES as u64).map(|i| 500_000 + i * i * 300_000).collect();
+        assert!(!is_uniform_timing(&human));
+    }
+
+    #[test]
+    fn test_engine_like_accuracy() {
+        let mut moves = vec![MoveClassification::Best; ANOMALY_MIN_MOVES];
+        assert!(is_engine_like(&moves));
+        moves[0] = MoveClassification::Mistake;
+        assert!(!is_engine_like(&moves));
+        moves[0] = MoveClassification::Good;
+        moves[1] = MoveClassification::Good;
+        assert!(!is_engine_like(&moves));
+    }
+
+    #[test]
+    fn test_operation_error_into_result() {
+        let result = OperationResult::from(OperationError::new(ErrorCode::MustCapture, "Must capture"));
+        assert!(matches!(
+            result,
+            OperationResult::Error { code: ErrorCode::MustCapture, ref message } if message == "Must capture"
+        ));
+        let result = OperationResult::from(OperationError::storage("Failed to save game: oops"));
+        assert!(matches!(result, OperationResult::Error { code: ErrorCode::Storage, .. }));
+    }
+
+    #[test]
+    fn test_game_config_defaults_and_validation() {
+        let config = GameConfig { allowed_time_controls: vec![TimeControl::Blitz3_0], ..GameConfig::default() };
+        assert!(config.validate().is_ok());
+        assert!(config.allows(&TimeControl::Blitz3_0));
+        assert!(!config.allows(&TimeControl::Bullet1_0));
+        assert!(GameConfig::default().allows(&TimeControl::Bullet1_0));
+
+        let bad = GameConfig { hub_chain_id: Some("not a chain".to_string()), ..GameConfig::default() };
+        assert!(bad.validate().is_err());
+        let no_treasury = GameConfig { rake_percent: 5, ..GameConfig::default() };
+        assert!(no_treasury.validate().is_err());
+        assert_eq!(split_rake(Amount::from_tokens(200), 5), (Amount::from_tokens(10), Amount::from_tokens(190)));
+    }
+
+    #[test]
+    fn test_multi_chain_game_chains() {
+        let mut game = CheckersGame::new("game_000001".to_string(), Some("alice".to_string()), PlayerType::Human);
+        game.black_player = Some("bob".to_string());
+        assert!(!game.is_hosted_elsewhere("chain_a"));
+        assert_eq!(game.chain_of("alice"), None);
+
+        game.host_chain = Some("chain_a".to_string());
+        game.red_chain = Some("chain_a".to_string());
+        game.black_chain = Some("chain_b".to_string());
+        assert!(!game.is_hosted_elsewhere("chain_a"));
+        assert!(game.is_hosted_elsewhere("chain_b"));
+        assert_eq!(game.chain_of("bob"), Some("chain_b"));
+        assert_eq!(game.chain_of("carol"), None);
+        assert_eq!(game.id_on_host(), "game_000001");
+    }
+
+    #[test]
+    fn test_replica_game_id() {
+        // The same counter ID from two hosts, and from this chain, stay distinct
+        let from_a = replica_game_id("chain_a", "game_000001");
+        let from_b = replica_game_id("chain_b", "game_000001");
+        assert_ne!(from_a, from_b);
+        assert_ne!(from_a, "game_000001");
+
+        let mut replica = CheckersGame::new(from_a.clone(), Some("alice".to_string()), PlayerType::Human);
+        replica.host_game_id = Some("game_000001".to_string());
+        assert_eq!(replica.id, from_a);
+        assert_eq!(replica.id_on_host(), "game_000001");
+    }
+
+    #[test]
+    fn test_spectated_game_applies_events_once() {
+        let mut game = SpectatedGame::new("chain_a".to_string(), "game_000001".to_string());
+        let checkers_move = CheckersMove::new(5, 0, 4, 1);
+        let event = GameEvent::MoveMade {
+            game_id: "game_000001".to_string(),
+            player: "alice".to_string(),
+            checkers_move,
+            move_count: 1,
+        };
+        game.apply(&event, 10);
+        game.apply(&event, 20);
+        assert_eq!(game.move_count, 1);
+        assert_eq!(game.moves.len(), 1);
+        assert_eq!(game.updated_at, 10);
+
+        game.apply(&GameEvent::GameEnded { game_id: "game_000001".to_string(), result: GameResult::Draw }, 30);
+        assert_eq!(game.result, Some(GameResult::Draw));
+        assert_eq!(event.game_id(), Some("game_000001"));
+    }
+
+    #[test]
+    fn test_wager_payouts() {
+        let alice = AccountOwner::from(linera_sdk::linera_base_types::CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(linera_sdk::linera_base_types::CryptoHash::test_hash("bob"));
+        let mut wager = Wager::new(Amount::from_tokens(5));
+        wager.stakes.push(Stake { player_id: "alice".to_string(), owner: alice });
+        assert_eq!(wager.payouts(None, 10), (vec![(alice, Amount::from_tokens(5))], Amount::ZERO));
+
+        wager.stakes.push(Stake { player_id: "bob".to_string(), owner: bob });
+        assert_eq!(wager.pot(), Amount::from_tokens(10));
+        assert_eq!(wager.payouts(Some("bob"), 0), (vec![(bob, Amount::from_tokens(10))], Amount::ZERO));
+        assert_eq!(
+            wager.payouts(Some("bob"), 10),
+            (vec![(bob, Amount::from_tokens(9))], Amount::from_tokens(1))
+        );
+        assert_eq!(
+            wager.payouts(None, 10).0,
+            vec![(alice, Amount::from_tokens(5)), (bob, Amount::from_tokens(5))]
+        );
+    }
+
+    #[test]
+    fn test_wager_dispute_window() {
+        let mut wager = Wager::new(Amount::from_tokens(5));
+        assert!(!wager.disputable(0));
+        assert!(!wager.payable(0));
+
+        wager.payable_at = Some(100);
+        assert!(wager.disputable(99));
+        assert!(!wager.payable(99));
+        assert!(!wager.disputable(100));
+        assert!(wager.payable(100));
+
+        wager.disputed_by = Some("alice".to_string());
+        assert!(!wager.disputable(50));
+        assert!(!wager.payable(200));
+    }
+
+    #[test]
+    fn test_tournament_prize_payouts() {
+        assert!(validate_payout_schedule(&[50, 30, 20]).is_ok());
+        assert!(validate_payout_schedule(&[50, 30]).is_err());
+        assert!(validate_payout_schedule(&[100, 0]).is_err());
+        assert!(validate_payout_schedule(&[]).is_err());
+
+        let owner = |name: &str| AccountOwner::from(linera_sdk::linera_base_types::CryptoHash::test_hash(name));
+        let mut tournament = Tournament {
+            winner: Some("carol".to_string()),
+            prize_pool: Amount::from_tokens(30),
+            payout_schedule: vec![50, 30, 20],
+            ..Tournament::default()
+        };
+        for (name, score) in [("alice", 2), ("bob", 1), ("carol", 2)] {
+            tournament.participants.push(SwissParticipant { player_id: name.to_string(), score, ..SwissParticipant::default() });
+            tournament.entries.push(Stake { player_id: name.to_string(), owner: owner(name) });
+        }
+        assert_eq!(tournament.final_standings(), vec!["carol", "alice", "bob"]);
+        assert_eq!(
+            tournament.prize_payouts(0).0,
+            vec![
+                (owner("carol"), Amount::from_tokens(15)),
+                (owner("alice"), Amount::from_tokens(9)),
+                (owner("bob"), Amount::from_tokens(6)),
+            ]
+        );
+
+        // Places nobody filled go to first place
+        tournament.participants.truncate(1);
+        assert_eq!(
+            tournament.prize_payouts(10),
+            (vec![(owner("alice"), Amount::from_tokens(27))], Amount::from_tokens(3))
+        );
+    }
+
+    #[test]
+    fn test_tournament_without_winner_refunds_entry_fees() {
+        let owner = |name: &str| AccountOwner::from(linera_sdk::linera_base_types::CryptoHash::test_hash(name));
+        let mut tournament = Tournament {
+            winner: None,
+            entry_fee: Some(Amount::from_tokens(10)),
+            prize_pool: Amount::from_tokens(30),
+            payout_schedule: vec![100],
+            ..Tournament::default()
+        };
+        for name in ["alice", "bob", "carol"] {
+            tournament.participants.push(SwissParticipant { player_id: name.to_string(), ..SwissParticipant::default() });
+            tournament.entries.push(Stake { player_id: name.to_string(), owner: owner(name) });
+        }
+        let refunds = vec![
+            (owner("alice"), Amount::from_tokens(10)),
+            (owner("bob"), Amount::from_tokens(10)),
+            (owner("carol"), Amount::from_tokens(10)),
+        ];
+        // No rake is taken from refunds
+        assert_eq!(tournament.prize_payouts(10), (refunds.clone(), Amount::ZERO));
+
+        // A winner with no paid places falls back to refunds too
+        tournament.winner = Some("alice".to_string());
+        tournament.payout_schedule.clear();
+        assert_eq!(tournament.prize_payouts(10), (refunds, Amount::ZERO));
+    }
+
+    #[test]
+    fn test_tournament_bracket_sides() {
+        let game = |id: &str, round: u32, match_number: u32, bracket: BracketSide| TournamentMatch {
+            id: id.to_string(),
+            round,
+            match_number,
+            bracket,
+            ..TournamentMatch::default()
+        };
+        let tournament = Tournament {
+            format: TournamentFormat::DoubleElimination,
+            matches: vec![
+                game("t_r2_m1", 2, 1, BracketSide::Winners),
+                game("t_l1_m1", 1, 1, BracketSide::Losers),
+                game("t_r1_m2", 1, 2, BracketSide::Winners),
+                game("t_r1_m1", 1, 1, BracketSide::Winners),
+                game("t_gf_m1", 1, 1, BracketSide::GrandFinal),
+            ],
+            ..Tournament::default()
+        };
+        let ids = |side| tournament.bracket(side).into_iter().map(|m| m.id).collect::<Vec<_>>();
+        assert_eq!(ids(BracketSide::Winners), vec!["t_r1_m1", "t_r1_m2", "t_r2_m1"]);
+        assert_eq!(ids(BracketSide::Losers), vec!["t_l1_m1"]);
+        assert_eq!(ids(BracketSide::GrandFinal), vec!["t_gf_m1"]);
+        assert!(tournament.format.is_elimination());
+        assert!(!TournamentFormat::Swiss.is_elimination());
+    }
+
+    #[test]
+    fn test_berger_pairings() {
+        // Matches the published table for four players
+        assert_eq!(
+            berger_pairings(4),
+            vec![
+                vec![(0, Some(3)), (1, Some(2))],
+                vec![(3, Some(2)), (0, Some(1))],
+                vec![(1, Some(3)), (2, Some(0))],
+            ]
+        );
+
+        for players in 2..=9 {
+            let rounds = berger_pairings(players);
+            assert_eq!(rounds.len(), players - 1 + players % 2);
+            let mut met = std::collections::BTreeSet::new();
+            let mut byes = vec![0; players];
+            for round in &rounds {
+                for &(a, b) in round {
+                    match b {
+                        Some(b) => assert!(met.insert((a.min(b), a.max(b)))),
+                        None => byes[a] += 1,
+                    }
+                }
+            }
+            assert_eq!(met.len(), players * (players - 1) / 2);
+            assert!(byes.iter().all(|&count| count == players % 2));
+        }
+    }
+
+    #[test]
+    fn test_tournament_crosstable() {
+        let played = |a: &str, b: &str, winner: Option<&str>| TournamentMatch {
+            player1: Some(a.to_string()),
+            player2: Some(b.to_string()),
+            winner: winner.map(str::to_string),
+            status: MatchStatus::Finished,
+            ..TournamentMatch::default()
+        };
+        let tournament = Tournament {
+            registered_players: vec!["alice".to_string(), "bob".to_string(), "carol".to_string()],
+            matches: vec![
+                played("alice", "bob", Some("bob")),
+                played("bob", "carol", None),
+                TournamentMatch { player1: Some("alice".to_string()), player2: Some("carol".to_string()), ..TournamentMatch::default() },
+            ],
+            ..Tournament::default()
+        };
+        let table = tournament.crosstable();
+        assert_eq!(table.iter().map(|row| (row.player_id.as_str(), row.score)).collect::<Vec<_>>(), vec![("bob", 3), ("carol", 1), ("alice", 0)]);
+        assert_eq!(
+            table[2].results,
+            vec![
+                CrosstableCell { opponent: "bob".to_string(), points: Some(0) },
+                CrosstableCell { opponent: "carol".to_string(), points: None },
+            ]
+        );
     }
 
     #[test]
-    fn test_is_valid_square_dark_squares() {
-        assert!(is_valid_square(0, 1));
-        assert!(is_valid_square(0, 3));
-        assert!(is_valid_square(1, 0));
-        assert!(is_valid_square(7, 6));
+    fn test_club_membership_and_results() {
+        let mut club = Club::new("club_000001".to_string(), "Kings Row".to_string(), "owner".to_string(), ClubJoinPolicy::Open, 0);
+        club.members.extend(["admin".to_string(), "member".to_string()]);
+        club.admins.push("admin".to_string());
+        assert!(club.is_admin("owner") && club.is_admin("admin") && !club.is_admin("member"));
+
+        // The owner leaving hands the club to the longest-serving admin
+        club.remove_member("owner");
+        assert_eq!(club.owner, "admin");
+        assert!(!club.is_member("owner"));
+
+        // With no admins left it goes to the longest-serving member, who becomes an admin
+        club.remove_member("admin");
+        assert_eq!(club.owner, "member");
+        assert_eq!(club.admins, vec!["member"]);
+
+        for i in 0..MAX_CLUB_RECENT_RESULTS + 1 {
+            club.record_result(ClubResult {
+                game_id: format!("game_{}", i),
+                player_id: "member".to_string(),
+                opponent: None,
+                points: 2,
+                finished_at: i as u64,
+            });
+        }
+        assert_eq!(club.recent_results.len(), MAX_CLUB_RECENT_RESULTS);
+        assert_eq!(club.recent_results[0].game_id, format!("game_{}", MAX_CLUB_RECENT_RESULTS));
     }
 
     #[test]
-    fn test_is_valid_square_light_squares() {
-        assert!(!is_valid_square(0, 0));
-        assert!(!is_valid_square(0, 2));
-        assert!(!is_valid_square(1, 1));
-        assert!(!is_valid_square(7, 7));
+    fn test_club_match_scoring() {
+        let board = |board: u32| ClubMatchBoard {
+            board,
+            home_player: format!("home_{}", board),
+            away_player: format!("away_{}", board),
+            game_id: format!("game_{}", board),
+            result: None,
+        };
+        let mut club_match = ClubMatch {
+            id: "club_match_000001".to_string(),
+            home_club: "club_home".to_string(),
+            away_club: "club_away".to_string(),
+            board_count: 3,
+            time_control: TimeControl::Blitz5_3,
+            status: ClubMatchStatus::InProgress,
+            boards: (1..=3).map(board).collect(),
+            home_score: 0,
+            away_score: 0,
+            winner_club: None,
+            created_at: 0,
+            finished_at: None,
+        };
+
+        // Home has red on board 1 and black on board 2
+        club_match.record_board("game_1", GameResult::RedWins, 10);
+        club_match.record_board("game_2", GameResult::RedWins, 20);
+        assert_eq!((club_match.home_score, club_match.away_score), (2, 2));
+        assert_eq!(club_match.status, ClubMatchStatus::InProgress);
+
+        // A board only counts once
+        club_match.record_board("game_2", GameResult::BlackWins, 25);
+        assert_eq!((club_match.home_score, club_match.away_score), (2, 2));
+
+        club_match.record_board("game_3", GameResult::Draw, 30);
+        assert_eq!((club_match.home_score, club_match.away_score), (3, 3));
+        assert_eq!(club_match.status, ClubMatchStatus::Finished);
+        assert_eq!(club_match.winner_club, None);
+        assert_eq!(club_match.finished_at, Some(30));
     }
 
     #[test]
-    fn test_is_valid_square_out_of_bounds() {
-        assert!(!is_valid_square(8, 0));
-        assert!(!is_valid_square(0, 8));
-        assert!(!is_valid_square(8, 8));
-    }
+    fn test_tournament_tiebreaks() {
+        let played = |a: &str, b: &str, winner: Option<&str>| TournamentMatch {
+            player1: Some(a.to_string()),
+            player2: Some(b.to_string()),
+            winner: winner.map(str::to_string),
+            status: MatchStatus::Finished,
+            ..TournamentMatch::default()
+        };
+        let participant = |name: &str, score: u32, opponents: &[&str]| SwissParticipant {
+            player_id: name.to_string(),
+            score,
+            opponents: opponents.iter().map(|o| o.to_string()).collect(),
+            has_bye: false,
+            withdrawn: false,
+        };
+        // alice and bob both finish on 2; alice beat the stronger player
+        let mut tournament = Tournament {
+            participants: vec![
+                participant("bob", 2, &["dave", "carol"]),
+                participant("alice", 2, &["carol", "dave"]),
+                participant("carol", 3, &["alice", "bob"]),
+                participant("dave", 1, &["bob", "alice"]),
+            ],
+            matches: vec![
+                played("alice", "carol", Some("alice")),
+                played("bob", "dave", Some("bob")),
+                played("carol", "bob", Some("carol")),
+                played("dave", "alice", None),
+            ],
+            ..Tournament::default()
+        };
+        assert_eq!(tournament.tiebreaks("alice"), Tiebreaks { buchholz: 4, sonneborn_berger: 7 });
+        assert_eq!(tournament.tiebreaks("bob"), Tiebreaks { buchholz: 4, sonneborn_berger: 2 });
+        assert_eq!(tournament.final_standings(), vec!["carol", "alice", "bob", "dave"]);
+        assert_eq!(tournament.tied_for_first(), None);
+
+        let standings = tournament.standings();
+        assert_eq!(standings[1].rank, 2);
+        assert_eq!(standings[1].player_id, "alice");
+        assert_eq!(standings[1].tiebreaks.sonneborn_berger, 7);
+        assert_eq!(
+            standings[1].rounds.iter().map(|r| (r.opponent.as_deref(), r.points)).collect::<Vec<_>>(),
+            vec![(Some("carol"), Some(2)), (Some("dave"), Some(1))]
+        );
 
-    // ========================================================================
-    // PIECE TESTS
-    // ========================================================================
+        tournament.winner = Some("dave".to_string());
+        assert_eq!(tournament.final_standings()[0], "dave");
 
-    #[test]
-    fn test_piece_is_red() {
-        assert!(Piece::Red.is_red());
-        assert!(Piece::RedKing.is_red());
-        assert!(!Piece::Black.is_red());
-        assert!(!Piece::BlackKing.is_red());
-        assert!(!Piece::Empty.is_red());
+        let records = tournament.records(9_000);
+        assert_eq!(records[0].0, "dave");
+        assert_eq!((records[0].1.placement, records[0].1.finished_at), (1, 9_000));
+        assert_eq!(records.last().unwrap().1.placement, records.len() as u32);
     }
 
     #[test]
-    fn test_piece_is_black() {
-        assert!(Piece::Black.is_black());
-        assert!(Piece::BlackKing.is_black());
-        assert!(!Piece::Red.is_black());
-        assert!(!Piece::RedKing.is_black());
-        assert!(!Piece::Empty.is_black());
+    fn test_tournament_tied_for_first() {
+        let participant = |name: &str, opponent: &str| SwissParticipant {
+            player_id: name.to_string(),
+            score: 1,
+            opponents: vec![opponent.to_string()],
+            ..SwissParticipant::default()
+        };
+        let drawn = TournamentMatch {
+            player1: Some("alice".to_string()),
+            player2: Some("bob".to_string()),
+            status: MatchStatus::Finished,
+            ..TournamentMatch::default()
+        };
+        let mut tournament = Tournament {
+            participants: vec![participant("alice", "bob"), participant("bob", "alice")],
+            matches: vec![drawn],
+            ..Tournament::default()
+        };
+        assert_eq!(tournament.tied_for_first(), Some(("alice".to_string(), "bob".to_string())));
+
+        // The playoff decides the winner without changing the standings behind it
+        tournament.matches.push(TournamentMatch {
+            player1: Some("alice".to_string()),
+            player2: Some("bob".to_string()),
+            winner: Some("bob".to_string()),
+            status: MatchStatus::Finished,
+            playoff: true,
+            ..TournamentMatch::default()
+        });
+        assert_eq!(tournament.tiebreaks("bob"), tournament.tiebreaks("alice"));
+        assert_eq!(tournament.standings()[0].rounds.len(), 1);
     }
 
     #[test]
-    fn test_piece_is_king() {
-        assert!(Piece::RedKing.is_king());
-        assert!(Piece::BlackKing.is_king());
-        assert!(!Piece::Red.is_king());
-        assert!(!Piece::Black.is_king());
-        assert!(!Piece::Empty.is_king());
+    fn test_tournament_series_length() {
+        let mut tournament = Tournament {
+            format: TournamentFormat::SingleElimination,
+            num_rounds: 3,
+            best_of: Some(3),
+            ..Tournament::default()
+        };
+        let semifinal = TournamentMatch { round: 2, ..TournamentMatch::default() };
+        let fin = TournamentMatch { round: 3, ..TournamentMatch::default() };
+        assert_eq!(tournament.series_length(&semifinal), 1);
+        assert_eq!(tournament.series_length(&fin), 3);
+
+        tournament.best_of_all_rounds = true;
+        assert_eq!(tournament.series_length(&semifinal), 3);
+
+        tournament.format = TournamentFormat::DoubleElimination;
+        tournament.best_of_all_rounds = false;
+        let grand_final = TournamentMatch { bracket: BracketSide::GrandFinal, ..TournamentMatch::default() };
+        assert_eq!(tournament.series_length(&fin), 1);
+        assert_eq!(tournament.series_length(&grand_final), 3);
+
+        tournament.format = TournamentFormat::Swiss;
+        assert_eq!(tournament.series_length(&fin), 1);
     }
 
     #[test]
-    fn test_piece_is_empty() {
-        assert!(Piece::Empty.is_empty());
-        assert!(!Piece::Red.is_empty());
-        assert!(!Piece::Black.is_empty());
-        assert!(!Piece::RedKing.is_empty());
-        assert!(!Piece::BlackKing.is_empty());
+    fn test_tournament_match_replay() {
+        let mut m = TournamentMatch {
+            player1: Some("alice".to_string()),
+            player2: Some("bob".to_string()),
+            game_id: Some("game_000001".to_string()),
+            status: MatchStatus::InProgress,
+            deadline: Some(100),
+            ready_players: vec!["alice".to_string(), "bob".to_string()],
+            ..TournamentMatch::default()
+        };
+        m.replay();
+        assert_eq!(m.status, MatchStatus::Ready);
+        assert_eq!(m.game_id, None);
+        assert_eq!(m.deadline, None);
+        assert!(m.ready_players.is_empty());
+        assert_eq!(m.player1.as_deref(), Some("alice"));
     }
 
     #[test]
-    fn test_piece_to_king() {
-        assert_eq!(Piece::Red.to_king(), Piece::RedKing);
-        assert_eq!(Piece::Black.to_king(), Piece::BlackKing);
-        assert_eq!(Piece::RedKing.to_king(), Piece::RedKing);
-        assert_eq!(Piece::BlackKing.to_king(), Piece::BlackKing);
-        assert_eq!(Piece::Empty.to_king(), Piece::Empty);
+    fn test_series_scoring_counts_draws() {
+        let mut m = TournamentMatch {
+            player1: Some("alice".to_string()),
+            player2: Some("bob".to_string()),
+            ..TournamentMatch::default()
+        };
+        m.record_series_game(Some("alice"));
+        assert_eq!(m.series_winner(3), None);
+        assert_eq!(m.series_leader().as_deref(), Some("alice"));
+        m.record_series_game(None);
+        assert_eq!(m.series_winner(3), None);
+        m.record_series_game(None);
+        assert_eq!(m.series_winner(3).as_deref(), Some("alice"));
+
+        // Level after every scheduled game: the next decisive game settles it
+        let mut m = TournamentMatch {
+            player1: Some("alice".to_string()),
+            player2: Some("bob".to_string()),
+            ..TournamentMatch::default()
+        };
+        for _ in 0..3 {
+            m.record_series_game(None);
+        }
+        assert_eq!(m.series_winner(3), None);
+        assert_eq!(m.series_leader(), None);
+        m.record_series_game(Some("bob"));
+        assert_eq!(m.series_winner(3).as_deref(), Some("bob"));
     }
 
-    // ========================================================================
-    // TURN TESTS
-    // ========================================================================
-
-    #[test]
-    fn test_turn_opposite() {
-        assert_eq!(Turn::Red.opposite(), Turn::Black);
-        assert_eq!(Turn::Black.opposite(), Turn::Red);
+    /// Start a double-elimination tournament for `players`, best seed first
+    fn double_elimination(players: &[&str]) -> Tournament {
+        let seeds: Vec<String> = players.iter().map(|p| p.to_string()).collect();
+        let mut tournament = Tournament {
+            id: "t".to_string(),
+            format: TournamentFormat::DoubleElimination,
+            status: TournamentStatus::InProgress,
+            registered_players: seeds.clone(),
+            current_round: 1,
+            ..Tournament::default()
+        };
+        tournament.generate_double_elimination_bracket(&seeds);
+        tournament
     }
 
-    #[test]
-    fn test_turn_double_opposite() {
-        assert_eq!(Turn::Red.opposite().opposite(), Turn::Red);
-        assert_eq!(Turn::Black.opposite().opposite(), Turn::Black);
+    /// Finish a Ready match with `winner` and advance the bracket
+    fn play_bracket_match(tournament: &mut Tournament, match_id: &str, winner: &str) {
+        let m = tournament.matches.iter_mut().find(|m| m.id == match_id).unwrap();
+        assert_eq!(m.status, MatchStatus::Ready, "{} is not ready", match_id);
+        assert!(m.player1.as_deref() == Some(winner) || m.player2.as_deref() == Some(winner));
+        m.status = MatchStatus::Finished;
+        m.winner = Some(winner.to_string());
+        tournament.advance_double_elimination();
     }
 
-    // ========================================================================
-    // CHECKERS MOVE TESTS
-    // ========================================================================
+    fn bracket_players(tournament: &Tournament, match_id: &str) -> (String, String) {
+        let m = tournament.matches.iter().find(|m| m.id == match_id).unwrap();
+        (m.player1.clone().unwrap_or_default(), m.player2.clone().unwrap_or_default())
+    }
 
     #[test]
-    fn test_checkers_move_new() {
-        let m = CheckersMove::new(2, 1, 3, 2);
-        assert_eq!(m.from_row, 2);
-        assert_eq!(m.from_col, 1);
-        assert_eq!(m.to_row, 3);
-        assert_eq!(m.to_col, 2);
-        assert!(m.captured_row.is_none());
-        assert!(!m.promoted);
+    fn test_double_elimination_four_players() {
+        let mut tournament = double_elimination(&["alice", "bob", "carol", "dave"]);
+        assert_eq!(tournament.num_rounds, 2);
+        assert_eq!(tournament.bracket(BracketSide::Winners).len(), 3);
+        assert_eq!(tournament.bracket(BracketSide::Losers).len(), 2);
+        assert_eq!(tournament.bracket(BracketSide::GrandFinal).len(), 1);
+        assert_eq!(bracket_players(&tournament, "t_r1_m1"), ("alice".to_string(), "dave".to_string()));
+        assert_eq!(bracket_players(&tournament, "t_r1_m2"), ("bob".to_string(), "carol".to_string()));
+        let gf = tournament.matches.iter().find(|m| m.id == "t_gf_m1").unwrap().clone();
+        assert_eq!(
+            tournament.bracket_feeders(&gf),
+            vec![("t_r2_m1".to_string(), true), ("t_l2_m1".to_string(), true)]
+        );
+
+        play_bracket_match(&mut tournament, "t_r1_m1", "alice");
+        play_bracket_match(&mut tournament, "t_r1_m2", "carol");
+        assert_eq!(bracket_players(&tournament, "t_l1_m1"), ("dave".to_string(), "bob".to_string()));
+        play_bracket_match(&mut tournament, "t_r2_m1", "alice");
+        play_bracket_match(&mut tournament, "t_l1_m1", "bob");
+        assert_eq!(bracket_players(&tournament, "t_l2_m1"), ("bob".to_string(), "carol".to_string()));
+        play_bracket_match(&mut tournament, "t_l2_m1", "bob");
+        assert_eq!(bracket_players(&tournament, "t_gf_m1"), ("alice".to_string(), "bob".to_string()));
+
+        // bob comes through the losers bracket and beats alice, so the final is reset
+        play_bracket_match(&mut tournament, "t_gf_m1", "bob");
+        assert_eq!(tournament.status, TournamentStatus::InProgress);
+        assert_eq!(bracket_players(&tournament, "t_gf_m2"), ("alice".to_string(), "bob".to_string()));
+        play_bracket_match(&mut tournament, "t_gf_m2", "bob");
+        assert_eq!(tournament.status, TournamentStatus::Finished);
+        assert_eq!(tournament.winner.as_deref(), Some("bob"));
     }
 
     #[test]
-    fn test_checkers_move_with_capture() {
-        let m = CheckersMove::new(2, 1, 4, 3).with_capture(3, 2);
-        assert_eq!(m.captured_row, Some(3));
-        assert_eq!(m.captured_col, Some(2));
+    fn test_double_elimination_eight_players() {
+        let players = ["p1", "p2", "p3", "p4", "p5", "p6", "p7", "p8"];
+        let mut tournament = double_elimination(&players);
+        assert_eq!(tournament.num_rounds, 3);
+        assert_eq!(tournament.bracket(BracketSide::Winners).len(), 7);
+        assert_eq!(tournament.bracket(BracketSide::Losers).len(), 6);
+
+        // The better seed wins every match
+        let mut played = Vec::new();
+        while tournament.status != TournamentStatus::Finished {
+            let m = tournament.matches.iter().find(|m| m.status == MatchStatus::Ready).expect("a match to play");
+            let (id, (p1, p2)) = (m.id.clone(), bracket_players(&tournament, &m.id));
+            let winner = p1.clone().min(p2.clone());
+            play_bracket_match(&mut tournament, &id, &winner);
+            played.push((id, p1, p2));
+        }
+        assert_eq!(played.len(), 14);
+        assert_eq!(tournament.winner.as_deref(), Some("p1"));
+
+        let pairing = |id: &str| {
+            played.iter().find(|(m, _, _)| m == id).map(|(_, p1, p2)| (p1.as_str(), p2.as_str())).unwrap()
+        };
+        assert_eq!(pairing("t_r1_m1"), ("p1", "p8"));
+        assert_eq!(pairing("t_r1_m2"), ("p4", "p5"));
+        assert_eq!(pairing("t_r3_m1"), ("p1", "p2"));
+        assert_eq!(pairing("t_l1_m1"), ("p8", "p5"));
+        assert_eq!(pairing("t_l1_m2"), ("p7", "p6"));
+        // Winners round 2 losers drop in reversed order
+        assert_eq!(pairing("t_l2_m1"), ("p5", "p3"));
+        assert_eq!(pairing("t_l2_m2"), ("p6", "p4"));
+        assert_eq!(pairing("t_l3_m1"), ("p3", "p4"));
+        assert_eq!(pairing("t_l4_m1"), ("p3", "p2"));
+        assert_eq!(pairing("t_gf_m1"), ("p1", "p2"));
+        assert!(!tournament.matches.iter().any(|m| m.id == "t_gf_m2"));
     }
 
     #[test]
-    fn test_checkers_move_with_promotion() {
-        let m = CheckersMove::new(6, 1, 7, 2).with_promotion();
-        assert!(m.promoted);
+    fn test_third_place_match_standings() {
+        let played = |round: u32, bracket: BracketSide, a: &str, b: &str, winner: &str| TournamentMatch {
+            round,
+            bracket,
+            player1: Some(a.to_string()),
+            player2: Some(b.to_string()),
+            winner: Some(winner.to_string()),
+            status: MatchStatus::Finished,
+            ..TournamentMatch::default()
+        };
+        let participant = |name: &str, score: u32| SwissParticipant {
+            player_id: name.to_string(),
+            score,
+            ..SwissParticipant::default()
+        };
+        // dave outscores carol, but carol reached the final
+        let tournament = Tournament {
+            format: TournamentFormat::SingleElimination,
+            num_rounds: 2,
+            winner: Some("alice".to_string()),
+            participants: vec![participant("alice", 4), participant("bob", 0), participant("carol", 2), participant("dave", 4)],
+            matches: vec![
+                played(1, BracketSide::Winners, "alice", "bob", "alice"),
+                played(1, BracketSide::Winners, "carol", "dave", "carol"),
+                played(2, BracketSide::Winners, "alice", "carol", "alice"),
+                played(2, BracketSide::ThirdPlace, "bob", "dave", "dave"),
+            ],
+            ..Tournament::default()
+        };
+        assert_eq!(tournament.final_standings(), vec!["alice", "carol", "dave", "bob"]);
     }
 
     #[test]
-    fn test_checkers_move_capture_and_promotion() {
-        let m = CheckersMove::new(5, 2, 7, 4).with_capture(6, 3).with_promotion();
-        assert!(m.promoted);
-        assert_eq!(m.captured_row, Some(6));
+    fn test_tournament_organizers() {
+        let tournament = Tournament {
+            creator: "alice".to_string(),
+            organizers: vec!["bob".to_string()],
+            ..Tournament::default()
+        };
+        assert!(tournament.is_organizer("alice"));
+        assert!(tournament.is_organizer("bob"));
+        assert!(!tournament.is_organizer("carol"));
     }
 
     // ========================================================================
@@ -1102,6 +5941,24 @@ mod tests {
         assert_eq!(tc.increment_ms(), 0);
     }
 
+    #[test]
+    fn test_time_control_category() {
+        assert_eq!(TimeControl::Bullet1_0.category(), RatingCategory::Bullet);
+        assert_eq!(TimeControl::Bullet2_1.category(), RatingCategory::Bullet);
+        assert_eq!(TimeControl::Blitz3_0.category(), RatingCategory::Blitz);
+        assert_eq!(TimeControl::Blitz5_3.category(), RatingCategory::Blitz);
+        assert_eq!(TimeControl::Rapid10_0.category(), RatingCategory::Rapid);
+    }
+
+    #[test]
+    fn test_time_control_from_clock() {
+        for tc in TimeControl::all() {
+            assert_eq!(TimeControl::from_clock(&Clock::new(tc)), Some(tc));
+        }
+        let custom = Clock { initial_time_ms: 45_000, ..Clock::new(TimeControl::Bullet1_0) };
+        assert_eq!(TimeControl::from_clock(&custom), None);
+    }
+
     #[test]
     fn test_time_control_all() {
         let all = TimeControl::all();
@@ -1110,6 +5967,107 @@ mod tests {
         assert!(all.contains(&TimeControl::Rapid10_0));
     }
 
+    // ========================================================================
+    // GAME FILTER TESTS
+    // ========================================================================
+
+    #[test]
+    fn test_game_filter_matches() {
+        let mut game = CheckersGame::new_timed("game_000001".to_string(), Some("alice".to_string()), PlayerType::Human, TimeControl::Rapid10_0);
+        game.black_player = Some("bob".to_string());
+        game.created_at = 1_000;
+        game.status = GameStatus::Finished;
+        game.result = Some(GameResult::RedWins);
+
+        assert!(GameFilter::default().matches(&game));
+        let filter = GameFilter {
+            status: Some(GameStatus::Finished),
+            time_control: Some(TimeControl::Rapid10_0),
+            player: Some("bob".to_string()),
+            result: Some(GameResult::RedWins),
+            created_after: Some(1_000),
+            created_before: Some(1_001),
+            ..Default::default()
+        };
+        assert!(filter.matches(&game));
+        assert!(!GameFilter { player: Some("carol".to_string()), ..filter.clone() }.matches(&game));
+        assert!(!GameFilter { time_control: Some(TimeControl::Blitz3_0), ..filter.clone() }.matches(&game));
+        assert!(!GameFilter { created_before: Some(1_000), ..filter.clone() }.matches(&game));
+        assert!(!GameFilter { tournament_id: Some("t000001".to_string()), ..filter }.matches(&game));
+    }
+
+    #[test]
+    fn test_tournament_filter_matches() {
+        let tournament = Tournament {
+            status: TournamentStatus::Registration,
+            time_control: TimeControl::Blitz5_3,
+            format: TournamentFormat::RoundRobin,
+            scheduled_start: Some(5_000),
+            ..Tournament::default()
+        };
+
+        assert!(TournamentFilter::default().matches(&tournament));
+        let filter = TournamentFilter {
+            status: Some(TournamentStatus::Registration),
+            time_control: Some(TimeControl::Blitz5_3),
+            format: Some(TournamentFormat::RoundRobin),
+            starting_before: Some(5_001),
+        };
+        assert!(filter.matches(&tournament));
+        assert!(!TournamentFilter { status: Some(TournamentStatus::InProgress), ..filter.clone() }.matches(&tournament));
+        assert!(!TournamentFilter { format: Some(TournamentFormat::Swiss), ..filter.clone() }.matches(&tournament));
+        assert!(!TournamentFilter { starting_before: Some(5_000), ..filter.clone() }.matches(&tournament));
+        assert!(!filter.matches(&Tournament { scheduled_start: None, ..tournament.clone() }));
+    }
+
+    // ========================================================================
+    // SEASON TESTS
+    // ========================================================================
+
+    #[test]
+    fn test_season_for_timestamp() {
+        let season = Season::for_timestamp(SEASON_LENGTH_MICROS * 3 + 42);
+        assert_eq!(season.id, 3);
+        assert_eq!(season.starts_at, SEASON_LENGTH_MICROS * 3);
+        assert_eq!(season.ends_at, SEASON_LENGTH_MICROS * 4);
+        assert_eq!(Season::for_timestamp(season.ends_at - 1).id, 3);
+        assert_eq!(Season::for_timestamp(season.ends_at).id, 4);
+    }
+
+    #[test]
+    fn test_season_trophies_awarded_once_after_end() {
+        let mut season = Season::for_timestamp(42);
+        assert!(!season.can_award_trophies(season.ends_at - 1));
+        assert!(season.can_award_trophies(season.ends_at));
+        season.trophies_awarded = true;
+        assert!(!season.can_award_trophies(season.ends_at));
+    }
+
+    #[test]
+    fn test_season_soft_reset() {
+        let stats = PlayerStats {
+            games_played: 40,
+            games_won: 30,
+            win_streak: 5,
+            bullet_rating: 1600,
+            blitz_rating: 1000,
+            blitz_games: 40,
+            ..PlayerStats::new("player".to_string())
+        };
+        let config = RatingConfig::default();
+        let season = stats.for_new_season(50, &config);
+        assert_eq!(season.chain_id, "player");
+        assert_eq!(season.bullet_rating, 1400);
+        assert_eq!(season.blitz_rating, 1100);
+        assert_eq!(season.rapid_rating, 1200);
+        assert_eq!(season.games_played, 0);
+        assert_eq!(season.blitz_games, 0);
+        assert_eq!(season.win_streak, 0);
+
+        assert_eq!(stats.for_new_season(100, &config).bullet_rating, 1200);
+        assert_eq!(stats.for_new_season(0, &config).bullet_rating, 1600);
+    }
+
     // ========================================================================
     // CLOCK TESTS
     // ========================================================================
@@ -1246,6 +6204,17 @@ mod tests {
         assert_eq!(stats.games_drawn, 1);
     }
 
+    #[test]
+    fn test_player_stats_color_split() {
+        let mut stats = PlayerStats::default();
+        stats.record_color_result(Turn::Red, GameResult::RedWins);
+        stats.record_color_result(Turn::Black, GameResult::RedWins);
+        stats.record_color_result(Turn::Black, GameResult::Draw);
+        stats.record_color_result(Turn::Red, GameResult::InProgress);
+        assert_eq!((stats.red_wins, stats.red_losses, stats.red_draws), (1, 0, 0));
+        assert_eq!((stats.black_wins, stats.black_losses, stats.black_draws), (0, 1, 1));
+    }
+
     #[test]
     fn test_player_stats_best_streak() {
         let mut stats = PlayerStats::default();
@@ -1297,20 +6266,38 @@ mod tests {
 
     #[test]
     fn test_elo_minimum_rating() {
-        let mut stats = PlayerStats::default();
-        stats.bullet_rating = 110;
+        let mut stats = PlayerStats { bullet_rating: 110, ..Default::default() };
         stats.update_rating(1500, 0.0, &TimeControl::Bullet1_0);
         assert!(stats.bullet_rating >= 100);
     }
 
     #[test]
     fn test_elo_maximum_rating() {
-        let mut stats = PlayerStats::default();
-        stats.blitz_rating = 2990;
+        let mut stats = PlayerStats { blitz_rating: 2990, ..Default::default() };
         stats.update_rating(1000, 1.0, &TimeControl::Blitz3_0);
         assert!(stats.blitz_rating <= 3000);
     }
 
+    #[test]
+    fn test_elo_configured_bounds_and_start() {
+        let config = RatingConfig { min_rating: 800, max_rating: 2000, starting_rating: 1000, ..Default::default() };
+        assert!(config.validate().is_ok());
+        assert!(RatingConfig { starting_rating: 700, ..config }.validate().is_err());
+
+        let mut stats = PlayerStats::with_config("player".to_string(), &config);
+        assert_eq!(stats.get_rating(&TimeControl::Rapid10_0), 1000);
+
+        stats.blitz_rating = 805;
+        stats.record_result_with_config(900, 0.0, &TimeControl::Blitz3_0, &config);
+        assert_eq!(stats.blitz_rating, 800);
+        assert_eq!(stats.games_lost, 1);
+
+        stats.bullet_rating = 1999;
+        stats.record_result_with_config(1900, 1.0, &TimeControl::Bullet1_0, &config);
+        assert_eq!(stats.bullet_rating, 2000);
+        assert_eq!(stats.games_won, 1);
+    }
+
     #[test]
     fn test_elo_k_factor_new_player() {
         let mut stats = PlayerStats::default();
@@ -1320,18 +6307,65 @@ mod tests {
 
     #[test]
     fn test_elo_k_factor_experienced_player() {
-        let mut stats = PlayerStats::default();
-        stats.blitz_games = 30;
+        let mut stats = PlayerStats { blitz_games: 30, ..Default::default() };
         stats.update_rating(1200, 1.0, &TimeControl::Blitz5_3);
         assert_eq!(stats.blitz_rating, 1208);
     }
 
     #[test]
-    fn test_get_rating_by_time_control() {
+    fn test_elo_k_factor_schedule() {
+        let config = RatingConfig {
+            k_factor_new: 40,
+            k_factor_games: 30,
+            k_factor_established: 20,
+            high_rating_threshold: Some(2400),
+            k_factor_high_rating: 10,
+            ..Default::default()
+        };
+        assert_eq!(config.k_factor(29, 2500), 40);
+        assert_eq!(config.k_factor(30, 2399), 20);
+        assert_eq!(config.k_factor(30, 2400), 10);
+        assert_eq!(RatingConfig::default().k_factor(100, 2900), 16);
+
+        let mut stats = PlayerStats::default();
+        stats.update_rating_with_config(1200, 1.0, &TimeControl::Blitz5_3, &config);
+        assert_eq!(stats.blitz_rating, 1220);
+        assert!(RatingConfig { k_factor_new: 0, ..config }.validate().is_err());
+    }
+
+    #[test]
+    fn test_rating_provisional_until_threshold() {
+        let mut stats = PlayerStats::default();
+        assert!(stats.is_provisional(&TimeControl::Blitz5_3));
+        assert!(!stats.has_established_rating());
+
+        for _ in 0..PROVISIONAL_GAMES {
+            stats.update_rating(1200, 0.5, &TimeControl::Blitz5_3);
+        }
+        assert!(!stats.is_provisional(&TimeControl::Blitz3_0));
+        assert!(stats.is_provisional(&TimeControl::Bullet1_0));
+        assert!(stats.has_established_rating());
+    }
+
+    #[test]
+    fn test_win_rate_bps() {
         let mut stats = PlayerStats::default();
-        stats.bullet_rating = 1100;
-        stats.blitz_rating = 1200;
-        stats.rapid_rating = 1300;
+        assert_eq!(stats.win_rate_bps(), 0);
+        stats.record_win();
+        stats.record_win();
+        stats.record_loss();
+        stats.record_draw();
+        assert_eq!(stats.win_rate_bps(), 5_000);
+    }
+
+    #[test]
+    fn test_get_rating_by_time_control() {
+        let stats = PlayerStats {
+            bullet_rating: 1100,
+            blitz_rating: 1200,
+            rapid_rating: 1300,
+            ..Default::default()
+        };
 
         assert_eq!(stats.get_rating(&TimeControl::Bullet1_0), 1100);
         assert_eq!(stats.get_rating(&TimeControl::Bullet2_1), 1100);
@@ -1451,10 +6485,169 @@ mod tests {
         assert!(game.clock.is_some());
     }
 
+    #[test]
+    fn test_draw_offer_limits() {
+        let mut game = CheckersGame::new_with_options("g".to_string(), "red".to_string(), ColorPreference::Red, false, None);
+        assert!(game.check_draw_offer_allowed(Turn::Red).is_ok());
+        game.draw_offers.push(DrawOfferRecord { offered_by: Turn::Red, move_count: 0 });
+        assert!(game.check_draw_offer_allowed(Turn::Red).is_err());
+        assert!(game.check_draw_offer_allowed(Turn::Black).is_ok());
+
+        game.move_count = DRAW_OFFER_COOLDOWN_MOVES;
+        assert!(game.check_draw_offer_allowed(Turn::Red).is_ok());
+        for i in 1..MAX_DRAW_OFFERS_PER_GAME as u32 {
+            game.draw_offers.push(DrawOfferRecord { offered_by: Turn::Red, move_count: i });
+        }
+        game.move_count = 1_000;
+        assert!(game.check_draw_offer_allowed(Turn::Red).is_err());
+    }
+
+    #[test]
+    fn test_is_abandoned() {
+        let mut game = CheckersGame::new_with_options("g".to_string(), "red".to_string(), ColorPreference::Red, false, None);
+        game.clock = None;
+        game.updated_at = 1_000;
+        assert!(!game.is_abandoned(1_000 + ABANDONED_GAME_MICROS));
+        game.status = GameStatus::Active;
+        assert!(!game.is_abandoned(1_000 + ABANDONED_GAME_MICROS - 1));
+        assert!(game.is_abandoned(1_000 + ABANDONED_GAME_MICROS));
+        game.clock = Some(Clock::new(TimeControl::Blitz5_3));
+        assert!(!game.is_abandoned(1_000 + ABANDONED_GAME_MICROS));
+    }
+
+    #[test]
+    fn test_is_over() {
+        let mut game = CheckersGame::new_with_options("g".to_string(), "red".to_string(), ColorPreference::Red, false, None);
+        assert!(!game.is_over());
+        game.status = GameStatus::Active;
+        assert!(!game.is_over());
+        game.status = GameStatus::Finished;
+        assert!(game.is_over());
+        game.status = GameStatus::Cancelled;
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn test_club_board_rated_once_played() {
+        let mut game = CheckersGame::new_with_options("g".to_string(), "red".to_string(), ColorPreference::Red, false, None);
+        game.move_count = 2;
+        game.rate_club_board_once_played();
+        assert!(!game.is_rated);
+
+        game.club_match_id = Some("cm_000001".to_string());
+        game.move_count = 1;
+        game.rate_club_board_once_played();
+        assert!(!game.is_rated);
+        game.move_count = 2;
+        game.rate_club_board_once_played();
+        assert!(game.is_rated);
+    }
+
+    #[test]
+    fn test_take_conditional_reply() {
+        let mut game = CheckersGame::new_with_options("g".to_string(), "red".to_string(), ColorPreference::Red, false, None);
+        game.black_player = Some("black".to_string());
+        let conditional = |player: &str, if_move: CheckersMove, reply: CheckersMove| ConditionalMove {
+            player: player.to_string(),
+            if_move,
+            reply,
+        };
+        game.conditional_moves = vec![
+            conditional("black", CheckersMove::new(2, 1, 3, 2), CheckersMove::new(5, 0, 4, 1)),
+            conditional("black", CheckersMove::new(2, 3, 3, 4), CheckersMove::new(5, 2, 4, 3)),
+            conditional("red", CheckersMove::new(5, 0, 4, 1), CheckersMove::new(2, 1, 3, 0)),
+        ];
+        assert_eq!(game.color_of("black"), Some(Turn::Black));
+
+        // Red just played 2,3 -> 3,4 and black is to move
+        game.current_turn = Turn::Black;
+        let reply = game.take_conditional_reply(&CheckersMove::new(2, 3, 3, 4)).unwrap();
+        assert!(reply.same_squares(&CheckersMove::new(5, 2, 4, 3)));
+        // Black's other lines are gone; red's are kept
+        assert_eq!(game.conditional_moves.len(), 1);
+        assert_eq!(game.conditional_moves[0].player, "red");
+
+        game.current_turn = Turn::Red;
+        assert!(game.take_conditional_reply(&CheckersMove::new(5, 6, 4, 7)).is_none());
+        assert!(game.conditional_moves.is_empty());
+    }
+
+    #[test]
+    fn test_checkers_game_ai_time_budget_default() {
+        let game = CheckersGame::new("g".to_string(), Some("p1".to_string()), PlayerType::Human);
+        assert!(game.ai_time_budget_ms.is_none());
+        assert_eq!(game.ai_personality, AiPersonality::Balanced);
+    }
+
+    // ========================================================================
+    // AI PERSONALITY TESTS
+    // ========================================================================
+
+    #[test]
+    fn test_ai_personality_weights_differ() {
+        let aggressive = AiPersonality::Aggressive.weights();
+        let defensive = AiPersonality::Defensive.weights();
+        let positional = AiPersonality::Positional.weights();
+        assert!(aggressive.advancement > defensive.advancement);
+        assert!(aggressive.opponent_material_pct > defensive.opponent_material_pct);
+        assert!(defensive.back_rank > aggressive.back_rank);
+        assert!(positional.center_control > AiPersonality::Balanced.weights().center_control);
+    }
+
     // ========================================================================
     // DRAW OFFER TESTS
     // ========================================================================
 
+    #[test]
+    fn test_game_pdn() {
+        assert_eq!(pdn_square(0, 1), 1);
+        assert_eq!(pdn_square(2, 7), 12);
+        assert_eq!(pdn_square(7, 6), 32);
+
+        let mut game = CheckersGame::new("g1".to_string(), Some("alice".to_string()), PlayerType::Human);
+        game.black_player = Some("bob".to_string());
+        game.black_username = Some("Bob".to_string());
+        game.created_at = 1_700_000_000_000_000;
+        game.result = Some(GameResult::BlackWins);
+        game.moves = vec![
+            CheckersMove::new(2, 1, 3, 2),
+            CheckersMove::new(5, 4, 4, 3),
+            CheckersMove::new(3, 2, 5, 4).with_capture(4, 3),
+            CheckersMove::new(6, 3, 4, 5).with_capture(5, 4),
+            CheckersMove::new(4, 5, 2, 3).with_capture(3, 4),
+        ];
+        let pdn = game_pdn(&game);
+        assert!(pdn.contains("[Date \"2023.11.14\"]"));
+        assert!(pdn.contains("[Black \"alice\"]"));
+        assert!(pdn.contains("[White \"Bob\"]"));
+        assert!(pdn.contains("[Result \"0-1\"]"));
+        assert!(pdn.ends_with("1. 9-14 23-18 2. 14x23 26x19x10 0-1\n"));
+    }
+
+    #[test]
+    fn test_play_style_stats() {
+        let mut moves = vec![
+            CheckersMove::new(2, 1, 3, 2),
+            CheckersMove::new(5, 4, 4, 3),
+            CheckersMove::new(3, 2, 5, 4).with_capture(4, 3),
+            CheckersMove::new(6, 3, 4, 5).with_capture(5, 4),
+            CheckersMove::new(4, 5, 2, 3).with_capture(3, 4),
+        ];
+        for (i, mv) in moves.iter_mut().enumerate() {
+            mv.timestamp = i as u64 * 2_000_000;
+        }
+        assert_eq!(group_turns(&moves).len(), 4);
+
+        let mut black = PlayStyleStats::default();
+        black.record_game(Turn::Black, &moves, false);
+        assert_eq!((black.games, black.moves, black.capturing_moves, black.captures), (1, 2, 1, 2));
+        assert_eq!((black.timed_moves, black.think_time_micros), (2, 4_000_000));
+
+        let mut red = PlayStyleStats::default();
+        red.record_game(Turn::Red, &moves, true);
+        assert_eq!((red.moves, red.captures, red.timed_moves, red.timeout_losses), (2, 1, 1, 1));
+    }
+
     #[test]
     fn test_draw_offer_state_default() {
         let state = DrawOfferState::default();
@@ -1477,6 +6670,69 @@ mod tests {
         assert_eq!(entry.chain_id, "chain1");
         assert_eq!(entry.time_control, TimeControl::Blitz5_3);
         assert_eq!(entry.joined_at, 12345);
+        assert!(!entry.is_expired(12345 + QUEUE_ENTRY_TTL_MICROS - 1));
+        assert!(entry.is_expired(12345 + QUEUE_ENTRY_TTL_MICROS));
+        assert_eq!(entry.pool(), QueuePool { time_control: TimeControl::Blitz5_3, is_rated: true });
+        assert_eq!(QueuePool::all().len(), 2 * TimeControl::all().len());
+    }
+
+    #[test]
+    fn test_queue_rating_band_widens() {
+        let entry = QueueEntry::new("chain1".to_string(), TimeControl::Blitz5_3, 1_000);
+        assert_eq!(entry.rating_band(1_000), QUEUE_INITIAL_RATING_BAND);
+        assert_eq!(entry.rating_band(0), QUEUE_INITIAL_RATING_BAND);
+        let later = 1_000 + 2 * QUEUE_BAND_WIDEN_INTERVAL_MICROS;
+        assert_eq!(entry.rating_band(later), QUEUE_INITIAL_RATING_BAND + 2 * QUEUE_RATING_BAND_STEP);
+        assert_eq!(rating_band_after(u64::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn test_color_commitment_and_toss() {
+        let commitment = color_commitment("secret");
+        assert_eq!(commitment, color_commitment("secret"));
+        assert_ne!(commitment, color_commitment("other"));
+        assert_eq!(color_toss(&["secret", "seed"]), color_toss(&["secret", "seed"]));
+        // Different inputs land on both sides
+        let tosses: Vec<bool> = (0..16).map(|i| color_toss(&["secret", &i.to_string()])).collect();
+        assert!(tosses.contains(&true) && tosses.contains(&false));
+    }
+
+    #[test]
+    fn test_balanced_colors() {
+        let mut a = ColorHistory::default();
+        let mut b = ColorHistory::default();
+        assert_eq!(balanced_red(&a, &b), None);
+
+        a.record(Turn::Red);
+        assert_eq!(balanced_red(&a, &b), Some(false));
+        assert_eq!(balanced_red(&b, &a), Some(true));
+
+        b.record(Turn::Red);
+        assert_eq!(balanced_red(&a, &b), None);
+        b.record(Turn::Black);
+        assert_eq!(balanced_red(&a, &b), Some(false));
+
+        for _ in 0..COLOR_HISTORY_LEN {
+            a.record(Turn::Black);
+        }
+        assert_eq!(a.recent.len(), COLOR_HISTORY_LEN);
+        assert_eq!(a.balance(), -(COLOR_HISTORY_LEN as i32));
+    }
+
+    #[test]
+    fn test_recent_opponents_cooldown() {
+        let mut recent = RecentOpponents::default();
+        recent.record("a", 0);
+        assert!(recent.in_cooldown("a", QUEUE_REMATCH_COOLDOWN_MICROS - 1));
+        assert!(!recent.in_cooldown("a", QUEUE_REMATCH_COOLDOWN_MICROS));
+        assert!(!recent.in_cooldown("b", 0));
+
+        for (i, id) in ["b", "c", "d"].iter().enumerate() {
+            recent.record(id, i as u64 + 1);
+        }
+        assert_eq!(recent.entries.len(), QUEUE_RECENT_OPPONENTS);
+        assert!(!recent.in_cooldown("a", 10));
+        assert!(recent.in_cooldown("b", 10));
     }
 
     // ========================================================================
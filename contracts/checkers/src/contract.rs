@@ -3,22 +3,55 @@
 mod state;
 
 use checkers_abi::{
-    CheckersAbi, CheckersGame, CheckersMove, Clock, ColorPreference, DrawOfferState, GameResult,
-    GameStatus, MatchStatus, Message, Operation, OperationResult, Piece, PlayerType,
-    SwissParticipant, TimeControl, Tournament, TournamentFormat, TournamentMatch, TournamentRound,
-    TournamentStatus, Turn,
-    count_pieces, get_piece, is_valid_square, set_piece, STARTING_BOARD,
+    Achievement, AiPersonality, ConditionalMove, DrawOfferRecord, Club, ClubJoinPolicy, ClubMatch, ClubMatchBoard, ClubMatchStatus, ClubResult, ErrorCode, PlayerBan, SuspicionFlag, SuspicionReason, Report, ReportStatus, LevelUp, MissionGoal, RatingCategory, AiWeights, Challenge, ChallengeStatus, ChatMessage, CheckersAbi, CheckersGame, CheckersMove, Clock, ColorPreference, DrawOfferState,
+    Emote, EmoteRecord, GameEvent, GameResult, GameStatus, InstantiationArgument, MatchStatus, Message, MoveAnnotation, MoveClassification, OpeningMove, Puzzle, PuzzleRushSession, Seek, Study, Operation, OperationError, OperationResult, Piece,
+    BracketSide, PlayerProfile, PlayerSettings, PlayerType, PlayoffMode, Stake, SwissParticipant, TimeControl, Tournament, TournamentAnnouncement, TournamentFormat, TournamentMatch, TournamentRound,
+    TournamentStatus, Turn, Wager,
+    berger_pairings, color_commitment, color_toss, count_pieces, get_piece, is_engine_like, is_uniform_timing, is_valid_square, position_hash, set_piece, replica_game_id, validate_payout_schedule, validate_username, ARMAGEDDON_BLACK_TIME_PCT, MAX_SERIES_GAMES, GAME_EVENT_STREAM, MAX_CHAT_MESSAGE_LEN, MAX_MATCH_REFERENCE_LEN,
+    CHALLENGE_TTL_MICROS, COLOR_REVEAL_TIMEOUT_MICROS, MAX_CONDITIONAL_MOVES, MIN_MOVES_FOR_ADJUDICATION, MAX_CLUB_MATCH_BOARDS, MAX_CLUB_MEMBERS, MAX_CLUB_NAME_LEN, MAX_FRIENDS, MAX_REPORT_REASON_LEN, MAX_SEEKS_PER_PLAYER, MAX_STUDY_COMMENT_LEN, MAX_STUDY_MEMBERS, MAX_ANNOUNCEMENT_LEN, MAX_TOURNAMENT_ANNOUNCEMENTS, MAX_TOURNAMENT_DESCRIPTION_LEN, MAX_TOURNAMENT_ORGANIZERS, OPENING_EXPLORER_PLIES, PUZZLE_RUSH_DURATION_MICROS, SEEK_TTL_MICROS,
+    STARTING_BOARD, WAGER_DISPUTE_WINDOW_MICROS, WAGER_INACTIVITY_REFUND_MICROS, XP_PER_GAME, XP_PER_MISSION, XP_PER_PUZZLE, XP_PER_TOURNAMENT, XP_PER_WIN,
 };
 use linera_sdk::{
-    linera_base_types::{ChainId, WithContractAbi},
+    linera_base_types::{Account, AccountOwner, Amount, ChainId, StreamName, StreamUpdate, WithContractAbi},
     views::{RootView, View},
     Contract, ContractRuntime,
 };
-use state::CheckersState;
+use state::{CheckersState, SCHEMA_VERSION};
+
+/// AI think time used when a game doesn't set `ai_time_budget_ms`
+const DEFAULT_AI_TIME_BUDGET_MS: u64 = 100;
+/// Largest AI think time a game may request
+const MAX_AI_TIME_BUDGET_MS: u64 = 2_000;
+/// Search nodes the AI may visit per millisecond of budget.
+/// Contract execution has no wall clock, so the budget is enforced as a node count
+/// calibrated to stay well inside the Wasm gas limit.
+const AI_NODES_PER_MS: u64 = 20;
+/// Deepest iteration the AI search will attempt
+const AI_MAX_SEARCH_DEPTH: u32 = 12;
+/// Score of a position where the side to move has no legal moves left
+const AI_WIN_SCORE: i32 = 100_000;
+/// Most search nodes spent judging one move of a game under analysis
+const ANALYSIS_NODES_PER_MOVE: u64 = 500;
+/// Search nodes shared by all the moves of a game under analysis, so long games
+/// are judged at a shallower depth rather than costing more
+const ANALYSIS_NODES_PER_GAME: u64 = 40_000;
+/// Deepest iteration used when judging a move under analysis
+const ANALYSIS_MAX_DEPTH: u32 = 8;
+
+/// Bookkeeping shared across one AI move search
+struct AiSearch {
+    weights: AiWeights,
+    /// Side the AI is playing; evaluations are computed from its point of view
+    ai_side: Turn,
+    nodes: u64,
+    node_limit: u64,
+}
 
 pub struct CheckersContract {
     state: CheckersState,
     runtime: ContractRuntime<Self>,
+    /// Events raised by the current operation or message, published once it has succeeded
+    pending_events: Vec<GameEvent>,
 }
 
 linera_sdk::contract!(CheckersContract);
@@ -30,27 +63,82 @@ impl WithContractAbi for CheckersContract {
 impl Contract for CheckersContract {
     type Message = Message;
     type Parameters = ();
-    type InstantiationArgument = ();
-    type EventValue = ();
+    type InstantiationArgument = InstantiationArgument;
+    type EventValue = GameEvent;
 
     async fn load(runtime: ContractRuntime<Self>) -> Self {
         let state = CheckersState::load(runtime.root_view_storage_context())
             .await
             .expect("Failed to load state");
-        CheckersContract { state, runtime }
+        let mut contract = CheckersContract { state, runtime, pending_events: Vec::new() };
+        contract.migrate().await;
+        contract
     }
 
-    async fn instantiate(&mut self, _argument: Self::InstantiationArgument) {
+    async fn instantiate(&mut self, argument: Self::InstantiationArgument) {
+        if let Err(message) = argument.rating.validate() {
+            panic!("Invalid instantiation argument: {}", message);
+        }
+        if let Err(message) = argument.game.validate() {
+            panic!("Invalid instantiation argument: {}", message);
+        }
+        self.state.schema_version.set(SCHEMA_VERSION);
         self.state.next_game_id.set(1);
         self.state.next_tournament_id.set(1);
+        self.state.rating_config.set(argument.rating);
+        self.state.game_config.set(argument.game);
+        for owner in &argument.admins {
+            self.state.add_admin(owner).expect("Failed to add admin");
+        }
     }
 
     async fn execute_operation(&mut self, operation: Self::Operation) -> Self::Response {
-        match operation {
-            Operation::CreateGame { vs_ai, time_control, color_preference, is_rated, player_id } => {
-                self.create_game(vs_ai, time_control, color_preference, is_rated, player_id).await
+        if let Some(player_id) = operation.player_id() {
+            if let Err(e) = self.authenticate_player(player_id).await {
+                return e.into();
+            }
+            if !operation.allowed_when_banned() && self.state.is_banned(player_id).await {
+                return OperationResult::Error { code: ErrorCode::Banned, message: "Player is banned".to_string() };
+            }
+        }
+
+        let result = match operation {
+            Operation::CreateGame {
+                vs_ai,
+                time_control,
+                color_preference,
+                is_rated,
+                ai_time_budget_ms,
+                ai_personality,
+                private,
+                wager,
+                color_commitment,
+                player_id,
+            } => {
+                self.create_game(
+                    vs_ai,
+                    time_control,
+                    color_preference,
+                    is_rated,
+                    ai_time_budget_ms,
+                    ai_personality,
+                    private.unwrap_or(false),
+                    wager,
+                    color_commitment,
+                    player_id,
+                ).await
+            }
+            Operation::JoinGame { game_id, player_id } => self.join_game(game_id, player_id, None).await,
+            Operation::RevealColor { game_id, secret, player_id } => self.reveal_color(game_id, secret, player_id).await,
+            Operation::AddConditionalMove { game_id, if_move, reply, player_id } => {
+                self.add_conditional_move(game_id, if_move, reply, player_id).await
+            }
+            Operation::ClearConditionalMoves { game_id, player_id } => self.clear_conditional_moves(game_id, player_id).await,
+            Operation::AdjudicateAbandonedGame { game_id } => self.adjudicate_abandoned_game(game_id).await,
+            Operation::AwardSeasonTrophies { season_id } => self.award_season_trophies(season_id).await,
+            Operation::UpdateSettings { auto_decline_draws, friends_only_challenges, default_rated, preferred_color, player_id } => {
+                self.update_settings(auto_decline_draws, friends_only_challenges, default_rated, preferred_color, player_id)
             }
-            Operation::JoinGame { game_id, player_id } => self.join_game(game_id, player_id).await,
             Operation::MakeMove {
                 game_id,
                 from_row,
@@ -64,14 +152,46 @@ impl Contract for CheckersContract {
             }
             Operation::Resign { game_id, player_id } => self.resign(game_id, player_id).await,
             Operation::RequestAiMove { game_id } => self.make_ai_move(game_id).await,
-            Operation::JoinQueue { time_control, player_id } => self.join_queue(time_control, player_id).await,
-            Operation::LeaveQueue { player_id } => self.leave_queue(player_id).await,
-            Operation::OfferDraw { game_id } => self.offer_draw(game_id).await,
-            Operation::AcceptDraw { game_id } => self.accept_draw(game_id).await,
-            Operation::DeclineDraw { game_id } => self.decline_draw(game_id).await,
-            Operation::ClaimTimeWin { game_id } => self.claim_time_win(game_id).await,
-            Operation::CreateTournament { name, time_control, max_players, is_public, scheduled_start, player_id } => {
-                self.create_tournament(name, time_control, max_players, is_public, scheduled_start, player_id).await
+            Operation::JoinQueue { time_control, is_rated, player_id } => self.join_queue(time_control, is_rated, player_id).await,
+            Operation::LeaveQueue { time_control, player_id } => self.leave_queue(time_control, player_id).await,
+            Operation::OfferDraw { game_id, player_id } => self.offer_draw(game_id, player_id).await,
+            Operation::AcceptDraw { game_id, player_id } => self.accept_draw(game_id, player_id).await,
+            Operation::DeclineDraw { game_id, player_id } => self.decline_draw(game_id, player_id).await,
+            Operation::ClaimTimeWin { game_id, player_id } => self.claim_time_win(game_id, player_id).await,
+            Operation::CreateTournament {
+                name,
+                time_control,
+                max_players,
+                is_public,
+                scheduled_start,
+                entry_fee,
+                payout_schedule,
+                format,
+                round_time_limit_minutes,
+                description,
+                playoff,
+                best_of,
+                best_of_all_rounds,
+                third_place_match,
+                player_id,
+            } => {
+                self.create_tournament(
+                    name,
+                    time_control,
+                    max_players,
+                    is_public,
+                    scheduled_start,
+                    entry_fee,
+                    payout_schedule,
+                    format,
+                    round_time_limit_minutes,
+                    description,
+                    playoff,
+                    best_of,
+                    best_of_all_rounds.unwrap_or(false),
+                    third_place_match.unwrap_or(false),
+                    player_id,
+                ).await
             }
             Operation::JoinTournament { tournament_id, player_id } => {
                 self.join_tournament(tournament_id, player_id).await
@@ -91,16 +211,156 @@ impl Contract for CheckersContract {
             Operation::ForfeitTournamentMatch { tournament_id, match_id, player_id } => {
                 self.forfeit_tournament_match(tournament_id, match_id, player_id).await
             }
+            Operation::PostTournamentAnnouncement { tournament_id, text, player_id } => {
+                self.post_tournament_announcement(tournament_id, text, player_id).await
+            }
+            Operation::SetTournamentOrganizer { tournament_id, organizer_id, organizer, player_id } => {
+                self.set_tournament_organizer(tournament_id, organizer_id, organizer, player_id).await
+            }
+            Operation::KickFromTournament { tournament_id, kicked_id, player_id } => {
+                self.kick_from_tournament(tournament_id, kicked_id, player_id).await
+            }
+            Operation::WithdrawFromTournament { tournament_id, player_id } => {
+                self.withdraw_from_tournament(tournament_id, player_id).await
+            }
+            Operation::ReadyForMatch { tournament_id, match_id, player_id } => {
+                self.ready_for_match(tournament_id, match_id, player_id).await
+            }
+            Operation::EnforceRoundDeadlines { tournament_id } => {
+                self.enforce_round_deadlines(tournament_id).await
+            }
             Operation::CancelTournament { tournament_id, player_id } => {
                 self.cancel_tournament(tournament_id, player_id).await
             }
+            Operation::WatchGame { game_id, player_id } => self.watch_game(game_id, player_id).await,
+            Operation::UnwatchGame { game_id, player_id } => self.unwatch_game(game_id, player_id).await,
+            Operation::SendGameMessage { game_id, text, player_id } => {
+                self.send_game_message(game_id, text, player_id).await
+            }
+            Operation::SendEmote { game_id, emote, player_id } => self.send_emote(game_id, emote, player_id).await,
+            Operation::AnalyzeGame { game_id } => self.analyze_game(game_id).await,
+            Operation::StartPuzzleRush { player_id } => self.start_puzzle_rush(player_id).await,
+            Operation::SubmitPuzzleMove { from_row, from_col, to_row, to_col, player_id } => {
+                self.submit_puzzle_move(from_row, from_col, to_row, to_col, player_id).await
+            }
+            Operation::CreateStudy { name, from_game_id, player_id } => {
+                self.create_study(name, from_game_id, player_id).await
+            }
+            Operation::AddStudyMember { study_id, member_id, player_id } => {
+                self.add_study_member(study_id, member_id, player_id).await
+            }
+            Operation::RemoveStudyMember { study_id, member_id, player_id } => {
+                self.remove_study_member(study_id, member_id, player_id).await
+            }
+            Operation::AddStudyMove { study_id, node_id, from_row, from_col, to_row, to_col, player_id } => {
+                self.add_study_move(study_id, node_id, (from_row, from_col, to_row, to_col), player_id).await
+            }
+            Operation::CommentStudyNode { study_id, node_id, text, player_id } => {
+                self.comment_study_node(study_id, node_id, text, player_id).await
+            }
+            Operation::SetStudyPosition { study_id, node_id, player_id } => {
+                self.set_study_position(study_id, node_id, player_id).await
+            }
+            Operation::SendFriendRequest { friend_id, player_id } => {
+                self.send_friend_request(friend_id, player_id).await
+            }
+            Operation::AcceptFriendRequest { friend_id, player_id } => {
+                self.accept_friend_request(friend_id, player_id).await
+            }
+            Operation::RemoveFriend { friend_id, player_id } => self.remove_friend(friend_id, player_id).await,
+            Operation::ChallengePlayer { opponent_id, time_control, rated, color, player_id } => {
+                self.challenge_player(opponent_id, time_control, rated, color, player_id).await
+            }
+            Operation::AcceptChallenge { challenge_id, player_id } => {
+                self.accept_challenge(challenge_id, player_id).await
+            }
+            Operation::DeclineChallenge { challenge_id, player_id } => {
+                self.decline_challenge(challenge_id, player_id).await
+            }
+            Operation::CleanupExpired => self.cleanup_expired().await,
+            Operation::PruneIndexes => self.prune_indexes().await,
+            Operation::JoinGameByCode { invite_code, player_id } => {
+                self.join_game_by_code(invite_code, player_id).await
+            }
+            Operation::RegisterUsername { username, player_id } => {
+                self.register_username(username, player_id).await
+            }
+            Operation::UpdateProfile { bio, country, avatar_hash, player_id } => {
+                self.update_profile(bio, country, avatar_hash, player_id)
+            }
+            Operation::AddAdmin { owner } => self.add_admin(owner).await,
+            Operation::RemoveAdmin { owner } => self.remove_admin(owner).await,
+            Operation::ResetRating { target_id, category } => self.reset_rating(target_id, category).await,
+            Operation::ForceEndGame { game_id, result, apply_ratings } => {
+                self.force_end_game(game_id, result, apply_ratings).await
+            }
+            Operation::ReportPlayer { reported_id, game_id, reason, player_id } => {
+                self.report_player(reported_id, game_id, reason, player_id).await
+            }
+            Operation::ResolveReport { report_id, dismiss, note } => {
+                self.resolve_report(report_id, dismiss, note).await
+            }
+            Operation::BanPlayer { target_id, reason } => self.ban_player(target_id, reason).await,
+            Operation::UnbanPlayer { target_id } => self.unban_player(target_id).await,
+            Operation::PostSeek { time_control, rated, color, min_rating, max_rating, player_id } => {
+                self.post_seek(time_control, rated, color, min_rating, max_rating, player_id).await
+            }
+            Operation::AcceptSeek { seek_id, player_id } => self.accept_seek(seek_id, player_id).await,
+            Operation::CancelSeek { seek_id, player_id } => self.cancel_seek(seek_id, player_id).await,
+            Operation::BlockPlayer { blocked_id, player_id } => self.block_player(blocked_id, player_id).await,
+            Operation::UnblockPlayer { blocked_id, player_id } => self.unblock_player(blocked_id, player_id).await,
+            Operation::ApproveMatchApp { app_id, player_id } => self.approve_match_app(app_id, player_id).await,
+            Operation::RevokeMatchApp { app_id, player_id } => self.revoke_match_app(app_id, player_id).await,
+            Operation::WithdrawTreasury { amount } => self.withdraw_treasury(amount).await,
+            Operation::ClaimWagerRefund { game_id, player_id } => self.claim_wager_refund(game_id, player_id).await,
+            Operation::DisputeWager { game_id, reason, player_id } => {
+                self.dispute_wager(game_id, reason, player_id).await
+            }
+            Operation::ResolveWagerDispute { game_id, winner } => self.resolve_wager_dispute(game_id, winner).await,
+            Operation::CreateMatch { red_player, black_player, time_control, rated, reference } => {
+                self.create_match(red_player, black_player, time_control, rated, reference).await
+            }
+            Operation::GetGameResult { game_id } => self.get_game_result(game_id).await,
+            Operation::SubscribeToGames { chain_id } => self.subscribe_to_games(chain_id).await,
+            Operation::UnsubscribeFromGames { chain_id } => self.unsubscribe_from_games(chain_id).await,
+            Operation::JoinRemoteGame { host_chain, game_id, player_id } => {
+                self.join_remote_game(host_chain, game_id, player_id)
+            }
+            Operation::CreateClub { name, join_policy, player_id } => {
+                self.create_club(name, join_policy, player_id).await
+            }
+            Operation::JoinClub { club_id, player_id } => self.join_club(club_id, player_id).await,
+            Operation::LeaveClub { club_id, player_id } => self.leave_club(club_id, player_id).await,
+            Operation::ApproveClubMember { club_id, member_id, player_id } => {
+                self.approve_club_member(club_id, member_id, player_id).await
+            }
+            Operation::KickClubMember { club_id, member_id, player_id } => {
+                self.kick_club_member(club_id, member_id, player_id).await
+            }
+            Operation::SetClubAdmin { club_id, member_id, admin, player_id } => {
+                self.set_club_admin(club_id, member_id, admin, player_id).await
+            }
+            Operation::ChallengeClub { club_id, opponent_club_id, boards, time_control, player_id } => {
+                self.challenge_club(club_id, opponent_club_id, boards, time_control, player_id).await
+            }
+            Operation::AcceptClubMatch { match_id, player_id } => self.accept_club_match(match_id, player_id).await,
+            Operation::DeclineClubMatch { match_id, player_id } => self.decline_club_match(match_id, player_id).await,
+        };
+
+        // A failed operation may have saved part of its work, but clients only hear
+        // about operations that succeeded
+        if matches!(result, OperationResult::Error { .. }) {
+            self.pending_events.clear();
+        } else {
+            self.publish_events();
         }
+        result
     }
 
     async fn execute_message(&mut self, message: Self::Message) {
         match message {
-            Message::JoinRequest { game_id, player_chain } => {
-                self.handle_join_request(&game_id, &player_chain).await;
+            Message::JoinRequest { game_id, player_id } => {
+                self.handle_join_request(game_id, player_id).await;
             }
             Message::MoveMade {
                 game_id,
@@ -121,11 +381,11 @@ impl Contract for CheckersContract {
                 self.handle_game_ended(&game_id, result, winner.as_deref()).await;
             }
             Message::SyncGameState { game } => {
-                let _ = self.state.save_game(game).await;
+                self.handle_sync_game_state(*game).await;
             }
-            Message::MatchFound { game_id, red_player, black_player, time_control } => {
-                // Handle match found notification - create/sync the game locally
-                self.handle_match_found(&game_id, &red_player, &black_player, time_control).await;
+            Message::MatchFound { game_id, red_player, black_player, time_control, clock } => {
+                // Announce a match made on the hub chain
+                self.handle_match_found(&game_id, &red_player, &black_player, time_control, clock);
             }
             Message::DrawOffered { game_id, offered_by } => {
                 // Handle draw offer notification
@@ -139,6 +399,62 @@ impl Contract for CheckersContract {
                 // Handle draw accepted notification
                 self.handle_draw_accepted(&game_id).await;
             }
+            Message::RelayMove { game_id, player_id, from_row, from_col, to_row, to_col, move_number } => {
+                self.handle_relay_move(game_id, player_id, (from_row, from_col), (to_row, to_col), move_number).await;
+            }
+            Message::RelayResign { game_id, player_id } => {
+                self.handle_relay_resign(game_id, player_id).await;
+            }
+            Message::JoinQueueRequest { player_id, time_control, is_rated } => {
+                self.handle_join_queue_request(player_id, time_control, is_rated).await;
+            }
+            Message::LeaveQueueRequest { player_id, time_control } => {
+                self.handle_leave_queue_request(player_id, time_control).await;
+            }
+            Message::TournamentCancelled { tournament_id } => {
+                // Pass the news on to the player's own subscribers
+                self.emit_event(GameEvent::TournamentUpdated { tournament_id, status: TournamentStatus::Cancelled });
+            }
+            Message::RelayOfferDraw { game_id, player_id } => {
+                if self.relay_target(&game_id, &player_id).await.is_some() && !self.state.is_banned(&player_id).await {
+                    let result = self.offer_draw(game_id.clone(), player_id).await;
+                    self.resync_if_rejected(&game_id, &result).await;
+                }
+            }
+            Message::RelayAcceptDraw { game_id, player_id } => {
+                if self.relay_target(&game_id, &player_id).await.is_some() && !self.state.is_banned(&player_id).await {
+                    let result = self.accept_draw(game_id.clone(), player_id).await;
+                    self.resync_if_rejected(&game_id, &result).await;
+                }
+            }
+            Message::RelayDeclineDraw { game_id, player_id } => {
+                if self.relay_target(&game_id, &player_id).await.is_some() {
+                    let result = self.decline_draw(game_id.clone(), player_id).await;
+                    self.resync_if_rejected(&game_id, &result).await;
+                }
+            }
+            Message::RelayClaimTimeWin { game_id, player_id } => {
+                if self.relay_target(&game_id, &player_id).await.is_some() && !self.state.is_banned(&player_id).await {
+                    let result = self.claim_time_win(game_id.clone(), player_id).await;
+                    self.resync_if_rejected(&game_id, &result).await;
+                }
+            }
+        }
+        self.publish_events();
+    }
+
+    async fn process_streams(&mut self, updates: Vec<StreamUpdate>) {
+        let stream_name = StreamName::from(GAME_EVENT_STREAM);
+        let now = self.runtime.system_time().micros();
+        for update in updates {
+            if update.stream_id.stream_name != stream_name {
+                continue;
+            }
+            let chain_id = update.chain_id.to_string();
+            for index in update.new_indices() {
+                let event = self.runtime.read_event(update.chain_id, stream_name.clone(), index);
+                let _ = self.state.record_spectated_event(&chain_id, &event, now).await;
+            }
         }
     }
 
@@ -148,14 +464,83 @@ impl Contract for CheckersContract {
 }
 
 impl CheckersContract {
+    /// Check that the deployment allows games at a time control
+    fn check_time_control(&self, time_control: &TimeControl) -> Result<(), String> {
+        if !self.state.game_config.get().allows(time_control) {
+            return Err(format!("Time control {:?} is not allowed here", time_control));
+        }
+        Ok(())
+    }
+
+    // ========================================================================
+    // SCHEMA MIGRATIONS
+    // ========================================================================
+
+    /// Bring stored state up to SCHEMA_VERSION, one version at a time
+    async fn migrate(&mut self) {
+        let stored = *self.state.schema_version.get();
+        assert!(
+            stored <= SCHEMA_VERSION,
+            "State schema version {} is newer than this application ({})",
+            stored,
+            SCHEMA_VERSION
+        );
+
+        for version in stored..SCHEMA_VERSION {
+            match version {
+                // Deployments from before versioning: fields added since then read
+                // through `#[serde(default)]`, but counters and indexes need rebuilding
+                0 => self.state.backfill_counters_and_indexes().await.expect("Failed to backfill counters and indexes"),
+                // Tournaments gained a status index
+                1 => self.state.index_tournament_statuses().await.expect("Failed to index tournaments"),
+                // Tournament matches are also stored on their own
+                2 => self.state.index_tournament_matches().await.expect("Failed to store tournament matches"),
+                // Cancelled tournaments were saved as Finished
+                3 => self.state.mark_cancelled_tournaments().await.expect("Failed to mark cancelled tournaments"),
+                // The matchmaking queue was split into pools by time control and rated flag
+                4 => self.state.clear_matchmaking_queue(),
+                _ => unreachable!("No migration from schema version {}", version),
+            }
+        }
+        if stored != SCHEMA_VERSION {
+            self.state.schema_version.set(SCHEMA_VERSION);
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     async fn create_game(
         &mut self,
         vs_ai: bool,
         time_control: Option<TimeControl>,
         color_preference: Option<ColorPreference>,
         is_rated: Option<bool>,
+        ai_time_budget_ms: Option<u64>,
+        ai_personality: Option<AiPersonality>,
+        private: bool,
+        wager: Option<Amount>,
+        color_commitment: Option<String>,
         player_id: String,
     ) -> OperationResult {
+        if let Some(amount) = wager {
+            if vs_ai {
+                return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Wagers need two human players".to_string() };
+            }
+            if amount.is_zero() {
+                return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Wager must be positive".to_string() };
+            }
+        }
+        if let Some(tc) = &time_control {
+            if let Err(e) = self.check_time_control(tc) {
+                return OperationResult::Error { code: ErrorCode::NotAllowed, message: e };
+            }
+        }
+        if ai_time_budget_ms.is_some_and(|budget| budget == 0 || budget > MAX_AI_TIME_BUDGET_MS) {
+            return OperationResult::Error {
+                code: ErrorCode::InvalidInput,
+                message: format!("AI time budget must be between 1 and {} ms", MAX_AI_TIME_BUDGET_MS),
+            };
+        }
+
         let game_id = self.state.generate_game_id().await;
         // Use player_id from frontend instead of chain_id
         let creator_id = player_id;
@@ -163,8 +548,9 @@ impl CheckersContract {
         // Convert micros to millis for clock
         let timestamp_ms = timestamp / 1000;
 
-        let color_pref = color_preference.unwrap_or(ColorPreference::Red);
-        let rated = is_rated.unwrap_or(true);
+        let settings = self.state.get_settings(&creator_id).await;
+        let color_pref = color_preference.or(settings.preferred_color).unwrap_or(ColorPreference::Red);
+        let rated = is_rated.or(settings.default_rated).unwrap_or(self.state.game_config.get().default_rated);
 
         // Use the new constructor with full options
         let mut game = CheckersGame::new_with_options(
@@ -176,6 +562,15 @@ impl CheckersContract {
         );
         game.created_at = timestamp;
         game.updated_at = timestamp;
+        if !vs_ai && game.creator_wants_random {
+            if rated && color_commitment.is_none() {
+                return OperationResult::Error {
+                    code: ErrorCode::InvalidInput,
+                    message: "Rated random-color games need a color commitment".to_string(),
+                };
+            }
+            game.color_commitment = color_commitment;
+        }
 
         if vs_ai {
             // Handle AI games based on color preference
@@ -194,7 +589,7 @@ impl CheckersContract {
                 }
                 ColorPreference::Random => {
                     // Use timestamp for randomness
-                    if timestamp % 2 == 0 {
+                    if timestamp.is_multiple_of(2) {
                         game.red_player = Some(creator_id.clone());
                         game.black_player = Some("AI".to_string());
                         game.black_player_type = PlayerType::AI;
@@ -207,61 +602,158 @@ impl CheckersContract {
             }
             game.status = GameStatus::Active;
             game.creator_wants_random = false; // Not needed for AI games
+            game.ai_time_budget_ms = ai_time_budget_ms;
+            game.ai_personality = ai_personality.unwrap_or_default();
 
             // Start the clock when game becomes active
             if let Some(ref mut clock) = game.clock {
                 clock.start(timestamp_ms);
             }
+        } else {
+            game.expires_at = Some(timestamp + SEEK_TTL_MICROS);
+            if private {
+                let invite_code = self.generate_invite_code(&game_id, timestamp);
+                if let Err(e) = self.state.save_game_invite_code(&invite_code, &game_id) {
+                    return OperationResult::Error { code: ErrorCode::Storage, message: e };
+                }
+                game.is_private = true;
+                game.invite_code = Some(invite_code);
+            }
+            if let Some(amount) = wager {
+                let mut stakes = Wager::new(amount);
+                match self.escrow_stake(&creator_id, amount) {
+                    Ok(stake) => stakes.stakes.push(stake),
+                    Err(e) => return e.into(),
+                }
+                game.wager = Some(stakes);
+            }
         }
 
+        let event = GameEvent::GameCreated {
+            game_id: game_id.clone(),
+            creator: creator_id.clone(),
+            time_control: game.clock.as_ref().and_then(TimeControl::from_clock),
+            is_rated: game.is_rated,
+        };
+        let started = game.status == GameStatus::Active;
+        let invite_code = game.invite_code.clone();
         if let Err(e) = self.state.save_game(game).await {
-            return OperationResult::Error { message: e };
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        if invite_code.is_none() {
+            self.emit_event(event);
         }
+        if started {
+            self.close_open_invitations(&creator_id, &game_id).await;
+        }
+
+        match invite_code {
+            Some(invite_code) => OperationResult::PrivateGameCreated { game_id, invite_code },
+            None => OperationResult::GameCreated { game_id },
+        }
+    }
 
-        OperationResult::GameCreated { game_id }
+    async fn join_game_by_code(&mut self, invite_code: String, player_id: String) -> OperationResult {
+        match self.state.get_game_id_by_code(&invite_code).await {
+            Some(game_id) => self.join_game(game_id, player_id, Some(invite_code)).await,
+            None => OperationResult::Error { code: ErrorCode::NotFound, message: "Invalid invite code".to_string() },
+        }
     }
 
-    async fn join_game(&mut self, game_id: String, player_id: String) -> OperationResult {
+    /// Join an open game. Private games need their invite code.
+    async fn join_game(&mut self, game_id: String, player_id: String, invite_code: Option<String>) -> OperationResult {
         // Use player_id from frontend instead of chain_id
         let joiner_id = player_id;
         let timestamp = self.runtime.system_time().micros();
-        let timestamp_ms = timestamp / 1000;
 
         let mut game = match self.state.get_game(&game_id).await {
             Some(g) => g,
-            None => return OperationResult::Error { message: "Game not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
         };
 
-        if game.status != GameStatus::Pending {
-            return OperationResult::Error { message: "Game not available".to_string() };
+        if game.status != GameStatus::Pending || game.awaiting_color_reveal() {
+            return OperationResult::Error { code: ErrorCode::GameNotActive, message: "Game not available".to_string() };
+        }
+        if game.is_expired_seek(timestamp) {
+            let _ = self.withdraw_seek(game).await;
+            return OperationResult::Error { code: ErrorCode::Expired, message: "Game expired".to_string() };
+        }
+        if game.is_private && invite_code.map(|code| code.to_uppercase()) != game.invite_code {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Game is private".to_string() };
         }
 
         // Check if joiner is the creator (can't join own game)
         if game.red_player.as_deref() == Some(joiner_id.as_str())
             || game.black_player.as_deref() == Some(joiner_id.as_str()) {
-            return OperationResult::Error { message: "Cannot join own game".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Cannot join own game".to_string() };
         }
 
+        if let Some(creator) = game.red_player.as_ref().or(game.black_player.as_ref()) {
+            if self.state.is_blocked_between(creator, &joiner_id).await {
+                return OperationResult::Error { code: ErrorCode::Blocked, message: "Cannot join this player's game".to_string() };
+            }
+        }
+
+        if let Some(wager) = &mut game.wager {
+            match self.escrow_stake(&joiner_id, wager.amount) {
+                Ok(stake) => wager.stakes.push(stake),
+                Err(e) => return e.into(),
+            }
+        }
+        self.seat_second_player(&mut game, joiner_id, timestamp);
+
+        if let Err(e) = self.state.save_game(game.clone()).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        for player in [&game.red_player, &game.black_player].into_iter().flatten() {
+            self.close_open_invitations(player, &game_id).await;
+        }
+
+        // Games joined here are played on this chain. Players on other chains join
+        // through JoinRemoteGame, which makes the game a multi-chain game.
+
+        OperationResult::GameJoined { game_id }
+    }
+
+    /// Seat the second player of a pending game according to the creator's color choice,
+    /// and start the game
+    fn seat_second_player(&self, game: &mut CheckersGame, joiner_id: String, timestamp: u64) {
         // Handle color assignment based on game setup
         if game.creator_wants_random {
-            // Random color: use timestamp to decide
-            if timestamp % 2 == 0 {
+            if game.color_commitment.is_some() {
+                // The creator settles the colors by revealing their secret;
+                // until then the joiner waits in the black seat
+                game.black_player = Some(joiner_id);
+                game.color_seed = Some(timestamp.to_string());
+                game.expires_at = None;
+                game.updated_at = timestamp;
+                return;
+            }
+            // Casual random color: toss over the game and both players, which no
+            // timing of the join can change. Rated games always commit and reveal.
+            let creator = game.red_player.clone().unwrap_or_default();
+            if color_toss(&[&joiner_id, &creator, &game.id]) {
                 // Swap: creator becomes black, joiner becomes red
                 let creator = game.red_player.take();
                 game.black_player = creator;
-                game.red_player = Some(joiner_id.clone());
+                game.red_player = Some(joiner_id);
             } else {
                 // Keep: creator is red, joiner is black
-                game.black_player = Some(joiner_id.clone());
+                game.black_player = Some(joiner_id);
             }
         } else if game.red_player.is_none() {
             // Creator chose black, joiner gets red
-            game.red_player = Some(joiner_id.clone());
+            game.red_player = Some(joiner_id);
         } else {
             // Creator chose red (default), joiner gets black
-            game.black_player = Some(joiner_id.clone());
+            game.black_player = Some(joiner_id);
         }
 
+        Self::start_seated_game(game, timestamp);
+    }
+
+    /// Start a game once both seats are filled
+    fn start_seated_game(game: &mut CheckersGame, timestamp: u64) {
         game.black_player_type = PlayerType::Human;
         game.red_player_type = PlayerType::Human;
         game.status = GameStatus::Active;
@@ -269,17 +761,57 @@ impl CheckersContract {
 
         // Start the clock when game becomes active
         if let Some(ref mut clock) = game.clock {
-            clock.start(timestamp_ms);
+            clock.start(timestamp / 1000);
         }
+    }
 
-        if let Err(e) = self.state.save_game(game.clone()).await {
-            return OperationResult::Error { message: e };
+    /// Settle the colors of a random-color game that waits for its creator's reveal
+    async fn reveal_color(&mut self, game_id: String, secret: Option<String>, player_id: String) -> OperationResult {
+        let timestamp = self.runtime.system_time().micros();
+        let Some(mut game) = self.state.get_game(&game_id).await else {
+            return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() };
+        };
+        if !game.awaiting_color_reveal() {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Game is not waiting for a color reveal".to_string() };
         }
+        let (Some(creator), Some(joiner)) = (game.red_player.clone(), game.black_player.clone()) else {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Game is missing a player".to_string() };
+        };
+
+        let creator_red = match secret {
+            Some(secret) => {
+                if player_id != creator {
+                    return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only the creator reveals the color secret".to_string() };
+                }
+                if game.color_commitment.as_deref() != Some(color_commitment(&secret).as_str()) {
+                    return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Secret does not match the commitment".to_string() };
+                }
+                color_toss(&[&secret, game.color_seed.as_deref().unwrap_or_default()])
+            }
+            None => {
+                if player_id != joiner {
+                    return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only the joiner can claim an unrevealed toss".to_string() };
+                }
+                if timestamp < game.updated_at + COLOR_REVEAL_TIMEOUT_MICROS {
+                    return OperationResult::Error { code: ErrorCode::NotAllowed, message: "The creator can still reveal".to_string() };
+                }
+                // A creator who never reveals loses the toss
+                false
+            }
+        };
 
-        // Note: With Hub Chain pattern, all players are on the same chain
-        // Cross-chain messaging is not needed - both players poll the same chain
+        if !creator_red {
+            game.red_player = Some(joiner);
+            game.black_player = Some(creator);
+        }
+        game.color_seed = None;
+        Self::start_seated_game(&mut game, timestamp);
+        let red_player = game.red_player.clone().unwrap_or_default();
 
-        OperationResult::GameJoined { game_id }
+        if let Err(e) = self.state.save_game(game).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        OperationResult::ColorsRevealed { game_id, red_player }
     }
 
     async fn make_move(
@@ -298,15 +830,20 @@ impl CheckersContract {
 
         let mut game = match self.state.get_game(&game_id).await {
             Some(g) => g,
-            None => return OperationResult::Error { message: "Game not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
         };
 
         if game.status != GameStatus::Active {
-            return OperationResult::Error { message: "Game not active".to_string() };
+            return OperationResult::Error { code: ErrorCode::GameNotActive, message: "Game not active".to_string() };
         }
 
         if !game.can_player_move(&player) {
-            return OperationResult::Error { message: "Not your turn".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotYourTurn, message: "Not your turn".to_string() };
+        }
+
+        let this_chain = self.runtime.chain_id().to_string();
+        if game.is_hosted_elsewhere(&this_chain) {
+            return self.relay_move(&game, player, (from_row, from_col), (to_row, to_col));
         }
 
         // Check if clock exists and if player has timed out
@@ -321,58 +858,183 @@ impl CheckersContract {
                 game.updated_at = timestamp;
 
                 if let Err(e) = self.state.save_game(game.clone()).await {
-                    return OperationResult::Error { message: e };
+                    return OperationResult::Error { code: ErrorCode::Storage, message: e };
                 }
 
                 if let Some(result) = game.result {
-                    let _ = self.state.record_game_result(&game, result).await;
+                    self.finish_game(&game, result).await;
                 }
+                // The move is rejected, but the game did end
+                self.publish_events();
 
                 return OperationResult::Error {
+                    code: ErrorCode::TimeExpired,
                     message: "Time expired".to_string()
                 };
             }
         }
 
-        match self.validate_and_execute_move(&mut game, from_row, from_col, to_row, to_col) {
-            Ok(checkers_move) => {
-                game.moves.push(checkers_move.clone());
-                game.move_count += 1;
-                game.updated_at = timestamp;
+        let mover = game.current_turn;
+        let (checkers_move, mut game_over) =
+            match self.play_move(&mut game, player, (from_row, from_col), (to_row, to_col), timestamp).await {
+                Ok(played) => played,
+                Err(e) => return e.into(),
+            };
 
-                // Update clock after successful move
-                if let Some(ref mut clock) = game.clock {
-                    if !clock.make_move(timestamp_ms) {
-                        // Time ran out during this move
-                        game.status = GameStatus::Finished;
-                        game.result = Some(match game.current_turn.opposite() {
-                            Turn::Red => GameResult::BlackWins,
-                            Turn::Black => GameResult::RedWins,
-                        });
-                    }
-                }
+        // Correspondence games: answer with the reply the opponent registered for this move
+        if !game_over && game.current_turn != mover {
+            if let Some(reply) = game.take_conditional_reply(&checkers_move) {
+                game_over = self.play_conditional_reply(&mut game, reply, timestamp).await;
+            }
+        }
 
-                // Clear any pending draw offer after a move
-                game.draw_offer = DrawOfferState::None;
+        if let Err(e) = self.state.save_game(game.clone()).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
 
-                let game_over = self.check_game_over(&mut game);
+        if game_over {
+            if let Some(result) = game.result {
+                self.finish_game(&game, result).await;
+            }
+        } else {
+            self.sync_replicas(&game).await;
+        }
 
-                if let Err(e) = self.state.save_game(game.clone()).await {
-                    return OperationResult::Error { message: e };
-                }
+        OperationResult::MoveMade { game_id, game_over }
+    }
 
-                if game_over {
-                    if let Some(result) = game.result {
-                        let _ = self.state.record_game_result(&game, result).await;
-                    }
-                }
+    /// Play a move for `player` and do the bookkeeping every move needs: the move
+    /// history, the clock, the pending draw offer and the game-over check.
+    /// Returns the move played and whether the game ended.
+    async fn play_move(
+        &mut self,
+        game: &mut CheckersGame,
+        player: String,
+        (from_row, from_col): (u8, u8),
+        (to_row, to_col): (u8, u8),
+        timestamp: u64,
+    ) -> Result<(CheckersMove, bool), OperationError> {
+        let checkers_move = self.validate_and_execute_move(game, from_row, from_col, to_row, to_col)?;
+        self.state.append_move(&game.id, checkers_move.clone()).await.map_err(OperationError::storage)?;
+        game.move_count += 1;
+        game.rate_club_board_once_played();
+        self.emit_event(GameEvent::MoveMade {
+            game_id: game.id.clone(),
+            player,
+            checkers_move: checkers_move.clone(),
+            move_count: game.move_count,
+        });
+        game.updated_at = timestamp;
+
+        // Update clock after successful move
+        if let Some(ref mut clock) = game.clock {
+            if !clock.make_move(timestamp / 1000) {
+                // Time ran out during this move
+                game.status = GameStatus::Finished;
+                game.result = Some(match game.current_turn.opposite() {
+                    Turn::Red => GameResult::BlackWins,
+                    Turn::Black => GameResult::RedWins,
+                });
+            }
+        }
+
+        // Clear any pending draw offer after a move
+        game.draw_offer = DrawOfferState::None;
 
-                self.notify_opponent(&game, checkers_move).await;
+        let game_over = self.check_game_over(game);
+        Ok((checkers_move, game_over))
+    }
 
-                OperationResult::MoveMade { game_id, game_over }
+    /// Play a conditional reply for the player to move. A reply that is not legal
+    /// in the position is skipped. Returns whether the game ended
+    async fn play_conditional_reply(&mut self, game: &mut CheckersGame, reply: CheckersMove, timestamp: u64) -> bool {
+        let player = match game.current_turn {
+            Turn::Red => game.red_player.clone(),
+            Turn::Black => game.black_player.clone(),
+        };
+        let Some(player) = player else { return false };
+        let mut next = game.clone();
+        match self.play_move(&mut next, player, (reply.from_row, reply.from_col), (reply.to_row, reply.to_col), timestamp).await {
+            Ok((_, game_over)) => {
+                *game = next;
+                game_over
             }
-            Err(e) => OperationResult::Error { message: e },
+            Err(_) => false,
+        }
+    }
+
+    /// Register a reply to play automatically if the opponent makes a given move
+    async fn add_conditional_move(
+        &mut self,
+        game_id: String,
+        if_move: CheckersMove,
+        reply: CheckersMove,
+        player_id: String,
+    ) -> OperationResult {
+        let Some(mut game) = self.state.get_game(&game_id).await else {
+            return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() };
+        };
+        if game.status != GameStatus::Active {
+            return OperationResult::Error { code: ErrorCode::GameNotActive, message: "Game not active".to_string() };
+        }
+        if game.clock.is_some() {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Conditional moves are for correspondence games".to_string() };
+        }
+        if game.is_hosted_elsewhere(&self.runtime.chain_id().to_string()) {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Conditional moves are kept on the game's host chain".to_string() };
+        }
+        let Some(color) = game.color_of(&player_id) else {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Not a player in this game".to_string() };
+        };
+        if game.current_turn == color {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "It is your turn".to_string() };
+        }
+
+        // Both moves must be legal in the positions they would be played in
+        let mut preview = game.clone();
+        if let Err(e) = self.validate_and_execute_move(&mut preview, if_move.from_row, if_move.from_col, if_move.to_row, if_move.to_col) {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: format!("Condition is not a legal move: {}", e.message) };
+        }
+        if preview.current_turn != color {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Condition must end the opponent's turn".to_string() };
+        }
+        if let Err(e) = self.validate_and_execute_move(&mut preview, reply.from_row, reply.from_col, reply.to_row, reply.to_col) {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: format!("Reply is not a legal move: {}", e.message) };
+        }
+
+        // A new reply to the same condition replaces the old one
+        game.conditional_moves.retain(|c| c.player != player_id || !c.if_move.same_squares(&if_move));
+        let registered = game.conditional_moves.iter().filter(|c| c.player == player_id).count();
+        if registered >= MAX_CONDITIONAL_MOVES {
+            return OperationResult::Error {
+                code: ErrorCode::LimitReached,
+                message: format!("At most {} conditional moves per game", MAX_CONDITIONAL_MOVES),
+            };
+        }
+        game.conditional_moves.push(ConditionalMove {
+            player: player_id,
+            if_move: CheckersMove::new(if_move.from_row, if_move.from_col, if_move.to_row, if_move.to_col),
+            reply: CheckersMove::new(reply.from_row, reply.from_col, reply.to_row, reply.to_col),
+        });
+
+        if let Err(e) = self.state.save_game(game).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        OperationResult::ConditionalMoveAdded { game_id, conditional_moves: registered as u32 + 1 }
+    }
+
+    async fn clear_conditional_moves(&mut self, game_id: String, player_id: String) -> OperationResult {
+        let Some(mut game) = self.state.get_game(&game_id).await else {
+            return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() };
+        };
+        if game.color_of(&player_id).is_none() {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Not a player in this game".to_string() };
+        }
+        game.conditional_moves.retain(|c| c.player != player_id);
+        if let Err(e) = self.state.save_game(game).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
         }
+        OperationResult::ConditionalMovesCleared { game_id }
     }
 
     async fn resign(&mut self, game_id: String, player_id: String) -> OperationResult {
@@ -381,18 +1043,23 @@ impl CheckersContract {
 
         let mut game = match self.state.get_game(&game_id).await {
             Some(g) => g,
-            None => return OperationResult::Error { message: "Game not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
         };
 
         if game.status != GameStatus::Active {
-            return OperationResult::Error { message: "Game not active".to_string() };
+            return OperationResult::Error { code: ErrorCode::GameNotActive, message: "Game not active".to_string() };
         }
 
         let is_red = game.red_player.as_deref() == Some(player.as_str());
         let is_black = game.black_player.as_deref() == Some(player.as_str());
 
         if !is_red && !is_black {
-            return OperationResult::Error { message: "Not in this game".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotInGame, message: "Not in this game".to_string() };
+        }
+
+        let this_chain = self.runtime.chain_id().to_string();
+        if game.is_hosted_elsewhere(&this_chain) {
+            return self.relay_resign(&game, player);
         }
 
         game.status = GameStatus::Finished;
@@ -400,11 +1067,11 @@ impl CheckersContract {
         game.updated_at = self.runtime.system_time().micros();
 
         if let Err(e) = self.state.save_game(game.clone()).await {
-            return OperationResult::Error { message: e };
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
         }
 
         if let Some(result) = game.result {
-            let _ = self.state.record_game_result(&game, result).await;
+            self.finish_game(&game, result).await;
         }
 
         // Update tournament if this is a tournament game
@@ -416,11 +1083,11 @@ impl CheckersContract {
     async fn make_ai_move(&mut self, game_id: String) -> OperationResult {
         let mut game = match self.state.get_game(&game_id).await {
             Some(g) => g,
-            None => return OperationResult::Error { message: "Game not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
         };
 
         if game.status != GameStatus::Active {
-            return OperationResult::Error { message: "Game not active".to_string() };
+            return OperationResult::Error { code: ErrorCode::GameNotActive, message: "Game not active".to_string() };
         }
 
         let is_ai_turn = match game.current_turn {
@@ -429,32 +1096,40 @@ impl CheckersContract {
         };
 
         if !is_ai_turn {
-            return OperationResult::Error { message: "Not AI's turn".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotYourTurn, message: "Not AI's turn".to_string() };
         }
 
         match self.calculate_ai_move(&game) {
-            Some((from_row, from_col, to_row, to_col)) => {
+            Some(((from_row, from_col, to_row, to_col), search_depth)) => {
                 match self.validate_and_execute_move(&mut game, from_row, from_col, to_row, to_col) {
                     Ok(checkers_move) => {
-                        game.moves.push(checkers_move);
+                        if let Err(e) = self.state.append_move(&game.id, checkers_move.clone()).await {
+                            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+                        }
                         game.move_count += 1;
+                        self.emit_event(GameEvent::MoveMade {
+                            game_id: game.id.clone(),
+                            player: "AI".to_string(),
+                            checkers_move,
+                            move_count: game.move_count,
+                        });
                         game.updated_at = self.runtime.system_time().micros();
 
                         let game_over = self.check_game_over(&mut game);
 
                         if let Err(e) = self.state.save_game(game.clone()).await {
-                            return OperationResult::Error { message: e };
+                            return OperationResult::Error { code: ErrorCode::Storage, message: e };
                         }
 
                         if game_over {
                             if let Some(result) = game.result {
-                                let _ = self.state.record_game_result(&game, result).await;
+                                self.finish_game(&game, result).await;
                             }
                         }
 
-                        OperationResult::AiMoveMade { game_id, game_over }
+                        OperationResult::AiMoveMade { game_id, game_over, search_depth }
                     }
-                    Err(e) => OperationResult::Error { message: e },
+                    Err(e) => e.into(),
                 }
             }
             None => {
@@ -466,14 +1141,14 @@ impl CheckersContract {
                 game.updated_at = self.runtime.system_time().micros();
 
                 if let Err(e) = self.state.save_game(game.clone()).await {
-                    return OperationResult::Error { message: e };
+                    return OperationResult::Error { code: ErrorCode::Storage, message: e };
                 }
 
                 if let Some(result) = game.result {
-                    let _ = self.state.record_game_result(&game, result).await;
+                    self.finish_game(&game, result).await;
                 }
 
-                OperationResult::AiMoveMade { game_id, game_over: true }
+                OperationResult::AiMoveMade { game_id, game_over: true, search_depth: 0 }
             }
         }
     }
@@ -485,9 +1160,9 @@ impl CheckersContract {
         from_col: u8,
         to_row: u8,
         to_col: u8,
-    ) -> Result<CheckersMove, String> {
+    ) -> Result<CheckersMove, OperationError> {
         if !is_valid_square(from_row, from_col) || !is_valid_square(to_row, to_col) {
-            return Err("Invalid square".to_string());
+            return Err(OperationError::new(ErrorCode::IllegalMove, "Invalid square"));
         }
 
         let piece = get_piece(&game.board_state, from_row, from_col);
@@ -495,25 +1170,25 @@ impl CheckersContract {
         match game.current_turn {
             Turn::Red => {
                 if !piece.is_red() {
-                    return Err("Not your piece".to_string());
+                    return Err(OperationError::new(ErrorCode::IllegalMove, "Not your piece"));
                 }
             }
             Turn::Black => {
                 if !piece.is_black() {
-                    return Err("Not your piece".to_string());
+                    return Err(OperationError::new(ErrorCode::IllegalMove, "Not your piece"));
                 }
             }
         }
 
         if !get_piece(&game.board_state, to_row, to_col).is_empty() {
-            return Err("Destination not empty".to_string());
+            return Err(OperationError::new(ErrorCode::IllegalMove, "Destination not empty"));
         }
 
         let row_diff = (to_row as i8 - from_row as i8).abs();
         let col_diff = (to_col as i8 - from_col as i8).abs();
 
         if row_diff != col_diff {
-            return Err("Must move diagonally".to_string());
+            return Err(OperationError::new(ErrorCode::IllegalMove, "Must move diagonally"));
         }
 
         let mut checkers_move = CheckersMove::new(from_row, from_col, to_row, to_col);
@@ -527,12 +1202,12 @@ impl CheckersContract {
                     Turn::Black => to_row < from_row,
                 };
                 if !valid_dir {
-                    return Err("Invalid direction".to_string());
+                    return Err(OperationError::new(ErrorCode::IllegalMove, "Invalid direction"));
                 }
             }
 
             if self.has_capture_available(game) {
-                return Err("Must capture".to_string());
+                return Err(OperationError::new(ErrorCode::MustCapture, "Must capture"));
             }
 
             game.board_state = set_piece(&game.board_state, from_row, from_col, Piece::Empty);
@@ -560,7 +1235,7 @@ impl CheckersContract {
             };
 
             if !is_enemy {
-                return Err("No piece to capture".to_string());
+                return Err(OperationError::new(ErrorCode::IllegalMove, "No piece to capture"));
             }
 
             if !piece.is_king() {
@@ -569,7 +1244,7 @@ impl CheckersContract {
                     Turn::Black => to_row < from_row,
                 };
                 if !valid_dir {
-                    return Err("Invalid capture direction".to_string());
+                    return Err(OperationError::new(ErrorCode::IllegalMove, "Invalid capture direction"));
                 }
             }
 
@@ -598,7 +1273,7 @@ impl CheckersContract {
             return Ok(checkers_move);
         }
 
-        Err("Invalid move distance".to_string())
+        Err(OperationError::new(ErrorCode::IllegalMove, "Invalid move distance"))
     }
 
     fn has_capture_available(&self, game: &CheckersGame) -> bool {
@@ -633,7 +1308,7 @@ impl CheckersContract {
             let to_r = row as i8 + 2 * dr;
             let to_c = col as i8 + 2 * dc;
 
-            if to_r >= 0 && to_r < 8 && to_c >= 0 && to_c < 8 {
+            if (0..8).contains(&to_r) && (0..8).contains(&to_c) {
                 let mid_piece = get_piece(&game.board_state, mid_r as u8, mid_c as u8);
                 let to_piece = get_piece(&game.board_state, to_r as u8, to_c as u8);
 
@@ -718,47 +1393,523 @@ impl CheckersContract {
         for (dr, dc) in dirs {
             let to_r = row as i8 + dr;
             let to_c = col as i8 + dc;
-            if to_r >= 0 && to_r < 8 && to_c >= 0 && to_c < 8 {
-                if get_piece(&game.board_state, to_r as u8, to_c as u8).is_empty() {
-                    return true;
-                }
+            if (0..8).contains(&to_r)
+                && (0..8).contains(&to_c)
+                && get_piece(&game.board_state, to_r as u8, to_c as u8).is_empty()
+            {
+                return true;
             }
         }
         false
     }
 
-    fn calculate_ai_move(&self, game: &CheckersGame) -> Option<(u8, u8, u8, u8)> {
-        let mut best_move: Option<(u8, u8, u8, u8)> = None;
-        let mut best_score = i32::MIN;
+    // ========================================================================
+    // EVENTS
+    // ========================================================================
 
-        for row in 0..8u8 {
-            for col in 0..8u8 {
-                let piece = get_piece(&game.board_state, row, col);
-                let is_ai = match game.current_turn {
-                    Turn::Red => piece.is_red(),
-                    Turn::Black => piece.is_black(),
-                };
+    /// Queue an event to publish when the current operation or message completes
+    fn emit_event(&mut self, event: GameEvent) {
+        self.pending_events.push(event);
+    }
 
-                if !is_ai {
-                    continue;
-                }
+    /// Publish the queued events on the game event stream
+    fn publish_events(&mut self) {
+        for event in std::mem::take(&mut self.pending_events) {
+            self.runtime.emit(StreamName::from(GAME_EVENT_STREAM), &event);
+        }
+    }
 
-                let moves = self.get_valid_moves_for_piece(game, row, col, piece);
+    /// Record the result of a finished game and announce it
+    async fn finish_game(&mut self, game: &CheckersGame, result: GameResult) {
+        let _ = self.state.record_game_result(game, result).await;
+        self.award_game_progress(game, result).await;
+        self.record_play_style(game, result).await;
+        self.record_opening(game, result).await;
+        self.check_move_timing(game).await;
+        self.record_club_results(game, result).await;
+        self.record_club_match_board(game, result).await;
+        let _ = self.settle_wager(&game.id).await;
+        self.emit_event(GameEvent::GameEnded { game_id: game.id.clone(), result });
+        if let (Some(owner_app), Some(red_player), Some(black_player)) =
+            (&game.owner_app, &game.red_player, &game.black_player)
+        {
+            self.emit_event(GameEvent::AppGameEnded {
+                game_id: game.id.clone(),
+                owner_app: owner_app.clone(),
+                reference: game.external_ref.clone(),
+                red_player: red_player.clone(),
+                black_player: black_player.clone(),
+                result,
+                rated: game.is_rated,
+            });
+        }
+        self.sync_replicas(game).await;
+    }
 
-                for (to_row, to_col, is_capture) in moves {
-                    let mut score = 0;
+    /// Move a player's stake from the signer's account into the application's
+    fn escrow_stake(&mut self, player_id: &str, amount: Amount) -> Result<Stake, OperationError> {
+        let owner = self.runtime
+            .authenticated_signer()
+            .ok_or_else(|| OperationError::new(ErrorCode::Unauthenticated, "Operation must be signed"))?;
+        let escrow = Account::new(self.runtime.chain_id(), self.runtime.application_id().into());
+        self.runtime.transfer(owner, escrow, amount);
+        Ok(Stake { player_id: player_id.to_string(), owner })
+    }
 
-                    if is_capture {
-                        score += 100;
-                    }
+    /// Pay tokens held by the application to an account on this chain
+    fn pay_from_escrow(&mut self, owner: AccountOwner, amount: Amount) {
+        let escrow: AccountOwner = self.runtime.application_id().into();
+        let chain_id = self.runtime.chain_id();
+        self.runtime.transfer(escrow, Account::new(chain_id, owner), amount);
+    }
 
-                    match game.current_turn {
-                        Turn::Red => {
-                            if !piece.is_king() {
-                                score += (to_row as i32) * 2;
-                                if to_row == 7 {
-                                    score += 50;
-                                }
+    /// Take a player's entry fee into the prize pool, if the tournament has one
+    fn pay_entry_fee(&mut self, tournament: &mut Tournament, player_id: &str) -> Result<(), OperationError> {
+        let Some(fee) = tournament.entry_fee else { return Ok(()) };
+        let entry = self.escrow_stake(player_id, fee)?;
+        tournament.entries.push(entry);
+        tournament.prize_pool = tournament.prize_pool.saturating_add(fee);
+        Ok(())
+    }
+
+    /// Give a player back their entry fee, if they paid one
+    fn refund_entry_fee(&mut self, tournament: &mut Tournament, player_id: &str) {
+        let Some(fee) = tournament.entry_fee else { return };
+        let Some(index) = tournament.entries.iter().position(|entry| entry.player_id == player_id) else { return };
+        let entry = tournament.entries.remove(index);
+        self.pay_from_escrow(entry.owner, fee);
+        tournament.prize_pool = tournament.prize_pool.saturating_sub(fee);
+    }
+
+    /// Pay out the stakes of a finished wager game according to its result.
+    /// A played game first holds them for `WAGER_DISPUTE_WINDOW_MICROS`; a
+    /// cancelled one gives them back straight away.
+    async fn settle_wager(&mut self, game_id: &str) -> Result<(), String> {
+        let Some(mut game) = self.state.get_game(game_id).await else { return Ok(()) };
+        let now = self.runtime.system_time().micros();
+        let (is_over, played) = (game.is_over(), game.status == GameStatus::Finished);
+        let Some(wager) = &mut game.wager else { return Ok(()) };
+        if wager.settled || wager.disputed_by.is_some() || !is_over {
+            return Ok(());
+        }
+        if played && !wager.payable(now) {
+            if wager.payable_at.is_none() {
+                wager.payable_at = Some(now.saturating_add(WAGER_DISPUTE_WINDOW_MICROS));
+                return self.state.save_game(game).await;
+            }
+            return Ok(());
+        }
+        let winner = game.winner().map(str::to_string);
+        self.pay_wager(&mut game, winner.as_deref());
+        self.state.save_game(game).await
+    }
+
+    /// Pay a wager's pot to the winner less the rake, or each stake back without one
+    fn pay_wager(&mut self, game: &mut CheckersGame, winner: Option<&str>) {
+        let Some(wager) = &mut game.wager else { return };
+        let rake_percent = self.state.game_config.get().rake_percent;
+        let (payouts, rake) = wager.payouts(winner, rake_percent);
+        for (owner, amount) in payouts {
+            self.pay_from_escrow(owner, amount);
+        }
+        self.state.treasury_balance.get_mut().saturating_add_assign(rake);
+        wager.settled = true;
+    }
+
+    /// Give both human players XP and mission progress for a finished game,
+    /// with a bonus for the winner
+    async fn award_game_progress(&mut self, game: &CheckersGame, result: GameResult) {
+        let category = game.clock.as_ref().and_then(TimeControl::from_clock).map(|tc| tc.category());
+        let sides = [
+            (&game.red_player, game.red_player_type, result == GameResult::RedWins),
+            (&game.black_player, game.black_player_type, result == GameResult::BlackWins),
+        ];
+        for (player, player_type, won) in sides {
+            let Some(player) = player.as_deref() else { continue };
+            if player == "AI" || player_type == PlayerType::AI {
+                continue;
+            }
+            let xp = if won { XP_PER_GAME + XP_PER_WIN } else { XP_PER_GAME };
+            self.award_xp(player, xp).await;
+
+            self.record_mission(player, MissionGoal::PlayGames, game.updated_at).await;
+            if won {
+                self.record_mission(player, MissionGoal::WinGames, game.updated_at).await;
+                match category {
+                    Some(RatingCategory::Bullet) => {
+                        self.record_mission(player, MissionGoal::WinBulletGames, game.updated_at).await;
+                    }
+                    Some(RatingCategory::Blitz) => {
+                        self.record_mission(player, MissionGoal::WinBlitzGames, game.updated_at).await;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Add a finished game to both human players' play-style statistics
+    async fn record_play_style(&mut self, game: &CheckersGame, result: GameResult) {
+        let moves = self.state.get_moves(game, 0, usize::MAX).await;
+        let timed_out = game.clock.as_ref().and_then(|clock| clock.timed_out(game.updated_at / 1000));
+        let sides = [
+            (&game.red_player, game.red_player_type, Turn::Red, GameResult::BlackWins),
+            (&game.black_player, game.black_player_type, Turn::Black, GameResult::RedWins),
+        ];
+        for (player, player_type, color, loss) in sides {
+            let Some(player) = player.as_deref() else { continue };
+            if player == "AI" || player_type == PlayerType::AI {
+                continue;
+            }
+            let mut stats = self.state.get_play_style_stats(player).await;
+            stats.record_game(color, &moves, result == loss && timed_out == Some(color));
+            let _ = self.state.save_play_style_stats(player, stats);
+        }
+    }
+
+    /// Count one step towards a player's missions with a goal, awarding XP for each completed
+    async fn record_mission(&mut self, player_id: &str, goal: MissionGoal, now: u64) {
+        let completed = self.state.record_mission_progress(player_id, goal, 1, now).await.unwrap_or_default();
+        for _ in completed {
+            self.award_xp(player_id, XP_PER_MISSION).await;
+        }
+    }
+
+    /// Add XP to a player, announcing any level gained
+    async fn award_xp(&mut self, player_id: &str, amount: u64) {
+        if let Ok(Some(LevelUp { player_id, level, xp })) = self.state.add_xp(player_id, amount).await {
+            self.emit_event(GameEvent::LeveledUp { player_id, level, xp });
+        }
+    }
+
+    /// Save a tournament and announce its new state
+    /// When a tournament finishes, each participant's history records how they
+    /// did. The winner is awarded TournamentVictory, and the prize pool is paid out,
+    /// or given back as entry fees when nobody won.
+    async fn save_tournament(&mut self, mut tournament: Tournament) -> Result<(), String> {
+        if let Some(minutes) = tournament.round_time_limit_minutes {
+            let deadline = self.runtime.system_time().micros() + minutes as u64 * 60 * 1_000_000;
+            for m in tournament.matches.iter_mut().filter(|m| m.status == MatchStatus::Ready && m.deadline.is_none()) {
+                m.deadline = Some(deadline);
+            }
+        }
+        let event = GameEvent::TournamentUpdated {
+            tournament_id: tournament.id.clone(),
+            status: tournament.status,
+        };
+        let finished_at = tournament.finished_at.unwrap_or_else(|| self.runtime.system_time().micros());
+        let already_finished = tournament.status == TournamentStatus::Finished
+            && self.state.get_tournament(&tournament.id).await
+                .is_some_and(|saved| saved.status == TournamentStatus::Finished);
+        if tournament.status == TournamentStatus::Finished && !already_finished {
+            for (player_id, record) in tournament.records(finished_at) {
+                self.state.record_tournament_result(&player_id, record).await?;
+            }
+            // Without a winner the entry fees go back, so the pool never stays locked
+            let rake_percent = self.state.game_config.get().rake_percent;
+            let (payouts, rake) = tournament.prize_payouts(rake_percent);
+            for (owner, amount) in payouts {
+                self.pay_from_escrow(owner, amount);
+            }
+            self.state.treasury_balance.get_mut().saturating_add_assign(rake);
+            tournament.prize_pool = Amount::ZERO;
+        }
+        if let (TournamentStatus::Finished, Some(winner)) = (tournament.status, &tournament.winner) {
+            if !already_finished {
+                for participant in &tournament.participants {
+                    self.award_xp(&participant.player_id, XP_PER_TOURNAMENT).await;
+                    self.record_mission(&participant.player_id, MissionGoal::PlayTournament, finished_at).await;
+                }
+            }
+            self.state
+                .award_achievement(winner, Achievement::TournamentVictory, &tournament.id, finished_at)
+                .await?;
+            if tournament.participants.len() >= 3 {
+                for player_id in tournament.final_standings().iter().take(3) {
+                    self.state
+                        .award_achievement(player_id, Achievement::TournamentPodium, &tournament.id, finished_at)
+                        .await?;
+                }
+            }
+        }
+        self.state.save_tournament(tournament).await?;
+        self.emit_event(event);
+        Ok(())
+    }
+
+    // ========================================================================
+    // AI SEARCH
+    // ========================================================================
+
+    /// Pick the AI move with an iterative-deepening alpha-beta search bounded by the
+    /// game's think-time budget. Returns the move and the deepest completed search depth.
+    fn calculate_ai_move(&self, game: &CheckersGame) -> Option<((u8, u8, u8, u8), u32)> {
+        // The search only needs the position, not the move history
+        let mut root = game.clone();
+        root.moves.clear();
+
+        let mut root_moves = self.ordered_ai_moves(&root);
+        if root_moves.is_empty() {
+            return None;
+        }
+
+        let budget_ms = game.ai_time_budget_ms
+            .unwrap_or(DEFAULT_AI_TIME_BUDGET_MS)
+            .min(MAX_AI_TIME_BUDGET_MS);
+        let mut search = AiSearch {
+            weights: game.ai_personality.weights(),
+            ai_side: game.current_turn,
+            nodes: 0,
+            node_limit: budget_ms * AI_NODES_PER_MS,
+        };
+
+        // If not even depth 1 fits the budget, fall back to the best-ordered move
+        let mut best_move = root_moves[0];
+        let mut completed_depth = 0;
+
+        for depth in 1..=AI_MAX_SEARCH_DEPTH {
+            match self.search_root(&root, &root_moves, depth, &mut search) {
+                Some(found) => {
+                    best_move = found;
+                    completed_depth = depth;
+                    // Search the previous best move first in the next iteration
+                    if let Some(pos) = root_moves.iter().position(|m| *m == found) {
+                        root_moves[..=pos].rotate_right(1);
+                    }
+                }
+                // Budget exhausted mid-iteration: keep the last complete result
+                None => break,
+            }
+        }
+
+        Some((best_move, completed_depth))
+    }
+
+    fn search_root(
+        &self,
+        game: &CheckersGame,
+        moves: &[(u8, u8, u8, u8)],
+        depth: u32,
+        search: &mut AiSearch,
+    ) -> Option<(u8, u8, u8, u8)> {
+        let mut alpha = -AI_WIN_SCORE - 1;
+        let beta = AI_WIN_SCORE + 1;
+        let mut best = None;
+
+        for &(from_row, from_col, to_row, to_col) in moves {
+            let mut child = game.clone();
+            if self.validate_and_execute_move(&mut child, from_row, from_col, to_row, to_col).is_err() {
+                continue;
+            }
+            let score = self.score_child(game, &child, depth - 1, alpha, beta, search)?;
+            if best.is_none() || score > alpha {
+                alpha = alpha.max(score);
+                best = Some((from_row, from_col, to_row, to_col));
+            }
+        }
+
+        best
+    }
+
+    /// Compare a played move with the engine's preferred move in the same position.
+    /// Returns the evaluation the played move gave up and, if positive, the better move.
+    /// Forced moves and positions the budget can't search are never penalized.
+    fn judge_move(&self, position: &CheckersGame, played: &CheckersMove, node_limit: u64) -> (i32, Option<CheckersMove>) {
+        let moves = self.ordered_ai_moves(position);
+        if moves.len() < 2 {
+            return (0, None);
+        }
+
+        let mut search = AiSearch {
+            weights: AiPersonality::Balanced.weights(),
+            ai_side: position.current_turn,
+            nodes: 0,
+            node_limit,
+        };
+        let mut scores = None;
+        for depth in 1..=ANALYSIS_MAX_DEPTH {
+            match self.score_root_moves(position, &moves, depth, &mut search) {
+                Some(found) => scores = Some(found),
+                None => break,
+            }
+        }
+        let Some(scores) = scores else {
+            return (0, None);
+        };
+
+        let played_key = (played.from_row, played.from_col, played.to_row, played.to_col);
+        let Some(played_score) = moves.iter().position(|m| *m == played_key).map(|i| scores[i]) else {
+            return (0, None);
+        };
+        let Some((best_index, &best_score)) = scores.iter().enumerate().max_by_key(|(_, score)| **score) else {
+            return (0, None);
+        };
+
+        let loss = best_score - played_score;
+        if loss <= 0 {
+            return (0, None);
+        }
+        let (from_row, from_col, to_row, to_col) = moves[best_index];
+        (loss, Some(CheckersMove::new(from_row, from_col, to_row, to_col)))
+    }
+
+    /// Exact score of every root move at the given depth, in the order given.
+    /// Returns None once the node budget is exhausted.
+    fn score_root_moves(
+        &self,
+        game: &CheckersGame,
+        moves: &[(u8, u8, u8, u8)],
+        depth: u32,
+        search: &mut AiSearch,
+    ) -> Option<Vec<i32>> {
+        let mut scores = Vec::with_capacity(moves.len());
+        for &(from_row, from_col, to_row, to_col) in moves {
+            let mut child = game.clone();
+            if self.validate_and_execute_move(&mut child, from_row, from_col, to_row, to_col).is_err() {
+                scores.push(-AI_WIN_SCORE - 1);
+                continue;
+            }
+            scores.push(self.score_child(game, &child, depth - 1, -AI_WIN_SCORE - 1, AI_WIN_SCORE + 1, search)?);
+        }
+        Some(scores)
+    }
+
+    /// Negamax with alpha-beta pruning. Scores are from the side to move's perspective.
+    /// Returns None once the node budget is exhausted.
+    fn negamax(
+        &self,
+        game: &CheckersGame,
+        depth: u32,
+        mut alpha: i32,
+        beta: i32,
+        search: &mut AiSearch,
+    ) -> Option<i32> {
+        search.nodes += 1;
+        if search.nodes > search.node_limit {
+            return None;
+        }
+
+        let moves = self.ordered_ai_moves(game);
+        if moves.is_empty() {
+            return Some(-AI_WIN_SCORE);
+        }
+        if depth == 0 {
+            let score = self.evaluate_position(game, &search.weights, search.ai_side);
+            return Some(if game.current_turn == search.ai_side { score } else { -score });
+        }
+
+        let mut best = -AI_WIN_SCORE - 1;
+        for (from_row, from_col, to_row, to_col) in moves {
+            let mut child = game.clone();
+            if self.validate_and_execute_move(&mut child, from_row, from_col, to_row, to_col).is_err() {
+                continue;
+            }
+            let score = self.score_child(game, &child, depth - 1, alpha, beta, search)?;
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        Some(best)
+    }
+
+    /// Score a child position from the parent's perspective.
+    /// Multi-jump captures keep the same side to move, so the score is only negated
+    /// when the turn actually passed to the opponent.
+    fn score_child(
+        &self,
+        parent: &CheckersGame,
+        child: &CheckersGame,
+        depth: u32,
+        alpha: i32,
+        beta: i32,
+        search: &mut AiSearch,
+    ) -> Option<i32> {
+        if child.current_turn == parent.current_turn {
+            self.negamax(child, depth, alpha, beta, search)
+        } else {
+            self.negamax(child, depth, -beta, -alpha, search).map(|score| -score)
+        }
+    }
+
+    /// Static evaluation from the AI's perspective, using its personality's weights
+    fn evaluate_position(&self, game: &CheckersGame, weights: &AiWeights, ai_side: Turn) -> i32 {
+        let mut own = 0;
+        let mut opponent = 0;
+
+        for row in 0..8u8 {
+            for col in 0..8u8 {
+                let piece = get_piece(&game.board_state, row, col);
+                if piece.is_empty() {
+                    continue;
+                }
+
+                let mut value = if piece.is_king() {
+                    weights.king_value
+                } else {
+                    // Men gain value as they advance towards promotion
+                    let advancement = if piece.is_red() { row } else { 7 - row };
+                    let home_row = if piece.is_red() { 0 } else { 7 };
+                    let mut man = weights.man_value + advancement as i32 * weights.advancement;
+                    if row == home_row {
+                        man += weights.back_rank;
+                    }
+                    man
+                };
+                if (2..=5).contains(&row) && (2..=5).contains(&col) {
+                    value += weights.center_control;
+                }
+
+                let is_mine = match ai_side {
+                    Turn::Red => piece.is_red(),
+                    Turn::Black => piece.is_black(),
+                };
+                if is_mine {
+                    own += value;
+                } else {
+                    opponent += value;
+                }
+            }
+        }
+
+        own - opponent * weights.opponent_material_pct / 100
+    }
+
+    /// All legal moves for the side to move, most promising first so that
+    /// alpha-beta pruning cuts as early as possible
+    fn ordered_ai_moves(&self, game: &CheckersGame) -> Vec<(u8, u8, u8, u8)> {
+        let mut scored_moves = Vec::new();
+
+        for row in 0..8u8 {
+            for col in 0..8u8 {
+                let piece = get_piece(&game.board_state, row, col);
+                let is_ai = match game.current_turn {
+                    Turn::Red => piece.is_red(),
+                    Turn::Black => piece.is_black(),
+                };
+
+                if !is_ai {
+                    continue;
+                }
+
+                let moves = self.get_valid_moves_for_piece(game, row, col, piece);
+
+                for (to_row, to_col, is_capture) in moves {
+                    let mut score = 0;
+
+                    if is_capture {
+                        score += 100;
+                    }
+
+                    match game.current_turn {
+                        Turn::Red => {
+                            if !piece.is_king() {
+                                score += (to_row as i32) * 2;
+                                if to_row == 7 {
+                                    score += 50;
+                                }
                             }
                         }
                         Turn::Black => {
@@ -771,21 +1922,19 @@ impl CheckersContract {
                         }
                     }
 
-                    let center_dist = ((to_row as i32 - 4).abs() + (to_col as i32 - 4).abs()) as i32;
+                    let center_dist = (to_row as i32 - 4).abs() + (to_col as i32 - 4).abs();
                     score -= center_dist;
 
-                    let random_factor = ((row as i32 * 13 + col as i32 * 17 + game.move_count as i32) % 5) as i32;
+                    let random_factor = (row as i32 * 13 + col as i32 * 17 + game.move_count as i32) % 5;
                     score += random_factor;
 
-                    if score > best_score {
-                        best_score = score;
-                        best_move = Some((row, col, to_row, to_col));
-                    }
+                    scored_moves.push((score, (row, col, to_row, to_col)));
                 }
             }
         }
 
-        best_move
+        scored_moves.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored_moves.into_iter().map(|(_, m)| m).collect()
     }
 
     fn get_valid_moves_for_piece(&self, game: &CheckersGame, row: u8, col: u8, piece: Piece) -> Vec<(u8, u8, bool)> {
@@ -807,7 +1956,7 @@ impl CheckersContract {
             let to_r = row as i8 + 2 * dr;
             let to_c = col as i8 + 2 * dc;
 
-            if to_r >= 0 && to_r < 8 && to_c >= 0 && to_c < 8 {
+            if (0..8).contains(&to_r) && (0..8).contains(&to_c) {
                 let mid_piece = get_piece(&game.board_state, mid_r as u8, mid_c as u8);
                 let to_piece = get_piece(&game.board_state, to_r as u8, to_c as u8);
 
@@ -826,10 +1975,11 @@ impl CheckersContract {
             for (dr, dc) in &dirs {
                 let to_r = row as i8 + dr;
                 let to_c = col as i8 + dc;
-                if to_r >= 0 && to_r < 8 && to_c >= 0 && to_c < 8 {
-                    if get_piece(&game.board_state, to_r as u8, to_c as u8).is_empty() {
-                        moves.push((to_r as u8, to_c as u8, false));
-                    }
+                if (0..8).contains(&to_r)
+                    && (0..8).contains(&to_c)
+                    && get_piece(&game.board_state, to_r as u8, to_c as u8).is_empty()
+                {
+                    moves.push((to_r as u8, to_c as u8, false));
                 }
             }
         }
@@ -837,17 +1987,6 @@ impl CheckersContract {
         moves
     }
 
-    async fn handle_join_request(&mut self, game_id: &str, player_chain: &str) {
-        if let Some(mut game) = self.state.get_game(game_id).await {
-            if game.status == GameStatus::Pending && game.black_player.is_none() {
-                game.black_player = Some(player_chain.to_string());
-                game.status = GameStatus::Active;
-                game.updated_at = self.runtime.system_time().micros();
-                let _ = self.state.save_game(game).await;
-            }
-        }
-    }
-
     async fn handle_move_received(
         &mut self,
         game_id: &str,
@@ -862,7 +2001,7 @@ impl CheckersContract {
             game.current_turn = new_turn;
             game.status = game_status;
             game.result = game_result;
-            game.moves.push(checkers_move);
+            let _ = self.state.append_move(game_id, checkers_move).await;
             game.move_count += 1;
             game.updated_at = self.runtime.system_time().micros();
             let _ = self.state.save_game(game).await;
@@ -885,61 +2024,78 @@ impl CheckersContract {
             game.result = Some(result);
             game.updated_at = self.runtime.system_time().micros();
             let _ = self.state.save_game(game.clone()).await;
-            let _ = self.state.record_game_result(&game, result).await;
+            self.finish_game(&game, result).await;
             // Update tournament if this is a tournament game
             self.handle_tournament_game_finished(&game).await;
         }
     }
 
-    async fn notify_opponent(&mut self, game: &CheckersGame, checkers_move: CheckersMove) {
-        let my_chain = self.runtime.chain_id().to_string();
-        let opponent = if game.red_player.as_deref() == Some(my_chain.as_str()) {
-            game.black_player.as_deref()
-        } else {
-            game.red_player.as_deref()
-        };
-
-        if let Some(opp) = opponent {
-            if opp == "AI" {
-                return;
-            }
-            if let Ok(chain_id) = opp.parse::<ChainId>() {
-                self.runtime
-                    .prepare_message(Message::MoveMade {
-                        game_id: game.id.clone(),
-                        chess_move: checkers_move,
-                        new_board_state: game.board_state.clone(),
-                        new_turn: game.current_turn,
-                        game_status: game.status,
-                        game_result: game.result,
-                    })
-                    .with_tracking()
-                    .send_to(chain_id);
-            }
-        }
-    }
-
     // ========================================================================
     // MATCHMAKING QUEUE OPERATIONS
     // ========================================================================
 
-    async fn join_queue(&mut self, time_control: TimeControl, player_id: String) -> OperationResult {
+    /// The configured hub chain, if it is another chain than this one
+    fn remote_hub_chain(&mut self) -> Option<ChainId> {
+        let hub = self.state.game_config.get().hub_chain_id.as_deref()?.parse::<ChainId>().ok()?;
+        (hub != self.runtime.chain_id()).then_some(hub)
+    }
+
+    /// Whether this chain runs the shared queue for players on other chains
+    fn is_hub_chain(&mut self) -> bool {
+        let this_chain = self.runtime.chain_id().to_string();
+        self.state.game_config.get().hub_chain_id.as_deref() == Some(this_chain.as_str())
+    }
+
+    async fn join_queue(&mut self, time_control: TimeControl, is_rated: Option<bool>, player_id: String) -> OperationResult {
+        if let Err(e) = self.check_time_control(&time_control) {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: e };
+        }
+        let is_rated = match is_rated {
+            Some(is_rated) => is_rated,
+            None => self.state.get_settings(&player_id).await.default_rated.unwrap_or(self.state.game_config.get().default_rated),
+        };
+        // With a hub chain configured, everyone queues on the hub
+        if let Some(hub) = self.remote_hub_chain() {
+            self.runtime
+                .prepare_message(Message::JoinQueueRequest { player_id, time_control, is_rated })
+                .with_authentication()
+                .with_tracking()
+                .send_to(hub);
+            return OperationResult::QueueJoinRequested { time_control };
+        }
+        self.enter_queue(time_control, is_rated, player_id, None).await
+    }
+
+    /// Queue a player on this chain, starting a game if an opponent is waiting.
+    /// `home_chain` is the chain a remote player queued from; a match involving
+    /// a remote player becomes a multi-chain game hosted here.
+    async fn enter_queue(&mut self, time_control: TimeControl, is_rated: bool, player_id: String, home_chain: Option<String>) -> OperationResult {
         let timestamp = self.runtime.system_time().micros();
         let timestamp_ms = timestamp / 1000;
 
-        match self.state.join_queue(&player_id, time_control, timestamp).await {
-            Ok(Some(opponent_chain_id)) => {
+        match self.state.join_queue(&player_id, time_control, is_rated, timestamp, home_chain.clone()).await {
+            Ok(Some(opponent)) => {
+                let opponent_chain_id = opponent.chain_id;
                 // Match found! Create a game with clock
                 let game_id = self.state.generate_game_id().await;
 
+                // Even out both players' colors; otherwise the first player in queue is red
+                let opponent_red = self.state.balanced_red(&opponent_chain_id, &player_id).await.unwrap_or(true);
+                let (red_player, red_home, black_player, black_home) = if opponent_red {
+                    (opponent_chain_id.clone(), opponent.home_chain, player_id.clone(), home_chain)
+                } else {
+                    (player_id.clone(), home_chain, opponent_chain_id.clone(), opponent.home_chain)
+                };
+
                 let mut game = CheckersGame::new(
                     game_id.clone(),
-                    Some(opponent_chain_id.clone()), // First player in queue is red
+                    Some(red_player.clone()),
                     PlayerType::Human,
                 );
-                game.black_player = Some(player_id.clone());
+                game.black_player = Some(black_player.clone());
                 game.black_player_type = PlayerType::Human;
                 game.status = GameStatus::Active;
+                game.is_rated = is_rated;
                 game.created_at = timestamp;
                 game.updated_at = timestamp;
 
@@ -948,27 +2104,29 @@ impl CheckersContract {
                 clock.start(timestamp_ms);
                 game.clock = Some(clock);
 
-                if let Err(e) = self.state.save_game(game.clone()).await {
-                    return OperationResult::Error { message: e };
+                if red_home.is_some() || black_home.is_some() {
+                    let this_chain = self.runtime.chain_id().to_string();
+                    game.host_chain = Some(this_chain.clone());
+                    game.red_chain = Some(red_home.unwrap_or_else(|| this_chain.clone()));
+                    game.black_chain = Some(black_home.unwrap_or(this_chain));
                 }
 
-                // Notify the opponent (red player) about the game
-                if let Ok(opponent_chain) = opponent_chain_id.parse::<ChainId>() {
-                    self.runtime
-                        .prepare_message(Message::GameStarted {
-                            game_id: game_id.clone(),
-                            red_player: opponent_chain_id.clone(),
-                            black_player: player_id.clone(),
-                        })
-                        .with_tracking()
-                        .send_to(opponent_chain);
-
-                    // Also sync the game state
-                    self.runtime
-                        .prepare_message(Message::SyncGameState { game })
-                        .with_tracking()
-                        .send_to(opponent_chain);
+                if let Err(e) = self.state.save_game(game.clone()).await {
+                    return OperationResult::Error { code: ErrorCode::Storage, message: e };
+                }
+                if let Err(e) = self.state.record_colors(&red_player, &black_player).await {
+                    return OperationResult::Error { code: ErrorCode::Storage, message: e };
                 }
+                self.emit_event(GameEvent::MatchFound {
+                    game_id: game_id.clone(),
+                    red_player,
+                    black_player,
+                    time_control,
+                    clock: game.clock.clone(),
+                });
+                self.close_open_invitations(&opponent_chain_id, &game_id).await;
+                self.close_open_invitations(&player_id, &game_id).await;
+                self.notify_match_found(&game, time_control).await;
 
                 OperationResult::MatchFound {
                     game_id,
@@ -979,50 +2137,119 @@ impl CheckersContract {
                 // Added to queue, no match yet
                 OperationResult::QueueJoined { time_control }
             }
-            Err(e) => OperationResult::Error { message: e },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    /// Send a new multi-chain game to the players' chains, followed by MatchFound
+    /// with the started clock. Each chain then emits the same MatchFound event the
+    /// host emitted for a player queued here, so every client learns of the match
+    /// the same way.
+    async fn notify_match_found(&mut self, game: &CheckersGame, time_control: TimeControl) {
+        self.sync_replicas(game).await;
+        let (Some(red_player), Some(black_player)) = (&game.red_player, &game.black_player) else { return };
+        let mut notified = Vec::new();
+        for chain in [&game.red_chain, &game.black_chain].into_iter().flatten() {
+            if game.host_chain.as_ref() == Some(chain) || notified.contains(&chain) {
+                continue;
+            }
+            notified.push(chain);
+            if let Ok(chain_id) = chain.parse::<ChainId>() {
+                self.runtime
+                    .prepare_message(Message::MatchFound {
+                        game_id: game.id.clone(),
+                        red_player: red_player.clone(),
+                        black_player: black_player.clone(),
+                        time_control,
+                        clock: game.clock.clone(),
+                    })
+                    .with_tracking()
+                    .send_to(chain_id);
+            }
         }
     }
 
-    async fn leave_queue(&mut self, player_id: String) -> OperationResult {
-        match self.state.leave_queue(&player_id).await {
+    async fn leave_queue(&mut self, time_control: Option<TimeControl>, player_id: String) -> OperationResult {
+        if let Some(hub) = self.remote_hub_chain() {
+            self.runtime
+                .prepare_message(Message::LeaveQueueRequest { player_id, time_control })
+                .with_authentication()
+                .with_tracking()
+                .send_to(hub);
+            return OperationResult::QueueLeft;
+        }
+        match self.state.leave_queue_pool(&player_id, time_control).await {
             Ok(_was_in_queue) => OperationResult::QueueLeft,
-            Err(e) => OperationResult::Error { message: e },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    /// Queue a player who asked from their own chain. Only the hub chain runs the shared queue.
+    async fn handle_join_queue_request(&mut self, player_id: String, time_control: TimeControl, is_rated: bool) {
+        let Some(origin) = self.runtime.message_origin_chain_id() else { return };
+        if !self.is_hub_chain() || self.check_time_control(&time_control).is_err() {
+            return;
+        }
+        if self.authenticate_player(&player_id).await.is_err() || self.state.is_banned(&player_id).await {
+            return;
+        }
+        self.enter_queue(time_control, is_rated, player_id, Some(origin.to_string())).await;
+    }
+
+    /// Take a remote player out of the queue, if the request comes from the chain they queued from
+    async fn handle_leave_queue_request(&mut self, player_id: String, time_control: Option<TimeControl>) {
+        let Some(origin) = self.runtime.message_origin_chain_id().map(|chain| chain.to_string()) else { return };
+        let entries = self.state.get_queue_entries(&player_id).await;
+        if entries.is_empty() || entries.iter().any(|entry| entry.home_chain.as_deref() != Some(origin.as_str())) {
+            return;
+        }
+        if self.authenticate_player(&player_id).await.is_err() {
+            return;
         }
+        let _ = self.state.leave_queue_pool(&player_id, time_control).await;
     }
 
     // ========================================================================
     // DRAW OPERATIONS
     // ========================================================================
 
-    async fn offer_draw(&mut self, game_id: String) -> OperationResult {
-        let player_chain = self.runtime.chain_id().to_string();
-
+    async fn offer_draw(&mut self, game_id: String, player_id: String) -> OperationResult {
         let mut game = match self.state.get_game(&game_id).await {
             Some(g) => g,
-            None => return OperationResult::Error { message: "Game not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
         };
 
         // Validate game is active
         if game.status != GameStatus::Active {
-            return OperationResult::Error { message: "Game not active".to_string() };
+            return OperationResult::Error { code: ErrorCode::GameNotActive, message: "Game not active".to_string() };
         }
 
         // Prevent draws in tournament games
         if game.tournament_id.is_some() {
-            return OperationResult::Error { message: "Draws not allowed in tournament games".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Draws not allowed in tournament games".to_string() };
         }
 
         // Validate player is in this game
-        let is_red = game.red_player.as_deref() == Some(player_chain.as_str());
-        let is_black = game.black_player.as_deref() == Some(player_chain.as_str());
+        let is_red = game.red_player.as_deref() == Some(player_id.as_str());
+        let is_black = game.black_player.as_deref() == Some(player_id.as_str());
 
         if !is_red && !is_black {
-            return OperationResult::Error { message: "Not in this game".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotInGame, message: "Not in this game".to_string() };
+        }
+
+        if game.is_hosted_elsewhere(&self.runtime.chain_id().to_string()) {
+            let message = Message::RelayOfferDraw { game_id: game.id_on_host().to_string(), player_id };
+            return self.relay_game_action(&game, message);
         }
 
         // Check no existing draw offer
         if game.draw_offer != DrawOfferState::None {
-            return OperationResult::Error { message: "Draw already offered".to_string() };
+            return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Draw already offered".to_string() };
+        }
+
+        let color = if is_red { Turn::Red } else { Turn::Black };
+        if let Err(e) = game.check_draw_offer_allowed(color) {
+            return OperationResult::Error { code: ErrorCode::LimitReached, message: e };
         }
 
         // Set draw offer
@@ -1031,39 +2258,61 @@ impl CheckersContract {
         } else {
             DrawOfferState::OfferedByBlack
         };
+        game.draw_offers.push(DrawOfferRecord { offered_by: color, move_count: game.move_count });
         game.updated_at = self.runtime.system_time().micros();
 
-        if let Err(e) = self.state.save_game(game).await {
-            return OperationResult::Error { message: e };
+        // Opponents who never want draw offers decline them straight away
+        let opponent = if is_red { game.black_player.clone() } else { game.red_player.clone() };
+        if let Some(opponent) = opponent {
+            if self.state.get_settings(&opponent).await.auto_decline_draws {
+                game.draw_offer = DrawOfferState::None;
+                if let Err(e) = self.state.save_game(game.clone()).await {
+                    return OperationResult::Error { code: ErrorCode::Storage, message: e };
+                }
+                self.sync_replicas(&game).await;
+                return OperationResult::DrawDeclined { game_id };
+            }
+        }
+
+        if let Err(e) = self.state.save_game(game.clone()).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
         }
+        self.sync_replicas(&game).await;
+        self.emit_event(GameEvent::DrawOffered {
+            game_id: game_id.clone(),
+            offered_by: if is_red { Turn::Red } else { Turn::Black },
+        });
 
         OperationResult::DrawOffered { game_id }
     }
 
-    async fn accept_draw(&mut self, game_id: String) -> OperationResult {
-        let player_chain = self.runtime.chain_id().to_string();
-
+    async fn accept_draw(&mut self, game_id: String, player_id: String) -> OperationResult {
         let mut game = match self.state.get_game(&game_id).await {
             Some(g) => g,
-            None => return OperationResult::Error { message: "Game not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
         };
 
         // Validate game is active
         if game.status != GameStatus::Active {
-            return OperationResult::Error { message: "Game not active".to_string() };
+            return OperationResult::Error { code: ErrorCode::GameNotActive, message: "Game not active".to_string() };
         }
 
         // Prevent draws in tournament games
         if game.tournament_id.is_some() {
-            return OperationResult::Error { message: "Draws not allowed in tournament games".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Draws not allowed in tournament games".to_string() };
         }
 
         // Validate player is in this game
-        let is_red = game.red_player.as_deref() == Some(player_chain.as_str());
-        let is_black = game.black_player.as_deref() == Some(player_chain.as_str());
+        let is_red = game.red_player.as_deref() == Some(player_id.as_str());
+        let is_black = game.black_player.as_deref() == Some(player_id.as_str());
 
         if !is_red && !is_black {
-            return OperationResult::Error { message: "Not in this game".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotInGame, message: "Not in this game".to_string() };
+        }
+
+        if game.is_hosted_elsewhere(&self.runtime.chain_id().to_string()) {
+            let message = Message::RelayAcceptDraw { game_id: game.id_on_host().to_string(), player_id };
+            return self.relay_game_action(&game, message);
         }
 
         // Validate accepter is the one who was offered the draw
@@ -1075,7 +2324,7 @@ impl CheckersContract {
         };
 
         if !can_accept {
-            return OperationResult::Error { message: "No draw offer to accept".to_string() };
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "No draw offer to accept".to_string() };
         }
 
         // End game as draw
@@ -1085,34 +2334,37 @@ impl CheckersContract {
         game.updated_at = self.runtime.system_time().micros();
 
         if let Err(e) = self.state.save_game(game.clone()).await {
-            return OperationResult::Error { message: e };
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
         }
 
         // Record the result
-        let _ = self.state.record_game_result(&game, GameResult::Draw).await;
+        self.finish_game(&game, GameResult::Draw).await;
 
         OperationResult::DrawAccepted { game_id }
     }
 
-    async fn decline_draw(&mut self, game_id: String) -> OperationResult {
-        let player_chain = self.runtime.chain_id().to_string();
-
+    async fn decline_draw(&mut self, game_id: String, player_id: String) -> OperationResult {
         let mut game = match self.state.get_game(&game_id).await {
             Some(g) => g,
-            None => return OperationResult::Error { message: "Game not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
         };
 
         // Validate game is active
         if game.status != GameStatus::Active {
-            return OperationResult::Error { message: "Game not active".to_string() };
+            return OperationResult::Error { code: ErrorCode::GameNotActive, message: "Game not active".to_string() };
         }
 
         // Validate player is in this game
-        let is_red = game.red_player.as_deref() == Some(player_chain.as_str());
-        let is_black = game.black_player.as_deref() == Some(player_chain.as_str());
+        let is_red = game.red_player.as_deref() == Some(player_id.as_str());
+        let is_black = game.black_player.as_deref() == Some(player_id.as_str());
 
         if !is_red && !is_black {
-            return OperationResult::Error { message: "Not in this game".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotInGame, message: "Not in this game".to_string() };
+        }
+
+        if game.is_hosted_elsewhere(&self.runtime.chain_id().to_string()) {
+            let message = Message::RelayDeclineDraw { game_id: game.id_on_host().to_string(), player_id };
+            return self.relay_game_action(&game, message);
         }
 
         // Validate decliner is the one who was offered the draw
@@ -1123,16 +2375,17 @@ impl CheckersContract {
         };
 
         if !can_decline {
-            return OperationResult::Error { message: "No draw offer to decline".to_string() };
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "No draw offer to decline".to_string() };
         }
 
         // Clear draw offer
         game.draw_offer = DrawOfferState::None;
         game.updated_at = self.runtime.system_time().micros();
 
-        if let Err(e) = self.state.save_game(game).await {
-            return OperationResult::Error { message: e };
+        if let Err(e) = self.state.save_game(game.clone()).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
         }
+        self.sync_replicas(&game).await;
 
         OperationResult::DrawDeclined { game_id }
     }
@@ -1141,109 +2394,2345 @@ impl CheckersContract {
     // TIME WIN CLAIM
     // ========================================================================
 
-    async fn claim_time_win(&mut self, game_id: String) -> OperationResult {
-        let player_chain = self.runtime.chain_id().to_string();
+    /// End an untimed game the player to move has abandoned. They lose, unless
+    /// too few moves were played to score it, in which case the game is aborted.
+    async fn adjudicate_abandoned_game(&mut self, game_id: String) -> OperationResult {
+        let now = self.runtime.system_time().micros();
+        let Some(mut game) = self.state.get_game(&game_id).await else {
+            return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() };
+        };
+        if game.is_hosted_elsewhere(&self.runtime.chain_id().to_string()) {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Adjudicate the game on its host chain".to_string() };
+        }
+        if !game.is_abandoned(now) {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Game is not abandoned".to_string() };
+        }
+
+        game.updated_at = now;
+        if game.move_count < MIN_MOVES_FOR_ADJUDICATION || game.has_ai_player() {
+            // Aborted: cancelled with no result, and any stakes go back
+            game.status = GameStatus::Cancelled;
+            game.result = None;
+            self.pay_wager(&mut game, None);
+            if let Err(e) = self.state.save_game(game.clone()).await {
+                return OperationResult::Error { code: ErrorCode::Storage, message: e };
+            }
+            self.sync_replicas(&game).await;
+            return OperationResult::GameAdjudicated { game_id, result: None };
+        }
+
+        let result = match game.current_turn {
+            Turn::Red => GameResult::BlackWins,
+            Turn::Black => GameResult::RedWins,
+        };
+        game.status = GameStatus::Finished;
+        game.result = Some(result);
+        if let Err(e) = self.state.save_game(game.clone()).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        self.finish_game(&game, result).await;
+        OperationResult::GameAdjudicated { game_id, result: Some(result) }
+    }
+
+    async fn claim_time_win(&mut self, game_id: String, player_id: String) -> OperationResult {
         let timestamp = self.runtime.system_time().micros();
         let timestamp_ms = timestamp / 1000;
 
         let mut game = match self.state.get_game(&game_id).await {
             Some(g) => g,
-            None => return OperationResult::Error { message: "Game not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
         };
 
         // Validate game is active
         if game.status != GameStatus::Active {
-            return OperationResult::Error { message: "Game not active".to_string() };
+            return OperationResult::Error { code: ErrorCode::GameNotActive, message: "Game not active".to_string() };
         }
 
         // Validate player is in this game
-        let is_red = game.red_player.as_deref() == Some(player_chain.as_str());
-        let is_black = game.black_player.as_deref() == Some(player_chain.as_str());
+        let is_red = game.red_player.as_deref() == Some(player_id.as_str());
+        let is_black = game.black_player.as_deref() == Some(player_id.as_str());
 
         if !is_red && !is_black {
-            return OperationResult::Error { message: "Not in this game".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotInGame, message: "Not in this game".to_string() };
         }
 
-        // Check if game has a clock
+        if game.is_hosted_elsewhere(&self.runtime.chain_id().to_string()) {
+            let message = Message::RelayClaimTimeWin { game_id: game.id_on_host().to_string(), player_id };
+            return self.relay_game_action(&game, message);
+        }
+
+        // Check if game has a clock
         let clock = match &game.clock {
             Some(c) => c,
-            None => return OperationResult::Error { message: "Not a timed game".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::InvalidState, message: "Not a timed game".to_string() },
+        };
+
+        // Check if opponent has timed out
+        if let Some(timed_out_player) = clock.timed_out(timestamp_ms) {
+            // Verify the claimant is not the one who timed out
+            let claimant_timed_out = match timed_out_player {
+                Turn::Red => is_red,
+                Turn::Black => is_black,
+            };
+
+            if claimant_timed_out {
+                return OperationResult::Error { code: ErrorCode::TimeExpired, message: "You timed out, not your opponent".to_string() };
+            }
+
+            // End game with claimant winning
+            game.status = GameStatus::Finished;
+            game.result = Some(match timed_out_player {
+                Turn::Red => GameResult::BlackWins,
+                Turn::Black => GameResult::RedWins,
+            });
+            game.updated_at = timestamp;
+
+            if let Err(e) = self.state.save_game(game.clone()).await {
+                return OperationResult::Error { code: ErrorCode::Storage, message: e };
+            }
+
+            if let Some(result) = game.result {
+                self.finish_game(&game, result).await;
+            }
+
+            // Update tournament if this is a tournament game
+            self.handle_tournament_game_finished(&game).await;
+
+            OperationResult::TimeWinClaimed { game_id }
+        } else {
+            OperationResult::Error { code: ErrorCode::InvalidState, message: "Opponent has not timed out".to_string() }
+        }
+    }
+
+    // ========================================================================
+    // SPECTATOR OPERATIONS
+    // ========================================================================
+
+    async fn watch_game(&mut self, game_id: String, player_id: String) -> OperationResult {
+        let mut game = match self.state.get_game(&game_id).await {
+            Some(g) => g,
+            None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
+        };
+
+        if game.is_over() {
+            return OperationResult::Error { code: ErrorCode::GameNotActive, message: "Game already finished".to_string() };
+        }
+        if game.red_player.as_deref() == Some(player_id.as_str())
+            || game.black_player.as_deref() == Some(player_id.as_str())
+        {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Players cannot watch their own game".to_string() };
+        }
+        if game.spectators.contains(&player_id) {
+            return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Already watching this game".to_string() };
+        }
+        if game.spectators.len() >= self.state.game_config.get().max_spectators as usize {
+            return OperationResult::Error { code: ErrorCode::LimitReached, message: "Too many spectators".to_string() };
+        }
+
+        game.spectators.push(player_id.clone());
+        game.spectator_count = game.spectators.len() as u32;
+        let spectator_count = game.spectator_count;
+
+        if let Err(e) = self.state.save_game(game).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        if let Err(e) = self.state.add_watched_game(&player_id, &game_id).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+
+        OperationResult::GameWatched { game_id, spectator_count }
+    }
+
+    async fn unwatch_game(&mut self, game_id: String, player_id: String) -> OperationResult {
+        let mut game = match self.state.get_game(&game_id).await {
+            Some(g) => g,
+            None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
+        };
+
+        if !game.spectators.contains(&player_id) {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Not watching this game".to_string() };
+        }
+
+        game.spectators.retain(|spectator| spectator != &player_id);
+        game.spectator_count = game.spectators.len() as u32;
+        let spectator_count = game.spectator_count;
+
+        if let Err(e) = self.state.save_game(game).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        if let Err(e) = self.state.remove_watched_game(&player_id, &game_id).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+
+        OperationResult::GameUnwatched { game_id, spectator_count }
+    }
+
+    // ========================================================================
+    // GAME CHAT
+    // ========================================================================
+
+    async fn send_game_message(&mut self, game_id: String, text: String, player_id: String) -> OperationResult {
+        let game = match self.state.get_game(&game_id).await {
+            Some(g) => g,
+            None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
+        };
+
+        if game.status != GameStatus::Active {
+            return OperationResult::Error { code: ErrorCode::GameNotActive, message: "Game not active".to_string() };
+        }
+        if game.red_player.as_deref() != Some(player_id.as_str())
+            && game.black_player.as_deref() != Some(player_id.as_str())
+        {
+            return OperationResult::Error { code: ErrorCode::NotInGame, message: "Not in this game".to_string() };
+        }
+
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Message cannot be empty".to_string() };
+        }
+        if text.chars().count() > MAX_CHAT_MESSAGE_LEN {
+            return OperationResult::Error {
+                code: ErrorCode::InvalidInput,
+                message: format!("Message cannot exceed {} characters", MAX_CHAT_MESSAGE_LEN),
+            };
+        }
+
+        let message = ChatMessage {
+            sender: player_id,
+            text,
+            timestamp: self.runtime.system_time().micros(),
+        };
+        match self.state.append_chat_message(&game_id, message).await {
+            Ok(message_count) => OperationResult::GameMessageSent { game_id, message_count },
+            Err(e) => e.into(),
+        }
+    }
+
+    async fn send_emote(&mut self, game_id: String, emote: Emote, player_id: String) -> OperationResult {
+        let game = match self.state.get_game(&game_id).await {
+            Some(g) => g,
+            None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
+        };
+
+        if game.status != GameStatus::Active {
+            return OperationResult::Error { code: ErrorCode::GameNotActive, message: "Game not active".to_string() };
+        }
+        let sender = if game.red_player.as_deref() == Some(player_id.as_str()) {
+            Turn::Red
+        } else if game.black_player.as_deref() == Some(player_id.as_str()) {
+            Turn::Black
+        } else {
+            return OperationResult::Error { code: ErrorCode::NotInGame, message: "Not in this game".to_string() };
+        };
+
+        let record = EmoteRecord {
+            sender,
+            emote,
+            timestamp: self.runtime.system_time().micros(),
+        };
+        match self.state.append_emote(&game_id, record).await {
+            Ok(()) => OperationResult::EmoteSent { game_id, emote },
+            Err(e) => e.into(),
+        }
+    }
+
+    // ========================================================================
+    // GAME ANALYSIS
+    // ========================================================================
+
+    /// Replay a finished game from the starting position and classify every move
+    /// by how much evaluation it gave up against the engine's preferred move
+    async fn analyze_game(&mut self, game_id: String) -> OperationResult {
+        let game = match self.state.get_game(&game_id).await {
+            Some(g) => g,
+            None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
+        };
+
+        if game.status != GameStatus::Finished {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Game not finished".to_string() };
+        }
+        if self.state.get_analysis(&game_id).await.is_some() {
+            return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Game already analyzed".to_string() };
+        }
+
+        let moves = self.state.get_moves(&game, 0, usize::MAX).await;
+        let mut position = self.starting_position(&game);
+        let nodes_per_move = (ANALYSIS_NODES_PER_GAME / moves.len().max(1) as u64).min(ANALYSIS_NODES_PER_MOVE);
+
+        let mut annotations = Vec::with_capacity(moves.len());
+        let (mut blunders, mut mistakes, mut inaccuracies) = (0, 0, 0);
+        for (index, played) in moves.iter().enumerate() {
+            let side = position.current_turn;
+            let (eval_loss, best_move) = self.judge_move(&position, played, nodes_per_move);
+            let classification = MoveClassification::from_loss(eval_loss);
+            match classification {
+                MoveClassification::Blunder => blunders += 1,
+                MoveClassification::Mistake => mistakes += 1,
+                MoveClassification::Inaccuracy => inaccuracies += 1,
+                MoveClassification::Best | MoveClassification::Good => {}
+            }
+            // A blunder position, where the engine found a clearly better move, makes a puzzle
+            if let (MoveClassification::Blunder, Some(solution)) = (classification, &best_move) {
+                let puzzle = Puzzle {
+                    id: String::new(),
+                    board_state: position.board_state.clone(),
+                    turn: side,
+                    source_game_id: game_id.clone(),
+                    solution: solution.clone(),
+                };
+                if let Err(e) = self.state.add_puzzle(puzzle) {
+                    return OperationResult::Error { code: ErrorCode::Storage, message: e };
+                }
+            }
+            annotations.push(MoveAnnotation {
+                move_index: index as u32,
+                side,
+                classification,
+                symbol: classification.symbol().to_string(),
+                eval_loss,
+                best_move,
+            });
+
+            if self
+                .validate_and_execute_move(&mut position, played.from_row, played.from_col, played.to_row, played.to_col)
+                .is_err()
+            {
+                return OperationResult::Error { code: ErrorCode::InvalidState, message: "Move history does not replay".to_string() };
+            }
+            position.move_count += 1;
+        }
+
+        if game.is_rated && !game.has_ai_player() {
+            let mut flags = Vec::new();
+            for (player, side) in [(&game.red_player, Turn::Red), (&game.black_player, Turn::Black)] {
+                let classifications: Vec<MoveClassification> = annotations.iter()
+                    .filter(|annotation| annotation.side == side)
+                    .map(|annotation| annotation.classification)
+                    .collect();
+                if let (Some(player), true) = (player, is_engine_like(&classifications)) {
+                    flags.push(SuspicionFlag {
+                        player_id: player.clone(),
+                        reason: SuspicionReason::EngineAccuracy,
+                        flagged_at: self.runtime.system_time().micros(),
+                    });
+                }
+            }
+            if let Err(e) = self.state.flag_game(&game_id, flags).await {
+                return e.into();
+            }
+        }
+
+        match self.state.save_analysis(&game_id, annotations) {
+            Ok(()) => OperationResult::GameAnalyzed { game_id, blunders, mistakes, inaccuracies },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    /// Flag players of a rated game whose think times were suspiciously even.
+    /// A move's think time runs from the previous move, so the first move is not timed.
+    async fn check_move_timing(&mut self, game: &CheckersGame) {
+        if !game.is_rated || game.has_ai_player() {
+            return;
+        }
+        let moves = self.state.get_moves(game, 0, usize::MAX).await;
+        let mut position = self.starting_position(game);
+        let (mut red_times, mut black_times) = (Vec::new(), Vec::new());
+        let mut previous: Option<u64> = None;
+        for played in &moves {
+            if let Some(previous) = previous {
+                let think_time = played.timestamp.saturating_sub(previous);
+                match position.current_turn {
+                    Turn::Red => red_times.push(think_time),
+                    Turn::Black => black_times.push(think_time),
+                }
+            }
+            previous = Some(played.timestamp);
+            if self
+                .validate_and_execute_move(&mut position, played.from_row, played.from_col, played.to_row, played.to_col)
+                .is_err()
+            {
+                return;
+            }
+            position.move_count += 1;
+        }
+
+        let mut flags = Vec::new();
+        for (player, times) in [(&game.red_player, red_times), (&game.black_player, black_times)] {
+            if let (Some(player), true) = (player, is_uniform_timing(&times)) {
+                flags.push(SuspicionFlag {
+                    player_id: player.clone(),
+                    reason: SuspicionReason::UniformMoveTimes,
+                    flagged_at: game.updated_at,
+                });
+            }
+        }
+        let _ = self.state.flag_game(&game.id, flags).await;
+    }
+
+    /// Add a finished game's first moves to the opening explorer.
+    /// Games against the AI are left out so the explorer reflects human play.
+    async fn record_opening(&mut self, game: &CheckersGame, result: GameResult) {
+        if result == GameResult::InProgress || game.has_ai_player() {
+            return;
+        }
+
+        let moves = self.state.get_moves(game, 0, OPENING_EXPLORER_PLIES).await;
+        let mut position = self.starting_position(game);
+        let mut hash = position_hash(&position.board_state, position.current_turn);
+        for played in moves {
+            if self
+                .validate_and_execute_move(&mut position, played.from_row, played.from_col, played.to_row, played.to_col)
+                .is_err()
+            {
+                break;
+            }
+            let next_hash = position_hash(&position.board_state, position.current_turn);
+            let next = OpeningMove {
+                from_row: played.from_row,
+                from_col: played.from_col,
+                to_row: played.to_row,
+                to_col: played.to_col,
+                position_hash: next_hash.clone(),
+                games: 0,
+            };
+            let _ = self.state.record_opening_position(&hash, result, Some(next)).await;
+            hash = next_hash;
+        }
+        let _ = self.state.record_opening_position(&hash, result, None).await;
+    }
+
+    /// The game's starting position, for replaying its moves. Only the board and
+    /// the side to move are set, which keeps the copies made while searching small.
+    fn starting_position(&self, game: &CheckersGame) -> CheckersGame {
+        CheckersGame::new(game.id.clone(), None, PlayerType::Human)
+    }
+
+    // ========================================================================
+    // PUZZLE RUSH
+    // ========================================================================
+
+    async fn start_puzzle_rush(&mut self, player_id: String) -> OperationResult {
+        let now = self.runtime.system_time().micros();
+        if let Some(previous) = self.state.get_puzzle_rush(&player_id).await {
+            if let Err(e) = self.end_puzzle_rush(previous).await {
+                return OperationResult::Error { code: ErrorCode::Storage, message: e };
+            }
+        }
+
+        let Some(puzzle_id) = self.state.pick_puzzle_id(self.puzzle_seed(now, 0)) else {
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "No puzzles available yet".to_string() };
+        };
+        let session = PuzzleRushSession {
+            player_id,
+            started_at: now,
+            ends_at: now + PUZZLE_RUSH_DURATION_MICROS,
+            streak: 0,
+            current_puzzle_id: Some(puzzle_id.clone()),
+        };
+        let ends_at = session.ends_at;
+        match self.state.save_puzzle_rush(session) {
+            Ok(()) => OperationResult::PuzzleRushStarted { puzzle_id, ends_at },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn submit_puzzle_move(
+        &mut self,
+        from_row: u8,
+        from_col: u8,
+        to_row: u8,
+        to_col: u8,
+        player_id: String,
+    ) -> OperationResult {
+        let now = self.runtime.system_time().micros();
+        let mut session = match self.state.get_puzzle_rush(&player_id).await {
+            Some(s) if s.current_puzzle_id.is_some() => s,
+            _ => return OperationResult::Error { code: ErrorCode::InvalidState, message: "No puzzle rush in progress".to_string() },
+        };
+
+        let puzzle = match &session.current_puzzle_id {
+            Some(id) => self.state.get_puzzle(id).await,
+            None => None,
+        };
+        let solved = session.is_active(now)
+            && puzzle.is_some_and(|p| p.is_solved_by(from_row, from_col, to_row, to_col));
+        if !solved {
+            let streak = session.streak;
+            return match self.end_puzzle_rush(session).await {
+                Ok(best_streak) => OperationResult::PuzzleRushEnded { streak, best_streak },
+                Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+            };
+        }
+
+        session.streak += 1;
+        let Some(next_puzzle_id) = self.state.pick_puzzle_id(self.puzzle_seed(session.started_at, session.streak)) else {
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "No puzzles available yet".to_string() };
+        };
+        session.current_puzzle_id = Some(next_puzzle_id.clone());
+        let streak = session.streak;
+        if let Err(e) = self.state.save_puzzle_rush(session) {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        self.award_xp(&player_id, XP_PER_PUZZLE).await;
+        self.record_mission(&player_id, MissionGoal::SolvePuzzles, now).await;
+        OperationResult::PuzzleSolved { streak, next_puzzle_id }
+    }
+
+    /// Close a puzzle rush session, keeping its streak if it is the player's best.
+    /// Returns the player's best streak.
+    async fn end_puzzle_rush(&mut self, mut session: PuzzleRushSession) -> Result<u32, String> {
+        let mut stats = self.state.get_player_stats(&session.player_id).await;
+        if session.current_puzzle_id.is_some() && session.streak > stats.puzzle_rush_best {
+            stats.puzzle_rush_best = session.streak;
+            self.state.update_player_stats(stats.clone()).await?;
+        }
+        session.current_puzzle_id = None;
+        self.state.save_puzzle_rush(session)?;
+        Ok(stats.puzzle_rush_best)
+    }
+
+    /// Seed for picking the puzzle at a given point of a puzzle rush
+    fn puzzle_seed(&self, started_at: u64, streak: u32) -> u64 {
+        started_at
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(streak as u64 * 1442695040888963407)
+            >> 33
+    }
+
+    // ========================================================================
+    // AUTHENTICATION
+    // ========================================================================
+
+    /// Check that the operation's signer owns `player_id`.
+    /// This chain's ID belongs to the chain's owners. Another chain's ID is
+    /// bound to the signer of a message that chain sends for it, since the
+    /// sending chain checked its owner; any other player ID belongs to the
+    /// first signer that uses it.
+    async fn authenticate_player(&mut self, player_id: &str) -> Result<(), OperationError> {
+        let unauthenticated = |message: &str| OperationError::new(ErrorCode::Unauthenticated, message);
+        if player_id.is_empty() || player_id == "AI" {
+            return Err(unauthenticated("Invalid player ID"));
+        }
+        let owner = self.runtime
+            .authenticated_signer()
+            .ok_or_else(|| unauthenticated("Operation must be signed"))?;
+        if player_id == self.runtime.chain_id().to_string() {
+            return if self.runtime.chain_ownership().verify_owner(&owner) {
+                Ok(())
+            } else {
+                Err(unauthenticated("Player ID belongs to another signer"))
+            };
+        }
+        let signer = owner.to_string();
+        let from_own_chain = self.runtime
+            .message_origin_chain_id()
+            .is_some_and(|origin| origin.to_string() == player_id);
+        match self.state.get_player_signer(player_id).await {
+            Some(bound) if bound == signer => Ok(()),
+            _ if from_own_chain => self.state.bind_player_signer(player_id, signer).map_err(OperationError::storage),
+            Some(_) => Err(unauthenticated("Player ID belongs to another signer")),
+            None if player_id.parse::<ChainId>().is_ok() => Err(unauthenticated("Player ID belongs to another chain")),
+            None => self.state.bind_player_signer(player_id, signer).map_err(OperationError::storage),
+        }
+    }
+
+    // ========================================================================
+    // MODERATION
+    // ========================================================================
+
+    /// Check that the operation is signed by an admin
+    async fn require_admin(&mut self) -> Result<(), OperationError> {
+        let signer = self.runtime.authenticated_signer().map(|owner| owner.to_string());
+        match signer {
+            Some(signer) if self.state.is_admin(&signer).await => Ok(()),
+            _ => Err(OperationError::new(ErrorCode::NotAuthorized, "Only admins can do this")),
+        }
+    }
+
+    /// Check that the operation runs on the chain that created the application
+    fn require_creator_chain(&mut self) -> Result<(), OperationError> {
+        if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+            return Err(OperationError::new(ErrorCode::NotAuthorized, "Admins are managed from the creator chain"));
+        }
+        Ok(())
+    }
+
+    /// Check that the operation runs on the creator chain and is signed by an
+    /// admin or by an owner of the creator chain
+    async fn require_admin_manager(&mut self) -> Result<(), OperationError> {
+        self.require_creator_chain()?;
+        let not_authorized = || OperationError::new(ErrorCode::NotAuthorized, "Only admins can do this");
+        let owner = self.runtime.authenticated_signer().ok_or_else(not_authorized)?;
+        if self.state.is_admin(&owner.to_string()).await || self.runtime.chain_ownership().verify_owner(&owner) {
+            Ok(())
+        } else {
+            Err(not_authorized())
+        }
+    }
+
+    async fn add_admin(&mut self, owner: String) -> OperationResult {
+        if let Err(e) = self.require_admin_manager().await {
+            return e.into();
+        }
+        match self.state.add_admin(&owner) {
+            Ok(()) => OperationResult::AdminAdded { owner },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn remove_admin(&mut self, owner: String) -> OperationResult {
+        if let Err(e) = self.require_admin_manager().await {
+            return e.into();
+        }
+        match self.state.remove_admin(&owner) {
+            Ok(()) => OperationResult::AdminRemoved { owner },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn prune_indexes(&mut self) -> OperationResult {
+        if let Err(e) = self.require_admin().await {
+            return e.into();
+        }
+        let invite_codes = match self.state.prune_invite_codes().await {
+            Ok(removed) => removed,
+            Err(e) => return OperationResult::Error { code: ErrorCode::Storage, message: e },
+        };
+        match self.state.prune_watched_games().await {
+            Ok(watched_games) => OperationResult::IndexesPruned { invite_codes, watched_games },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn reset_rating(&mut self, target_id: String, category: RatingCategory) -> OperationResult {
+        if let Err(e) = self.require_admin().await {
+            return e.into();
+        }
+        match self.state.reset_rating(&target_id, category).await {
+            Ok(rating) => OperationResult::RatingReset { player_id: target_id, category, rating },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn force_end_game(&mut self, game_id: String, result: GameResult, apply_ratings: bool) -> OperationResult {
+        if let Err(e) = self.require_admin().await {
+            return e.into();
+        }
+        if result == GameResult::InProgress {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "A forced result must end the game".to_string() };
+        }
+
+        let mut game = match self.state.get_game(&game_id).await {
+            Some(g) => g,
+            None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
+        };
+        if game.status != GameStatus::Active {
+            return OperationResult::Error { code: ErrorCode::GameNotActive, message: "Game not active".to_string() };
+        }
+
+        game.status = GameStatus::Finished;
+        game.result = Some(result);
+        game.updated_at = self.runtime.system_time().micros();
+        if let Err(e) = self.state.save_game(game.clone()).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+
+        if apply_ratings {
+            self.finish_game(&game, result).await;
+        } else {
+            let _ = self.settle_wager(&game.id).await;
+            self.emit_event(GameEvent::GameEnded { game_id: game.id.clone(), result });
+        }
+        // Let the tournament schedule move on
+        self.handle_tournament_game_finished(&game).await;
+
+        OperationResult::GameForceEnded { game_id, result }
+    }
+
+    /// A wager game that still holds stakes, for refund and dispute operations
+    async fn unsettled_wager_game(&mut self, game_id: &str) -> Result<CheckersGame, OperationResult> {
+        let Some(game) = self.state.get_game(game_id).await else {
+            return Err(OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() });
+        };
+        if game.wager.as_ref().is_none_or(|wager| wager.settled) {
+            return Err(OperationResult::Error { code: ErrorCode::InvalidState, message: "No unsettled wager on this game".to_string() });
+        }
+        Ok(game)
+    }
+
+    async fn claim_wager_refund(&mut self, game_id: String, player_id: String) -> OperationResult {
+        let mut game = match self.unsettled_wager_game(&game_id).await {
+            Ok(game) => game,
+            Err(result) => return result,
+        };
+        let Some(wager) = &game.wager else {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "No unsettled wager on this game".to_string() };
+        };
+        if !wager.has_stake(&player_id) {
+            return OperationResult::Error { code: ErrorCode::NotInGame, message: "You have no stake in this game".to_string() };
+        }
+        if wager.disputed_by.is_some() {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Wager is disputed; an admin will settle it".to_string() };
+        }
+
+        let now = self.runtime.system_time().micros();
+        match game.status {
+            GameStatus::Pending if game.awaiting_color_reveal() => {
+                return OperationResult::Error { code: ErrorCode::InvalidState, message: "The colors are still being settled".to_string() };
+            }
+            GameStatus::Pending => {
+                if let Err(e) = self.withdraw_seek(game).await {
+                    return OperationResult::Error { code: ErrorCode::Storage, message: e };
+                }
+            }
+            GameStatus::Active if now >= game.updated_at.saturating_add(WAGER_INACTIVITY_REFUND_MICROS) => {
+                game.status = GameStatus::Cancelled;
+                game.result = None;
+                game.updated_at = now;
+                self.pay_wager(&mut game, None);
+                if let Err(e) = self.state.save_game(game).await {
+                    return OperationResult::Error { code: ErrorCode::Storage, message: e };
+                }
+            }
+            GameStatus::Finished if wager.payable(now) => {
+                if let Err(e) = self.settle_wager(&game_id).await {
+                    return OperationResult::Error { code: ErrorCode::Storage, message: e };
+                }
+                return OperationResult::WagerSettled { game_id };
+            }
+            GameStatus::Finished => {
+                return OperationResult::Error { code: ErrorCode::InvalidState, message: "The dispute window is still open".to_string() };
+            }
+            _ => return OperationResult::Error { code: ErrorCode::InvalidState, message: "Game is still being played".to_string() },
+        }
+        OperationResult::WagerRefunded { game_id }
+    }
+
+    async fn dispute_wager(&mut self, game_id: String, reason: String, player_id: String) -> OperationResult {
+        let mut game = match self.unsettled_wager_game(&game_id).await {
+            Ok(game) => game,
+            Err(result) => return result,
+        };
+        let reason = reason.trim().to_string();
+        if reason.is_empty() || reason.chars().count() > MAX_REPORT_REASON_LEN {
+            return OperationResult::Error {
+                code: ErrorCode::InvalidInput,
+                message: format!("Reason must be between 1 and {} characters", MAX_REPORT_REASON_LEN),
+            };
+        }
+        let now = self.runtime.system_time().micros();
+        let Some(wager) = &mut game.wager else {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "No unsettled wager on this game".to_string() };
+        };
+        if !wager.has_stake(&player_id) {
+            return OperationResult::Error { code: ErrorCode::NotInGame, message: "You have no stake in this game".to_string() };
+        }
+        if wager.disputed_by.is_some() {
+            return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Wager already disputed".to_string() };
+        }
+        if !wager.disputable(now) {
+            return OperationResult::Error {
+                code: ErrorCode::InvalidState,
+                message: "Only a finished game's result can be disputed, within a day of it ending".to_string(),
+            };
+        }
+        wager.disputed_by = Some(player_id);
+        wager.dispute_reason = Some(reason);
+        if let Err(e) = self.state.save_game(game).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        OperationResult::WagerDisputed { game_id }
+    }
+
+    async fn resolve_wager_dispute(&mut self, game_id: String, winner: Option<String>) -> OperationResult {
+        if let Err(e) = self.require_admin().await {
+            return e.into();
+        }
+        let mut game = match self.unsettled_wager_game(&game_id).await {
+            Ok(game) => game,
+            Err(result) => return result,
+        };
+        if game.wager.as_ref().is_none_or(|wager| wager.disputed_by.is_none()) {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Wager is not disputed".to_string() };
+        }
+        if winner.as_ref().is_some_and(|winner| game.wager.as_ref().is_none_or(|wager| !wager.has_stake(winner))) {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Winner has no stake in this game".to_string() };
+        }
+        self.pay_wager(&mut game, winner.as_deref());
+        if let Err(e) = self.state.save_game(game).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        OperationResult::WagerDisputeResolved { game_id, winner }
+    }
+
+    /// Send treasury funds to the configured treasury owner
+    async fn withdraw_treasury(&mut self, amount: Option<Amount>) -> OperationResult {
+        if let Err(e) = self.require_admin().await {
+            return e.into();
+        }
+        let Some(owner) = self.state.game_config.get().treasury_owner.as_deref().and_then(|owner| owner.parse().ok()) else {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "No treasury owner configured".to_string() };
+        };
+        let balance = *self.state.treasury_balance.get();
+        let amount = amount.unwrap_or(balance);
+        if amount.is_zero() || amount > balance {
+            return OperationResult::Error {
+                code: ErrorCode::InvalidInput,
+                message: format!("Can withdraw between 0 and {} from the treasury", balance),
+            };
+        }
+        self.state.treasury_balance.set(balance.saturating_sub(amount));
+        self.pay_from_escrow(owner, amount);
+        OperationResult::TreasuryWithdrawn { amount }
+    }
+
+    async fn report_player(
+        &mut self,
+        reported_id: String,
+        game_id: Option<String>,
+        reason: String,
+        player_id: String,
+    ) -> OperationResult {
+        if reported_id == player_id {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Cannot report yourself".to_string() };
+        }
+        let reason = reason.trim().to_string();
+        if reason.is_empty() || reason.chars().count() > MAX_REPORT_REASON_LEN {
+            return OperationResult::Error {
+                code: ErrorCode::InvalidInput,
+                message: format!("Reason must be 1 to {} characters", MAX_REPORT_REASON_LEN),
+            };
+        }
+        if let Some(game_id) = &game_id {
+            let Some(game) = self.state.get_game(game_id).await else {
+                return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() };
+            };
+            let reported = Some(reported_id.as_str());
+            if game.red_player.as_deref() != reported && game.black_player.as_deref() != reported {
+                return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Reported player is not in this game".to_string() };
+            }
+        }
+
+        let report_id = self.state.generate_report_id();
+        let report = Report {
+            id: report_id.clone(),
+            reporter: player_id,
+            reported_id,
+            game_id,
+            reason,
+            status: ReportStatus::Open,
+            created_at: self.runtime.system_time().micros(),
+            resolved_by: None,
+            resolution_note: None,
+            resolved_at: None,
+        };
+        match self.state.save_report(report) {
+            Ok(()) => OperationResult::PlayerReported { report_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn resolve_report(&mut self, report_id: String, dismiss: bool, note: String) -> OperationResult {
+        if let Err(e) = self.require_admin().await {
+            return e.into();
+        }
+        let mut report = match self.state.get_report(&report_id).await {
+            Some(r) => r,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Report not found".to_string() },
+        };
+        if report.status != ReportStatus::Open {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Report already closed".to_string() };
+        }
+
+        report.status = if dismiss { ReportStatus::Dismissed } else { ReportStatus::Resolved };
+        report.resolved_by = self.runtime.authenticated_signer().map(|owner| owner.to_string());
+        report.resolution_note = Some(note.trim().chars().take(MAX_REPORT_REASON_LEN).collect());
+        report.resolved_at = Some(self.runtime.system_time().micros());
+        let status = report.status;
+        match self.state.save_report(report) {
+            Ok(()) => OperationResult::ReportClosed { report_id, status },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    /// Ban a player, taking them out of the queue and closing their open invitations
+    async fn ban_player(&mut self, target_id: String, reason: String) -> OperationResult {
+        if let Err(e) = self.require_admin().await {
+            return e.into();
+        }
+        let ban = PlayerBan {
+            player_id: target_id.clone(),
+            reason: reason.trim().chars().take(MAX_REPORT_REASON_LEN).collect(),
+            banned_at: self.runtime.system_time().micros(),
+        };
+        if let Err(e) = self.state.ban_player(ban) {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        if let Err(e) = self.state.leave_queue(&target_id).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        self.close_open_invitations(&target_id, "").await;
+        OperationResult::PlayerBanned { player_id: target_id }
+    }
+
+    async fn unban_player(&mut self, target_id: String) -> OperationResult {
+        if let Err(e) = self.require_admin().await {
+            return e.into();
+        }
+        match self.state.unban_player(&target_id) {
+            Ok(()) => OperationResult::PlayerUnbanned { player_id: target_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    // ========================================================================
+    // USERNAMES
+    // ========================================================================
+
+    async fn register_username(&mut self, username: String, player_id: String) -> OperationResult {
+        let username = username.trim().to_string();
+        if let Err(e) = validate_username(&username) {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: e };
+        }
+        match self.state.register_username(&player_id, &username).await {
+            Ok(()) => OperationResult::UsernameRegistered { username },
+            Err(e) => e.into(),
+        }
+    }
+
+    fn update_profile(
+        &mut self,
+        bio: Option<String>,
+        country: Option<String>,
+        avatar_hash: Option<String>,
+        player_id: String,
+    ) -> OperationResult {
+        let profile = PlayerProfile {
+            player_id,
+            bio: bio.map(|bio| bio.trim().to_string()).unwrap_or_default(),
+            country: country.map(|country| country.trim().to_uppercase()).filter(|c| !c.is_empty()),
+            avatar_hash: avatar_hash.map(|hash| hash.trim().to_lowercase()).filter(|h| !h.is_empty()),
+            updated_at: self.runtime.system_time().micros(),
+        };
+        if let Err(e) = profile.validate() {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: e };
+        }
+        match self.state.save_profile(profile) {
+            Ok(()) => OperationResult::ProfileUpdated,
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    fn update_settings(
+        &mut self,
+        auto_decline_draws: Option<bool>,
+        friends_only_challenges: Option<bool>,
+        default_rated: Option<bool>,
+        preferred_color: Option<ColorPreference>,
+        player_id: String,
+    ) -> OperationResult {
+        let settings = PlayerSettings {
+            auto_decline_draws: auto_decline_draws.unwrap_or(false),
+            friends_only_challenges: friends_only_challenges.unwrap_or(false),
+            default_rated,
+            preferred_color,
+        };
+        match self.state.save_settings(&player_id, settings) {
+            Ok(()) => OperationResult::SettingsUpdated,
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    // ========================================================================
+    // FRIENDS
+    // ========================================================================
+
+    async fn send_friend_request(&mut self, friend_id: String, player_id: String) -> OperationResult {
+        if friend_id == player_id {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Cannot befriend yourself".to_string() };
+        }
+        if self.state.is_blocked_between(&player_id, &friend_id).await {
+            return OperationResult::Error { code: ErrorCode::Blocked, message: "Cannot befriend this player".to_string() };
+        }
+        if self.state.are_friends(&player_id, &friend_id).await {
+            return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Already friends".to_string() };
+        }
+
+        // Two players asking each other is as good as an accepted request
+        if self.state.has_friend_request(&player_id, &friend_id).await {
+            return self.accept_friend_request(friend_id, player_id).await;
+        }
+
+        let sent_at = self.runtime.system_time().micros();
+        match self.state.add_friend_request(&friend_id, &player_id, sent_at).await {
+            Ok(()) => OperationResult::FriendRequestSent { friend_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn accept_friend_request(&mut self, friend_id: String, player_id: String) -> OperationResult {
+        if !self.state.has_friend_request(&player_id, &friend_id).await {
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "No friend request from this player".to_string() };
+        }
+        if self.state.get_friends(&player_id).await.len() >= MAX_FRIENDS
+            || self.state.get_friends(&friend_id).await.len() >= MAX_FRIENDS
+        {
+            return OperationResult::Error { code: ErrorCode::LimitReached, message: "Friend list is full".to_string() };
+        }
+
+        if let Err(e) = self.state.remove_friend_request(&player_id, &friend_id).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        if let Err(e) = self.state.remove_friend_request(&friend_id, &player_id).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        match self.state.add_friendship(&player_id, &friend_id).await {
+            Ok(()) => OperationResult::FriendAdded { friend_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn remove_friend(&mut self, friend_id: String, player_id: String) -> OperationResult {
+        let is_friend = self.state.are_friends(&player_id, &friend_id).await;
+        let received = self.state.has_friend_request(&player_id, &friend_id).await;
+        let sent = self.state.has_friend_request(&friend_id, &player_id).await;
+        if !is_friend && !received && !sent {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Not friends".to_string() };
+        }
+
+        if is_friend {
+            if let Err(e) = self.state.remove_friendship(&player_id, &friend_id).await {
+                return OperationResult::Error { code: ErrorCode::Storage, message: e };
+            }
+        }
+        if received {
+            if let Err(e) = self.state.remove_friend_request(&player_id, &friend_id).await {
+                return OperationResult::Error { code: ErrorCode::Storage, message: e };
+            }
+        }
+        if sent {
+            if let Err(e) = self.state.remove_friend_request(&friend_id, &player_id).await {
+                return OperationResult::Error { code: ErrorCode::Storage, message: e };
+            }
+        }
+        OperationResult::FriendRemoved { friend_id }
+    }
+
+    // ========================================================================
+    // BLOCK LIST
+    // ========================================================================
+
+    /// Block a player, ending any friendship and cancelling challenges between the two
+    async fn block_player(&mut self, blocked_id: String, player_id: String) -> OperationResult {
+        if blocked_id == player_id {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Cannot block yourself".to_string() };
+        }
+        if self.state.has_blocked(&player_id, &blocked_id).await {
+            return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Player already blocked".to_string() };
+        }
+
+        if let Err(e) = self.state.block_player(&player_id, &blocked_id).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        if self.state.are_friends(&player_id, &blocked_id).await {
+            let _ = self.state.remove_friendship(&player_id, &blocked_id).await;
+        }
+        let _ = self.state.remove_friend_request(&player_id, &blocked_id).await;
+        let _ = self.state.remove_friend_request(&blocked_id, &player_id).await;
+
+        for mut challenge in self.state.get_pending_challenges(&player_id).await {
+            if challenge.challenger == blocked_id || challenge.opponent == blocked_id {
+                challenge.status = if challenge.challenger == player_id {
+                    ChallengeStatus::Cancelled
+                } else {
+                    ChallengeStatus::Declined
+                };
+                let _ = self.state.save_challenge(challenge).await;
+            }
+        }
+
+        OperationResult::PlayerBlocked { blocked_id }
+    }
+
+    async fn unblock_player(&mut self, blocked_id: String, player_id: String) -> OperationResult {
+        if !self.state.has_blocked(&player_id, &blocked_id).await {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Player not blocked".to_string() };
+        }
+        match self.state.unblock_player(&player_id, &blocked_id).await {
+            Ok(()) => OperationResult::PlayerUnblocked { blocked_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    // ========================================================================
+    // CHALLENGES
+    // ========================================================================
+
+    async fn challenge_player(
+        &mut self,
+        opponent_id: String,
+        time_control: TimeControl,
+        rated: bool,
+        color: ColorPreference,
+        player_id: String,
+    ) -> OperationResult {
+        if let Err(e) = self.check_time_control(&time_control) {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: e };
+        }
+        if opponent_id == player_id {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Cannot challenge yourself".to_string() };
+        }
+        if self.state.is_blocked_between(&player_id, &opponent_id).await {
+            return OperationResult::Error { code: ErrorCode::Blocked, message: "Cannot challenge this player".to_string() };
+        }
+        if self.state.get_settings(&opponent_id).await.friends_only_challenges
+            && !self.state.are_friends(&player_id, &opponent_id).await
+        {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "This player only accepts challenges from friends".to_string() };
+        }
+        let now = self.runtime.system_time().micros();
+        let already_challenged = self.state.get_pending_challenges(&player_id).await
+            .iter()
+            .any(|c| c.challenger == player_id && c.opponent == opponent_id && !c.is_expired(now));
+        if already_challenged {
+            return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Challenge already pending".to_string() };
+        }
+
+        let challenge = Challenge {
+            id: self.state.generate_challenge_id(),
+            challenger: player_id,
+            opponent: opponent_id,
+            time_control,
+            is_rated: rated,
+            color,
+            status: ChallengeStatus::Pending,
+            game_id: None,
+            created_at: now,
+            expires_at: now + CHALLENGE_TTL_MICROS,
+        };
+        let challenge_id = challenge.id.clone();
+        match self.state.save_challenge(challenge).await {
+            Ok(()) => OperationResult::ChallengeSent { challenge_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn accept_challenge(&mut self, challenge_id: String, player_id: String) -> OperationResult {
+        let mut challenge = match self.state.get_challenge(&challenge_id).await {
+            Some(c) => c,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Challenge not found".to_string() },
+        };
+
+        if challenge.opponent != player_id {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Challenge is not for you".to_string() };
+        }
+        if challenge.status != ChallengeStatus::Pending {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Challenge no longer pending".to_string() };
+        }
+        let timestamp = self.runtime.system_time().micros();
+        if challenge.is_expired(timestamp) {
+            challenge.status = ChallengeStatus::Expired;
+            let _ = self.state.save_challenge(challenge).await;
+            return OperationResult::Error { code: ErrorCode::Expired, message: "Challenge expired".to_string() };
+        }
+
+        let game_id = match self.start_arranged_game(
+            &challenge_id,
+            challenge.challenger.clone(),
+            challenge.color,
+            challenge.is_rated,
+            challenge.time_control,
+            player_id,
+        ).await {
+            Ok(game_id) => game_id,
+            Err(e) => return OperationResult::Error { code: ErrorCode::Storage, message: e },
+        };
+
+        challenge.status = ChallengeStatus::Accepted;
+        challenge.game_id = Some(game_id.clone());
+        let players = [challenge.challenger.clone(), challenge.opponent.clone()];
+        if let Err(e) = self.state.save_challenge(challenge).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        for player in &players {
+            self.close_open_invitations(player, &game_id).await;
+        }
+
+        OperationResult::ChallengeAccepted { challenge_id, game_id }
+    }
+
+    /// Create and start a game between the creator of a challenge or seek and the
+    /// player who accepted it. Returns the new game's ID.
+    /// A random color is tossed over the invitation and both players, all fixed
+    /// before it is accepted, so the acceptor can't steer it by when they accept.
+    async fn start_arranged_game(
+        &mut self,
+        invitation_id: &str,
+        creator: String,
+        color: ColorPreference,
+        is_rated: bool,
+        time_control: TimeControl,
+        opponent: String,
+    ) -> Result<String, String> {
+        let color = match color {
+            ColorPreference::Random if color_toss(&[&opponent, &creator, invitation_id]) => ColorPreference::Black,
+            ColorPreference::Random => ColorPreference::Red,
+            color => color,
+        };
+        let game_id = self.state.generate_game_id().await;
+        let timestamp = self.runtime.system_time().micros();
+        let mut game = CheckersGame::new_with_options(
+            game_id.clone(),
+            creator.clone(),
+            color,
+            is_rated,
+            Some(time_control),
+        );
+        game.created_at = timestamp;
+        self.seat_second_player(&mut game, opponent, timestamp);
+
+        let event = GameEvent::GameCreated {
+            game_id: game_id.clone(),
+            creator,
+            time_control: Some(time_control),
+            is_rated,
+        };
+        self.state.save_game(game).await?;
+        self.emit_event(event);
+        Ok(game_id)
+    }
+
+    async fn decline_challenge(&mut self, challenge_id: String, player_id: String) -> OperationResult {
+        let mut challenge = match self.state.get_challenge(&challenge_id).await {
+            Some(c) => c,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Challenge not found".to_string() },
+        };
+
+        if challenge.status != ChallengeStatus::Pending {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Challenge no longer pending".to_string() };
+        }
+        challenge.status = if challenge.opponent == player_id {
+            ChallengeStatus::Declined
+        } else if challenge.challenger == player_id {
+            ChallengeStatus::Cancelled
+        } else {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Not your challenge".to_string() };
+        };
+
+        match self.state.save_challenge(challenge).await {
+            Ok(()) => OperationResult::ChallengeDeclined { challenge_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    /// Once a player starts a game, cancel the challenges they sent, withdraw
+    /// their seeks and other open games, and take them out of the queue
+    async fn close_open_invitations(&mut self, player_id: &str, started_game_id: &str) {
+        if player_id == "AI" {
+            return;
+        }
+
+        let _ = self.state.leave_queue(player_id).await;
+
+        for mut challenge in self.state.get_pending_challenges(player_id).await {
+            if challenge.challenger == player_id {
+                challenge.status = ChallengeStatus::Cancelled;
+                let _ = self.state.save_challenge(challenge).await;
+            }
+        }
+
+        for seek in self.state.get_seeks().await {
+            if seek.player == player_id {
+                let _ = self.state.remove_seek(&seek.id);
+            }
+        }
+
+        let open_games = self.state
+            .get_player_games(player_id, Some(GameStatus::Pending), None, None, usize::MAX)
+            .await;
+        // A game an opponent has joined is settled by the color reveal, not withdrawn
+        for game in open_games {
+            if game.id != started_game_id && !game.awaiting_color_reveal() {
+                let _ = self.withdraw_seek(game).await;
+            }
+        }
+    }
+
+    /// Close an open game nobody joined.
+    /// It is cancelled rather than finished, so it never counts as a played game.
+    async fn withdraw_seek(&mut self, mut game: CheckersGame) -> Result<(), String> {
+        if game.awaiting_color_reveal() {
+            return Err("An opponent has joined; the game waits for the color reveal".to_string());
+        }
+        game.status = GameStatus::Cancelled;
+        game.result = None;
+        game.updated_at = self.runtime.system_time().micros();
+        let game_id = game.id.clone();
+        self.state.save_game(game).await?;
+        self.settle_wager(&game_id).await
+    }
+
+    /// Close challenges, open games, seeks and queue entries that outlived their TTL.
+    /// Each call handles a bounded batch so it always fits in one block.
+    async fn cleanup_expired(&mut self) -> OperationResult {
+        const CLEANUP_BATCH: usize = 50;
+        // Open games checked per call; they are checked oldest first
+        const CLEANUP_SCAN: usize = 4 * CLEANUP_BATCH;
+        let now = self.runtime.system_time().micros();
+
+        let mut challenges = 0;
+        for mut challenge in self.state.get_all_pending_challenges(CLEANUP_BATCH).await {
+            if challenge.is_expired(now) {
+                challenge.status = ChallengeStatus::Expired;
+                if self.state.save_challenge(challenge).await.is_ok() {
+                    challenges += 1;
+                }
+            }
+        }
+
+        let mut games = 0;
+        for game in self.state.get_expired_pending_games(now, CLEANUP_BATCH, CLEANUP_SCAN).await {
+            if self.withdraw_seek(game).await.is_ok() {
+                games += 1;
+            }
+        }
+
+        let mut seeks = 0;
+        for seek in self.state.get_seeks().await.into_iter().filter(|seek| seek.is_expired(now)).take(CLEANUP_BATCH) {
+            if self.state.remove_seek(&seek.id).is_ok() {
+                seeks += 1;
+            }
+        }
+
+        let queue_entries = self.state.expire_queue_entries(now, CLEANUP_BATCH).await.unwrap_or(0);
+
+        OperationResult::ExpiredCleaned { challenges, games, seeks, queue_entries }
+    }
+
+    async fn award_season_trophies(&mut self, season_id: u32) -> OperationResult {
+        let now = self.runtime.system_time().micros();
+        match self.state.award_season_trophies(season_id, now).await {
+            Ok(Some(trophies)) => OperationResult::SeasonTrophiesAwarded { season_id, trophies },
+            Ok(None) => OperationResult::SeasonTrophiesPending { season_id },
+            Err(e) => e.into(),
+        }
+    }
+
+    // ========================================================================
+    // SEEK BOARD
+    // ========================================================================
+
+    async fn post_seek(
+        &mut self,
+        time_control: TimeControl,
+        rated: bool,
+        color: ColorPreference,
+        min_rating: Option<u32>,
+        max_rating: Option<u32>,
+        player_id: String,
+    ) -> OperationResult {
+        if let Err(e) = self.check_time_control(&time_control) {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: e };
+        }
+        if let (Some(min), Some(max)) = (min_rating, max_rating) {
+            if min > max {
+                return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Minimum rating above maximum".to_string() };
+            }
+        }
+
+        let now = self.runtime.system_time().micros();
+        let open_seeks = self.state.get_seeks().await
+            .iter()
+            .filter(|seek| seek.player == player_id && !seek.is_expired(now))
+            .count();
+        if open_seeks >= MAX_SEEKS_PER_PLAYER {
+            return OperationResult::Error {
+                code: ErrorCode::LimitReached,
+                message: format!("Cannot have more than {} open seeks", MAX_SEEKS_PER_PLAYER),
+            };
+        }
+
+        let seek = Seek {
+            id: self.state.generate_seek_id(),
+            player: player_id,
+            time_control,
+            is_rated: rated,
+            color,
+            min_rating,
+            max_rating,
+            created_at: now,
+            expires_at: now + SEEK_TTL_MICROS,
+        };
+        let seek_id = seek.id.clone();
+        match self.state.save_seek(seek) {
+            Ok(()) => OperationResult::SeekPosted { seek_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn accept_seek(&mut self, seek_id: String, player_id: String) -> OperationResult {
+        let seek = match self.state.get_seek(&seek_id).await {
+            Some(s) => s,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Seek not found".to_string() },
+        };
+
+        if seek.player == player_id {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Cannot accept own seek".to_string() };
+        }
+        if seek.is_expired(self.runtime.system_time().micros()) {
+            let _ = self.state.remove_seek(&seek_id);
+            return OperationResult::Error { code: ErrorCode::Expired, message: "Seek expired".to_string() };
+        }
+        if self.state.is_blocked_between(&seek.player, &player_id).await {
+            return OperationResult::Error { code: ErrorCode::Blocked, message: "Cannot accept this player's seek".to_string() };
+        }
+        let rating = self.state.get_player_stats(&player_id).await.get_rating(&seek.time_control);
+        if !seek.accepts_rating(rating) {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Rating outside the seek's range".to_string() };
+        }
+
+        let poster = seek.player.clone();
+        let game_id = match self.start_arranged_game(
+            &seek_id,
+            seek.player,
+            seek.color,
+            seek.is_rated,
+            seek.time_control,
+            player_id.clone(),
+        ).await {
+            Ok(game_id) => game_id,
+            Err(e) => return OperationResult::Error { code: ErrorCode::Storage, message: e },
+        };
+        if let Err(e) = self.state.remove_seek(&seek_id) {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        self.close_open_invitations(&poster, &game_id).await;
+        self.close_open_invitations(&player_id, &game_id).await;
+
+        OperationResult::SeekAccepted { seek_id, game_id }
+    }
+
+    async fn cancel_seek(&mut self, seek_id: String, player_id: String) -> OperationResult {
+        let seek = match self.state.get_seek(&seek_id).await {
+            Some(s) => s,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Seek not found".to_string() },
+        };
+
+        if seek.player != player_id {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Not your seek".to_string() };
+        }
+        match self.state.remove_seek(&seek_id) {
+            Ok(()) => OperationResult::SeekCancelled { seek_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    // ========================================================================
+    // STUDY OPERATIONS
+    // ========================================================================
+
+    async fn create_study(&mut self, name: String, from_game_id: Option<String>, player_id: String) -> OperationResult {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Study name cannot be empty".to_string() };
+        }
+
+        let (board_state, turn) = match from_game_id {
+            Some(game_id) => match self.state.get_game(&game_id).await {
+                Some(game) => (game.board_state, game.current_turn),
+                None => return OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
+            },
+            None => (STARTING_BOARD.to_string(), Turn::Red),
+        };
+
+        let study_id = self.state.generate_study_id();
+        let now = self.runtime.system_time().micros();
+        let study = Study::new(study_id.clone(), name, player_id, board_state, turn, now);
+        match self.state.save_study(study).await {
+            Ok(()) => OperationResult::StudyCreated { study_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn add_study_member(&mut self, study_id: String, member_id: String, player_id: String) -> OperationResult {
+        let mut study = match self.state.get_study(&study_id).await {
+            Some(s) => s,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Study not found".to_string() },
+        };
+
+        if study.owner != player_id {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only the owner can add members".to_string() };
+        }
+        if study.is_member(&member_id) {
+            return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Already a member".to_string() };
+        }
+        if study.members.len() >= MAX_STUDY_MEMBERS {
+            return OperationResult::Error { code: ErrorCode::LimitReached, message: "Study is full".to_string() };
+        }
+
+        study.members.push(member_id);
+        self.save_study_update(study).await
+    }
+
+    async fn remove_study_member(&mut self, study_id: String, member_id: String, player_id: String) -> OperationResult {
+        let mut study = match self.state.get_study(&study_id).await {
+            Some(s) => s,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Study not found".to_string() },
+        };
+
+        // Members may leave on their own; only the owner can remove others
+        if study.owner != player_id && member_id != player_id {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only the owner can remove members".to_string() };
+        }
+        if member_id == study.owner {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "The owner cannot leave the study".to_string() };
+        }
+        if !study.is_member(&member_id) {
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "Not a member".to_string() };
+        }
+
+        study.members.retain(|m| m != &member_id);
+        if let Err(e) = self.state.remove_player_study(&member_id, &study_id).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        self.save_study_update(study).await
+    }
+
+    async fn add_study_move(
+        &mut self,
+        study_id: String,
+        node_id: u32,
+        (from_row, from_col, to_row, to_col): (u8, u8, u8, u8),
+        player_id: String,
+    ) -> OperationResult {
+        let mut study = match self.state.get_study(&study_id).await {
+            Some(s) => s,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Study not found".to_string() },
+        };
+        if !study.is_member(&player_id) {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Not a member of this study".to_string() };
+        }
+        let Some(node) = study.node(node_id) else {
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "Study position not found".to_string() };
+        };
+
+        // Moves are checked with the game rules on a scratch game set to the node's position
+        let mut position = CheckersGame::new(study_id.clone(), None, PlayerType::Human);
+        position.board_state = node.board_state.clone();
+        position.current_turn = node.turn;
+        let checkers_move = match self.validate_and_execute_move(&mut position, from_row, from_col, to_row, to_col) {
+            Ok(m) => m,
+            Err(e) => return e.into(),
+        };
+
+        let child = match study.add_node(node_id, checkers_move, position.board_state, position.current_turn) {
+            Ok(child) => child,
+            Err(e) => return e.into(),
+        };
+        study.current_node = child;
+        self.save_study_update(study).await
+    }
+
+    async fn comment_study_node(&mut self, study_id: String, node_id: u32, text: String, player_id: String) -> OperationResult {
+        let mut study = match self.state.get_study(&study_id).await {
+            Some(s) => s,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Study not found".to_string() },
+        };
+        if !study.is_member(&player_id) {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Not a member of this study".to_string() };
+        }
+
+        let text = text.trim().to_string();
+        if text.chars().count() > MAX_STUDY_COMMENT_LEN {
+            return OperationResult::Error {
+                code: ErrorCode::InvalidInput,
+                message: format!("Comment cannot exceed {} characters", MAX_STUDY_COMMENT_LEN),
+            };
+        }
+        let Some(node) = study.nodes.get_mut(node_id as usize) else {
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "Study position not found".to_string() };
+        };
+
+        // An empty comment clears the existing one
+        node.comment = if text.is_empty() { None } else { Some(text) };
+        study.current_node = node_id;
+        self.save_study_update(study).await
+    }
+
+    async fn set_study_position(&mut self, study_id: String, node_id: u32, player_id: String) -> OperationResult {
+        let mut study = match self.state.get_study(&study_id).await {
+            Some(s) => s,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Study not found".to_string() },
+        };
+        if !study.is_member(&player_id) {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Not a member of this study".to_string() };
+        }
+        if study.node(node_id).is_none() {
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "Study position not found".to_string() };
+        }
+
+        study.current_node = node_id;
+        self.save_study_update(study).await
+    }
+
+    /// Save an edited study, stamping the edit time
+    async fn save_study_update(&mut self, mut study: Study) -> OperationResult {
+        study.updated_at = self.runtime.system_time().micros();
+        let study_id = study.id.clone();
+        let node_id = study.current_node;
+        match self.state.save_study(study).await {
+            Ok(()) => OperationResult::StudyUpdated { study_id, node_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    // ========================================================================
+    // CLUBS
+    // ========================================================================
+
+    async fn create_club(&mut self, name: String, join_policy: ClubJoinPolicy, player_id: String) -> OperationResult {
+        let name = name.trim().to_string();
+        if name.is_empty() || name.chars().count() > MAX_CLUB_NAME_LEN {
+            return OperationResult::Error {
+                code: ErrorCode::InvalidInput,
+                message: format!("Club name must be 1-{} characters", MAX_CLUB_NAME_LEN),
+            };
+        }
+        if self.state.get_player_club_id(&player_id).await.is_some() {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Already in a club".to_string() };
+        }
+
+        let club_id = self.state.generate_club_id();
+        let now = self.runtime.system_time().micros();
+        let club = Club::new(club_id.clone(), name, player_id, join_policy, now);
+        match self.state.save_club(club).await {
+            Ok(()) => OperationResult::ClubCreated { club_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn join_club(&mut self, club_id: String, player_id: String) -> OperationResult {
+        let mut club = match self.state.get_club(&club_id).await {
+            Some(c) => c,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Club not found".to_string() },
+        };
+        match self.state.get_player_club_id(&player_id).await {
+            Some(current) if current == club_id => {
+                return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Already a member or waiting to join".to_string() };
+            }
+            Some(_) => return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Already in a club".to_string() },
+            None => {}
+        }
+        if club.members.len() >= MAX_CLUB_MEMBERS {
+            return OperationResult::Error { code: ErrorCode::LimitReached, message: "Club is full".to_string() };
+        }
+
+        let result = match club.join_policy {
+            ClubJoinPolicy::Open => {
+                club.members.push(player_id);
+                OperationResult::ClubJoined { club_id }
+            }
+            ClubJoinPolicy::Approval => {
+                club.join_requests.push(player_id);
+                OperationResult::ClubJoinRequested { club_id }
+            }
+        };
+        match self.state.save_club(club).await {
+            Ok(()) => result,
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    /// Leave a club, or withdraw a request to join it. The last member leaving
+    /// closes the club.
+    async fn leave_club(&mut self, club_id: String, player_id: String) -> OperationResult {
+        let mut club = match self.state.get_club(&club_id).await {
+            Some(c) => c,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Club not found".to_string() },
+        };
+        if !club.is_member(&player_id) && !club.join_requests.contains(&player_id) {
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "Not a member".to_string() };
+        }
+
+        club.join_requests.retain(|p| p != &player_id);
+        club.remove_member(&player_id);
+        if let Err(e) = self.state.clear_player_club(&player_id) {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        let saved = if club.members.is_empty() {
+            club.join_requests
+                .iter()
+                .try_for_each(|applicant| self.state.clear_player_club(applicant))
+                .and_then(|()| self.state.remove_club(&club_id))
+        } else {
+            self.state.save_club(club).await
+        };
+        match saved {
+            Ok(()) => OperationResult::ClubLeft { club_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn approve_club_member(&mut self, club_id: String, member_id: String, player_id: String) -> OperationResult {
+        let mut club = match self.state.get_club(&club_id).await {
+            Some(c) => c,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Club not found".to_string() },
+        };
+        if !club.is_admin(&player_id) {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only club admins can do this".to_string() };
+        }
+        if !club.join_requests.contains(&member_id) {
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "No request to join from this player".to_string() };
+        }
+        if club.members.len() >= MAX_CLUB_MEMBERS {
+            return OperationResult::Error { code: ErrorCode::LimitReached, message: "Club is full".to_string() };
+        }
+
+        club.join_requests.retain(|p| p != &member_id);
+        club.members.push(member_id.clone());
+        match self.state.save_club(club).await {
+            Ok(()) => OperationResult::ClubMemberApproved { club_id, member_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn kick_club_member(&mut self, club_id: String, member_id: String, player_id: String) -> OperationResult {
+        let mut club = match self.state.get_club(&club_id).await {
+            Some(c) => c,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Club not found".to_string() },
+        };
+        if !club.is_admin(&player_id) {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only club admins can do this".to_string() };
+        }
+        if member_id == player_id {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Use LeaveClub to leave the club".to_string() };
+        }
+        if club.is_admin(&member_id) && club.owner != player_id {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only the owner can remove admins".to_string() };
+        }
+        if !club.is_member(&member_id) && !club.join_requests.contains(&member_id) {
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "Not a member".to_string() };
+        }
+
+        club.join_requests.retain(|p| p != &member_id);
+        club.remove_member(&member_id);
+        if let Err(e) = self.state.clear_player_club(&member_id) {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        match self.state.save_club(club).await {
+            Ok(()) => OperationResult::ClubMemberKicked { club_id, member_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn set_club_admin(&mut self, club_id: String, member_id: String, admin: bool, player_id: String) -> OperationResult {
+        let mut club = match self.state.get_club(&club_id).await {
+            Some(c) => c,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Club not found".to_string() },
+        };
+        if club.owner != player_id {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only the club owner can do this".to_string() };
+        }
+        if member_id == club.owner {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "The owner is always an admin".to_string() };
+        }
+        if !club.is_member(&member_id) {
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "Not a member".to_string() };
+        }
+
+        club.admins.retain(|a| a != &member_id);
+        if admin {
+            club.admins.push(member_id.clone());
+        }
+        match self.state.save_club(club).await {
+            Ok(()) => OperationResult::ClubAdminSet { club_id, member_id, admin },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn challenge_club(
+        &mut self,
+        club_id: String,
+        opponent_club_id: String,
+        boards: u32,
+        time_control: TimeControl,
+        player_id: String,
+    ) -> OperationResult {
+        let (Some(club), Some(opponent)) =
+            (self.state.get_club(&club_id).await, self.state.get_club(&opponent_club_id).await)
+        else {
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "Club not found".to_string() };
+        };
+        if !club.is_admin(&player_id) {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only club admins can do this".to_string() };
+        }
+        if club.id == opponent.id {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "A club cannot challenge itself".to_string() };
+        }
+        if !(1..=MAX_CLUB_MATCH_BOARDS).contains(&boards) {
+            return OperationResult::Error {
+                code: ErrorCode::InvalidInput,
+                message: format!("Club matches are played over 1-{} boards", MAX_CLUB_MATCH_BOARDS),
+            };
+        }
+        if let Err(e) = self.check_time_control(&time_control) {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: e };
+        }
+        if club.members.len() < boards as usize || opponent.members.len() < boards as usize {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Club has fewer members than boards".to_string() };
+        }
+
+        let match_id = self.state.generate_club_match_id();
+        let club_match = ClubMatch {
+            id: match_id.clone(),
+            home_club: club_id,
+            away_club: opponent_club_id,
+            board_count: boards,
+            time_control,
+            status: ClubMatchStatus::Proposed,
+            boards: Vec::new(),
+            home_score: 0,
+            away_score: 0,
+            winner_club: None,
+            created_at: self.runtime.system_time().micros(),
+            finished_at: None,
+        };
+        match self.state.save_club_match(club_match).await {
+            Ok(()) => OperationResult::ClubMatchProposed { match_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    /// A club's strongest members for a time control, best first, leaving out
+    /// banned players
+    async fn club_lineup(&self, club: &Club, time_control: &TimeControl, boards: u32) -> Vec<String> {
+        let mut rated = Vec::new();
+        for member in &club.members {
+            if self.state.is_banned(member).await {
+                continue;
+            }
+            let rating = self.state.get_player_stats(member).await.get_rating(time_control);
+            rated.push((member.clone(), rating));
+        }
+        rated.sort_by_key(|(_, rating)| std::cmp::Reverse(*rating));
+        rated.into_iter().take(boards as usize).map(|(member, _)| member).collect()
+    }
+
+    async fn accept_club_match(&mut self, match_id: String, player_id: String) -> OperationResult {
+        let mut club_match = match self.state.get_club_match(&match_id).await {
+            Some(m) => m,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Club match not found".to_string() },
+        };
+        let (Some(home), Some(away)) =
+            (self.state.get_club(&club_match.home_club).await, self.state.get_club(&club_match.away_club).await)
+        else {
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "Club not found".to_string() };
+        };
+        if !away.is_admin(&player_id) {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only admins of the challenged club can accept".to_string() };
+        }
+        if club_match.status != ClubMatchStatus::Proposed {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Club match no longer proposed".to_string() };
+        }
+
+        let home_lineup = self.club_lineup(&home, &club_match.time_control, club_match.board_count).await;
+        let away_lineup = self.club_lineup(&away, &club_match.time_control, club_match.board_count).await;
+        if home_lineup.len() < club_match.board_count as usize || away_lineup.len() < club_match.board_count as usize {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Club has fewer members than boards".to_string() };
+        }
+
+        for (i, (home_player, away_player)) in home_lineup.iter().zip(&away_lineup).enumerate() {
+            if self.state.is_blocked_between(home_player, away_player).await {
+                return OperationResult::Error {
+                    code: ErrorCode::Blocked,
+                    message: format!("The players on board {} cannot play each other", i + 1),
+                };
+            }
+        }
+
+        let timestamp = self.runtime.system_time().micros();
+        let mut game_ids = Vec::new();
+        for (i, (home_player, away_player)) in home_lineup.into_iter().zip(away_lineup).enumerate() {
+            let board = i as u32 + 1;
+            let (red_player, black_player) = if board % 2 == 1 {
+                (home_player.clone(), away_player.clone())
+            } else {
+                (away_player.clone(), home_player.clone())
+            };
+            let game_id = self.state.generate_game_id().await;
+            // Rated once both players have moved; see rate_club_board_once_played()
+            let mut game = CheckersGame::new_with_options(
+                game_id.clone(),
+                red_player.clone(),
+                ColorPreference::Red,
+                false,
+                Some(club_match.time_control),
+            );
+            game.created_at = timestamp;
+            game.club_match_id = Some(match_id.clone());
+            self.seat_second_player(&mut game, black_player, timestamp);
+            if let Err(e) = self.state.save_game(game).await {
+                return OperationResult::Error { code: ErrorCode::Storage, message: e };
+            }
+            self.emit_event(GameEvent::GameCreated {
+                game_id: game_id.clone(),
+                creator: red_player,
+                time_control: Some(club_match.time_control),
+                is_rated: false,
+            });
+            club_match.boards.push(ClubMatchBoard {
+                board,
+                home_player,
+                away_player,
+                game_id: game_id.clone(),
+                result: None,
+            });
+            game_ids.push(game_id);
+        }
+
+        club_match.status = ClubMatchStatus::InProgress;
+        match self.state.save_club_match(club_match).await {
+            Ok(()) => OperationResult::ClubMatchStarted { match_id, game_ids },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn decline_club_match(&mut self, match_id: String, player_id: String) -> OperationResult {
+        let mut club_match = match self.state.get_club_match(&match_id).await {
+            Some(m) => m,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Club match not found".to_string() },
+        };
+        let mut is_admin = false;
+        for club_id in [&club_match.home_club, &club_match.away_club] {
+            is_admin |= self.state.get_club(club_id).await.is_some_and(|club| club.is_admin(&player_id));
+        }
+        if !is_admin {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only club admins can do this".to_string() };
+        }
+        if club_match.status != ClubMatchStatus::Proposed {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Club match no longer proposed".to_string() };
+        }
+
+        club_match.status = ClubMatchStatus::Declined;
+        match self.state.save_club_match(club_match).await {
+            Ok(()) => OperationResult::ClubMatchDeclined { match_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    /// Count a finished game towards the club match it is a board of
+    async fn record_club_match_board(&mut self, game: &CheckersGame, result: GameResult) {
+        let Some(match_id) = &game.club_match_id else { return };
+        let Some(mut club_match) = self.state.get_club_match(match_id).await else { return };
+        club_match.record_board(&game.id, result, self.runtime.system_time().micros());
+        let _ = self.state.save_club_match(club_match).await;
+    }
+
+    /// Show a finished game on the club page of each player who is in a club
+    async fn record_club_results(&mut self, game: &CheckersGame, result: GameResult) {
+        let (red_points, black_points) = match result {
+            GameResult::RedWins => (2, 0),
+            GameResult::BlackWins => (0, 2),
+            GameResult::Draw => (1, 1),
+            GameResult::InProgress => return,
         };
+        let now = self.runtime.system_time().micros();
+        let sides = [
+            (&game.red_player, &game.black_player, red_points),
+            (&game.black_player, &game.red_player, black_points),
+        ];
+        for (player, opponent, points) in sides {
+            let Some(player_id) = player else { continue };
+            let Some(mut club) = self.state.get_player_club(player_id).await else { continue };
+            club.record_result(ClubResult {
+                game_id: game.id.clone(),
+                player_id: player_id.clone(),
+                opponent: opponent.clone(),
+                points,
+                finished_at: now,
+            });
+            let _ = self.state.save_club(club).await;
+        }
+    }
 
-        // Check if opponent has timed out
-        if let Some(timed_out_player) = clock.timed_out(timestamp_ms) {
-            // Verify the claimant is not the one who timed out
-            let claimant_timed_out = match timed_out_player {
-                Turn::Red => is_red,
-                Turn::Black => is_black,
+    // ========================================================================
+    // MULTI-CHAIN PLAY
+    // ========================================================================
+    //
+    // A game joined from another chain through JoinRemoteGame is hosted on the
+    // chain it was created on, which keeps the authoritative copy. Each player's
+    // chain keeps a replica: moves and resignations made on a replica are relayed
+    // to the host with the player's signature, and the host sends the updated game
+    // back to both player chains.
+
+    /// Ask the host chain of a pending game to seat this player. The host replies
+    /// with the game once the player is seated; a refused join gets no reply.
+    fn join_remote_game(&mut self, host_chain: String, game_id: String, player_id: String) -> OperationResult {
+        let host = match host_chain.parse::<ChainId>() {
+            Ok(chain) => chain,
+            Err(_) => return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Invalid chain ID".to_string() },
+        };
+        if host == self.runtime.chain_id() {
+            return OperationResult::Error {
+                code: ErrorCode::NotAllowed,
+                message: "Game is hosted on this chain; join it directly".to_string(),
             };
+        }
+        self.runtime
+            .prepare_message(Message::JoinRequest { game_id: game_id.clone(), player_id })
+            .with_authentication()
+            .with_tracking()
+            .send_to(host);
+        OperationResult::RemoteJoinRequested { game_id: replica_game_id(&host.to_string(), &game_id) }
+    }
 
-            if claimant_timed_out {
-                return OperationResult::Error { message: "You timed out, not your opponent".to_string() };
+    fn host_chain_of(game: &CheckersGame) -> Result<ChainId, OperationError> {
+        game.host_chain
+            .as_deref()
+            .and_then(|host| host.parse::<ChainId>().ok())
+            .ok_or_else(|| OperationError::new(ErrorCode::InvalidInput, "Invalid chain ID"))
+    }
+
+    /// Send a move made on a replica to the game's host chain
+    fn relay_move(&mut self, game: &CheckersGame, player_id: String, from: (u8, u8), to: (u8, u8)) -> OperationResult {
+        let host = match Self::host_chain_of(game) {
+            Ok(host) => host,
+            Err(e) => return e.into(),
+        };
+        let move_number = game.move_count;
+        self.runtime
+            .prepare_message(Message::RelayMove {
+                game_id: game.id_on_host().to_string(),
+                player_id,
+                from_row: from.0,
+                from_col: from.1,
+                to_row: to.0,
+                to_col: to.1,
+                move_number,
+            })
+            .with_authentication()
+            .with_tracking()
+            .send_to(host);
+        OperationResult::MoveRelayed { game_id: game.id.clone(), move_number }
+    }
+
+    /// Send a resignation made on a replica to the game's host chain
+    fn relay_resign(&mut self, game: &CheckersGame, player_id: String) -> OperationResult {
+        let host = match Self::host_chain_of(game) {
+            Ok(host) => host,
+            Err(e) => return e.into(),
+        };
+        self.runtime
+            .prepare_message(Message::RelayResign { game_id: game.id_on_host().to_string(), player_id })
+            .with_authentication()
+            .with_tracking()
+            .send_to(host);
+        OperationResult::ResignationRelayed { game_id: game.id.clone() }
+    }
+
+    /// Send a draw action or time-win claim made on a replica to the game's host chain
+    fn relay_game_action(&mut self, game: &CheckersGame, message: Message) -> OperationResult {
+        let host = match Self::host_chain_of(game) {
+            Ok(host) => host,
+            Err(e) => return e.into(),
+        };
+        self.runtime
+            .prepare_message(message)
+            .with_authentication()
+            .with_tracking()
+            .send_to(host);
+        OperationResult::GameActionRelayed { game_id: game.id.clone() }
+    }
+
+    /// Send the authoritative copy of a hosted game, with its full move history,
+    /// to the chains of both players. Does nothing for single-chain games.
+    async fn sync_replicas(&mut self, game: &CheckersGame) {
+        let this_chain = self.runtime.chain_id().to_string();
+        if game.host_chain.as_deref() != Some(this_chain.as_str()) {
+            return;
+        }
+        let mut replica = game.clone();
+        replica.moves = self.state.get_moves(game, 0, usize::MAX).await;
+        for chain in [&game.red_chain, &game.black_chain].into_iter().flatten() {
+            if *chain == this_chain {
+                continue;
             }
+            if let Ok(chain_id) = chain.parse::<ChainId>() {
+                self.runtime
+                    .prepare_message(Message::SyncGameState { game: Box::new(replica.clone()) })
+                    .with_tracking()
+                    .send_to(chain_id);
+            }
+        }
+    }
 
-            // End game with claimant winning
-            game.status = GameStatus::Finished;
-            game.result = Some(match timed_out_player {
-                Turn::Red => GameResult::BlackWins,
-                Turn::Black => GameResult::RedWins,
-            });
-            game.updated_at = timestamp;
+    /// Seat a player who asked to join from another chain, turning the game into a
+    /// multi-chain game hosted here
+    async fn handle_join_request(&mut self, game_id: String, player_id: String) {
+        let Some(origin) = self.runtime.message_origin_chain_id() else { return };
+        if self.authenticate_player(&player_id).await.is_err() || self.state.is_banned(&player_id).await {
+            return;
+        }
+        // Stakes are taken from accounts on this chain, so wager games are joined here
+        if self.state.get_game(&game_id).await.is_none_or(|game| game.wager.is_some()) {
+            return;
+        }
+        let OperationResult::GameJoined { .. } = self.join_game(game_id.clone(), player_id.clone(), None).await else {
+            return;
+        };
+        let Some(mut game) = self.state.get_game(&game_id).await else { return };
+
+        let this_chain = self.runtime.chain_id().to_string();
+        let origin = origin.to_string();
+        game.host_chain = Some(this_chain.clone());
+        if game.red_player.as_deref() == Some(player_id.as_str()) {
+            game.red_chain = Some(origin);
+            game.black_chain = Some(this_chain);
+        } else {
+            game.red_chain = Some(this_chain);
+            game.black_chain = Some(origin);
+        }
+        if self.state.save_game(game.clone()).await.is_ok() {
+            self.sync_replicas(&game).await;
+        }
+    }
 
-            if let Err(e) = self.state.save_game(game.clone()).await {
-                return OperationResult::Error { message: e };
+    /// The hosted game a relayed message acts on, if the message comes from the
+    /// player's own chain and carries the player's signature
+    async fn relay_target(&mut self, game_id: &str, player_id: &str) -> Option<CheckersGame> {
+        let origin = self.runtime.message_origin_chain_id()?.to_string();
+        let game = self.state.get_game(game_id).await?;
+        let this_chain = self.runtime.chain_id().to_string();
+        if game.host_chain.as_deref() != Some(this_chain.as_str()) || game.chain_of(player_id) != Some(origin.as_str()) {
+            return None;
+        }
+        self.authenticate_player(player_id).await.ok()?;
+        Some(game)
+    }
+
+    /// Apply a relayed move. Moves made on an out-of-date replica, and moves the
+    /// host rejects, are dropped and the replicas get the current game instead.
+    async fn handle_relay_move(
+        &mut self,
+        game_id: String,
+        player_id: String,
+        from: (u8, u8),
+        to: (u8, u8),
+        move_number: u32,
+    ) {
+        let Some(game) = self.relay_target(&game_id, &player_id).await else { return };
+        if game.move_count == move_number && !self.state.is_banned(&player_id).await {
+            let result = self.make_move(game_id.clone(), from.0, from.1, to.0, to.1, player_id).await;
+            if !matches!(result, OperationResult::Error { .. }) {
+                return;
             }
+        }
+        if let Some(game) = self.state.get_game(&game_id).await {
+            self.sync_replicas(&game).await;
+        }
+    }
 
-            if let Some(result) = game.result {
-                let _ = self.state.record_game_result(&game, result).await;
+    async fn handle_relay_resign(&mut self, game_id: String, player_id: String) {
+        if self.relay_target(&game_id, &player_id).await.is_none() {
+            return;
+        }
+        if let OperationResult::Error { .. } = self.resign(game_id.clone(), player_id).await {
+            if let Some(game) = self.state.get_game(&game_id).await {
+                self.sync_replicas(&game).await;
             }
+        }
+    }
 
-            // Update tournament if this is a tournament game
-            self.handle_tournament_game_finished(&game).await;
+    /// Send the replicas the current game after the host rejects a relayed action
+    async fn resync_if_rejected(&mut self, game_id: &str, result: &OperationResult) {
+        if let OperationResult::Error { .. } = result {
+            if let Some(game) = self.state.get_game(game_id).await {
+                self.sync_replicas(&game).await;
+            }
+        }
+    }
 
-            OperationResult::TimeWinClaimed { game_id }
-        } else {
-            OperationResult::Error { message: "Opponent has not timed out".to_string() }
+    /// Store the host's copy of a multi-chain game, keyed by host chain and game ID.
+    /// Copies sent by any other chain, and copies older than the one already
+    /// stored, are ignored.
+    async fn handle_sync_game_state(&mut self, mut game: CheckersGame) {
+        let origin = self.runtime.message_origin_chain_id().map(|chain| chain.to_string());
+        let Some(host_chain) = game.host_chain.clone().filter(|_| game.host_chain == origin) else { return };
+        let host_game_id = std::mem::take(&mut game.id);
+        game.id = replica_game_id(&host_chain, &host_game_id);
+        game.host_game_id = Some(host_game_id);
+        if let Some(local) = self.state.get_game(&game.id).await {
+            if local.host_chain != game.host_chain
+                || local.is_over()
+                || local.move_count > game.move_count
+            {
+                return;
+            }
         }
+        let _ = self.state.save_game(game).await;
     }
 
     // ========================================================================
-    // MESSAGE HANDLERS FOR NEW MESSAGE TYPES
+    // GAME SUBSCRIPTIONS
+    // ========================================================================
+
+    async fn subscribe_to_games(&mut self, chain_id: String) -> OperationResult {
+        let chain = match chain_id.parse::<ChainId>() {
+            Ok(chain) => chain,
+            Err(_) => return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Invalid chain ID".to_string() },
+        };
+        if chain == self.runtime.chain_id() {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "Cannot subscribe to this chain".to_string() };
+        }
+        if self.state.is_subscribed_to_games(&chain_id).await {
+            return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Already subscribed".to_string() };
+        }
+        if let Err(e) = self.state.add_game_subscription(&chain_id) {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        let application_id = self.runtime.application_id().forget_abi();
+        self.runtime.subscribe_to_events(chain, application_id, StreamName::from(GAME_EVENT_STREAM));
+        OperationResult::GamesSubscribed { chain_id }
+    }
+
+    async fn unsubscribe_from_games(&mut self, chain_id: String) -> OperationResult {
+        if !self.state.is_subscribed_to_games(&chain_id).await {
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "Not subscribed".to_string() };
+        }
+        let Ok(chain) = chain_id.parse::<ChainId>() else {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Invalid chain ID".to_string() };
+        };
+        if let Err(e) = self.state.remove_game_subscription(&chain_id) {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        let application_id = self.runtime.application_id().forget_abi();
+        self.runtime.unsubscribe_from_events(chain, application_id, StreamName::from(GAME_EVENT_STREAM));
+        OperationResult::GamesUnsubscribed { chain_id }
+    }
+
+    // ========================================================================
+    // CROSS-APPLICATION CALLS
     // ========================================================================
 
-    async fn handle_match_found(
+    /// Start a game between two players for the application calling this one.
+    /// The game is only rated if the operation is signed for both players;
+    /// otherwise an application could put ratings at stake without their say.
+    async fn create_match(
         &mut self,
-        game_id: &str,
-        red_player: &str,
-        black_player: &str,
-        time_control: TimeControl,
-    ) {
-        // Check if game already exists
-        if self.state.get_game(game_id).await.is_some() {
-            return;
+        red_player: String,
+        black_player: String,
+        time_control: Option<TimeControl>,
+        rated: bool,
+        reference: Option<String>,
+    ) -> OperationResult {
+        let Some(owner_app) = self.runtime.authenticated_caller_id().map(|id| id.to_string()) else {
+            return OperationResult::Error {
+                code: ErrorCode::NotAuthorized,
+                message: "Only applications can create matches".to_string(),
+            };
+        };
+        if red_player == black_player || [&red_player, &black_player].iter().any(|p| p.is_empty() || *p == "AI") {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Invalid players".to_string() };
+        }
+        if reference.as_ref().is_some_and(|reference| reference.chars().count() > MAX_MATCH_REFERENCE_LEN) {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Reference is too long".to_string() };
+        }
+        if let Some(time_control) = &time_control {
+            if let Err(e) = self.check_time_control(time_control) {
+                return OperationResult::Error { code: ErrorCode::NotAllowed, message: e };
+            }
+        }
+        for player in [&red_player, &black_player] {
+            if self.state.is_banned(player).await {
+                return OperationResult::Error { code: ErrorCode::Banned, message: "Player is banned".to_string() };
+            }
         }
+        let rated = rated
+            && self.state.has_approved_match_app(&red_player, &owner_app).await
+            && self.state.has_approved_match_app(&black_player, &owner_app).await;
 
+        let game_id = self.state.generate_game_id().await;
         let timestamp = self.runtime.system_time().micros();
-        let timestamp_ms = timestamp / 1000;
-
-        // Create the game locally
-        let mut game = CheckersGame::new(
-            game_id.to_string(),
-            Some(red_player.to_string()),
-            PlayerType::Human,
+        let mut game = CheckersGame::new_with_options(
+            game_id.clone(),
+            red_player,
+            ColorPreference::Red,
+            rated,
+            time_control,
         );
-        game.black_player = Some(black_player.to_string());
-        game.black_player_type = PlayerType::Human;
-        game.status = GameStatus::Active;
         game.created_at = timestamp;
-        game.updated_at = timestamp;
+        game.owner_app = Some(owner_app.clone());
+        game.external_ref = reference;
+        self.seat_second_player(&mut game, black_player, timestamp);
+
+        if let Err(e) = self.state.save_game(game).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        self.emit_event(GameEvent::GameCreated {
+            game_id: game_id.clone(),
+            creator: owner_app,
+            time_control,
+            is_rated: rated,
+        });
+        OperationResult::MatchCreated { game_id, rated }
+    }
 
-        // Initialize and start the clock
-        let mut clock = Clock::new(time_control);
-        clock.start(timestamp_ms);
-        game.clock = Some(clock);
+    async fn approve_match_app(&mut self, app_id: String, player_id: String) -> OperationResult {
+        if app_id.is_empty() {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Invalid application ID".to_string() };
+        }
+        if self.state.has_approved_match_app(&player_id, &app_id).await {
+            return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Application already approved".to_string() };
+        }
+        match self.state.approve_match_app(&player_id, &app_id).await {
+            Ok(()) => OperationResult::MatchAppApproved { app_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
 
-        let _ = self.state.save_game(game).await;
+    async fn revoke_match_app(&mut self, app_id: String, player_id: String) -> OperationResult {
+        if !self.state.has_approved_match_app(&player_id, &app_id).await {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Application not approved".to_string() };
+        }
+        match self.state.revoke_match_app(&player_id, &app_id).await {
+            Ok(()) => OperationResult::MatchAppRevoked { app_id },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn get_game_result(&mut self, game_id: String) -> OperationResult {
+        match self.state.get_game(&game_id).await {
+            Some(game) => OperationResult::GameResultInfo { game_id, status: game.status, result: game.result },
+            None => OperationResult::Error { code: ErrorCode::GameNotFound, message: "Game not found".to_string() },
+        }
+    }
+
+    // ========================================================================
+    // MESSAGE HANDLERS FOR NEW MESSAGE TYPES
+    // ========================================================================
+
+    /// The hub chain matched a player of this chain. The game itself arrives just
+    /// before, as a SyncGameState message; this only announces the match, under
+    /// the ID the replica is stored under here.
+    fn handle_match_found(
+        &mut self,
+        game_id: &str,
+        red_player: &str,
+        black_player: &str,
+        time_control: TimeControl,
+        clock: Option<Clock>,
+    ) {
+        let origin = self.runtime.message_origin_chain_id().map(|chain| chain.to_string());
+        let Some(hub) = origin.filter(|origin| self.state.game_config.get().hub_chain_id.as_ref() == Some(origin)) else {
+            return;
+        };
+        self.emit_event(GameEvent::MatchFound {
+            game_id: replica_game_id(&hub, game_id),
+            red_player: red_player.to_string(),
+            black_player: black_player.to_string(),
+            time_control,
+            clock,
+        });
     }
 
     async fn handle_draw_offered(&mut self, game_id: &str, offered_by: Turn) {
@@ -1277,7 +4766,7 @@ impl CheckersContract {
                 game.draw_offer = DrawOfferState::None;
                 game.updated_at = self.runtime.system_time().micros();
                 let _ = self.state.save_game(game.clone()).await;
-                let _ = self.state.record_game_result(&game, GameResult::Draw).await;
+                self.finish_game(&game, GameResult::Draw).await;
                 // Note: Draws in tournaments are rare but if they happen, we don't advance anyone
                 // Tournament games should not allow draws - the match would need replay
             }
@@ -1288,6 +4777,7 @@ impl CheckersContract {
     // TOURNAMENT OPERATIONS
     // ========================================================================
 
+    #[allow(clippy::too_many_arguments)]
     async fn create_tournament(
         &mut self,
         name: String,
@@ -1295,12 +4785,66 @@ impl CheckersContract {
         max_players: u32,
         is_public: bool,
         scheduled_start: Option<u64>,
+        entry_fee: Option<Amount>,
+        payout_schedule: Option<Vec<u32>>,
+        format: Option<TournamentFormat>,
+        round_time_limit_minutes: Option<u32>,
+        description: Option<String>,
+        playoff: Option<PlayoffMode>,
+        best_of: Option<u32>,
+        best_of_all_rounds: bool,
+        third_place_match: bool,
         player_id: String,
     ) -> OperationResult {
+        if let Err(e) = self.check_time_control(&time_control) {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: e };
+        }
+        let description = description.map(|d| d.trim().to_string()).unwrap_or_default();
+        if description.chars().count() > MAX_TOURNAMENT_DESCRIPTION_LEN {
+            return OperationResult::Error {
+                code: ErrorCode::InvalidInput,
+                message: format!("Description cannot exceed {} characters", MAX_TOURNAMENT_DESCRIPTION_LEN),
+            };
+        }
+        let format = format.unwrap_or_default();
+        if playoff.is_some() && format != TournamentFormat::Swiss {
+            return OperationResult::Error {
+                code: ErrorCode::InvalidInput,
+                message: "Playoffs are only for Swiss tournaments".to_string(),
+            };
+        }
+        let best_of = best_of.filter(|games| *games > 1);
+        if let Some(games) = best_of {
+            if !format.is_elimination() {
+                return OperationResult::Error {
+                    code: ErrorCode::InvalidInput,
+                    message: "Best-of series are only for elimination tournaments".to_string(),
+                };
+            }
+            if games % 2 == 0 || games > MAX_SERIES_GAMES {
+                return OperationResult::Error {
+                    code: ErrorCode::InvalidInput,
+                    message: format!("Series must be an odd number of games up to {}", MAX_SERIES_GAMES),
+                };
+            }
+        }
+        if third_place_match && format != TournamentFormat::SingleElimination {
+            return OperationResult::Error {
+                code: ErrorCode::InvalidInput,
+                message: "Third-place matches are only for single elimination".to_string(),
+            };
+        }
+        let payout_schedule = payout_schedule.unwrap_or_else(|| vec![100]);
+        if let Err(e) = validate_payout_schedule(&payout_schedule) {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: e };
+        }
+        let entry_fee = entry_fee.filter(|fee| !fee.is_zero());
         // Validate max_players is within reasonable bounds
-        if max_players < 2 || max_players > 64 {
+        let max_allowed = self.state.game_config.get().max_tournament_players;
+        if !(2..=max_allowed).contains(&max_players) {
             return OperationResult::Error {
-                message: "Max players must be between 2 and 64".to_string(),
+                code: ErrorCode::InvalidInput,
+                message: format!("Max players must be between 2 and {}", max_allowed),
             };
         }
 
@@ -1318,14 +4862,14 @@ impl CheckersContract {
         // Calculate total rounds: log2(max_players)
         let total_rounds = (max_players as f64).log2() as u32;
 
-        let tournament = Tournament {
+        let mut tournament = Tournament {
             id: tournament_id.clone(),
             name,
             creator: creator.clone(),
             status: TournamentStatus::Registration,
             time_control,
             max_players,
-            registered_players: vec![creator], // Creator auto-joins
+            registered_players: vec![creator.clone()], // Creator auto-joins
             matches: Vec::new(),
             current_round: 0,
             total_rounds,
@@ -1336,34 +4880,49 @@ impl CheckersContract {
             is_public,
             invite_code: invite_code.clone(),
             scheduled_start,
-            format: TournamentFormat::Swiss,
+            format,
             participants: Vec::new(),
             rounds: Vec::new(),
             num_rounds: 0,
+            entry_fee,
+            prize_pool: Amount::ZERO,
+            payout_schedule,
+            entries: Vec::new(),
+            round_time_limit_minutes: round_time_limit_minutes.filter(|minutes| *minutes > 0),
+            organizers: Vec::new(),
+            description,
+            playoff,
+            best_of,
+            best_of_all_rounds,
+            third_place_match,
+            announcements: Vec::new(),
         };
+        if let Err(e) = self.pay_entry_fee(&mut tournament, &creator) {
+            return e.into();
+        }
 
-        if let Err(e) = self.state.save_tournament(tournament).await {
-            return OperationResult::Error { message: e };
+        if let Err(e) = self.save_tournament(tournament).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
         }
 
         // Save invite code index for private tournaments
         if let Some(code) = &invite_code {
             if let Err(e) = self.state.save_invite_code_index(code, &tournament_id).await {
-                return OperationResult::Error { message: e };
+                return OperationResult::Error { code: ErrorCode::Storage, message: e };
             }
         }
 
         OperationResult::TournamentCreated { tournament_id }
     }
 
-    /// Generate a 6-character alphanumeric invite code
-    fn generate_invite_code(&self, tournament_id: &str, timestamp: u64) -> String {
+    /// Generate a 6-character alphanumeric invite code for a private tournament or game
+    fn generate_invite_code(&self, id: &str, timestamp: u64) -> String {
         // Characters that are easy to read (no 0/O, 1/I/l confusion)
         const CHARS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
         let mut code = String::with_capacity(6);
 
-        // Mix tournament_id and timestamp for better uniqueness
-        let id_hash: u64 = tournament_id.bytes().fold(0u64, |acc, b| {
+        // Mix the ID and timestamp for better uniqueness
+        let id_hash: u64 = id.bytes().fold(0u64, |acc, b| {
             acc.wrapping_mul(31).wrapping_add(b as u64)
         });
         let mut seed = timestamp.wrapping_mul(id_hash);
@@ -1382,30 +4941,33 @@ impl CheckersContract {
 
         let mut tournament = match self.state.get_tournament(&tournament_id).await {
             Some(t) => t,
-            None => return OperationResult::Error { message: "Tournament not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::TournamentNotFound, message: "Tournament not found".to_string() },
         };
 
         // Only allow joining public tournaments via this method
         if !tournament.is_public {
-            return OperationResult::Error { message: "Private tournament - use invite code to join".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Private tournament - use invite code to join".to_string() };
         }
 
         if tournament.status != TournamentStatus::Registration {
-            return OperationResult::Error { message: "Tournament not accepting registrations".to_string() };
+            return OperationResult::Error { code: ErrorCode::RegistrationClosed, message: "Tournament not accepting registrations".to_string() };
         }
 
         if tournament.registered_players.contains(&player) {
-            return OperationResult::Error { message: "Already registered".to_string() };
+            return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Already registered".to_string() };
         }
 
         if tournament.registered_players.len() >= tournament.max_players as usize {
-            return OperationResult::Error { message: "Tournament is full".to_string() };
+            return OperationResult::Error { code: ErrorCode::TournamentFull, message: "Tournament is full".to_string() };
         }
 
+        if let Err(e) = self.pay_entry_fee(&mut tournament, &player) {
+            return e.into();
+        }
         tournament.registered_players.push(player);
 
-        if let Err(e) = self.state.save_tournament(tournament).await {
-            return OperationResult::Error { message: e };
+        if let Err(e) = self.save_tournament(tournament).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
         }
 
         OperationResult::TournamentJoined { tournament_id }
@@ -1417,33 +4979,36 @@ impl CheckersContract {
         // Look up tournament by invite code
         let mut tournament = match self.state.get_tournament_by_code(&invite_code).await {
             Some(t) => t,
-            None => return OperationResult::Error { message: "Invalid invite code".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Invalid invite code".to_string() },
         };
 
         // Verify this is a private tournament with matching code
         let code_upper = invite_code.to_uppercase();
         if tournament.is_public || tournament.invite_code.as_deref() != Some(code_upper.as_str()) {
-            return OperationResult::Error { message: "Invalid invite code".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotFound, message: "Invalid invite code".to_string() };
         }
 
         if tournament.status != TournamentStatus::Registration {
-            return OperationResult::Error { message: "Tournament not accepting registrations".to_string() };
+            return OperationResult::Error { code: ErrorCode::RegistrationClosed, message: "Tournament not accepting registrations".to_string() };
         }
 
         if tournament.registered_players.contains(&player) {
-            return OperationResult::Error { message: "Already registered".to_string() };
+            return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Already registered".to_string() };
         }
 
         if tournament.registered_players.len() >= tournament.max_players as usize {
-            return OperationResult::Error { message: "Tournament is full".to_string() };
+            return OperationResult::Error { code: ErrorCode::TournamentFull, message: "Tournament is full".to_string() };
         }
 
         let tournament_id = tournament.id.clone();
         let tournament_name = tournament.name.clone();
+        if let Err(e) = self.pay_entry_fee(&mut tournament, &player) {
+            return e.into();
+        }
         tournament.registered_players.push(player);
 
-        if let Err(e) = self.state.save_tournament(tournament).await {
-            return OperationResult::Error { message: e };
+        if let Err(e) = self.save_tournament(tournament).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
         }
 
         OperationResult::TournamentJoinedByCode { tournament_id, tournament_name }
@@ -1454,29 +5019,209 @@ impl CheckersContract {
 
         let mut tournament = match self.state.get_tournament(&tournament_id).await {
             Some(t) => t,
-            None => return OperationResult::Error { message: "Tournament not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::TournamentNotFound, message: "Tournament not found".to_string() },
         };
 
         if tournament.status != TournamentStatus::Registration {
-            return OperationResult::Error { message: "Cannot leave after tournament started".to_string() };
+            return OperationResult::Error { code: ErrorCode::RegistrationClosed, message: "Cannot leave after tournament started".to_string() };
         }
 
         if tournament.creator == player {
-            return OperationResult::Error { message: "Creator cannot leave tournament".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Creator cannot leave tournament".to_string() };
         }
 
         let original_len = tournament.registered_players.len();
         tournament.registered_players.retain(|p| p != &player);
 
         if tournament.registered_players.len() == original_len {
-            return OperationResult::Error { message: "Not registered in this tournament".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Not registered in this tournament".to_string() };
+        }
+        self.refund_entry_fee(&mut tournament, &player);
+
+        if let Err(e) = self.save_tournament(tournament).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+
+        OperationResult::TournamentLeft { tournament_id }
+    }
+
+    async fn withdraw_from_tournament(&mut self, tournament_id: String, player_id: String) -> OperationResult {
+        let tournament = match self.state.get_tournament(&tournament_id).await {
+            Some(t) => t,
+            None => return OperationResult::Error { code: ErrorCode::TournamentNotFound, message: "Tournament not found".to_string() },
+        };
+        if tournament.status != TournamentStatus::InProgress {
+            return OperationResult::Error {
+                code: ErrorCode::InvalidState,
+                message: "Tournament not in progress; use LeaveTournament during registration".to_string(),
+            };
+        }
+        if let Err(result) = self.withdraw_participant(tournament, &player_id).await {
+            return result;
+        }
+
+        OperationResult::TournamentWithdrawn { tournament_id }
+    }
+
+    /// Take a player out of a running tournament: they are left out of future
+    /// pairings, their pending matches are forfeited and a game in progress is
+    /// lost, as if resigned. Results already played stand.
+    async fn withdraw_participant(&mut self, mut tournament: Tournament, player_id: &str) -> Result<(), OperationResult> {
+        let participant = match tournament.participants.iter_mut().find(|p| p.player_id == player_id) {
+            Some(p) => p,
+            None => return Err(OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Not registered in this tournament".to_string() }),
+        };
+        if participant.withdrawn {
+            return Err(OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Already withdrawn".to_string() });
+        }
+        participant.withdrawn = true;
+
+        let running_game = tournament.matches
+            .iter()
+            .find(|m| {
+                m.status == MatchStatus::InProgress
+                    && (m.player1.as_deref() == Some(player_id) || m.player2.as_deref() == Some(player_id))
+            })
+            .and_then(|m| m.game_id.clone());
+        self.advance_to_next_round(&mut tournament);
+        if let Err(e) = self.save_tournament(tournament).await {
+            return Err(OperationResult::Error { code: ErrorCode::Storage, message: e });
+        }
+
+        if let Some(mut game) = match running_game {
+            Some(game_id) => self.state.get_game(&game_id).await,
+            None => None,
+        } {
+            if game.status == GameStatus::Active {
+                let result = if game.red_player.as_deref() == Some(player_id) {
+                    GameResult::BlackWins
+                } else {
+                    GameResult::RedWins
+                };
+                game.status = GameStatus::Finished;
+                game.result = Some(result);
+                game.updated_at = self.runtime.system_time().micros();
+                if let Err(e) = self.state.save_game(game.clone()).await {
+                    return Err(OperationResult::Error { code: ErrorCode::Storage, message: e });
+                }
+                self.finish_game(&game, result).await;
+                self.handle_tournament_game_finished(&game).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove a player from a tournament. Organizers may do this during
+    /// registration, refunding any entry fee; once play has started only admins
+    /// may, and the player is withdrawn with their results kept.
+    async fn kick_from_tournament(&mut self, tournament_id: String, kicked_id: String, player_id: String) -> OperationResult {
+        let mut tournament = match self.state.get_tournament(&tournament_id).await {
+            Some(t) => t,
+            None => return OperationResult::Error { code: ErrorCode::TournamentNotFound, message: "Tournament not found".to_string() },
+        };
+        let is_admin = self.require_admin().await.is_ok();
+
+        match tournament.status {
+            TournamentStatus::Registration => {
+                if !tournament.is_organizer(&player_id) && !is_admin {
+                    return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only organizers can remove players".to_string() };
+                }
+                if tournament.creator == kicked_id {
+                    return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Creator cannot be removed".to_string() };
+                }
+                let original_len = tournament.registered_players.len();
+                tournament.registered_players.retain(|p| p != &kicked_id);
+                if tournament.registered_players.len() == original_len {
+                    return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Not registered in this tournament".to_string() };
+                }
+                self.refund_entry_fee(&mut tournament, &kicked_id);
+                if let Err(e) = self.save_tournament(tournament).await {
+                    return OperationResult::Error { code: ErrorCode::Storage, message: e };
+                }
+            }
+            TournamentStatus::InProgress => {
+                // Results are at stake now, so organizers alone cannot remove anyone
+                if !is_admin {
+                    return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only admins can remove players once the tournament has started".to_string() };
+                }
+                if let Err(result) = self.withdraw_participant(tournament, &kicked_id).await {
+                    return result;
+                }
+            }
+            _ => {
+                return OperationResult::Error { code: ErrorCode::InvalidState, message: "Tournament is over".to_string() };
+            }
+        }
+
+        OperationResult::TournamentPlayerKicked { tournament_id, player_id: kicked_id }
+    }
+
+    async fn set_tournament_organizer(
+        &mut self,
+        tournament_id: String,
+        organizer_id: String,
+        organizer: bool,
+        player_id: String,
+    ) -> OperationResult {
+        let mut tournament = match self.state.get_tournament(&tournament_id).await {
+            Some(t) => t,
+            None => return OperationResult::Error { code: ErrorCode::TournamentNotFound, message: "Tournament not found".to_string() },
+        };
+        if tournament.creator != player_id {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only the creator can do this".to_string() };
+        }
+        if organizer_id == tournament.creator {
+            return OperationResult::Error { code: ErrorCode::NotAllowed, message: "The creator is always an organizer".to_string() };
+        }
+        if tournament.status == TournamentStatus::Finished || tournament.status == TournamentStatus::Cancelled {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Tournament is over".to_string() };
+        }
+
+        tournament.organizers.retain(|o| o != &organizer_id);
+        if organizer {
+            if tournament.organizers.len() >= MAX_TOURNAMENT_ORGANIZERS {
+                return OperationResult::Error { code: ErrorCode::LimitReached, message: "Too many organizers".to_string() };
+            }
+            tournament.organizers.push(organizer_id.clone());
+        }
+        match self.save_tournament(tournament).await {
+            Ok(()) => OperationResult::TournamentOrganizerSet { tournament_id, organizer_id, organizer },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
+    }
+
+    async fn post_tournament_announcement(&mut self, tournament_id: String, text: String, player_id: String) -> OperationResult {
+        let mut tournament = match self.state.get_tournament(&tournament_id).await {
+            Some(t) => t,
+            None => return OperationResult::Error { code: ErrorCode::TournamentNotFound, message: "Tournament not found".to_string() },
+        };
+        if !tournament.is_organizer(&player_id) {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only organizers can post announcements".to_string() };
         }
 
-        if let Err(e) = self.state.save_tournament(tournament).await {
-            return OperationResult::Error { message: e };
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            return OperationResult::Error { code: ErrorCode::InvalidInput, message: "Announcement cannot be empty".to_string() };
+        }
+        if text.chars().count() > MAX_ANNOUNCEMENT_LEN {
+            return OperationResult::Error {
+                code: ErrorCode::InvalidInput,
+                message: format!("Announcement cannot exceed {} characters", MAX_ANNOUNCEMENT_LEN),
+            };
         }
 
-        OperationResult::TournamentLeft { tournament_id }
+        tournament.announcements.push(TournamentAnnouncement {
+            author: player_id,
+            text,
+            timestamp: self.runtime.system_time().micros(),
+        });
+        let overflow = tournament.announcements.len().saturating_sub(MAX_TOURNAMENT_ANNOUNCEMENTS);
+        tournament.announcements.drain(..overflow);
+        let announcement_count = tournament.announcements.len() as u32;
+        match self.save_tournament(tournament).await {
+            Ok(()) => OperationResult::TournamentAnnouncementPosted { tournament_id, announcement_count },
+            Err(e) => OperationResult::Error { code: ErrorCode::Storage, message: e },
+        }
     }
 
     async fn start_tournament(&mut self, tournament_id: String, player_id: String) -> OperationResult {
@@ -1484,21 +5229,22 @@ impl CheckersContract {
 
         let mut tournament = match self.state.get_tournament(&tournament_id).await {
             Some(t) => t,
-            None => return OperationResult::Error { message: "Tournament not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::TournamentNotFound, message: "Tournament not found".to_string() },
         };
 
-        if tournament.creator != player {
-            return OperationResult::Error { message: "Only creator can start tournament".to_string() };
+        if !tournament.is_organizer(&player) {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only organizers can start tournament".to_string() };
         }
 
         if tournament.status != TournamentStatus::Registration {
-            return OperationResult::Error { message: "Tournament already started".to_string() };
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Tournament already started".to_string() };
         }
 
         // Enforce minimum players: at least 25% of max_players
         let min_players = (tournament.max_players / 4).max(2) as usize;
         if tournament.registered_players.len() < min_players {
             return OperationResult::Error {
+                code: ErrorCode::InvalidState,
                 message: format!("Need at least {} players (25% of max) to start", min_players)
             };
         }
@@ -1511,6 +5257,7 @@ impl CheckersContract {
             let scheduled_start_micros = scheduled_start * 1000; // Convert ms to µs
             if timestamp < scheduled_start_micros {
                 return OperationResult::Error {
+                    code: ErrorCode::InvalidState,
                     message: "Tournament cannot start before scheduled time".to_string()
                 };
             }
@@ -1520,13 +5267,24 @@ impl CheckersContract {
         tournament.current_round = 1;
 
         // Generate bracket
-        self.generate_bracket(&mut tournament);
+        match tournament.format {
+            TournamentFormat::Swiss => self.generate_bracket(&mut tournament),
+            TournamentFormat::SingleElimination => {
+                let seeds = self.seed_by_rating(&tournament).await;
+                tournament.generate_elimination_bracket(&seeds);
+            }
+            TournamentFormat::DoubleElimination => {
+                let seeds = self.seed_by_rating(&tournament).await;
+                tournament.generate_double_elimination_bracket(&seeds);
+            }
+            TournamentFormat::RoundRobin => self.generate_round_robin(&mut tournament),
+        }
 
         // Process any byes immediately
         self.process_byes(&mut tournament);
 
-        if let Err(e) = self.state.save_tournament(tournament).await {
-            return OperationResult::Error { message: e };
+        if let Err(e) = self.save_tournament(tournament).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
         }
 
         OperationResult::TournamentStarted { tournament_id }
@@ -1543,6 +5301,7 @@ impl CheckersContract {
                 score: 0,
                 opponents: Vec::new(),
                 has_bye: false,
+                withdrawn: false,
             })
             .collect();
 
@@ -1574,6 +5333,14 @@ impl CheckersContract {
                 game_id: None,
                 winner: if is_bye { p1.clone() } else { None },
                 status,
+                bracket: BracketSide::Winners,
+                deadline: None,
+                double_forfeit: false,
+                ready_players: Vec::new(),
+                playoff: false,
+                player1_wins: 0,
+                player2_wins: 0,
+                series_draws: 0,
             });
 
             // If bye, mark participant
@@ -1598,17 +5365,128 @@ impl CheckersContract {
         });
     }
 
-    fn get_seed_order(&self, bracket_size: usize) -> Vec<usize> {
-        match bracket_size {
-            4 => vec![0, 3, 1, 2],
-            8 => vec![0, 7, 3, 4, 1, 6, 2, 5],
-            16 => vec![0, 15, 7, 8, 3, 12, 4, 11, 1, 14, 6, 9, 2, 13, 5, 10],
-            32 => vec![
-                0, 31, 15, 16, 7, 24, 8, 23, 3, 28, 12, 19, 4, 27, 11, 20,
-                1, 30, 14, 17, 6, 25, 9, 22, 2, 29, 13, 18, 5, 26, 10, 21,
-            ],
-            _ => (0..bracket_size).collect(),
+    /// Registered players ordered strongest first by their rating for the
+    /// tournament's time control. Equal ratings keep registration order.
+    async fn seed_by_rating(&self, tournament: &Tournament) -> Vec<String> {
+        let mut rated = Vec::new();
+        for player_id in &tournament.registered_players {
+            let rating = self.state.get_player_stats(player_id).await.get_rating(&tournament.time_control);
+            rated.push((player_id.clone(), rating));
+        }
+        rated.sort_by_key(|(_, rating)| std::cmp::Reverse(*rating));
+        rated.into_iter().map(|(player_id, _)| player_id).collect()
+    }
+
+    /// Move to the next elimination round once every match in the current one is
+    /// done, and finish the tournament when the final has a winner and any
+    /// third-place match is settled
+    fn advance_elimination_round(&self, tournament: &mut Tournament) -> bool {
+        tournament.fill_bracket_matches();
+        tournament.sync_elimination_rounds();
+        let mut advanced = false;
+        // Walkovers can settle a whole round at once, so keep going while rounds are done
+        while tournament.rounds
+            .get(tournament.current_round as usize - 1)
+            .is_some_and(|round| round.completed)
+        {
+            advanced = true;
+            if tournament.current_round >= tournament.num_rounds {
+                let third_place_pending = tournament.bracket(BracketSide::ThirdPlace)
+                    .iter()
+                    .any(|m| !matches!(m.status, MatchStatus::Finished | MatchStatus::Bye));
+                if third_place_pending {
+                    break;
+                }
+                tournament.status = TournamentStatus::Finished;
+                tournament.winner = tournament.bracket(BracketSide::Winners).last().and_then(|m| m.winner.clone());
+                break;
+            }
+            tournament.current_round += 1;
+        }
+        advanced
+    }
+
+    // Round Robin
+
+    /// Lays out every round of an all-play-all from the Berger tables. Round 1 is
+    /// ready to play; later rounds stay Pending until advance_round_robin() opens them.
+    fn generate_round_robin(&self, tournament: &mut Tournament) {
+        tournament.participants = tournament.registered_players
+            .iter()
+            .map(|pid| SwissParticipant {
+                player_id: pid.clone(),
+                ..SwissParticipant::default()
+            })
+            .collect();
+
+        let players = &tournament.registered_players;
+        let mut rounds = Vec::new();
+        for (round_idx, pairs) in berger_pairings(players.len()).into_iter().enumerate() {
+            let round = round_idx as u32 + 1;
+            let matches: Vec<TournamentMatch> = pairs
+                .into_iter()
+                .enumerate()
+                .map(|(i, (white, black))| {
+                    let player1 = players.get(white).cloned();
+                    let player2 = black.and_then(|black| players.get(black).cloned());
+                    let status = match (round, &player2) {
+                        (_, None) => MatchStatus::Bye,
+                        (1, _) => MatchStatus::Ready,
+                        _ => MatchStatus::Pending,
+                    };
+                    TournamentMatch {
+                        id: format!("{}_r{}_m{}", tournament.id, round, i + 1),
+                        round,
+                        match_number: i as u32 + 1,
+                        winner: if player2.is_none() { player1.clone() } else { None },
+                        player1,
+                        player2,
+                        status,
+                        ..TournamentMatch::default()
+                    }
+                })
+                .collect();
+            rounds.push(TournamentRound { round_number: round, matches, completed: false });
+        }
+
+        tournament.num_rounds = rounds.len() as u32;
+        tournament.total_rounds = tournament.num_rounds;
+        tournament.matches = rounds.iter().flat_map(|round| round.matches.clone()).collect();
+        tournament.rounds = rounds;
+    }
+
+    /// Opens the next round-robin round once the current one is done, and finishes
+    /// the tournament after the last
+    fn advance_round_robin(&self, tournament: &mut Tournament) -> bool {
+        let current_round = tournament.current_round;
+        let round_done = tournament.matches
+            .iter()
+            .filter(|m| m.round == current_round)
+            .all(|m| matches!(m.status, MatchStatus::Finished | MatchStatus::Bye));
+        if !round_done {
+            return false;
+        }
+        if let Some(round) = tournament.rounds.get_mut(current_round as usize - 1) {
+            round.completed = true;
+        }
+
+        if current_round >= tournament.num_rounds {
+            tournament.status = TournamentStatus::Finished;
+            tournament.winner = tournament.final_standings().first().cloned();
+            return true;
+        }
+
+        let next_round = current_round + 1;
+        tournament.current_round = next_round;
+        let round_matches = tournament.matches
+            .iter_mut()
+            .chain(tournament.rounds.iter_mut().flat_map(|round| round.matches.iter_mut()))
+            .filter(|m| m.round == next_round && m.status == MatchStatus::Pending);
+        for m in round_matches {
+            m.status = MatchStatus::Ready;
         }
+        self.process_byes(tournament);
+        true
     }
 
     // Swiss Tournament Utility Functions
@@ -1644,7 +5522,7 @@ impl CheckersContract {
 
     fn generate_swiss_pairings(
         &self,
-        participants: &mut Vec<SwissParticipant>,
+        participants: &mut [SwissParticipant],
     ) -> Vec<(Option<String>, Option<String>)> {
         let mut pairings = Vec::new();
 
@@ -1654,14 +5532,15 @@ impl CheckersContract {
                 .then_with(|| a.player_id.cmp(&b.player_id))
         });
 
-        // Track who's been paired this round
-        let mut paired: Vec<bool> = vec![false; participants.len()];
+        // Track who's been paired this round; withdrawn players sit out for good
+        let mut paired: Vec<bool> = participants.iter().map(|p| p.withdrawn).collect();
+        let active = paired.iter().filter(|done| !**done).count();
 
         // Handle bye for odd number - give to lowest scorer without bye
         // BUG #17 FIX: Don't add score here - it will be added in process_byes()
-        if participants.len() % 2 == 1 {
+        if active % 2 == 1 {
             for i in (0..participants.len()).rev() {
-                if !participants[i].has_bye {
+                if !paired[i] && !participants[i].has_bye {
                     let bye_player = participants[i].player_id.clone();
                     pairings.push((Some(bye_player.clone()), Some(bye_player)));
                     participants[i].has_bye = true;
@@ -1699,12 +5578,7 @@ impl CheckersContract {
 
             // Fallback: allow repeat if no valid opponent
             if best_opponent.is_none() {
-                for j in (i + 1)..participants.len() {
-                    if !paired[j] {
-                        best_opponent = Some(j);
-                        break;
-                    }
-                }
+                best_opponent = ((i + 1)..participants.len()).find(|&j| !paired[j]);
             }
 
             if let Some(j) = best_opponent {
@@ -1725,7 +5599,7 @@ impl CheckersContract {
 
     fn record_swiss_result(
         &self,
-        participants: &mut Vec<SwissParticipant>,
+        participants: &mut [SwissParticipant],
         winner_id: &str,
         loser_id: &str,
         is_draw: bool,
@@ -1762,6 +5636,16 @@ impl CheckersContract {
 
         for (match_id, winner_id) in bye_matches {
             if let Some(winner) = winner_id {
+                if tournament.format.is_elimination() {
+                    if tournament.format == TournamentFormat::SingleElimination {
+                        self.advance_winner(tournament, &match_id, &winner);
+                    }
+                    if let Some(participant) = tournament.participants.iter_mut().find(|p| p.player_id == winner) {
+                        participant.score += 2;
+                    }
+                    continue;
+                }
+
                 // Update match status
                 if let Some(m) = tournament.matches.iter_mut().find(|m| m.id == match_id) {
                     m.winner = Some(winner.clone());
@@ -1778,11 +5662,14 @@ impl CheckersContract {
                     }
                 }
 
-                // Update participant score (Swiss scoring: bye = 2 points)
-                if let Some(participant) = tournament.participants.iter_mut()
-                    .find(|p| p.player_id == winner)
-                {
-                    participant.score += 2;
+                // Update participant score (Swiss scoring: bye = 2 points). In a round
+                // robin everyone sits out the same number of times, so byes score nothing.
+                if tournament.format == TournamentFormat::Swiss {
+                    if let Some(participant) = tournament.participants.iter_mut()
+                        .find(|p| p.player_id == winner)
+                    {
+                        participant.score += 2;
+                    }
                 }
             }
         }
@@ -1798,6 +5685,10 @@ impl CheckersContract {
             if m.status != MatchStatus::Bye {
                 m.status = MatchStatus::Finished;
             }
+            // Only winners bracket matches lead anywhere
+            if m.bracket != BracketSide::Winners {
+                return;
+            }
         }
 
         // Parse match_id to get round and match_number
@@ -1813,7 +5704,7 @@ impl CheckersContract {
         }
 
         // Find next round match
-        let next_match_num = (match_num + 1) / 2;
+        let next_match_num = match_num.div_ceil(2);
         let next_match_id = format!("{}_r{}_m{}", tournament.id, round + 1, next_match_num);
 
         if let Some(next_match) = tournament.matches.iter_mut().find(|m| m.id == next_match_id) {
@@ -1829,7 +5720,54 @@ impl CheckersContract {
         }
     }
 
+    /// Move the tournament on as far as results allow, forfeiting the matches of
+    /// withdrawn players as they come up
     fn advance_to_next_round(&self, tournament: &mut Tournament) -> bool {
+        let mut advanced = false;
+        loop {
+            advanced |= self.advance_round(tournament);
+            if !self.forfeit_withdrawn_matches(tournament) {
+                return advanced;
+            }
+        }
+    }
+
+    /// Settle ready matches involving a withdrawn player: the other player wins,
+    /// or both lose if they have both withdrawn. Returns whether any were settled.
+    fn forfeit_withdrawn_matches(&self, tournament: &mut Tournament) -> bool {
+        let withdrawn: Vec<String> = tournament.participants
+            .iter()
+            .filter(|p| p.withdrawn)
+            .map(|p| p.player_id.clone())
+            .collect();
+        if withdrawn.is_empty() || tournament.status != TournamentStatus::InProgress {
+            return false;
+        }
+        let is_withdrawn = |player: &Option<String>| player.as_ref().is_some_and(|p| withdrawn.contains(p));
+
+        let mut settled = false;
+        for idx in 0..tournament.matches.len() {
+            let m = &tournament.matches[idx];
+            if m.status != MatchStatus::Ready || !(is_withdrawn(&m.player1) || is_withdrawn(&m.player2)) {
+                continue;
+            }
+            let stays = [&m.player1, &m.player2].into_iter().find(|p| !is_withdrawn(p)).cloned().flatten();
+            match stays {
+                Some(winner_id) => self.award_match(tournament, idx, &winner_id),
+                None => self.double_forfeit_match(tournament, idx),
+            }
+            settled = true;
+        }
+        settled
+    }
+
+    fn advance_round(&self, tournament: &mut Tournament) -> bool {
+        match tournament.format {
+            TournamentFormat::SingleElimination => return self.advance_elimination_round(tournament),
+            TournamentFormat::DoubleElimination => return tournament.advance_double_elimination(),
+            TournamentFormat::RoundRobin => return self.advance_round_robin(tournament),
+            TournamentFormat::Swiss => {}
+        }
         let current_round = tournament.current_round as usize;
 
         // Check if current round is complete
@@ -1853,15 +5791,31 @@ impl CheckersContract {
                 );
 
                 if all_final_matches_done {
+                    let playoff_winner = match tournament.matches.iter().find(|m| m.playoff) {
+                        Some(m) if m.status != MatchStatus::Finished => return false,
+                        Some(m) => m.winner.clone(),
+                        None => {
+                            if let (Some(_), Some((p1, p2))) = (tournament.playoff, tournament.tied_for_first()) {
+                                let round = tournament.num_rounds + 1;
+                                tournament.matches.push(TournamentMatch {
+                                    id: format!("{}_playoff_m1", tournament.id),
+                                    round,
+                                    match_number: 1,
+                                    player1: Some(p1),
+                                    player2: Some(p2),
+                                    status: MatchStatus::Ready,
+                                    playoff: true,
+                                    ..TournamentMatch::default()
+                                });
+                                return true;
+                            }
+                            None
+                        }
+                    };
                     tournament.status = TournamentStatus::Finished;
 
-                    // Determine winner (highest score)
-                    if let Some(winner) = tournament.participants
-                        .iter()
-                        .max_by_key(|p| p.score)
-                    {
-                        tournament.winner = Some(winner.player_id.clone());
-                    }
+                    // Determine winner (playoff, then highest score, then tiebreaks)
+                    tournament.winner = playoff_winner.or_else(|| tournament.final_standings().first().cloned());
                     return true;
                 }
             }
@@ -1893,6 +5847,14 @@ impl CheckersContract {
                 game_id: None,
                 winner: if is_bye { p1.clone() } else { None },
                 status,
+                bracket: BracketSide::Winners,
+                deadline: None,
+                double_forfeit: false,
+                ready_players: Vec::new(),
+                playoff: false,
+                player1_wins: 0,
+                player2_wins: 0,
+                series_draws: 0,
             });
         }
 
@@ -1916,6 +5878,155 @@ impl CheckersContract {
         true
     }
 
+    /// Mark a player ready for their match, starting the game once both are
+    async fn ready_for_match(&mut self, tournament_id: String, match_id: String, player_id: String) -> OperationResult {
+        let mut tournament = match self.state.get_tournament(&tournament_id).await {
+            Some(t) => t,
+            None => return OperationResult::Error { code: ErrorCode::TournamentNotFound, message: "Tournament not found".to_string() },
+        };
+        let tournament_match = match tournament.matches.iter_mut().find(|m| m.id == match_id) {
+            Some(m) => m,
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Match not found".to_string() },
+        };
+        if tournament_match.status != MatchStatus::Ready || tournament_match.game_id.is_some() {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Match not ready".to_string() };
+        }
+        if tournament_match.player1.as_ref() != Some(&player_id) && tournament_match.player2.as_ref() != Some(&player_id) {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Not in this match".to_string() };
+        }
+        if tournament_match.ready_players.contains(&player_id) {
+            return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Already ready".to_string() };
+        }
+
+        tournament_match.ready_players.push(player_id.clone());
+        let both_ready = tournament_match.ready_players.len() == 2;
+        if let Err(e) = self.save_tournament(tournament).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+        if both_ready {
+            return self.start_tournament_match(tournament_id, match_id, player_id).await;
+        }
+        OperationResult::MatchReadied { tournament_id, match_id }
+    }
+
+    /// A rated game for a tournament match, with its clock running
+    fn tournament_game(
+        &self,
+        tournament: &Tournament,
+        match_id: &str,
+        game_id: String,
+        red_player: String,
+        black_player: String,
+        timestamp: u64,
+    ) -> CheckersGame {
+        let mut game = CheckersGame {
+            id: game_id,
+            red_player: Some(red_player),
+            black_player: Some(black_player),
+            red_player_type: PlayerType::Human,
+            black_player_type: PlayerType::Human,
+            board_state: STARTING_BOARD.to_string(),
+            current_turn: Turn::Red,
+            moves: Vec::new(),
+            move_count: 0,
+            status: GameStatus::Active,
+            result: None,
+            created_at: timestamp,
+            updated_at: timestamp,
+            clock: Some(Clock::new(tournament.time_control)),
+            draw_offer: DrawOfferState::None,
+            is_rated: true,
+            color_preference: ColorPreference::Random,
+            creator_wants_random: false,
+            tournament_id: Some(tournament.id.clone()),
+            tournament_match_id: Some(match_id.to_string()),
+            ai_time_budget_ms: None,
+            ai_personality: AiPersonality::default(),
+            spectators: Vec::new(),
+            spectator_count: 0,
+            expires_at: None,
+            is_private: false,
+            invite_code: None,
+            red_username: None,
+            black_username: None,
+            suspicion_flags: Vec::new(),
+            host_chain: None,
+            host_game_id: None,
+            red_chain: None,
+            black_chain: None,
+            owner_app: None,
+            external_ref: None,
+            wager: None,
+            club_match_id: None,
+            color_commitment: None,
+            color_seed: None,
+            conditional_moves: Vec::new(),
+            draw_offers: Vec::new(),
+        };
+
+        // Start the clock
+        if let Some(ref mut clock) = game.clock {
+            // Armageddon: red must win, so black gets less time
+            let playoff = tournament.matches.iter().any(|m| m.id == match_id && m.playoff);
+            if playoff && tournament.playoff == Some(PlayoffMode::Armageddon) {
+                clock.black_time_ms = clock.initial_time_ms * ARMAGEDDON_BLACK_TIME_PCT / 100;
+            }
+            clock.start(timestamp / 1000);
+        }
+        game
+    }
+
+    /// Count a finished game towards its match's best-of series. Returns the
+    /// player who has won the series, or None after starting the next game with
+    /// the colors swapped. Past the match deadline no further game is started:
+    /// the winner of the game just ended takes the series, so a player who runs
+    /// out the clock forfeits all of it.
+    async fn score_series_game(&mut self, tournament: &mut Tournament, match_idx: usize, game: &CheckersGame) -> Option<String> {
+        let best_of = tournament.series_length(&tournament.matches[match_idx]);
+        let now = self.runtime.system_time().micros();
+        let game_winner = match game.result {
+            Some(GameResult::RedWins) => game.red_player.clone(),
+            Some(GameResult::BlackWins) => game.black_player.clone(),
+            _ => None,
+        };
+        let m = &mut tournament.matches[match_idx];
+        m.record_series_game(game_winner.as_deref());
+        if let Some(winner) = m.series_winner(best_of) {
+            return Some(winner);
+        }
+        if m.deadline.is_some_and(|deadline| now >= deadline) {
+            if let Some(winner) = game_winner.clone().or_else(|| m.series_leader()) {
+                return Some(winner);
+            }
+        }
+        // Nobody is left to play a withdrawn player
+        let withdrawn = tournament.participants.iter().any(|p| {
+            p.withdrawn && [&game.red_player, &game.black_player].into_iter().any(|player| player.as_ref() == Some(&p.player_id))
+        });
+        if withdrawn {
+            return game_winner;
+        }
+
+        let (Some(red_player), Some(black_player)) = (game.black_player.clone(), game.red_player.clone()) else {
+            return game_winner;
+        };
+        let game_id = self.state.generate_game_id().await;
+        let timestamp = self.runtime.system_time().micros();
+        let next = self.tournament_game(
+            tournament,
+            &tournament.matches[match_idx].id,
+            game_id.clone(),
+            red_player.clone(),
+            black_player.clone(),
+            timestamp,
+        );
+        if self.state.save_game(next).await.is_ok() {
+            let _ = self.state.record_colors(&red_player, &black_player).await;
+            tournament.matches[match_idx].game_id = Some(game_id);
+        }
+        None
+    }
+
     async fn start_tournament_match(
         &mut self,
         tournament_id: String,
@@ -1924,43 +6035,42 @@ impl CheckersContract {
     ) -> OperationResult {
         let player = player_id;
         let timestamp = self.runtime.system_time().micros();
-        let timestamp_ms = timestamp / 1000;
 
         let mut tournament = match self.state.get_tournament(&tournament_id).await {
             Some(t) => t,
-            None => return OperationResult::Error { message: "Tournament not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::TournamentNotFound, message: "Tournament not found".to_string() },
         };
 
         let match_idx = match tournament.matches.iter().position(|m| m.id == match_id) {
             Some(idx) => idx,
-            None => return OperationResult::Error { message: "Match not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Match not found".to_string() },
         };
 
         let tournament_match = &tournament.matches[match_idx];
 
         if tournament_match.status != MatchStatus::Ready {
-            return OperationResult::Error { message: "Match not ready".to_string() };
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Match not ready".to_string() };
         }
 
         // Prevent race condition: check if game already created
         if tournament_match.game_id.is_some() {
-            return OperationResult::Error { message: "Match already started".to_string() };
+            return OperationResult::Error { code: ErrorCode::AlreadyDone, message: "Match already started".to_string() };
         }
 
         let is_player1 = tournament_match.player1.as_ref() == Some(&player);
         let is_player2 = tournament_match.player2.as_ref() == Some(&player);
         if !is_player1 && !is_player2 {
-            return OperationResult::Error { message: "Not in this match".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Not in this match".to_string() };
         }
 
         // Validate both players exist before proceeding (BUG #6 FIX)
         let player1 = match tournament_match.player1.clone() {
             Some(p) => p,
-            None => return OperationResult::Error { message: "Player 1 not set".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::InvalidState, message: "Player 1 not set".to_string() },
         };
         let player2 = match tournament_match.player2.clone() {
             Some(p) => p,
-            None => return OperationResult::Error { message: "Player 2 not set".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::InvalidState, message: "Player 2 not set".to_string() },
         };
 
         // Create game ID and claim it atomically in tournament (BUG #1 FIX)
@@ -1970,52 +6080,32 @@ impl CheckersContract {
         tournament.matches[match_idx].game_id = Some(game_id.clone());
         tournament.matches[match_idx].status = MatchStatus::InProgress;
 
-        if let Err(e) = self.state.save_tournament(tournament.clone()).await {
-            return OperationResult::Error { message: e };
+        if let Err(e) = self.save_tournament(tournament.clone()).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
         }
 
-        // Random color assignment
-        let (red_player, black_player) = if timestamp % 2 == 0 {
+        // Even out both players' colors, falling back to a toss over the match
+        // that neither player can steer by choosing when to start it
+        let player1_red = match self.state.balanced_red(&player1, &player2).await {
+            Some(player1_red) => player1_red,
+            None => color_toss(&[&player1, &player2, &match_id]),
+        };
+        let (red_player, black_player) = if player1_red {
             (player1, player2)
         } else {
             (player2, player1)
         };
 
-        let mut game = CheckersGame {
-            id: game_id.clone(),
-            red_player: Some(red_player),
-            black_player: Some(black_player),
-            red_player_type: PlayerType::Human,
-            black_player_type: PlayerType::Human,
-            board_state: STARTING_BOARD.to_string(),
-            current_turn: Turn::Red,
-            moves: Vec::new(),
-            move_count: 0,
-            status: GameStatus::Active,
-            result: None,
-            created_at: timestamp,
-            updated_at: timestamp,
-            clock: Some(Clock::new(tournament.time_control)),
-            draw_offer: DrawOfferState::None,
-            is_rated: true,
-            color_preference: ColorPreference::Random,
-            creator_wants_random: false,
-            tournament_id: Some(tournament_id.clone()),
-            tournament_match_id: Some(match_id.clone()),
-        };
-
-        // Start the clock
-        if let Some(ref mut clock) = game.clock {
-            clock.start(timestamp_ms);
-        }
+        let game = self.tournament_game(&tournament, &match_id, game_id.clone(), red_player.clone(), black_player.clone(), timestamp);
 
         // Now create the actual game (tournament already updated above)
         if let Err(e) = self.state.save_game(game).await {
             // If game save fails, we need to rollback tournament update
             // But Linera doesn't support rollback, so we accept this inconsistency
             // The match will show InProgress but no game exists
-            return OperationResult::Error { message: e };
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
         }
+        let _ = self.state.record_colors(&red_player, &black_player).await;
 
         OperationResult::TournamentMatchStarted {
             tournament_id,
@@ -2034,19 +6124,19 @@ impl CheckersContract {
 
         let mut tournament = match self.state.get_tournament(&tournament_id).await {
             Some(t) => t,
-            None => return OperationResult::Error { message: "Tournament not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::TournamentNotFound, message: "Tournament not found".to_string() },
         };
 
         let match_idx = match tournament.matches.iter().position(|m| m.id == match_id) {
             Some(idx) => idx,
-            None => return OperationResult::Error { message: "Match not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::NotFound, message: "Match not found".to_string() },
         };
 
         let tournament_match = &tournament.matches[match_idx];
 
         // Can only forfeit matches that are Ready or InProgress
         if tournament_match.status != MatchStatus::Ready && tournament_match.status != MatchStatus::InProgress {
-            return OperationResult::Error { message: "Match not active".to_string() };
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Match not active".to_string() };
         }
 
         // Determine who is forfeiting and who wins
@@ -2057,14 +6147,36 @@ impl CheckersContract {
             // Player 2 forfeits, player 1 wins
             tournament_match.player1.clone()
         } else {
-            return OperationResult::Error { message: "Not in this match".to_string() };
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Not in this match".to_string() };
         };
 
         let winner_id = match winner {
             Some(w) => w,
-            None => return OperationResult::Error { message: "Cannot determine winner".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::InvalidState, message: "Cannot determine winner".to_string() },
         };
 
+        self.award_match(&mut tournament, match_idx, &winner_id);
+
+        // Check if round is complete and advance
+        self.advance_to_next_round(&mut tournament);
+
+        if let Err(e) = self.save_tournament(tournament).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+
+        OperationResult::TournamentMatchForfeited {
+            tournament_id,
+            match_id,
+            winner: winner_id,
+        }
+    }
+
+    /// Give a tournament match to `winner_id` without a game being played
+    fn award_match(&self, tournament: &mut Tournament, match_idx: usize, winner_id: &str) {
+        let winner_id = winner_id.to_string();
+        let match_id = tournament.matches[match_idx].id.clone();
+        let current_round = tournament.current_round;
+
         // Update match
         tournament.matches[match_idx].winner = Some(winner_id.clone());
         tournament.matches[match_idx].status = MatchStatus::Finished;
@@ -2076,7 +6188,7 @@ impl CheckersContract {
             tournament.matches[match_idx].player1.clone()
         };
 
-        if let Some(loser) = loser_id {
+        if let Some(loser) = loser_id.filter(|_| !tournament.matches[match_idx].playoff) {
             self.record_swiss_result(
                 &mut tournament.participants,
                 &winner_id,
@@ -2086,25 +6198,94 @@ impl CheckersContract {
         }
 
         // Update round status
-        if let Some(round) = tournament.rounds.iter_mut().find(|r| r.round_number == tournament.current_round) {
+        if let Some(round) = tournament.rounds.iter_mut().find(|r| r.round_number == current_round) {
             if let Some(match_in_round) = round.matches.iter_mut().find(|m| m.id == match_id) {
                 match_in_round.winner = Some(winner_id.clone());
                 match_in_round.status = MatchStatus::Finished;
             }
         }
+        if tournament.format == TournamentFormat::SingleElimination {
+            self.advance_winner(tournament, &match_id, &winner_id);
+        }
+    }
 
-        // Check if round is complete and advance
-        self.advance_to_next_round(&mut tournament);
+    /// Both players lose the match, in the match list and the round it belongs to
+    fn double_forfeit_match(&self, tournament: &mut Tournament, match_idx: usize) {
+        let m = &mut tournament.matches[match_idx];
+        m.status = MatchStatus::Finished;
+        m.winner = None;
+        m.double_forfeit = true;
+        let forfeited = m.clone();
+        for round in tournament.rounds.iter_mut() {
+            if let Some(round_match) = round.matches.iter_mut().find(|rm| rm.id == forfeited.id) {
+                *round_match = forfeited.clone();
+            }
+        }
+    }
 
-        if let Err(e) = self.state.save_tournament(tournament).await {
-            return OperationResult::Error { message: e };
+    async fn enforce_round_deadlines(&mut self, tournament_id: String) -> OperationResult {
+        let mut tournament = match self.state.get_tournament(&tournament_id).await {
+            Some(t) => t,
+            None => return OperationResult::Error { code: ErrorCode::TournamentNotFound, message: "Tournament not found".to_string() },
+        };
+        if tournament.status != TournamentStatus::InProgress {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Tournament not in progress".to_string() };
         }
 
-        OperationResult::TournamentMatchForfeited {
-            tournament_id,
-            match_id,
-            winner: winner_id,
+        let now = self.runtime.system_time().micros();
+        let overdue: Vec<usize> = (0..tournament.matches.len())
+            .filter(|&idx| {
+                let m = &tournament.matches[idx];
+                matches!(m.status, MatchStatus::Ready | MatchStatus::InProgress) && m.deadline.is_some_and(|d| now >= d)
+            })
+            .collect();
+        if overdue.is_empty() {
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "No match is past its deadline".to_string() };
+        }
+
+        // Matches nobody started are lost by both players, unless one of them was
+        // ready to play
+        let mut started_games = Vec::new();
+        for &idx in &overdue {
+            if let [ready_player] = tournament.matches[idx].ready_players.as_slice() {
+                if tournament.matches[idx].status == MatchStatus::Ready {
+                    let winner_id = ready_player.clone();
+                    self.award_match(&mut tournament, idx, &winner_id);
+                    continue;
+                }
+            }
+            let m = &mut tournament.matches[idx];
+            match &m.game_id {
+                Some(game_id) if m.status == MatchStatus::InProgress => started_games.push(game_id.clone()),
+                _ => self.double_forfeit_match(&mut tournament, idx),
+            }
+        }
+        self.advance_to_next_round(&mut tournament);
+        if let Err(e) = self.save_tournament(tournament).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
+
+        // In started games the player on move is holding things up and loses
+        for game_id in started_games {
+            let Some(mut game) = self.state.get_game(&game_id).await else { continue };
+            if game.status != GameStatus::Active {
+                continue;
+            }
+            let result = match game.current_turn {
+                Turn::Red => GameResult::BlackWins,
+                Turn::Black => GameResult::RedWins,
+            };
+            game.status = GameStatus::Finished;
+            game.result = Some(result);
+            game.updated_at = now;
+            if let Err(e) = self.state.save_game(game.clone()).await {
+                return OperationResult::Error { code: ErrorCode::Storage, message: e };
+            }
+            self.finish_game(&game, result).await;
+            self.handle_tournament_game_finished(&game).await;
         }
+
+        OperationResult::RoundDeadlinesEnforced { tournament_id, forfeited: overdue.len() as u32 }
     }
 
     async fn cancel_tournament(&mut self, tournament_id: String, player_id: String) -> OperationResult {
@@ -2112,25 +6293,40 @@ impl CheckersContract {
 
         let mut tournament = match self.state.get_tournament(&tournament_id).await {
             Some(t) => t,
-            None => return OperationResult::Error { message: "Tournament not found".to_string() },
+            None => return OperationResult::Error { code: ErrorCode::TournamentNotFound, message: "Tournament not found".to_string() },
         };
 
-        // Only creator can cancel
-        if tournament.creator != player {
-            return OperationResult::Error { message: "Only creator can cancel tournament".to_string() };
+        // Only an organizer or an admin can cancel
+        if !tournament.is_organizer(&player) && self.require_admin().await.is_err() {
+            return OperationResult::Error { code: ErrorCode::NotAuthorized, message: "Only organizers can cancel tournament".to_string() };
         }
 
         // Can only cancel during registration
         if tournament.status != TournamentStatus::Registration {
-            return OperationResult::Error { message: "Can only cancel during registration".to_string() };
+            return OperationResult::Error { code: ErrorCode::InvalidState, message: "Can only cancel during registration".to_string() };
         }
 
-        // Mark as cancelled by setting status to Finished with no winner
-        tournament.status = TournamentStatus::Finished;
+        for player in tournament.registered_players.clone() {
+            self.refund_entry_fee(&mut tournament, &player);
+        }
+
+        // Saving releases the invite code
+        tournament.status = TournamentStatus::Cancelled;
         tournament.finished_at = Some(self.runtime.system_time().micros());
+        let registered_players = tournament.registered_players.clone();
+
+        if let Err(e) = self.save_tournament(tournament).await {
+            return OperationResult::Error { code: ErrorCode::Storage, message: e };
+        }
 
-        if let Err(e) = self.state.save_tournament(tournament).await {
-            return OperationResult::Error { message: e };
+        // Let players on other chains know
+        let this_chain = self.runtime.chain_id();
+        for chain_id in registered_players.iter().filter_map(|p| p.parse::<ChainId>().ok()) {
+            if chain_id != this_chain {
+                self.runtime
+                    .prepare_message(Message::TournamentCancelled { tournament_id: tournament_id.clone() })
+                    .send_to(chain_id);
+            }
         }
 
         OperationResult::TournamentCancelled { tournament_id }
@@ -2157,9 +6353,31 @@ impl CheckersContract {
         };
 
         // BUG #11 FIX: Handle draw case properly
+        let playoff = tournament.matches[match_idx].playoff;
+        let best_of = tournament.series_length(&tournament.matches[match_idx]);
         let winner = match game.result {
+            Some(GameResult::RedWins | GameResult::BlackWins | GameResult::Draw) if best_of > 1 => {
+                match self.score_series_game(&mut tournament, match_idx, game).await {
+                    Some(winner) => Some(winner),
+                    None => {
+                        let _ = self.save_tournament(tournament).await;
+                        return;
+                    }
+                }
+            }
             Some(GameResult::RedWins) => game.red_player.clone(),
             Some(GameResult::BlackWins) => game.black_player.clone(),
+            // Black has draw odds in Armageddon
+            Some(GameResult::Draw) if playoff && tournament.playoff == Some(PlayoffMode::Armageddon) => {
+                game.black_player.clone()
+            }
+            Some(GameResult::Draw) if playoff || tournament.format.is_elimination() => {
+                // Someone has to go through, so a drawn elimination match is replayed
+                tournament.matches[match_idx].replay();
+                tournament.sync_elimination_rounds();
+                let _ = self.save_tournament(tournament).await;
+                return;
+            },
             Some(GameResult::Draw) => {
                 // Record draw for both players
                 if let (Some(p1), Some(p2)) = (&game.red_player, &game.black_player) {
@@ -2175,7 +6393,7 @@ impl CheckersContract {
                 }
 
                 self.advance_to_next_round(&mut tournament);
-                let _ = self.state.save_tournament(tournament).await;
+                let _ = self.save_tournament(tournament).await;
                 return;
             },
             _ => return, // No result yet
@@ -2186,38 +6404,121 @@ impl CheckersContract {
             None => return,
         };
 
-        // Update the match
+        self.award_match(&mut tournament, match_idx, &winner_id);
 
-        tournament.matches[match_idx].winner = Some(winner_id.clone());
-        tournament.matches[match_idx].status = MatchStatus::Finished;
+        // Check if round is complete and advance
+        self.advance_to_next_round(&mut tournament);
 
-        // Update Swiss scores
-        let loser_id = if tournament.matches[match_idx].player1.as_ref() == Some(&winner_id) {
-            tournament.matches[match_idx].player2.clone()
-        } else {
-            tournament.matches[match_idx].player1.clone()
+        let _ = self.save_tournament(tournament).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use checkers_abi::{
+        ErrorCode, InstantiationArgument, MatchStatus, Operation, OperationResult, Tournament, TournamentFormat,
+        TournamentStatus,
+    };
+    use linera_sdk::{
+        linera_base_types::{AccountOwner, ChainId, ChainOwnership, CryptoHash},
+        util::BlockingWait,
+        views::View,
+        Contract, ContractRuntime,
+    };
+
+    use super::{CheckersContract, CheckersState};
+
+    fn owner(name: &str) -> AccountOwner {
+        AccountOwner::Address32(CryptoHash::test_hash(name))
+    }
+
+    /// A contract on its creator chain, owned by `chain_owner`, with `admins`
+    /// and operations signed by `signer`
+    fn create_contract(chain_owner: AccountOwner, admins: &[AccountOwner], signer: AccountOwner) -> CheckersContract {
+        let chain_id = ChainId(CryptoHash::test_hash("creator chain"));
+        let runtime = ContractRuntime::new()
+            .with_application_parameters(())
+            .with_chain_id(chain_id)
+            .with_application_creator_chain_id(chain_id)
+            .with_chain_ownership(ChainOwnership::single(chain_owner))
+            .with_authenticated_signer(signer);
+        let state = CheckersState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to read from mock key value store");
+        let mut contract = CheckersContract { state, runtime, pending_events: Vec::new() };
+        let argument = InstantiationArgument {
+            admins: admins.iter().map(ToString::to_string).collect(),
+            ..InstantiationArgument::default()
         };
+        contract.instantiate(argument).blocking_wait();
+        contract
+    }
 
-        if let Some(loser) = loser_id {
-            self.record_swiss_result(
-                &mut tournament.participants,
-                &winner_id,
-                &loser,
-                false, // Not a draw
-            );
-        }
+    #[test]
+    fn test_add_admin_rejects_non_admin_signer() {
+        let stranger = owner("stranger");
+        let mut contract = create_contract(owner("chain owner"), &[owner("admin")], stranger);
+        let result = contract
+            .execute_operation(Operation::AddAdmin { owner: stranger.to_string() })
+            .blocking_wait();
+        assert!(matches!(result, OperationResult::Error { code: ErrorCode::NotAuthorized, .. }));
+        assert!(!contract.state.is_admin(&stranger.to_string()).blocking_wait());
+
+        let result = contract
+            .execute_operation(Operation::RemoveAdmin { owner: owner("admin").to_string() })
+            .blocking_wait();
+        assert!(matches!(result, OperationResult::Error { code: ErrorCode::NotAuthorized, .. }));
+        assert!(contract.state.is_admin(&owner("admin").to_string()).blocking_wait());
+    }
 
-        // Update round status
-        if let Some(round) = tournament.rounds.iter_mut().find(|r| r.round_number == tournament.current_round) {
-            if let Some(match_in_round) = round.matches.iter_mut().find(|m| m.id == match_id.clone()) {
-                match_in_round.winner = Some(winner_id.clone());
-                match_in_round.status = MatchStatus::Finished;
+    #[test]
+    fn test_add_admin_by_admin_or_chain_owner() {
+        let admin = owner("admin");
+        let mut contract = create_contract(owner("chain owner"), &[admin], admin);
+        let result = contract
+            .execute_operation(Operation::AddAdmin { owner: owner("new admin").to_string() })
+            .blocking_wait();
+        assert!(matches!(result, OperationResult::AdminAdded { .. }));
+
+        let chain_owner = owner("chain owner");
+        let mut contract = create_contract(chain_owner, &[], chain_owner);
+        let result = contract
+            .execute_operation(Operation::AddAdmin { owner: owner("new admin").to_string() })
+            .blocking_wait();
+        assert!(matches!(result, OperationResult::AdminAdded { .. }));
+        assert!(contract.state.is_admin(&owner("new admin").to_string()).blocking_wait());
+    }
+
+    #[test]
+    fn test_swiss_round_advances_past_double_withdrawal() {
+        let admin = owner("admin");
+        let contract = create_contract(admin, &[], admin);
+        let players: Vec<String> = ["alice", "bob", "carol", "dave"].iter().map(|p| p.to_string()).collect();
+        let mut tournament = Tournament {
+            id: "t".to_string(),
+            format: TournamentFormat::Swiss,
+            status: TournamentStatus::InProgress,
+            registered_players: players,
+            current_round: 1,
+            ..Tournament::default()
+        };
+        contract.generate_bracket(&mut tournament);
+        assert_eq!(tournament.rounds[0].matches.len(), 2);
+
+        // Both players of the first match withdraw; the second is played
+        let first = tournament.matches[0].clone();
+        for participant in tournament.participants.iter_mut() {
+            if [&first.player1, &first.player2].into_iter().any(|p| p.as_ref() == Some(&participant.player_id)) {
+                participant.withdrawn = true;
             }
         }
-
-        // Check if round is complete and advance
-        self.advance_to_next_round(&mut tournament);
-
-        let _ = self.state.save_tournament(tournament).await;
+        let winner = tournament.matches[1].player1.clone().unwrap();
+        contract.award_match(&mut tournament, 1, &winner);
+
+        assert!(contract.advance_to_next_round(&mut tournament));
+        assert!(tournament.matches[0].double_forfeit);
+        assert_eq!(tournament.rounds[0].matches[0].status, MatchStatus::Finished);
+        assert!(tournament.rounds[0].matches[0].double_forfeit);
+        assert!(tournament.current_round > 1);
     }
 }
@@ -4,15 +4,18 @@ mod state;
 
 use std::sync::Arc;
 use async_graphql::{EmptySubscription, Object, Request, Response, Schema};
-use checkers_abi::{CheckersAbi, CheckersGame, Operation, PlayerStats, GameStatus, QueueEntry, QueueStatus, Tournament};
+use checkers_abi::{Badge, BracketSide, PlayerBan, Report, Challenge, ChatMessage, CheckersAbi, Club, ClubMatch, ConditionalMove, CrosstableRow, CheckersGame, CheckersMove, EmoteRecord, FriendActivity, FriendRequest, GameConfig, GameFilter, GamePdn, GameSummary, GlobalStats, LeaderboardSort, Mission, MoveAnnotation, OpeningStats, Operation, Puzzle, PuzzleRushSession, Seek, SpectatedGame, Study, PlayerRank, PlayerProfile, PlayerSettings, PlayerStats, PlayStyleStats, GameStatus, QueueEntry, QueueStatus, RatingChange, RatingConfig, Season, TimeControl, Totals, Tournament, TournamentFilter, TournamentMatch, TournamentRecord, TournamentStanding, Trophy, Turn, color_commitment, game_pdn, STARTING_BOARD};
 use linera_sdk::{
     graphql::GraphQLMutationRoot,
-    linera_base_types::WithServiceAbi,
+    linera_base_types::{Amount, WithServiceAbi},
     views::View,
     Service, ServiceRuntime,
 };
 use state::CheckersState;
 
+/// Largest page a paginated query will return
+const MAX_PAGE_SIZE: i32 = 100;
+
 pub struct CheckersService {
     runtime: Arc<ServiceRuntime<Self>>,
 }
@@ -42,6 +45,7 @@ impl Service for CheckersService {
         let schema = Schema::build(
             QueryRoot {
                 state: Arc::new(state),
+                now: self.runtime.system_time().micros(),
             },
             Operation::mutation_root(runtime),
             EmptySubscription,
@@ -54,42 +58,432 @@ impl Service for CheckersService {
 
 struct QueryRoot {
     state: Arc<CheckersState>,
+    /// Time the query is handled, in microseconds
+    now: u64,
 }
 
 #[Object]
 impl QueryRoot {
-    async fn all_games(&self) -> Vec<CheckersGame> {
-        self.state.get_all_games().await
+    /// All games, finished ones included, oldest first
+    /// Pass `first` to page through them, with the ID of the last game received as `after`.
+    async fn all_games(&self, first: Option<i32>, after: Option<String>) -> Vec<CheckersGame> {
+        let first = first.map_or(usize::MAX, |first| first.clamp(0, MAX_PAGE_SIZE) as usize);
+        self.state.with_usernames(self.state.get_games_page(after, first).await).await
     }
 
+    /// A single game, with its full move history
     async fn game(&self, id: String) -> Option<CheckersGame> {
-        self.state.get_game(&id).await
+        let mut game = self.state.get_game(&id).await?;
+        game.moves = self.state.get_moves(&game, 0, usize::MAX).await;
+        self.state.with_usernames(vec![game]).await.pop()
+    }
+
+    /// The replies a player registered in advance on a correspondence game.
+    /// Anyone can list them, the opponent included: chain state is public.
+    async fn conditional_moves(&self, game_id: String, player_id: String) -> Vec<ConditionalMove> {
+        match self.state.get_game(&game_id).await {
+            Some(game) => game.conditional_moves.into_iter().filter(|c| c.player == player_id).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// The commitment to pass to `createGame` for a random-color game whose
+    /// colors are settled by revealing `secret`
+    async fn color_commitment(&self, secret: String) -> String {
+        color_commitment(&secret)
+    }
+
+    /// A page of a game's moves, starting at move index `from`
+    async fn moves(&self, game_id: String, from: Option<i32>, limit: Option<i32>) -> Vec<CheckersMove> {
+        let Some(game) = self.state.get_game(&game_id).await else {
+            return Vec::new();
+        };
+        let from = from.unwrap_or(0).max(0) as usize;
+        let limit = limit.unwrap_or(MAX_PAGE_SIZE).clamp(0, MAX_PAGE_SIZE) as usize;
+        self.state.get_moves(&game, from, limit).await
+    }
+
+    /// Engine annotations of a game's moves, empty until the game has been analyzed
+    async fn game_analysis(&self, game_id: String) -> Vec<MoveAnnotation> {
+        self.state.get_analysis(&game_id).await.unwrap_or_default()
+    }
+
+    /// Results of finished games through a position, the starting position by default.
+    /// Follow `moves[].positionHash` to explore continuations.
+    async fn opening_explorer(&self, position_hash: Option<String>) -> OpeningStats {
+        let position_hash = position_hash.unwrap_or_else(|| checkers_abi::position_hash(STARTING_BOARD, Turn::Red));
+        self.state.get_opening(&position_hash).await
+            .unwrap_or_else(|| OpeningStats::new(position_hash))
+    }
+
+    /// A page of a game's chat, oldest first, starting at message index `from`
+    async fn game_chat(&self, game_id: String, from: Option<i32>, limit: Option<i32>) -> Vec<ChatMessage> {
+        let from = from.unwrap_or(0).max(0) as usize;
+        let limit = limit.unwrap_or(50).clamp(0, MAX_PAGE_SIZE) as usize;
+        self.state.get_chat_messages(&game_id, from, limit).await
+    }
+
+    /// A page of the emotes sent in a game, oldest first, starting at index `from`
+    async fn game_emotes(&self, game_id: String, from: Option<i32>, limit: Option<i32>) -> Vec<EmoteRecord> {
+        let from = from.unwrap_or(0).max(0) as usize;
+        let limit = limit.unwrap_or(50).clamp(0, MAX_PAGE_SIZE) as usize;
+        self.state.get_emotes(&game_id, from, limit).await
+    }
+
+    /// Summaries of finished games, newest first
+    /// Pass the ID of the last summary received as `after` to get the next page.
+    async fn archived_games(&self, after: Option<String>, limit: Option<i32>) -> Vec<GameSummary> {
+        let limit = limit.unwrap_or(20).clamp(0, MAX_PAGE_SIZE) as usize;
+        self.state.get_archived_summaries(after, limit).await
     }
 
+    /// Games matching all of the given filters, newest first
+    /// Pass the ID of the last game received as `after` to get the next page.
+    async fn search_games(
+        &self,
+        filter: Option<GameFilter>,
+        after: Option<String>,
+        limit: Option<i32>,
+    ) -> Vec<CheckersGame> {
+        let limit = limit.unwrap_or(20).clamp(0, MAX_PAGE_SIZE) as usize;
+        let games = self.state.search_games(&filter.unwrap_or_default(), after, limit).await;
+        self.state.with_usernames(games).await
+    }
+
+    /// Open games waiting for an opponent, leaving out expired and private ones
     async fn pending_games(&self) -> Vec<CheckersGame> {
-        self.state.get_pending_games().await
+        let games = self.state.get_pending_games().await
+            .into_iter()
+            .filter(|game| !game.is_expired_seek(self.now) && !game.is_private)
+            .collect();
+        self.state.with_usernames(games).await
     }
 
-    async fn active_games(&self) -> Vec<CheckersGame> {
-        self.state
-            .get_all_games()
-            .await
+    /// Open seeks, oldest first, optionally only those at a time control
+    /// or only those a given player's rating lets them accept
+    async fn seeks(&self, time_control: Option<TimeControl>, eligible_player: Option<String>) -> Vec<Seek> {
+        let stats = match &eligible_player {
+            Some(player) => Some(self.state.get_player_stats(player).await),
+            None => None,
+        };
+        self.state.get_seeks().await
             .into_iter()
-            .filter(|g| g.status == GameStatus::Active)
+            .filter(|seek| !seek.is_expired(self.now))
+            .filter(|seek| time_control.is_none_or(|tc| seek.time_control == tc))
+            .filter(|seek| match &stats {
+                Some(stats) => seek.player != stats.chain_id && seek.accepts_rating(stats.get_rating(&seek.time_control)),
+                None => true,
+            })
             .collect()
     }
 
-    async fn player_games(&self, chain_id: String) -> Vec<CheckersGame> {
-        self.state.get_player_games(&chain_id).await
+    async fn active_games(&self) -> Vec<CheckersGame> {
+        self.state.with_usernames(self.state.get_active_games().await).await
+    }
+
+    /// The game currently on TV; it stays selected until it ends
+    async fn tv_game(&self) -> Option<CheckersGame> {
+        let game = self.state.get_tv_game().await?;
+        self.state.with_usernames(vec![game]).await.pop()
+    }
+
+    /// Active games between human players, highest average rating first
+    async fn featured_games(&self, limit: Option<i32>) -> Vec<CheckersGame> {
+        let limit = limit.unwrap_or(10).clamp(0, MAX_PAGE_SIZE) as usize;
+        self.state.with_usernames(self.state.get_featured_games(limit).await).await
+    }
+
+    /// A player's games, newest first
+    /// Pass the ID of the last game received as `after` to get the next page.
+    async fn player_games(
+        &self,
+        chain_id: String,
+        status: Option<GameStatus>,
+        time_control: Option<TimeControl>,
+        after: Option<String>,
+        limit: Option<i32>,
+    ) -> Vec<CheckersGame> {
+        let limit = limit.unwrap_or(20).clamp(0, MAX_PAGE_SIZE) as usize;
+        let games = self.state.get_player_games(&chain_id, status, time_control, after, limit).await;
+        self.state.with_usernames(games).await
+    }
+
+    /// A player's finished games in PDN, newest first, for backups and desktop tools
+    /// Pass the ID of the last game received as `after` to get the next page.
+    async fn player_games_pdn(&self, chain_id: String, after: Option<String>, limit: Option<i32>) -> Vec<GamePdn> {
+        let limit = limit.unwrap_or(20).clamp(0, MAX_PAGE_SIZE) as usize;
+        let games = self.state.get_player_games(&chain_id, Some(GameStatus::Finished), None, after, limit).await;
+        let mut exported = Vec::new();
+        for mut game in self.state.with_usernames(games).await {
+            game.moves = self.state.get_moves(&game, 0, usize::MAX).await;
+            exported.push(GamePdn { game_id: game.id.clone(), pdn: game_pdn(&game) });
+        }
+        exported
+    }
+
+    /// Version of the stored state layout
+    async fn schema_version(&self) -> u32 {
+        *self.state.schema_version.get()
+    }
+
+    async fn totals(&self) -> Totals {
+        self.state.get_totals()
+    }
+
+    async fn global_stats(&self) -> GlobalStats {
+        self.state.get_global_stats(self.now).await
+    }
+
+    /// Unfinished games a player is watching
+    async fn watched_games(&self, player_id: String) -> Vec<CheckersGame> {
+        self.state.with_usernames(self.state.get_watched_games(&player_id).await).await
     }
 
     async fn player_stats(&self, chain_id: String) -> PlayerStats {
         self.state.get_player_stats(&chain_id).await
     }
 
-    async fn leaderboard(&self, limit: Option<i32>) -> Vec<PlayerStats> {
-        let limit = limit.unwrap_or(10) as usize;
-        self.state.get_leaderboard(limit).await
+    /// Averages of how a player plays: game length, captures, promotions, move times, timeouts
+    async fn play_style_stats(&self, chain_id: String) -> PlayStyleStats {
+        self.state.get_play_style_stats(&chain_id).await
+    }
+
+    /// Stats of the player with a username, ignoring case
+    async fn player_by_username(&self, name: String) -> Option<PlayerStats> {
+        let player_id = self.state.get_player_by_username(&name).await?;
+        Some(self.state.get_player_stats(&player_id).await)
+    }
+
+    /// Usernames of the given players, in order, `null` for players without one.
+    /// Only the first `MAX_PAGE_SIZE` (100) IDs are resolved; request longer lists in chunks.
+    async fn usernames(&self, player_ids: Vec<String>) -> Vec<Option<String>> {
+        let mut usernames = Vec::with_capacity(player_ids.len().min(MAX_PAGE_SIZE as usize));
+        for player_id in player_ids.iter().take(MAX_PAGE_SIZE as usize) {
+            usernames.push(self.state.get_username(player_id).await);
+        }
+        usernames
+    }
+
+    /// Account owners allowed to moderate
+    async fn admins(&self) -> Vec<String> {
+        self.state.get_admins().await
+    }
+
+    /// Reports waiting for an admin, oldest first
+    async fn open_reports(&self, limit: Option<i32>) -> Vec<Report> {
+        let limit = limit.unwrap_or(50).clamp(0, MAX_PAGE_SIZE) as usize;
+        self.state.get_open_reports(limit).await
+    }
+
+    async fn report(&self, report_id: String) -> Option<Report> {
+        self.state.get_report(&report_id).await
+    }
+
+    /// Games with anti-cheat flags waiting for review
+    async fn flagged_games(&self, limit: Option<i32>) -> Vec<CheckersGame> {
+        let limit = limit.unwrap_or(50).clamp(0, MAX_PAGE_SIZE) as usize;
+        self.state.with_usernames(self.state.get_flagged_games(limit).await).await
+    }
+
+    async fn banned_players(&self) -> Vec<PlayerBan> {
+        self.state.get_banned_players().await
+    }
+
+    /// Rake collected and not yet withdrawn
+    async fn treasury_balance(&self) -> Amount {
+        *self.state.treasury_balance.get()
+    }
+
+    /// Chains whose game event streams this chain follows
+    async fn game_subscriptions(&self) -> Vec<String> {
+        self.state.get_game_subscriptions().await
+    }
+
+    /// Games followed on other chains, most recently updated first
+    async fn spectated_games(&self, chain_id: Option<String>, limit: Option<i32>) -> Vec<SpectatedGame> {
+        let limit = limit.unwrap_or(20).clamp(0, MAX_PAGE_SIZE) as usize;
+        self.state.get_spectated_games(chain_id.as_deref(), limit).await
+    }
+
+    async fn spectated_game(&self, chain_id: String, game_id: String) -> Option<SpectatedGame> {
+        self.state.get_spectated_game(&chain_id, &game_id).await
+    }
+
+    /// A player's bio, country and avatar
+    async fn player_profile(&self, chain_id: String) -> PlayerProfile {
+        self.state.get_profile(&chain_id).await
+    }
+
+    async fn player_settings(&self, chain_id: String) -> PlayerSettings {
+        self.state.get_settings(&chain_id).await
+    }
+
+    async fn leaderboard(
+        &self,
+        limit: Option<i32>,
+        offset: Option<i32>,
+        sort_by: Option<LeaderboardSort>,
+        time_control: Option<TimeControl>,
+        min_games: Option<i32>,
+    ) -> Vec<PlayerStats> {
+        let limit = limit.unwrap_or(10).clamp(0, MAX_PAGE_SIZE) as usize;
+        let offset = offset.unwrap_or(0).max(0) as usize;
+        let sort = sort_by.unwrap_or_default();
+        let time_control = time_control.unwrap_or(TimeControl::Blitz5_3);
+        let min_games = min_games.unwrap_or(0).max(0) as u32;
+        self.state.get_leaderboard(offset, limit, sort, time_control, min_games).await
+    }
+
+    /// Players ranked by their best puzzle rush streak
+    async fn puzzle_rush_leaderboard(&self, limit: Option<i32>, offset: Option<i32>) -> Vec<PlayerStats> {
+        let limit = limit.unwrap_or(10).clamp(0, MAX_PAGE_SIZE) as usize;
+        let offset = offset.unwrap_or(0).max(0) as usize;
+        self.state.get_puzzle_rush_leaderboard(offset, limit).await
+    }
+
+    /// A player's latest puzzle rush session
+    async fn puzzle_rush(&self, player_id: String) -> Option<PuzzleRushSession> {
+        self.state.get_puzzle_rush(&player_id).await
+    }
+
+    async fn friends(&self, player_id: String) -> Vec<String> {
+        self.state.get_friends(&player_id).await
+    }
+
+    /// Friend requests a player has received and not yet answered
+    async fn friend_requests(&self, player_id: String) -> Vec<FriendRequest> {
+        self.state.get_friend_requests(&player_id).await
+    }
+
+    /// Friends of a player who are playing right now
+    async fn friends_playing(&self, player_id: String) -> Vec<FriendActivity> {
+        self.state.get_friend_activity(&player_id).await
+    }
+
+    /// Players a player has blocked
+    async fn blocked_players(&self, player_id: String) -> Vec<String> {
+        self.state.get_blocked_players(&player_id).await
+    }
+
+    /// Applications a player lets create rated matches for them
+    async fn approved_match_apps(&self, player_id: String) -> Vec<String> {
+        self.state.get_approved_match_apps(&player_id).await
+    }
+
+    async fn challenge(&self, challenge_id: String) -> Option<Challenge> {
+        self.state.get_challenge(&challenge_id).await
+    }
+
+    /// Pending challenges a player has sent or received, newest first
+    async fn player_challenges(&self, player_id: String) -> Vec<Challenge> {
+        self.state.get_pending_challenges(&player_id).await
+            .into_iter()
+            .filter(|challenge| !challenge.is_expired(self.now))
+            .collect()
+    }
+
+    async fn study(&self, study_id: String) -> Option<Study> {
+        self.state.get_study(&study_id).await
+    }
+
+    /// Studies a player owns or is a member of
+    async fn player_studies(&self, player_id: String) -> Vec<Study> {
+        self.state.get_player_studies(&player_id).await
+    }
+
+    async fn club(&self, club_id: String) -> Option<Club> {
+        self.state.get_club(&club_id).await
+    }
+
+    /// Clubs, largest first
+    async fn clubs(&self, limit: Option<i32>) -> Vec<Club> {
+        let limit = limit.unwrap_or(50).clamp(0, MAX_PAGE_SIZE) as usize;
+        self.state.get_clubs(limit).await
+    }
+
+    async fn club_match(&self, match_id: String) -> Option<ClubMatch> {
+        self.state.get_club_match(&match_id).await
+    }
+
+    /// Matches a club has played or been challenged to, newest first
+    async fn club_matches(&self, club_id: String) -> Vec<ClubMatch> {
+        self.state.get_club_matches(&club_id).await
+    }
+
+    /// The club a player is a member of
+    async fn player_club(&self, player_id: String) -> Option<Club> {
+        self.state.get_player_club(&player_id).await
+    }
+
+    async fn puzzle(&self, puzzle_id: String) -> Option<Puzzle> {
+        self.state.get_puzzle(&puzzle_id).await
+    }
+
+    async fn player_rank(&self, chain_id: String) -> Vec<PlayerRank> {
+        self.state.get_player_ranks(&chain_id).await
+    }
+
+    async fn rating_config(&self) -> RatingConfig {
+        *self.state.rating_config.get()
+    }
+
+    async fn game_config(&self) -> GameConfig {
+        self.state.game_config.get().clone()
+    }
+
+    async fn rating_history(
+        &self,
+        player_id: String,
+        time_control: Option<TimeControl>,
+        limit: Option<i32>,
+    ) -> Vec<RatingChange> {
+        let limit = limit.unwrap_or(50).clamp(0, MAX_PAGE_SIZE) as usize;
+        self.state.get_rating_history(&player_id, time_control, limit).await
+    }
+
+    async fn current_season(&self) -> Option<Season> {
+        self.state.get_current_season().await
+    }
+
+    async fn seasons(&self) -> Vec<Season> {
+        self.state.get_seasons().await
+    }
+
+    /// Standings for a season, defaulting to the current one
+    async fn season_standings(
+        &self,
+        season_id: Option<u32>,
+        time_control: Option<TimeControl>,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> Vec<PlayerStats> {
+        let season_id = match season_id.or(*self.state.current_season_id.get()) {
+            Some(id) => id,
+            None => return Vec::new(),
+        };
+        let limit = limit.unwrap_or(10).clamp(0, MAX_PAGE_SIZE) as usize;
+        let offset = offset.unwrap_or(0).max(0) as usize;
+        let time_control = time_control.unwrap_or(TimeControl::Blitz5_3);
+        self.state.get_season_standings(season_id, offset, limit, time_control).await
+    }
+
+    async fn season_stats(&self, season_id: u32, chain_id: String) -> PlayerStats {
+        let lifetime = self.state.get_player_stats(&chain_id).await;
+        self.state.get_season_stats(season_id, &lifetime).await
+    }
+
+    async fn trophies(&self, player_id: String) -> Vec<Trophy> {
+        self.state.get_trophies(&player_id).await
+    }
+
+    /// A player's daily and weekly missions, with their progress
+    async fn missions(&self, player_id: String) -> Vec<Mission> {
+        self.state.get_missions(&player_id, self.now).await
+    }
+
+    /// A player's achievement badges
+    async fn achievements(&self, player_id: String) -> Vec<Badge> {
+        self.state.get_achievements(&player_id).await
     }
 
     async fn queue_status(&self) -> Vec<QueueStatus> {
@@ -97,7 +491,12 @@ impl QueryRoot {
     }
 
     async fn my_queue_status(&self, chain_id: String) -> Option<QueueEntry> {
-        self.state.get_player_queue_entry(&chain_id).await
+        self.state.get_queue_entries(&chain_id).await.into_iter().next()
+    }
+
+    /// Every pool a player is waiting in
+    async fn my_queue_entries(&self, chain_id: String) -> Vec<QueueEntry> {
+        self.state.get_queue_entries(&chain_id).await
     }
 
     // Tournament queries
@@ -122,7 +521,50 @@ impl QueryRoot {
         self.state.get_public_tournaments().await
     }
 
+    /// Public tournaments matching all of the given filters, newest first
+    /// Pass the ID of the last tournament received as `after` to get the next page.
+    async fn search_tournaments(
+        &self,
+        filter: Option<TournamentFilter>,
+        after: Option<String>,
+        limit: Option<i32>,
+    ) -> Vec<Tournament> {
+        let limit = limit.unwrap_or(20).clamp(0, MAX_PAGE_SIZE) as usize;
+        self.state.search_tournaments(&filter.unwrap_or_default(), after, limit).await
+    }
+
+    /// A player's finished tournaments with their placement, most recent first
+    async fn tournament_history(&self, player_id: String, limit: Option<i32>) -> Vec<TournamentRecord> {
+        let limit = limit.unwrap_or(50).clamp(0, MAX_PAGE_SIZE) as usize;
+        self.state.get_tournament_history(&player_id, limit).await
+    }
+
     async fn tournament_by_code(&self, code: String) -> Option<Tournament> {
         self.state.get_tournament_by_code(&code).await
     }
+
+    /// Participants ranked by score and tiebreaks, with their result in each round
+    async fn tournament_standings(&self, tournament_id: String) -> Vec<TournamentStanding> {
+        self.state.get_tournament(&tournament_id).await.map(|tournament| tournament.standings()).unwrap_or_default()
+    }
+
+    /// The matches of one tournament round, without the rest of the tournament
+    async fn tournament_round(&self, tournament_id: String, round: u32) -> Vec<TournamentMatch> {
+        self.state.get_tournament_round(&tournament_id, round).await
+    }
+
+    async fn tournament_match(&self, id: String) -> Option<TournamentMatch> {
+        self.state.get_tournament_match(&id).await
+    }
+
+    /// Round-robin crosstable: each player's result against every other
+    async fn tournament_crosstable(&self, id: String) -> Vec<CrosstableRow> {
+        self.state.get_tournament(&id).await.map(|tournament| tournament.crosstable()).unwrap_or_default()
+    }
+
+    /// One side of a tournament's bracket: the winners bracket, the losers bracket
+    /// or the grand final
+    async fn tournament_bracket(&self, id: String, side: BracketSide) -> Vec<TournamentMatch> {
+        self.state.get_tournament(&id).await.map(|tournament| tournament.bracket(side)).unwrap_or_default()
+    }
 }
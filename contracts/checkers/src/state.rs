@@ -1,6 +1,19 @@
 // Checkers Game State Management
-use checkers_abi::{CheckersGame, GameResult, GameStatus, PlayerStats, PlayerType, QueueEntry, QueueStatus, TimeControl, Tournament};
-use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext};
+// Shared by the contract and service binaries, each of which only uses part of it.
+#![allow(dead_code)]
+
+use checkers_abi::{Achievement, ErrorCode, OperationError, ColorHistory, Club, ClubMatch, PlayerBan, SuspicionFlag, Report, ReportStatus, Badge, Challenge, ChallengeStatus, ChatMessage, CheckersGame, CheckersMove, EmoteRecord, FriendActivity, FriendRequest, GameConfig, GameEvent, GameFilter, GameResult, GameStatus, GameSummary, GlobalStats, LeaderboardSort, LevelUp, Mission, MissionGoal, MissionPeriod, MoveAnnotation, OpeningMove, OpeningStats, PlayerProfile, PlayerSettings, PlayerStats, PlayStyleStats, Puzzle, PuzzleRushSession, Study, PlayerRank, PlayerType, QueueEntry, QueuePool, QueueStatus, RatingCategory, RecentOpponents, RatingChange, RatingConfig, Season, SeasonTrophyScan, Seek, SpectatedGame, TimeControl, Totals, Tournament, TournamentFilter, TournamentMatch, TournamentRecord, TournamentStatus, Trophy, TrophyKind, Turn, balanced_red, count_pieces, level_for_xp, EMOTE_COOLDOWN_MICROS, MAX_CHAT_MESSAGES_PER_GAME, MAX_EMOTES_PER_GAME, MICROS_PER_DAY, SEASON_RATING_DECAY_PCT, SEASON_TROPHY_PLACES};
+use linera_sdk::linera_base_types::Amount;
+use linera_sdk::views::{linera_views, CollectionView, LogView, MapView, RegisterView, RootView, View, ViewStorageContext};
+use std::cmp::{Ordering, Reverse};
+
+/// Maximum number of player stats entries a leaderboard query will scan
+pub const MAX_LEADERBOARD_SCAN: usize = 5_000;
+
+/// Version of the stored state layout this code expects.
+/// Bump it, and add a step to the contract's `migrate`, when stored data needs rewriting
+/// rather than just reading with `#[serde(default)]`.
+pub const SCHEMA_VERSION: u32 = 5;
 
 /// The application state stored on-chain
 #[derive(RootView)]
@@ -9,17 +22,158 @@ pub struct CheckersState {
     /// All games indexed by game ID
     pub games: MapView<String, CheckersGame>,
 
+    /// Finished games, moved out of `games` to keep scans of live games small
+    pub archived_games: MapView<String, CheckersGame>,
+
+    /// Compact records of finished games, for archive listings
+    pub game_summaries: MapView<String, GameSummary>,
+
+    /// Append-only move log per game
+    pub game_moves: CollectionView<String, LogView<CheckersMove>>,
+
+    /// Chat log per game
+    pub game_chat: CollectionView<String, LogView<ChatMessage>>,
+
+    /// Emotes sent in each game
+    pub game_emotes: CollectionView<String, LogView<EmoteRecord>>,
+
+    /// Engine annotations of analyzed games, one per move
+    pub game_analysis: MapView<String, Vec<MoveAnnotation>>,
+
+    /// Opening explorer: results of finished games by position hash
+    pub opening_explorer: MapView<String, OpeningStats>,
+
+    /// Puzzles taken from blunders in analyzed games, by puzzle ID
+    pub puzzles: MapView<String, Puzzle>,
+
+    /// Counter for generating sequential puzzle IDs
+    pub next_puzzle_id: RegisterView<u64>,
+
+    /// Each player's latest puzzle rush session
+    pub puzzle_rush_sessions: MapView<String, PuzzleRushSession>,
+
+    /// Shared study boards by study ID
+    pub studies: MapView<String, Study>,
+
+    /// Counter for generating unique study IDs
+    pub next_study_id: RegisterView<u64>,
+
+    /// IDs of the studies each player is a member of
+    pub player_studies: CollectionView<String, MapView<String, ()>>,
+
+    /// Clubs by club ID
+    pub clubs: MapView<String, Club>,
+
+    /// Counter for generating unique club IDs
+    pub next_club_id: RegisterView<u64>,
+
+    /// The club each player belongs to, or is waiting to join
+    pub player_clubs: MapView<String, String>,
+
+    /// Club matches by match ID
+    pub club_matches: MapView<String, ClubMatch>,
+
+    /// Counter for generating unique club match IDs
+    pub next_club_match_id: RegisterView<u64>,
+
+    /// IDs of the matches each club has played or been challenged to
+    pub club_match_index: CollectionView<String, MapView<String, ()>>,
+
+    /// Each player's friends, stored on both sides of a friendship
+    pub friends: CollectionView<String, MapView<String, ()>>,
+
+    /// Pending friend requests by recipient, then sender, with the time sent
+    pub friend_requests: CollectionView<String, MapView<String, u64>>,
+
+    /// Players each player has blocked
+    pub blocked_players: CollectionView<String, MapView<String, ()>>,
+
+    /// Owner of each username, keyed by the lowercased name
+    pub usernames: MapView<String, String>,
+
+    /// Signer each player ID was bound to on its first signed operation
+    pub player_signers: MapView<String, String>,
+
+    /// Bio, country and avatar of players who set them
+    pub profiles: MapView<String, PlayerProfile>,
+
+    /// Preferences of players who changed them from the defaults
+    pub player_settings: MapView<String, PlayerSettings>,
+
+    /// Direct challenges by challenge ID
+    pub challenges: MapView<String, Challenge>,
+
+    /// Counter for generating unique challenge IDs
+    pub next_challenge_id: RegisterView<u64>,
+
+    /// IDs of the challenges each player has sent or received
+    pub player_challenges: CollectionView<String, MapView<String, ()>>,
+
+    /// Index of challenges still waiting for an answer
+    pub pending_challenges: MapView<String, ()>,
+
+    /// Open seeks on the seek board, removed once accepted, cancelled or expired
+    pub seeks: MapView<String, Seek>,
+
+    /// Counter for generating unique seek IDs
+    pub next_seek_id: RegisterView<u64>,
+
     /// Counter for generating unique game IDs
     pub next_game_id: RegisterView<u64>,
 
     /// Player statistics for leaderboard
     pub player_stats: MapView<String, PlayerStats>,
 
+    /// Move-level statistics of players' finished games
+    pub play_style_stats: MapView<String, PlayStyleStats>,
+
+    /// Version of the layout the stored state was last migrated to; 0 before versioning
+    pub schema_version: RegisterView<u32>,
+
+    /// Rating bounds and starting rating, set at instantiation
+    pub rating_config: RegisterView<RatingConfig>,
+
+    /// Gameplay settings and limits, set at instantiation
+    pub game_config: RegisterView<GameConfig>,
+
+    /// Account owners allowed to moderate, seeded at instantiation
+    pub admins: MapView<String, ()>,
+
+    /// Player reports by report ID
+    pub reports: MapView<String, Report>,
+
+    /// Counter for generating unique report IDs
+    pub next_report_id: RegisterView<u64>,
+
+    /// IDs of reports still waiting for an admin
+    pub open_reports: MapView<String, ()>,
+
+    /// Players banned by an admin
+    pub banned_players: MapView<String, PlayerBan>,
+
+    /// Games carrying anti-cheat flags, for moderator review
+    pub flagged_games: MapView<String, ()>,
+
     /// List of games waiting for opponents (for matchmaking)
     pub pending_games: MapView<String, bool>,
 
-    /// Matchmaking queue indexed by player chain ID
-    pub matchmaking_queue: MapView<String, QueueEntry>,
+    /// Index of games currently being played
+    pub active_games: MapView<String, ()>,
+
+    /// Active games between two human players, with their average rating
+    pub featured_games: MapView<String, u32>,
+
+    /// Game currently shown on TV, kept until it ends
+    pub tv_game_id: RegisterView<Option<String>>,
+
+    /// Matchmaking pools by time control and rated flag, each indexed by player chain ID
+    pub matchmaking_queue: CollectionView<QueuePool, MapView<String, QueueEntry>>,
+
+    /// Each player's last few queue opponents, kept apart for a cooldown
+    pub recent_queue_opponents: MapView<String, RecentOpponents>,
+
+    /// Colors each player got in their recent queue and tournament games
+    pub color_history: MapView<String, ColorHistory>,
 
     /// All tournaments indexed by tournament ID
     pub tournaments: MapView<String, Tournament>,
@@ -29,6 +183,94 @@ pub struct CheckersState {
 
     /// Index from invite code to tournament ID for fast lookup
     pub invite_code_index: MapView<String, String>,
+
+    /// IDs of the tournaments in each status
+    pub tournament_status_index: CollectionView<TournamentStatus, MapView<String, ()>>,
+
+    /// Each tournament's matches by match ID, so clients can fetch a round or a
+    /// match without loading the whole tournament
+    pub tournament_matches: CollectionView<String, MapView<String, TournamentMatch>>,
+
+    /// Each player's finished tournaments, by tournament ID
+    pub tournament_history: CollectionView<String, MapView<String, TournamentRecord>>,
+
+    /// Index from invite code to the ID of a private game waiting for an opponent
+    pub game_invite_index: MapView<String, String>,
+
+    /// Number of players at each rating, per category, for rank lookups
+    pub rating_index: CollectionView<RatingCategory, MapView<u32, u32>>,
+
+    /// Append-only log of rating changes per player
+    pub rating_history: CollectionView<String, LogView<RatingChange>>,
+
+    /// Seasons that have seen at least one rated game, by season ID
+    pub seasons: MapView<u32, Season>,
+
+    /// ID of the most recent season with a rated game
+    pub current_season_id: RegisterView<Option<u32>>,
+
+    /// Per-season player stats, kept apart from lifetime stats
+    pub season_stats: CollectionView<u32, MapView<String, PlayerStats>>,
+
+    /// Season trophies won by each player
+    pub trophies: CollectionView<String, LogView<Trophy>>,
+
+    /// Achievements each player has earned
+    pub achievements: CollectionView<String, MapView<Achievement, Badge>>,
+
+    /// Each player's current missions, keyed by period and slot
+    pub missions: CollectionView<String, MapView<(MissionPeriod, u32), Mission>>,
+
+    /// Number of games ever created
+    pub total_game_count: RegisterView<u64>,
+
+    /// Number of games waiting for an opponent
+    pub pending_game_count: RegisterView<u64>,
+
+    /// Number of games that have finished
+    pub finished_game_count: RegisterView<u64>,
+
+    /// Number of players in the matchmaking queue
+    pub queued_player_count: RegisterView<u64>,
+
+    /// Number of games currently active
+    pub active_game_count: RegisterView<u64>,
+
+    /// Number of players with stats
+    pub player_count: RegisterView<u64>,
+
+    /// Number of tournaments created
+    pub tournament_count: RegisterView<u64>,
+
+    /// IDs of the games each player has taken part in
+    pub player_game_index: CollectionView<String, MapView<String, ()>>,
+
+    /// IDs of the games each player is watching
+    pub watched_games: CollectionView<String, MapView<String, ()>>,
+
+    /// IDs of the games played at each time control
+    pub time_control_game_index: CollectionView<TimeControl, MapView<String, ()>>,
+
+    /// IDs of the games belonging to each tournament
+    pub tournament_game_index: CollectionView<String, MapView<String, ()>>,
+
+    /// Moves made per day, keyed by days since the Unix epoch
+    pub moves_by_day: MapView<u64, u64>,
+
+    /// Chains whose game event streams this chain follows
+    pub game_subscriptions: MapView<String, ()>,
+
+    /// Games followed on other chains, keyed by chain ID and game ID
+    pub spectated_games: MapView<(String, String), SpectatedGame>,
+
+    /// Rake collected from wagers and prize pools, held by the application until withdrawn
+    pub treasury_balance: RegisterView<Amount>,
+
+    /// Applications each player lets create rated matches for them
+    pub match_app_approvals: CollectionView<String, MapView<String, ()>>,
+
+    /// Trophy awards in progress, by season
+    pub season_trophy_scans: MapView<u32, SeasonTrophyScan>,
 }
 
 impl CheckersState {
@@ -39,19 +281,48 @@ impl CheckersState {
         format!("game_{:06}", id)
     }
 
-    /// Get a game by ID
+    /// Get a game by ID, looking in the archive if it has finished
     pub async fn get_game(&self, game_id: &str) -> Option<CheckersGame> {
-        self.games.get(game_id).await.ok().flatten()
+        match self.games.get(game_id).await.ok().flatten() {
+            Some(game) => Some(game),
+            None => self.archived_games.get(game_id).await.ok().flatten(),
+        }
     }
 
     /// Save or update a game
     pub async fn save_game(&mut self, game: CheckersGame) -> Result<(), String> {
         let game_id = game.id.clone();
-        let is_pending = game.status == GameStatus::Pending;
+        // A game waiting for its color reveal is no longer open to join
+        let is_pending = game.status == GameStatus::Pending && !game.awaiting_color_reveal();
+        let is_active = game.status == GameStatus::Active;
+        let previous = self.get_game(&game_id).await;
+        self.update_game_counters(previous.as_ref(), &game).await?;
+        self.index_game_players(previous.as_ref(), &game).await?;
+        self.index_game_attributes(previous.as_ref(), &game).await?;
+        self.index_featured_game(previous.as_ref(), &game).await?;
 
-        self.games
-            .insert(&game_id, game)
-            .map_err(|e| format!("Failed to save game: {}", e))?;
+        if game.is_over() {
+            // Watch lists only show games still being played
+            if previous.as_ref().is_some_and(|g| !g.is_over()) {
+                for spectator in &game.spectators {
+                    self.remove_watched_game(spectator, &game_id).await?;
+                }
+            }
+            // Cancelled games were never played, so they stay out of summaries
+            if game.status == GameStatus::Finished {
+                self.game_summaries
+                    .insert(&game_id, GameSummary::from_game(&game))
+                    .map_err(|e| format!("Failed to save game summary: {}", e))?;
+            }
+            self.archived_games
+                .insert(&game_id, game)
+                .map_err(|e| format!("Failed to archive game: {}", e))?;
+            let _ = self.games.remove(&game_id);
+        } else {
+            self.games
+                .insert(&game_id, game)
+                .map_err(|e| format!("Failed to save game: {}", e))?;
+        }
 
         if is_pending {
             self.pending_games
@@ -59,347 +330,2310 @@ impl CheckersState {
                 .map_err(|e| format!("Failed to update pending: {}", e))?;
         } else {
             let _ = self.pending_games.remove(&game_id);
+            // Invite codes only matter while the game is open
+            if let Some(code) = previous.as_ref().and_then(|g| g.invite_code.as_ref()) {
+                let _ = self.game_invite_index.remove(code);
+            }
+        }
+
+        if is_active {
+            self.active_games
+                .insert(&game_id, ())
+                .map_err(|e| format!("Failed to update active: {}", e))?;
+        } else {
+            let _ = self.active_games.remove(&game_id);
         }
 
+        self.update_tv_game(&game_id, is_active).await;
+
         Ok(())
     }
 
-    /// Get all games
-    pub async fn get_all_games(&self) -> Vec<CheckersGame> {
-        let mut games = Vec::new();
-        let _ = self.games
+    /// Keep the global counters in step with a game being saved
+    async fn update_game_counters(
+        &mut self,
+        previous: Option<&CheckersGame>,
+        game: &CheckersGame,
+    ) -> Result<(), String> {
+        let previous_status = previous.map(|g| g.status);
+        if previous_status.is_none() {
+            *self.total_game_count.get_mut() += 1;
+        }
+        if previous_status != Some(game.status) {
+            match previous_status {
+                Some(GameStatus::Pending) => decrement(&mut self.pending_game_count),
+                Some(GameStatus::Active) => decrement(&mut self.active_game_count),
+                _ => {}
+            }
+            match game.status {
+                GameStatus::Pending => *self.pending_game_count.get_mut() += 1,
+                GameStatus::Active => *self.active_game_count.get_mut() += 1,
+                GameStatus::Finished => *self.finished_game_count.get_mut() += 1,
+                GameStatus::Cancelled => {}
+            }
+        }
+
+        let new_moves = game.move_count.saturating_sub(previous.map_or(0, |g| g.move_count));
+        if new_moves > 0 {
+            let day = game.updated_at / MICROS_PER_DAY;
+            let count = self.moves_by_day.get(&day).await.ok().flatten().unwrap_or(0);
+            self.moves_by_day
+                .insert(&day, count + new_moves as u64)
+                .map_err(|e| format!("Failed to update move count: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Rebuild the game and player counters, the rating index and the pending
+    /// challenge index from the stored games, stats and challenges. Deployments
+    /// from before these were added hold the data but never counted or indexed it.
+    pub async fn backfill_counters_and_indexes(&mut self) -> Result<(), String> {
+        let (mut total, mut pending, mut active, mut finished) = (0u64, 0u64, 0u64, 0u64);
+        let mut count = |game: &CheckersGame| {
+            total += 1;
+            match game.status {
+                GameStatus::Pending => pending += 1,
+                GameStatus::Active => active += 1,
+                GameStatus::Finished => finished += 1,
+                GameStatus::Cancelled => {}
+            }
+        };
+        self.games
             .for_each_index_value(|_id, game| {
-                games.push(game.into_owned());
+                count(&game);
                 Ok(())
             })
-            .await;
-        games
+            .await
+            .map_err(|e| format!("Failed to count games: {}", e))?;
+        self.archived_games
+            .for_each_index_value(|_id, game| {
+                count(&game);
+                Ok(())
+            })
+            .await
+            .map_err(|e| format!("Failed to count archived games: {}", e))?;
+        self.total_game_count.set(total);
+        self.pending_game_count.set(pending);
+        self.active_game_count.set(active);
+        self.finished_game_count.set(finished);
+
+        let time_controls = [TimeControl::Bullet1_0, TimeControl::Blitz3_0, TimeControl::Rapid10_0];
+        let mut buckets: Vec<std::collections::HashMap<u32, u32>> = vec![Default::default(); time_controls.len()];
+        let mut players = 0u64;
+        self.player_stats
+            .for_each_index_value(|_id, stats| {
+                players += 1;
+                for (buckets, time_control) in buckets.iter_mut().zip(&time_controls) {
+                    if stats.get_games_in_category(time_control) > 0 {
+                        *buckets.entry(stats.get_rating(time_control)).or_insert(0) += 1;
+                    }
+                }
+                Ok(())
+            })
+            .await
+            .map_err(|e| format!("Failed to scan player stats: {}", e))?;
+        self.player_count.set(players);
+        self.rating_index.clear();
+        for (buckets, time_control) in buckets.into_iter().zip(&time_controls) {
+            let index = self.rating_index
+                .load_entry_mut(&time_control.category())
+                .await
+                .map_err(|e| format!("Failed to load rating index: {}", e))?;
+            for (rating, count) in buckets {
+                index.insert(&rating, count)
+                    .map_err(|e| format!("Failed to update rating index: {}", e))?;
+            }
+        }
+
+        let mut pending_challenges = Vec::new();
+        self.challenges
+            .for_each_index_value(|challenge_id, challenge| {
+                if challenge.status == ChallengeStatus::Pending {
+                    pending_challenges.push(challenge_id);
+                }
+                Ok(())
+            })
+            .await
+            .map_err(|e| format!("Failed to scan challenges: {}", e))?;
+        self.pending_challenges.clear();
+        for challenge_id in pending_challenges {
+            self.pending_challenges
+                .insert(&challenge_id, ())
+                .map_err(|e| format!("Failed to update pending challenges: {}", e))?;
+        }
+        Ok(())
     }
 
-    /// Get pending games
-    pub async fn get_pending_games(&self) -> Vec<CheckersGame> {
-        let mut game_ids = Vec::new();
-        let _ = self.pending_games
-            .for_each_index(|game_id| {
-                game_ids.push(game_id.clone());
+    /// Add a game to the index of each human player newly seated in it
+    async fn index_game_players(
+        &mut self,
+        previous: Option<&CheckersGame>,
+        game: &CheckersGame,
+    ) -> Result<(), String> {
+        // A game cancelled before any move drops out of its creator's history
+        if game.status == GameStatus::Cancelled && game.move_count == 0 {
+            for player in [&game.red_player, &game.black_player].into_iter().flatten() {
+                self.player_game_index
+                    .load_entry_mut(player)
+                    .await
+                    .map_err(|e| format!("Failed to load player game index: {}", e))?
+                    .remove(&game.id)
+                    .map_err(|e| format!("Failed to unindex game: {}", e))?;
+            }
+            return Ok(());
+        }
+        for player in [&game.red_player, &game.black_player].into_iter().flatten() {
+            let already_seated = previous.is_some_and(|g| {
+                g.red_player.as_ref() == Some(player) || g.black_player.as_ref() == Some(player)
+            });
+            if player == "AI" || already_seated {
+                continue;
+            }
+            self.player_game_index
+                .load_entry_mut(player)
+                .await
+                .map_err(|e| format!("Failed to load player game index: {}", e))?
+                .insert(&game.id, ())
+                .map_err(|e| format!("Failed to index game: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Index a game by time control and tournament when it is first saved with them
+    async fn index_game_attributes(
+        &mut self,
+        previous: Option<&CheckersGame>,
+        game: &CheckersGame,
+    ) -> Result<(), String> {
+        let time_control = game.clock.as_ref().and_then(TimeControl::from_clock);
+        let previous_time_control = previous.and_then(|g| g.clock.as_ref().and_then(TimeControl::from_clock));
+        if let Some(tc) = time_control.filter(|tc| previous_time_control != Some(*tc)) {
+            self.time_control_game_index
+                .load_entry_mut(&tc)
+                .await
+                .map_err(|e| format!("Failed to load time control index: {}", e))?
+                .insert(&game.id, ())
+                .map_err(|e| format!("Failed to index game: {}", e))?;
+        }
+
+        let previous_tournament = previous.and_then(|g| g.tournament_id.as_ref());
+        if let Some(tournament_id) = game.tournament_id.as_ref().filter(|id| previous_tournament != Some(*id)) {
+            self.tournament_game_index
+                .load_entry_mut(tournament_id)
+                .await
+                .map_err(|e| format!("Failed to load tournament game index: {}", e))?
+                .insert(&game.id, ())
+                .map_err(|e| format!("Failed to index game: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Get the game, player and queue counts
+    pub fn get_totals(&self) -> Totals {
+        Totals {
+            total_games: *self.total_game_count.get(),
+            pending_games: *self.pending_game_count.get(),
+            active_games: *self.active_game_count.get(),
+            registered_players: *self.player_count.get(),
+            queued_players: *self.queued_player_count.get(),
+        }
+    }
+
+    /// Track active games between two human players by their average rating
+    async fn index_featured_game(
+        &mut self,
+        previous: Option<&CheckersGame>,
+        game: &CheckersGame,
+    ) -> Result<(), String> {
+        let was_active = previous.is_some_and(|g| g.status == GameStatus::Active);
+        let is_active = game.status == GameStatus::Active;
+        if was_active && !is_active {
+            let _ = self.featured_games.remove(&game.id);
+            return Ok(());
+        }
+        if was_active || !is_active {
+            return Ok(());
+        }
+
+        let humans = game.red_player_type == PlayerType::Human && game.black_player_type == PlayerType::Human;
+        if let (true, Some(red), Some(black)) = (humans, &game.red_player, &game.black_player) {
+            if red == "AI" || black == "AI" {
+                return Ok(());
+            }
+            let time_control = game.clock.as_ref()
+                .and_then(TimeControl::from_clock)
+                .unwrap_or(TimeControl::Blitz5_3);
+            let red_rating = self.get_player_stats(red).await.get_rating(&time_control);
+            let black_rating = self.get_player_stats(black).await.get_rating(&time_control);
+            self.featured_games
+                .insert(&game.id, (red_rating + black_rating) / 2)
+                .map_err(|e| format!("Failed to update featured games: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Pick a new TV game when there is none or the current one has ended
+    async fn update_tv_game(&mut self, saved_game_id: &str, is_active: bool) {
+        let current = self.tv_game_id.get().clone();
+        let needs_pick = match &current {
+            None => true,
+            Some(tv_game_id) => tv_game_id == saved_game_id && !is_active,
+        };
+        if needs_pick {
+            let best = self.pick_tv_game().await;
+            self.tv_game_id.set(best);
+        }
+    }
+
+    /// The best featured game: highest average rating plus a bonus for slower
+    /// time controls, with the most recently started game winning ties
+    async fn pick_tv_game(&self) -> Option<String> {
+        let mut entries = Vec::new();
+        let _ = self.featured_games
+            .for_each_index_value(|game_id, rating| {
+                entries.push((game_id, *rating));
                 Ok(())
             })
             .await;
 
-        let mut result = Vec::new();
-        for game_id in game_ids {
-            if let Some(game) = self.get_game(&game_id).await {
-                result.push(game);
+        let mut best: Option<((u32, u64, String), String)> = None;
+        for (game_id, rating) in entries {
+            let Some(game) = self.get_game(&game_id).await else {
+                continue;
+            };
+            let bonus = match game.clock.as_ref().and_then(TimeControl::from_clock).map(|tc| tc.category()) {
+                Some(RatingCategory::Rapid) => 150,
+                Some(RatingCategory::Blitz) => 100,
+                Some(RatingCategory::Bullet) => 50,
+                None => 0,
+            };
+            let score = (rating + bonus, game.created_at, game_id.clone());
+            if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+                best = Some((score, game_id));
             }
         }
-        result
+        best.map(|(_, game_id)| game_id)
     }
 
-    /// Get games for a player
-    pub async fn get_player_games(&self, chain_id: &str) -> Vec<CheckersGame> {
-        self.get_all_games()
-            .await
-            .into_iter()
-            .filter(|g| {
-                g.red_player.as_deref() == Some(chain_id)
-                    || g.black_player.as_deref() == Some(chain_id)
+    /// Get the game currently shown on TV
+    pub async fn get_tv_game(&self) -> Option<CheckersGame> {
+        let game_id = self.tv_game_id.get().clone()?;
+        self.get_game(&game_id).await.filter(|game| game.status == GameStatus::Active)
+    }
+
+    /// Get the highest-rated active games, by average player rating
+    pub async fn get_featured_games(&self, limit: usize) -> Vec<CheckersGame> {
+        let mut entries = Vec::new();
+        let _ = self.featured_games
+            .for_each_index_value(|game_id, rating| {
+                entries.push((*rating, game_id));
+                Ok(())
             })
-            .collect()
+            .await;
+        entries.sort_by(|a, b| b.cmp(a));
+
+        let game_ids = entries.into_iter().take(limit).map(|(_, game_id)| game_id).collect();
+        self.get_games_by_id(game_ids).await
     }
 
-    /// Get player stats
-    pub async fn get_player_stats(&self, chain_id: &str) -> PlayerStats {
-        self.player_stats
-            .get(chain_id)
+    /// Get application-wide totals, with moves counted for the day containing `now`
+    pub async fn get_global_stats(&self, now: u64) -> GlobalStats {
+        GlobalStats {
+            games_played: *self.finished_game_count.get(),
+            games_active: *self.active_game_count.get(),
+            players: *self.player_count.get(),
+            tournaments: *self.tournament_count.get(),
+            moves_today: self.moves_by_day.get(&(now / MICROS_PER_DAY)).await.ok().flatten().unwrap_or(0),
+        }
+    }
+
+    /// Append a move to a game's move log
+    pub async fn append_move(&mut self, game_id: &str, checkers_move: CheckersMove) -> Result<(), String> {
+        self.game_moves
+            .load_entry_mut(game_id)
             .await
-            .ok()
-            .flatten()
-            .unwrap_or_else(|| PlayerStats::new(chain_id.to_string()))
+            .map_err(|e| format!("Failed to record move: {}", e))?
+            .push(checkers_move);
+        Ok(())
     }
 
-    /// Update player stats
-    pub async fn update_player_stats(&mut self, stats: PlayerStats) -> Result<(), String> {
-        let chain_id = stats.chain_id.clone();
-        self.player_stats
-            .insert(&chain_id, stats)
-            .map_err(|e| format!("Failed to update stats: {}", e))
+    /// Get up to `limit` moves of a game starting at move index `from`
+    /// Moves stored on the game itself come first, followed by the move log.
+    pub async fn get_moves(&self, game: &CheckersGame, from: usize, limit: usize) -> Vec<CheckersMove> {
+        let end = from.saturating_add(limit);
+        let mut moves: Vec<CheckersMove> = game.moves.iter().skip(from).take(limit).cloned().collect();
+
+        if let Ok(Some(log)) = self.game_moves.try_load_entry(&game.id).await {
+            let log_from = from.saturating_sub(game.moves.len());
+            let log_end = end.saturating_sub(game.moves.len()).min(log.count());
+            if log_from < log_end {
+                moves.extend(log.read(log_from..log_end).await.unwrap_or_default());
+            }
+        }
+        moves
     }
 
-    /// Get leaderboard
-    pub async fn get_leaderboard(&self, limit: usize) -> Vec<PlayerStats> {
-        let mut all_stats = Vec::new();
-        let _ = self.player_stats
-            .for_each_index_value(|_id, stats| {
-                all_stats.push(stats.into_owned());
-                Ok(())
-            })
-            .await;
+    /// Store the annotations produced by analyzing a game
+    pub fn save_analysis(&mut self, game_id: &str, annotations: Vec<MoveAnnotation>) -> Result<(), String> {
+        self.game_analysis
+            .insert(game_id, annotations)
+            .map_err(|e| format!("Failed to save analysis: {}", e))
+    }
 
-        all_stats.sort_by(|a, b| b.games_won.cmp(&a.games_won));
-        all_stats.truncate(limit);
-        all_stats
+    /// Get the annotations of an analyzed game
+    pub async fn get_analysis(&self, game_id: &str) -> Option<Vec<MoveAnnotation>> {
+        self.game_analysis.get(game_id).await.ok().flatten()
     }
 
-    /// Record game result with ELO rating updates
-    /// For casual games (is_rated == false), only updates win/loss counts, not ELO
-    pub async fn record_game_result(
+    /// Count a finished game in the opening explorer entry of a position it passed through
+    pub async fn record_opening_position(
         &mut self,
-        game: &CheckersGame,
+        position_hash: &str,
         result: GameResult,
+        next: Option<OpeningMove>,
     ) -> Result<(), String> {
-        // Skip entirely for in-progress games
-        if result == GameResult::InProgress {
-            return Ok(());
-        }
+        let mut stats = self.get_opening(position_hash).await
+            .unwrap_or_else(|| OpeningStats::new(position_hash.to_string()));
+        stats.record(result, next);
+        self.opening_explorer
+            .insert(position_hash, stats)
+            .map_err(|e| format!("Failed to save opening stats: {}", e))
+    }
 
-        let red_is_ai = game.red_player.as_deref() == Some("AI") || game.red_player_type == PlayerType::AI;
-        let black_is_ai = game.black_player.as_deref() == Some("AI") || game.black_player_type == PlayerType::AI;
+    /// Get the opening explorer entry of a position
+    pub async fn get_opening(&self, position_hash: &str) -> Option<OpeningStats> {
+        self.opening_explorer.get(position_hash).await.ok().flatten()
+    }
 
-        // For casual games, just update win/loss counts without ELO changes
-        if !game.is_rated {
-            return self.record_game_counts_only(game, result, red_is_ai, black_is_ai).await;
+    /// Add a puzzle and return its ID
+    pub fn add_puzzle(&mut self, mut puzzle: Puzzle) -> Result<String, String> {
+        let next_id = self.next_puzzle_id.get() + 1;
+        self.next_puzzle_id.set(next_id);
+        puzzle.id = format!("puzzle_{:06}", next_id);
+        let puzzle_id = puzzle.id.clone();
+        self.puzzles
+            .insert(&puzzle_id, puzzle)
+            .map_err(|e| format!("Failed to save puzzle: {}", e))?;
+        Ok(puzzle_id)
+    }
+
+    pub async fn get_puzzle(&self, puzzle_id: &str) -> Option<Puzzle> {
+        self.puzzles.get(puzzle_id).await.ok().flatten()
+    }
+
+    /// Pick a puzzle from a seed. Puzzle IDs are sequential, so any number maps to one.
+    pub fn pick_puzzle_id(&self, seed: u64) -> Option<String> {
+        let count = *self.next_puzzle_id.get();
+        if count == 0 {
+            return None;
         }
+        Some(format!("puzzle_{:06}", seed % count + 1))
+    }
 
-        // Derive time control from clock, default to Blitz5_3 if not set
-        let time_control = game.clock.as_ref()
-            .map(|clock| {
-                // Match initial_time_ms and increment_ms to find the TimeControl
-                match (clock.initial_time_ms, clock.increment_ms) {
-                    (60_000, 0) => TimeControl::Bullet1_0,
-                    (120_000, 1_000) => TimeControl::Bullet2_1,
-                    (180_000, 0) => TimeControl::Blitz3_0,
-                    (300_000, 3_000) => TimeControl::Blitz5_3,
-                    (600_000, 0) => TimeControl::Rapid10_0,
-                    _ => TimeControl::Blitz5_3, // Default fallback
-                }
-            })
-            .unwrap_or(TimeControl::Blitz5_3);
+    pub async fn get_puzzle_rush(&self, player_id: &str) -> Option<PuzzleRushSession> {
+        self.puzzle_rush_sessions.get(player_id).await.ok().flatten()
+    }
 
-        // AI rating is fixed at 1500
-        const AI_RATING: u32 = 1500;
+    pub fn save_puzzle_rush(&mut self, session: PuzzleRushSession) -> Result<(), String> {
+        let player_id = session.player_id.clone();
+        self.puzzle_rush_sessions
+            .insert(&player_id, session)
+            .map_err(|e| format!("Failed to save puzzle rush: {}", e))
+    }
 
-        // Get current stats for both players
-        let mut red_stats = if !red_is_ai {
-            if let Some(chain) = game.red_player.as_deref() {
-                self.get_player_stats(chain).await
-            } else {
-                PlayerStats::new("unknown".to_string())
-            }
-        } else {
-            PlayerStats::new("AI".to_string())
-        };
+    /// Get a page of players by best puzzle rush streak
+    pub async fn get_puzzle_rush_leaderboard(&self, offset: usize, limit: usize) -> Vec<PlayerStats> {
+        top_player_stats(
+            &self.player_stats,
+            offset,
+            limit,
+            LeaderboardSort::PuzzleRush,
+            TimeControl::Blitz5_3,
+            |stats| stats.puzzle_rush_best > 0,
+        )
+        .await
+    }
 
-        let mut black_stats = if !black_is_ai {
-            if let Some(chain) = game.black_player.as_deref() {
-                self.get_player_stats(chain).await
-            } else {
-                PlayerStats::new("unknown".to_string())
-            }
-        } else {
+    /// Record that a player is watching a game
+    pub async fn add_watched_game(&mut self, player_id: &str, game_id: &str) -> Result<(), String> {
+        self.watched_games
+            .load_entry_mut(player_id)
+            .await
+            .map_err(|e| format!("Failed to load watched games: {}", e))?
+            .insert(game_id, ())
+            .map_err(|e| format!("Failed to watch game: {}", e))
+    }
+
+    /// Record that a player stopped watching a game
+    pub async fn remove_watched_game(&mut self, player_id: &str, game_id: &str) -> Result<(), String> {
+        self.watched_games
+            .load_entry_mut(player_id)
+            .await
+            .map_err(|e| format!("Failed to load watched games: {}", e))?
+            .remove(game_id)
+            .map_err(|e| format!("Failed to unwatch game: {}", e))
+    }
+
+    /// Get the unfinished games a player is watching
+    pub async fn get_watched_games(&self, player_id: &str) -> Vec<CheckersGame> {
+        let game_ids = index_keys(&self.watched_games, &player_id.to_string()).await;
+        self.get_games_by_id(game_ids)
+            .await
+            .into_iter()
+            .filter(|game| !game.is_over())
+            .collect()
+    }
+
+    /// Append a chat message to a game's chat log
+    /// Returns the number of messages in the log afterwards
+    pub async fn append_chat_message(&mut self, game_id: &str, message: ChatMessage) -> Result<u32, OperationError> {
+        let log = self.game_chat
+            .load_entry_mut(game_id)
+            .await
+            .map_err(|e| OperationError::storage(format!("Failed to load game chat: {}", e)))?;
+        if log.count() >= MAX_CHAT_MESSAGES_PER_GAME {
+            return Err(OperationError::new(ErrorCode::LimitReached, "Chat is full for this game"));
+        }
+        log.push(message);
+        Ok(log.count() as u32)
+    }
+
+    /// Get up to `limit` chat messages of a game starting at index `from`
+    pub async fn get_chat_messages(&self, game_id: &str, from: usize, limit: usize) -> Vec<ChatMessage> {
+        let log = match self.game_chat.try_load_entry(game_id).await {
+            Ok(Some(log)) => log,
+            _ => return Vec::new(),
+        };
+        let end = from.saturating_add(limit).min(log.count());
+        if from >= end {
+            return Vec::new();
+        }
+        log.read(from..end).await.unwrap_or_default()
+    }
+
+    /// Append an emote to a game, enforcing the per-side cooldown and the per-game cap
+    pub async fn append_emote(&mut self, game_id: &str, record: EmoteRecord) -> Result<(), OperationError> {
+        let log = self.game_emotes
+            .load_entry_mut(game_id)
+            .await
+            .map_err(|e| OperationError::storage(format!("Failed to load game emotes: {}", e)))?;
+        let count = log.count();
+        if count >= MAX_EMOTES_PER_GAME {
+            return Err(OperationError::new(ErrorCode::LimitReached, "Emote limit reached for this game"));
+        }
+
+        // Only the last few entries can fall inside the cooldown window
+        let recent = log.read(count.saturating_sub(10)..count).await.unwrap_or_default();
+        let too_soon = recent.iter().any(|previous| {
+            previous.sender == record.sender
+                && record.timestamp < previous.timestamp.saturating_add(EMOTE_COOLDOWN_MICROS)
+        });
+        if too_soon {
+            return Err(OperationError::new(ErrorCode::LimitReached, "Sending emotes too quickly"));
+        }
+
+        log.push(record);
+        Ok(())
+    }
+
+    /// Get up to `limit` emotes of a game starting at index `from`
+    pub async fn get_emotes(&self, game_id: &str, from: usize, limit: usize) -> Vec<EmoteRecord> {
+        let log = match self.game_emotes.try_load_entry(game_id).await {
+            Ok(Some(log)) => log,
+            _ => return Vec::new(),
+        };
+        let end = from.saturating_add(limit).min(log.count());
+        if from >= end {
+            return Vec::new();
+        }
+        log.read(from..end).await.unwrap_or_default()
+    }
+
+    /// Get a page of finished game summaries, newest first
+    /// `after` is the ID of the last summary of the previous page.
+    pub async fn get_archived_summaries(&self, after: Option<String>, limit: usize) -> Vec<GameSummary> {
+        let mut game_ids = self.game_summaries.indices().await.unwrap_or_default();
+        game_ids.sort_by(|a, b| b.cmp(a));
+
+        let mut summaries = Vec::new();
+        for game_id in game_ids
+            .into_iter()
+            .filter(|id| after.as_ref().is_none_or(|after| id < after))
+            .take(limit)
+        {
+            if let Some(summary) = self.game_summaries.get(&game_id).await.ok().flatten() {
+                summaries.push(summary);
+            }
+        }
+        summaries
+    }
+
+    /// Get a page of games, live and archived, oldest first
+    /// Game IDs are zero-padded counters, so they sort in creation order and only the
+    /// games on the page are loaded. `after` is the ID of the last game of the previous page.
+    pub async fn get_games_page(&self, after: Option<String>, first: usize) -> Vec<CheckersGame> {
+        let mut game_ids = self.games.indices().await.unwrap_or_default();
+        game_ids.extend(self.archived_games.indices().await.unwrap_or_default());
+        game_ids.sort();
+
+        let start = after.map_or(0, |after| game_ids.partition_point(|id| *id <= after));
+        let page = game_ids.into_iter().skip(start).take(first).collect();
+        self.get_games_by_id(page).await
+    }
+
+    /// Search games, newest first
+    /// Candidates come from the most selective index the filter allows (player,
+    /// tournament, status, then time control); the remaining filters are checked
+    /// on each candidate. `after` is the ID of the last game of the previous page.
+    pub async fn search_games(&self, filter: &GameFilter, after: Option<String>, limit: usize) -> Vec<CheckersGame> {
+        let mut game_ids = if let Some(player) = &filter.player {
+            index_keys(&self.player_game_index, player).await
+        } else if let Some(tournament_id) = &filter.tournament_id {
+            index_keys(&self.tournament_game_index, tournament_id).await
+        } else if let Some(status) = filter.status {
+            match status {
+                GameStatus::Pending => self.pending_games.indices().await.unwrap_or_default(),
+                GameStatus::Active => self.active_games.indices().await.unwrap_or_default(),
+                GameStatus::Finished => self.game_summaries.indices().await.unwrap_or_default(),
+                GameStatus::Cancelled => Vec::new(),
+            }
+        } else if let Some(time_control) = filter.time_control {
+            index_keys(&self.time_control_game_index, &time_control).await
+        } else {
+            let mut ids = self.games.indices().await.unwrap_or_default();
+            ids.extend(self.archived_games.indices().await.unwrap_or_default());
+            ids
+        };
+        // Game IDs are zero-padded counters, so they sort in creation order
+        game_ids.sort_by(|a, b| b.cmp(a));
+
+        let mut games = Vec::new();
+        for game_id in game_ids {
+            if games.len() >= limit {
+                break;
+            }
+            if after.as_ref().is_some_and(|after| &game_id >= after) {
+                continue;
+            }
+            if let Some(game) = self.get_game(&game_id).await {
+                if filter.matches(&game) {
+                    games.push(game);
+                }
+            }
+        }
+        games
+    }
+
+    /// Get pending games
+    pub async fn get_pending_games(&self) -> Vec<CheckersGame> {
+        let game_ids = self.pending_games.indices().await.unwrap_or_default();
+        self.get_games_by_id(game_ids).await
+    }
+
+    /// Up to `limit` open games that expired before anyone joined. Pending games
+    /// are checked oldest first, and at most `max_checked` of them are loaded.
+    pub async fn get_expired_pending_games(&self, now: u64, limit: usize, max_checked: usize) -> Vec<CheckersGame> {
+        let mut game_ids = Vec::new();
+        let _ = self.pending_games
+            .for_each_index_while(|game_id| {
+                game_ids.push(game_id);
+                Ok(game_ids.len() < max_checked)
+            })
+            .await;
+        let mut expired = Vec::new();
+        for game_id in game_ids {
+            if expired.len() >= limit {
+                break;
+            }
+            if let Some(game) = self.get_game(&game_id).await.filter(|game| game.is_expired_seek(now)) {
+                expired.push(game);
+            }
+        }
+        expired
+    }
+
+    /// Save the invite code of a private game
+    pub fn save_game_invite_code(&mut self, invite_code: &str, game_id: &str) -> Result<(), String> {
+        self.game_invite_index
+            .insert(&invite_code.to_uppercase(), game_id.to_string())
+            .map_err(|e| format!("Failed to save invite code: {}", e))
+    }
+
+    /// Get the ID of the open private game with this invite code
+    pub async fn get_game_id_by_code(&self, invite_code: &str) -> Option<String> {
+        self.game_invite_index.get(&invite_code.to_uppercase()).await.ok().flatten()
+    }
+
+    /// Get active games
+    pub async fn get_active_games(&self) -> Vec<CheckersGame> {
+        let game_ids = self.active_games.indices().await.unwrap_or_default();
+        self.get_games_by_id(game_ids).await
+    }
+
+    /// Load the games with the given IDs, skipping any that no longer exist
+    async fn get_games_by_id(&self, game_ids: Vec<String>) -> Vec<CheckersGame> {
+        let mut result = Vec::new();
+        for game_id in game_ids {
+            if let Some(game) = self.get_game(&game_id).await {
+                result.push(game);
+            }
+        }
+        result
+    }
+
+    /// Get a page of a player's games, newest first
+    /// `after` is the ID of the last game of the previous page.
+    pub async fn get_player_games(
+        &self,
+        chain_id: &str,
+        status: Option<GameStatus>,
+        time_control: Option<TimeControl>,
+        after: Option<String>,
+        limit: usize,
+    ) -> Vec<CheckersGame> {
+        let filter = GameFilter {
+            player: Some(chain_id.to_string()),
+            status,
+            time_control,
+            ..Default::default()
+        };
+        self.search_games(&filter, after, limit).await
+    }
+
+    /// Get player stats
+    pub async fn get_player_stats(&self, chain_id: &str) -> PlayerStats {
+        self.player_stats
+            .get(chain_id)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| PlayerStats::with_config(chain_id.to_string(), self.rating_config.get()))
+    }
+
+    /// A player's play-style statistics, empty before their first finished game
+    pub async fn get_play_style_stats(&self, chain_id: &str) -> PlayStyleStats {
+        self.play_style_stats.get(chain_id).await.ok().flatten().unwrap_or_default()
+    }
+
+    pub fn save_play_style_stats(&mut self, chain_id: &str, stats: PlayStyleStats) -> Result<(), String> {
+        self.play_style_stats
+            .insert(chain_id, stats)
+            .map_err(|e| format!("Failed to save play-style stats: {}", e))
+    }
+
+    /// Update player stats
+    pub async fn update_player_stats(&mut self, stats: PlayerStats) -> Result<(), String> {
+        let chain_id = stats.chain_id.clone();
+        if !self.player_stats.contains_key(&chain_id).await.unwrap_or(true) {
+            *self.player_count.get_mut() += 1;
+        }
+        self.player_stats
+            .insert(&chain_id, stats)
+            .map_err(|e| format!("Failed to update stats: {}", e))
+    }
+
+    /// Get a page of the leaderboard
+    /// Players whose ratings are all still provisional are left out, as are players
+    /// with fewer than `min_games` games (counted in the rating category when sorting by rating).
+    /// At most MAX_LEADERBOARD_SCAN entries are scanned, and only the top `offset + limit`
+    /// entries are kept in memory while scanning.
+    pub async fn get_leaderboard(
+        &self,
+        offset: usize,
+        limit: usize,
+        sort: LeaderboardSort,
+        time_control: TimeControl,
+        min_games: u32,
+    ) -> Vec<PlayerStats> {
+        top_player_stats(&self.player_stats, offset, limit, sort, time_control, |stats| {
+            let games = match sort {
+                LeaderboardSort::Rating => stats.get_games_in_category(&time_control),
+                _ => stats.games_played,
+            };
+            stats.has_established_rating() && games >= min_games
+        })
+        .await
+    }
+
+    /// Record game result with ELO rating updates
+    /// For casual games (is_rated == false), only updates win/loss counts, not ELO
+    pub async fn record_game_result(
+        &mut self,
+        game: &CheckersGame,
+        result: GameResult,
+    ) -> Result<(), String> {
+        // Skip entirely for in-progress games
+        if result == GameResult::InProgress {
+            return Ok(());
+        }
+
+        let red_is_ai = game.red_player.as_deref() == Some("AI") || game.red_player_type == PlayerType::AI;
+        let black_is_ai = game.black_player.as_deref() == Some("AI") || game.black_player_type == PlayerType::AI;
+
+        // For casual games, just update win/loss counts without ELO changes
+        if !game.is_rated {
+            self.record_game_counts_only(game, result, red_is_ai, black_is_ai).await?;
+            return self.award_game_achievements(game, result).await;
+        }
+
+        // Derive time control from clock, default to Blitz5_3 if not set
+        let time_control = game.clock.as_ref()
+            .and_then(TimeControl::from_clock)
+            .unwrap_or(TimeControl::Blitz5_3);
+
+        // AI rating is fixed by the deployment
+        let ai_rating = self.game_config.get().ai_rating;
+
+        // Get current stats for both players
+        let mut red_stats = if !red_is_ai {
+            if let Some(chain) = game.red_player.as_deref() {
+                self.get_player_stats(chain).await
+            } else {
+                PlayerStats::new("unknown".to_string())
+            }
+        } else {
+            PlayerStats::new("AI".to_string())
+        };
+
+        let mut black_stats = if !black_is_ai {
+            if let Some(chain) = game.black_player.as_deref() {
+                self.get_player_stats(chain).await
+            } else {
+                PlayerStats::new("unknown".to_string())
+            }
+        } else {
             PlayerStats::new("AI".to_string())
         };
 
-        // Get ratings BEFORE updates
-        let red_rating = if red_is_ai { AI_RATING } else { red_stats.get_rating(&time_control) };
-        let black_rating = if black_is_ai { AI_RATING } else { black_stats.get_rating(&time_control) };
+        // Season stats start from a soft reset of the lifetime stats
+        let season_id = self.ensure_current_season(game.updated_at).await?;
+        let mut red_season = self.get_season_stats(season_id, &red_stats).await;
+        let mut black_season = self.get_season_stats(season_id, &black_stats).await;
+
+        // Get ratings BEFORE updates
+        let red_rating = if red_is_ai { ai_rating } else { red_stats.get_rating(&time_control) };
+        let black_rating = if black_is_ai { ai_rating } else { black_stats.get_rating(&time_control) };
+        let red_season_rating = if red_is_ai { ai_rating } else { red_season.get_rating(&time_control) };
+        let black_season_rating = if black_is_ai { ai_rating } else { black_season.get_rating(&time_control) };
+
+        let config = *self.rating_config.get();
+        apply_rated_result(&mut red_stats, &mut black_stats, red_rating, black_rating, result, &time_control, &config);
+        red_stats.record_color_result(Turn::Red, result);
+        black_stats.record_color_result(Turn::Black, result);
+        apply_rated_result(&mut red_season, &mut black_season, red_season_rating, black_season_rating, result, &time_control, &config);
+        red_season.record_color_result(Turn::Red, result);
+        black_season.record_color_result(Turn::Black, result);
+        red_stats.update_title();
+        black_stats.update_title();
+        red_season.title = red_stats.title;
+        black_season.title = black_stats.title;
+
+        if !red_is_ai {
+            self.log_rating_change(&red_stats, game, time_control, red_rating).await?;
+            self.update_rating_index(&red_stats, time_control, red_rating).await?;
+            self.update_player_stats(red_stats).await?;
+            self.update_season_stats(season_id, red_season).await?;
+        }
+        if !black_is_ai {
+            self.log_rating_change(&black_stats, game, time_control, black_rating).await?;
+            self.update_rating_index(&black_stats, time_control, black_rating).await?;
+            self.update_player_stats(black_stats).await?;
+            self.update_season_stats(season_id, black_season).await?;
+        }
+
+        self.award_game_achievements(game, result).await
+    }
+
+    /// Award the achievements each human player earned with a finished game
+    async fn award_game_achievements(&mut self, game: &CheckersGame, result: GameResult) -> Result<(), String> {
+        let (red_pieces, black_pieces) = count_pieces(&game.board_state);
+        let sides = [
+            (&game.red_player, game.red_player_type, result == GameResult::RedWins, red_pieces),
+            (&game.black_player, game.black_player_type, result == GameResult::BlackWins, black_pieces),
+        ];
+        for (player, player_type, won, pieces_left) in sides {
+            let Some(player) = player.as_deref() else { continue };
+            if player == "AI" || player_type == PlayerType::AI {
+                continue;
+            }
+            let stats = self.get_player_stats(player).await;
+            for achievement in Achievement::earned_after_game(&stats, won, pieces_left) {
+                self.award_achievement(player, achievement, &game.id, game.updated_at).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Add XP to a player's stats, returning the new level if they gained one
+    pub async fn add_xp(&mut self, player_id: &str, amount: u64) -> Result<Option<LevelUp>, String> {
+        let mut stats = self.get_player_stats(player_id).await;
+        let old_level = level_for_xp(stats.xp);
+        stats.xp += amount;
+        let level = level_for_xp(stats.xp);
+        let xp = stats.xp;
+        self.update_player_stats(stats).await?;
+        Ok((level > old_level).then(|| LevelUp { player_id: player_id.to_string(), level, xp }))
+    }
+
+    /// A player's missions for the periods containing `now`
+    pub async fn get_missions(&self, player_id: &str, now: u64) -> Vec<Mission> {
+        self.current_missions(player_id, now).await
+            .into_iter()
+            .map(|(_, mission)| mission)
+            .collect()
+    }
+
+    /// Current missions with their storage keys.
+    /// Missions saved in an earlier period are replaced by the new rotation.
+    async fn current_missions(&self, player_id: &str, now: u64) -> Vec<((MissionPeriod, u32), Mission)> {
+        let saved = self.missions.try_load_entry(player_id).await.ok().flatten();
+        let mut missions = Vec::new();
+        for period in [MissionPeriod::Daily, MissionPeriod::Weekly] {
+            let period_index = period.index_at(now);
+            for (slot, fresh) in Mission::rotation(period, period_index).into_iter().enumerate() {
+                let key = (period, slot as u32);
+                let current = match &saved {
+                    Some(map) => map.get(&key).await.ok().flatten()
+                        .filter(|mission| mission.period_index == period_index),
+                    None => None,
+                };
+                missions.push((key, current.unwrap_or(fresh)));
+            }
+        }
+        missions
+    }
+
+    /// Count progress on a player's current missions with the given goal.
+    /// Returns the missions this completed.
+    pub async fn record_mission_progress(
+        &mut self,
+        player_id: &str,
+        goal: MissionGoal,
+        amount: u32,
+        now: u64,
+    ) -> Result<Vec<Mission>, String> {
+        let missions = self.current_missions(player_id, now).await;
+        let map = self.missions
+            .load_entry_mut(player_id)
+            .await
+            .map_err(|e| format!("Failed to load missions: {}", e))?;
+        let mut completed = Vec::new();
+        for (key, mut mission) in missions {
+            if mission.goal != goal {
+                continue;
+            }
+            if mission.advance(amount, now) {
+                completed.push(mission.clone());
+            }
+            map.insert(&key, mission)
+                .map_err(|e| format!("Failed to save mission: {}", e))?;
+        }
+        Ok(completed)
+    }
+
+    /// Give a player an achievement unless they already have it
+    pub async fn award_achievement(
+        &mut self,
+        player_id: &str,
+        achievement: Achievement,
+        source_id: &str,
+        awarded_at: u64,
+    ) -> Result<(), String> {
+        let badges = self.achievements
+            .load_entry_mut(player_id)
+            .await
+            .map_err(|e| format!("Failed to load achievements: {}", e))?;
+        if badges.contains_key(&achievement).await.unwrap_or(false) {
+            return Ok(());
+        }
+        badges
+            .insert(&achievement, Badge { achievement, source_id: source_id.to_string(), awarded_at })
+            .map_err(|e| format!("Failed to award achievement: {}", e))
+    }
+
+    /// Get a player's badges, in achievement order
+    pub async fn get_achievements(&self, player_id: &str) -> Vec<Badge> {
+        let mut badges = Vec::new();
+        if let Ok(Some(map)) = self.achievements.try_load_entry(player_id).await {
+            let _ = map
+                .for_each_index_value(|_, badge| {
+                    badges.push(badge.into_owned());
+                    Ok(())
+                })
+                .await;
+        }
+        badges
+    }
+
+    /// Append a rating change to the player's history
+    async fn log_rating_change(
+        &mut self,
+        stats: &PlayerStats,
+        game: &CheckersGame,
+        time_control: TimeControl,
+        old_rating: u32,
+    ) -> Result<(), String> {
+        let change = RatingChange {
+            game_id: game.id.clone(),
+            old_rating,
+            new_rating: stats.get_rating(&time_control),
+            time_control,
+            timestamp: game.updated_at,
+        };
+        self.rating_history
+            .load_entry_mut(&stats.chain_id)
+            .await
+            .map_err(|e| format!("Failed to record rating history: {}", e))?
+            .push(change);
+        Ok(())
+    }
+
+    /// Move a player from their old rating bucket to their new one
+    /// Players enter the index with their first rated game in a category.
+    async fn update_rating_index(
+        &mut self,
+        stats: &PlayerStats,
+        time_control: TimeControl,
+        old_rating: u32,
+    ) -> Result<(), String> {
+        let new_rating = stats.get_rating(&time_control);
+        let was_indexed = stats.get_games_in_category(&time_control) > 1;
+        self.move_in_rating_index(time_control.category(), was_indexed.then_some(old_rating), new_rating).await
+    }
+
+    /// Move one player between rating buckets; `None` adds a player not yet indexed
+    async fn move_in_rating_index(
+        &mut self,
+        category: RatingCategory,
+        old_rating: Option<u32>,
+        new_rating: u32,
+    ) -> Result<(), String> {
+        if old_rating == Some(new_rating) {
+            return Ok(());
+        }
+
+        let index = self.rating_index
+            .load_entry_mut(&category)
+            .await
+            .map_err(|e| format!("Failed to load rating index: {}", e))?;
+        if let Some(old_rating) = old_rating {
+            let count = index.get(&old_rating).await.ok().flatten().unwrap_or(0);
+            if count > 1 {
+                index.insert(&old_rating, count - 1)
+                    .map_err(|e| format!("Failed to update rating index: {}", e))?;
+            } else {
+                index.remove(&old_rating)
+                    .map_err(|e| format!("Failed to update rating index: {}", e))?;
+            }
+        }
+        let count = index.get(&new_rating).await.ok().flatten().unwrap_or(0);
+        index.insert(&new_rating, count + 1)
+            .map_err(|e| format!("Failed to update rating index: {}", e))
+    }
+
+    /// Put a player's rating in a category back to the starting rating.
+    /// Returns the new rating.
+    pub async fn reset_rating(&mut self, player_id: &str, category: RatingCategory) -> Result<u32, String> {
+        let time_control = category.time_control();
+        let mut stats = self.get_player_stats(player_id).await;
+        let old_rating = stats.get_rating(&time_control);
+        let new_rating = self.rating_config.get().starting_rating;
+        stats.set_rating(&time_control, new_rating);
+        if stats.get_games_in_category(&time_control) > 0 {
+            self.move_in_rating_index(category, Some(old_rating), new_rating).await?;
+        }
+        self.update_player_stats(stats).await?;
+        Ok(new_rating)
+    }
+
+    /// Get a player's rank and percentile in each category they have played
+    pub async fn get_player_ranks(&self, chain_id: &str) -> Vec<PlayerRank> {
+        let stats = self.get_player_stats(chain_id).await;
+        let mut ranks = Vec::new();
+        for time_control in [TimeControl::Bullet1_0, TimeControl::Blitz3_0, TimeControl::Rapid10_0] {
+            if stats.get_games_in_category(&time_control) == 0 {
+                continue;
+            }
+            let rating = stats.get_rating(&time_control);
+            let index = match self.rating_index.try_load_entry(&time_control.category()).await {
+                Ok(Some(index)) => index,
+                _ => continue,
+            };
+
+            let (mut above, mut below, mut total) = (0u32, 0u32, 0u32);
+            let _ = index
+                .for_each_index_value(|bucket, count| {
+                    let count = *count;
+                    total += count;
+                    match bucket.cmp(&rating) {
+                        Ordering::Greater => above += count,
+                        Ordering::Less => below += count,
+                        Ordering::Equal => {}
+                    }
+                    Ok(())
+                })
+                .await;
+            if total == 0 {
+                continue;
+            }
+
+            ranks.push(PlayerRank {
+                category: time_control.category(),
+                rating,
+                rank: above + 1,
+                total_players: total,
+                percentile_bps: (below as u64 * 10_000 / total as u64) as u32,
+            });
+        }
+        ranks
+    }
+
+    /// Get the most recent rating changes for a player, oldest first
+    /// Optionally restricted to the rating category of a time control
+    pub async fn get_rating_history(
+        &self,
+        player_id: &str,
+        time_control: Option<TimeControl>,
+        limit: usize,
+    ) -> Vec<RatingChange> {
+        let log = match self.rating_history.try_load_entry(player_id).await {
+            Ok(Some(log)) => log,
+            _ => return Vec::new(),
+        };
+
+        let end = log.count();
+        let Some(time_control) = time_control else {
+            return log.read(end.saturating_sub(limit)..end).await.unwrap_or_default();
+        };
+
+        // Read backwards a chunk at a time until enough changes in the category are found
+        const CHUNK: usize = 100;
+        let mut changes: Vec<RatingChange> = Vec::new();
+        let mut chunk_end = end;
+        while chunk_end > 0 && changes.len() < limit {
+            let chunk_start = chunk_end.saturating_sub(CHUNK);
+            let chunk = log.read(chunk_start..chunk_end).await.unwrap_or_default();
+            changes.extend(
+                chunk
+                    .into_iter()
+                    .rev()
+                    .filter(|c| c.time_control.category() == time_control.category()),
+            );
+            chunk_end = chunk_start;
+        }
+        changes.truncate(limit);
+        changes.reverse();
+        changes
+    }
+
+    // ========================================================================
+    // SEASON METHODS
+    // ========================================================================
+
+    /// Make sure the season containing `timestamp` is recorded and current
+    /// Trophies of earlier seasons are not awarded here but by `award_season_trophies`,
+    /// so recording a result never scans the season's players.
+    /// Returns the current season ID
+    pub async fn ensure_current_season(&mut self, timestamp: u64) -> Result<u32, String> {
+        let season = Season::for_timestamp(timestamp);
+        let season_id = season.id;
+        if *self.current_season_id.get() != Some(season_id) {
+            self.seasons
+                .insert(&season_id, season)
+                .map_err(|e| format!("Failed to start season: {}", e))?;
+            self.current_season_id.set(Some(season_id));
+        }
+        Ok(season_id)
+    }
+
+    /// Get a season by ID
+    pub async fn get_season(&self, season_id: u32) -> Option<Season> {
+        self.seasons.get(&season_id).await.ok().flatten()
+    }
+
+    /// Get the current season, if any rated game has been played
+    pub async fn get_current_season(&self) -> Option<Season> {
+        match *self.current_season_id.get() {
+            Some(season_id) => self.get_season(season_id).await,
+            None => None,
+        }
+    }
+
+    /// Get all recorded seasons, newest first
+    pub async fn get_seasons(&self) -> Vec<Season> {
+        let mut seasons = Vec::new();
+        let _ = self.seasons
+            .for_each_index_value(|_id, season| {
+                seasons.push(season.into_owned());
+                Ok(())
+            })
+            .await;
+        seasons.sort_by_key(|season| Reverse(season.id));
+        seasons
+    }
+
+    /// Get a player's stats for a season, starting from their lifetime stats if
+    /// they have not played in it yet
+    pub async fn get_season_stats(&self, season_id: u32, lifetime: &PlayerStats) -> PlayerStats {
+        let existing = match self.season_stats.try_load_entry(&season_id).await {
+            Ok(Some(stats)) => stats.get(&lifetime.chain_id).await.ok().flatten(),
+            _ => None,
+        };
+        match existing {
+            // The username may have changed since the season record was created
+            Some(stats) => PlayerStats { username: lifetime.username.clone(), ..stats },
+            None => {
+                let decay_pct = self.get_season(season_id)
+                    .await
+                    .map(|season| season.rating_decay_pct)
+                    .unwrap_or(SEASON_RATING_DECAY_PCT);
+                lifetime.for_new_season(decay_pct, self.rating_config.get())
+            }
+        }
+    }
+
+    /// Update a player's stats for a season
+    pub async fn update_season_stats(&mut self, season_id: u32, stats: PlayerStats) -> Result<(), String> {
+        let chain_id = stats.chain_id.clone();
+        self.season_stats
+            .load_entry_mut(&season_id)
+            .await
+            .map_err(|e| format!("Failed to load season stats: {}", e))?
+            .insert(&chain_id, stats)
+            .map_err(|e| format!("Failed to update season stats: {}", e))
+    }
+
+    /// Award trophies for a finished season: the top-rated players in each rating
+    /// category, the player with the most games and the player with the best streak.
+    /// Each call scans up to MAX_LEADERBOARD_SCAN more of the season's players.
+    /// Returns None while players remain, then the number of trophies handed out.
+    pub async fn award_season_trophies(&mut self, season_id: u32, awarded_at: u64) -> Result<Option<u32>, OperationError> {
+        let mut season = match self.get_season(season_id).await {
+            Some(season) => season,
+            None => return Err(OperationError::new(ErrorCode::NotFound, "Season not found")),
+        };
+        if !season.can_award_trophies(awarded_at) {
+            return Err(OperationError::new(ErrorCode::InvalidState, "Season trophies cannot be awarded now"));
+        }
+
+        let time_controls = [TimeControl::Bullet1_0, TimeControl::Blitz3_0, TimeControl::Rapid10_0];
+        let mut scan = self.season_trophy_scans.get(&season_id).await.ok().flatten().unwrap_or_default();
+        scan.top_rated.resize(time_controls.len(), Vec::new());
+        let mut finished = true;
+        if let Ok(Some(stats)) = self.season_stats.try_load_entry(&season_id).await {
+            let skip = scan.scanned;
+            let end = skip + MAX_LEADERBOARD_SCAN as u64;
+            let mut index = 0;
+            let _ = stats
+                .for_each_index_value_while(|id, stats| {
+                    index += 1;
+                    if index <= skip {
+                        return Ok(true);
+                    }
+                    if index > end {
+                        finished = false;
+                        return Ok(false);
+                    }
+                    for (top, time_control) in scan.top_rated.iter_mut().zip(&time_controls) {
+                        if stats.get_games_in_category(time_control) > 0 {
+                            rank_into(top, &stats, SEASON_TROPHY_PLACES, LeaderboardSort::Rating, time_control);
+                        }
+                    }
+                    // Ties go to the smaller chain ID, which is scanned first
+                    if stats.games_played > 0 && scan.most_games.as_ref().is_none_or(|(n, _)| stats.games_played > *n) {
+                        scan.most_games = Some((stats.games_played, id.clone()));
+                    }
+                    if stats.best_streak > 0 && scan.best_streak.as_ref().is_none_or(|(n, _)| stats.best_streak > *n) {
+                        scan.best_streak = Some((stats.best_streak, id.clone()));
+                    }
+                    Ok(true)
+                })
+                .await;
+            scan.scanned = index.min(end);
+        }
+        if !finished {
+            self.season_trophy_scans
+                .insert(&season_id, scan)
+                .map_err(|e| OperationError::storage(format!("Failed to save trophy scan: {}", e)))?;
+            return Ok(None);
+        }
+
+        season.trophies_awarded = true;
+        self.seasons
+            .insert(&season_id, season)
+            .map_err(|e| OperationError::storage(format!("Failed to update season: {}", e)))?;
+        self.season_trophy_scans
+            .remove(&season_id)
+            .map_err(|e| OperationError::storage(format!("Failed to clear trophy scan: {}", e)))?;
+
+        let mut awards: Vec<(String, Trophy)> = Vec::new();
+        let trophy = |kind, rank, category| Trophy { season_id, kind, rank, category, awarded_at };
+        for (top, time_control) in scan.top_rated.into_iter().zip(&time_controls) {
+            for (i, stats) in top.into_iter().enumerate() {
+                awards.push((stats.chain_id, trophy(TrophyKind::TopRated, i as u32 + 1, Some(time_control.category()))));
+            }
+        }
+        if let Some((_, player_id)) = scan.most_games {
+            awards.push((player_id, trophy(TrophyKind::MostGames, 1, None)));
+        }
+        if let Some((_, player_id)) = scan.best_streak {
+            awards.push((player_id, trophy(TrophyKind::BestStreak, 1, None)));
+        }
+
+        let count = awards.len() as u32;
+        for (player_id, trophy) in awards {
+            self.trophies
+                .load_entry_mut(&player_id)
+                .await
+                .map_err(|e| OperationError::storage(format!("Failed to award trophy: {}", e)))?
+                .push(trophy);
+        }
+        Ok(Some(count))
+    }
+
+    /// Get all trophies won by a player, oldest first
+    pub async fn get_trophies(&self, player_id: &str) -> Vec<Trophy> {
+        match self.trophies.try_load_entry(player_id).await {
+            Ok(Some(log)) => log.read(..).await.unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get a page of season standings, ordered by rating in the given time control's category
+    /// Only players with at least one game in that category this season are included
+    pub async fn get_season_standings(
+        &self,
+        season_id: u32,
+        offset: usize,
+        limit: usize,
+        time_control: TimeControl,
+    ) -> Vec<PlayerStats> {
+        let stats = match self.season_stats.try_load_entry(&season_id).await {
+            Ok(Some(stats)) => stats,
+            _ => return Vec::new(),
+        };
+        top_player_stats(&stats, offset, limit, LeaderboardSort::Rating, time_control, |stats| {
+            stats.get_games_in_category(&time_control) > 0
+        })
+        .await
+    }
+
+    /// Record game counts only (for casual games - no ELO updates)
+    async fn record_game_counts_only(
+        &mut self,
+        game: &CheckersGame,
+        result: GameResult,
+        red_is_ai: bool,
+        black_is_ai: bool,
+    ) -> Result<(), String> {
+        // Get current stats for both players
+        let mut red_stats = if !red_is_ai {
+            if let Some(chain) = game.red_player.as_deref() {
+                self.get_player_stats(chain).await
+            } else {
+                return Ok(());
+            }
+        } else {
+            return Ok(()); // AI-only scenario
+        };
+
+        let mut black_stats = if !black_is_ai {
+            if let Some(chain) = game.black_player.as_deref() {
+                self.get_player_stats(chain).await
+            } else {
+                PlayerStats::new("unknown".to_string())
+            }
+        } else {
+            PlayerStats::new("AI".to_string())
+        };
+
+        if let Some(time_control) = game.clock.as_ref().and_then(TimeControl::from_clock) {
+            let red_outcome = match result {
+                GameResult::RedWins => 1.0,
+                GameResult::BlackWins => 0.0,
+                _ => 0.5,
+            };
+            red_stats.record_category_streak(&time_control, red_outcome);
+            black_stats.record_category_streak(&time_control, 1.0 - red_outcome);
+        }
+        red_stats.record_color_result(Turn::Red, result);
+        black_stats.record_color_result(Turn::Black, result);
+
+        match result {
+            GameResult::RedWins => {
+                if !red_is_ai {
+                    red_stats.record_win();
+                    self.update_player_stats(red_stats).await?;
+                }
+                if !black_is_ai {
+                    black_stats.record_loss();
+                    self.update_player_stats(black_stats).await?;
+                }
+            }
+            GameResult::BlackWins => {
+                if !black_is_ai {
+                    black_stats.record_win();
+                    self.update_player_stats(black_stats).await?;
+                }
+                if !red_is_ai {
+                    red_stats.record_loss();
+                    self.update_player_stats(red_stats).await?;
+                }
+            }
+            GameResult::Draw => {
+                if !red_is_ai {
+                    red_stats.record_draw();
+                    self.update_player_stats(red_stats).await?;
+                }
+                if !black_is_ai {
+                    black_stats.record_draw();
+                    self.update_player_stats(black_stats).await?;
+                }
+            }
+            GameResult::InProgress => {}
+        }
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // MATCHMAKING QUEUE METHODS
+    // ========================================================================
+
+    /// Empty the matchmaking queue, whose entries were stored under one map before
+    /// it was split into pools and don't read as pool entries. Queued players
+    /// just join again.
+    pub fn clear_matchmaking_queue(&mut self) {
+        self.matchmaking_queue.clear();
+        self.queued_player_count.set(0);
+    }
+
+    /// Join the matchmaking pool for one time control, rated or casual. A player
+    /// may wait in several pools at once; `home_chain` is the chain a remote
+    /// player queued from.
+    /// Returns the opponent's queue entry if a match was found, None if added to queue
+    pub async fn join_queue(
+        &mut self,
+        chain_id: &str,
+        time_control: TimeControl,
+        is_rated: bool,
+        timestamp: u64,
+        home_chain: Option<String>,
+    ) -> Result<Option<QueueEntry>, String> {
+        let queue_pool = QueuePool { time_control, is_rated };
+        let mut candidates = Vec::new();
+        let mut expired = Vec::new();
+        if let Ok(Some(pool)) = self.matchmaking_queue.try_load_entry(&queue_pool).await {
+            let _ = pool
+                .for_each_index_value(|opponent_chain_id, entry| {
+                    if entry.is_expired(timestamp) {
+                        expired.push(opponent_chain_id);
+                    } else if opponent_chain_id != chain_id {
+                        candidates.push(entry.into_owned());
+                    }
+                    Ok(())
+                })
+                .await;
+        }
+        // Entries left behind by clients that went away are dropped, not matched
+        for stale_id in expired {
+            self.remove_queue_entry(&stale_id, queue_pool).await?;
+        }
+
+        // Pair with the closest-rated opponent whose search band covers the
+        // gap; bands widen with wait time so nobody waits forever.
+        // Players who blocked each other are never paired, and recent
+        // opponents are skipped until their cooldown runs out
+        let rating = self.get_player_stats(chain_id).await.get_rating(&time_control);
+        let recent_opponents = self.get_recent_queue_opponents(chain_id).await;
+        let mut matched_opponent: Option<(u32, QueueEntry)> = None;
+        for candidate in candidates {
+            let opponent_rating = self.get_player_stats(&candidate.chain_id).await.get_rating(&time_control);
+            let gap = rating.abs_diff(opponent_rating);
+            if gap > candidate.rating_band(timestamp) {
+                continue;
+            }
+            if self.is_blocked_between(chain_id, &candidate.chain_id).await {
+                continue;
+            }
+            if recent_opponents.in_cooldown(&candidate.chain_id, timestamp) {
+                continue;
+            }
+            let better = match &matched_opponent {
+                None => true,
+                Some((best_gap, best)) => (gap, candidate.joined_at) < (*best_gap, best.joined_at),
+            };
+            if better {
+                matched_opponent = Some((gap, candidate));
+            }
+        }
+        let matched_opponent = matched_opponent.map(|(_, entry)| entry);
+
+        if let Some(opponent) = matched_opponent {
+            // Match found: both players leave every pool they were waiting in
+            self.leave_queue(&opponent.chain_id).await?;
+            self.leave_queue(chain_id).await?;
+            self.record_queue_pairing(chain_id, &opponent.chain_id, timestamp).await?;
+            self.record_queue_pairing(&opponent.chain_id, chain_id, timestamp).await?;
+            Ok(Some(opponent))
+        } else {
+            // No match: add player to this pool. Joining a pool again renews the
+            // entry but keeps its place, so the rating band keeps widening
+            let newly_queued = self.get_queue_entries(chain_id).await.is_empty();
+            let mut entry = QueueEntry::new(chain_id.to_string(), time_control, timestamp);
+            if let Some(existing) = self.get_queue_entry(chain_id, queue_pool).await {
+                entry.joined_at = existing.joined_at;
+            }
+            entry.home_chain = home_chain;
+            entry.is_rated = is_rated;
+            self.matchmaking_queue
+                .load_entry_mut(&queue_pool)
+                .await
+                .map_err(|e| format!("Failed to join queue: {}", e))?
+                .insert(&chain_id.to_string(), entry)
+                .map_err(|e| format!("Failed to join queue: {}", e))?;
+            if newly_queued {
+                *self.queued_player_count.get_mut() += 1;
+            }
+            Ok(None)
+        }
+    }
+
+    /// A player's recent queue opponents
+    pub async fn get_recent_queue_opponents(&self, chain_id: &str) -> RecentOpponents {
+        self.recent_queue_opponents.get(chain_id).await.ok().flatten().unwrap_or_default()
+    }
+
+    /// Remember that `chain_id` was just paired with `opponent` by the queue
+    async fn record_queue_pairing(&mut self, chain_id: &str, opponent: &str, timestamp: u64) -> Result<(), String> {
+        let mut recent = self.get_recent_queue_opponents(chain_id).await;
+        recent.record(opponent, timestamp);
+        self.recent_queue_opponents
+            .insert(&chain_id.to_string(), recent)
+            .map_err(|e| format!("Failed to record queue opponent: {}", e))
+    }
+
+    /// The colors a player got in their recent queue and tournament games
+    pub async fn get_color_history(&self, chain_id: &str) -> ColorHistory {
+        self.color_history.get(chain_id).await.ok().flatten().unwrap_or_default()
+    }
+
+    /// Whether `a` should play red against `b`, going by both players' color
+    /// history. None when neither is owed a color
+    pub async fn balanced_red(&self, a: &str, b: &str) -> Option<bool> {
+        balanced_red(&self.get_color_history(a).await, &self.get_color_history(b).await)
+    }
+
+    /// Remember the colors of a newly started game
+    pub async fn record_colors(&mut self, red_player: &str, black_player: &str) -> Result<(), String> {
+        for (player, color) in [(red_player, Turn::Red), (black_player, Turn::Black)] {
+            let mut history = self.get_color_history(player).await;
+            history.record(color);
+            self.color_history
+                .insert(&player.to_string(), history)
+                .map_err(|e| format!("Failed to record colors: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// A player's entry in one pool, if they are waiting in it
+    pub async fn get_queue_entry(&self, chain_id: &str, queue_pool: QueuePool) -> Option<QueueEntry> {
+        let pool = self.matchmaking_queue.try_load_entry(&queue_pool).await.ok()??;
+        pool.get(chain_id).await.ok().flatten()
+    }
+
+    /// Every pool a player is waiting in
+    pub async fn get_queue_entries(&self, chain_id: &str) -> Vec<QueueEntry> {
+        let mut entries = Vec::new();
+        for queue_pool in QueuePool::all() {
+            if let Some(entry) = self.get_queue_entry(chain_id, queue_pool).await {
+                entries.push(entry);
+            }
+        }
+        entries
+    }
+
+    /// Take a player out of one pool, counting them as gone once no entry is left
+    async fn remove_queue_entry(&mut self, chain_id: &str, queue_pool: QueuePool) -> Result<(), String> {
+        self.matchmaking_queue
+            .load_entry_mut(&queue_pool)
+            .await
+            .map_err(|e| format!("Failed to leave queue: {}", e))?
+            .remove(chain_id)
+            .map_err(|e| format!("Failed to leave queue: {}", e))?;
+        if self.get_queue_entries(chain_id).await.is_empty() {
+            decrement(&mut self.queued_player_count);
+        }
+        Ok(())
+    }
+
+    /// Leave the rated and casual pools of one time control, or every pool
+    /// when `time_control` is None
+    /// Returns true if player was in queue, false otherwise
+    pub async fn leave_queue_pool(&mut self, chain_id: &str, time_control: Option<TimeControl>) -> Result<bool, String> {
+        let mut removed = false;
+        for entry in self.get_queue_entries(chain_id).await {
+            if time_control.is_some_and(|tc| tc != entry.time_control) {
+                continue;
+            }
+            self.remove_queue_entry(chain_id, entry.pool()).await?;
+            removed = true;
+        }
+        Ok(removed)
+    }
+
+    /// Drop up to `limit` queue entries that outlived their TTL.
+    /// Returns how many were removed
+    pub async fn expire_queue_entries(&mut self, now: u64, limit: usize) -> Result<u32, String> {
+        let mut expired = Vec::new();
+        for queue_pool in QueuePool::all() {
+            if let Ok(Some(pool)) = self.matchmaking_queue.try_load_entry(&queue_pool).await {
+                let _ = pool
+                    .for_each_index_value(|chain_id, entry| {
+                        if entry.is_expired(now) && expired.len() < limit {
+                            expired.push((chain_id, queue_pool));
+                        }
+                        Ok(())
+                    })
+                    .await;
+            }
+        }
+        for (chain_id, queue_pool) in &expired {
+            self.remove_queue_entry(chain_id, *queue_pool).await?;
+        }
+        Ok(expired.len() as u32)
+    }
+
+    /// Leave every matchmaking pool
+    /// Returns true if player was in queue, false otherwise
+    pub async fn leave_queue(&mut self, chain_id: &str) -> Result<bool, String> {
+        self.leave_queue_pool(chain_id, None).await
+    }
+
+    /// Get rated and casual queue counts for each time control
+    pub async fn get_queue_counts(&self) -> Vec<QueueStatus> {
+        let mut statuses = Vec::new();
+        for tc in TimeControl::all() {
+            statuses.push(QueueStatus {
+                time_control: tc,
+                player_count: self.pool_size(QueuePool { time_control: tc, is_rated: true }).await,
+                casual_player_count: self.pool_size(QueuePool { time_control: tc, is_rated: false }).await,
+            });
+        }
+        statuses
+    }
+
+    async fn pool_size(&self, queue_pool: QueuePool) -> u32 {
+        match self.matchmaking_queue.try_load_entry(&queue_pool).await {
+            Ok(Some(pool)) => pool.count().await.unwrap_or(0) as u32,
+            _ => 0,
+        }
+    }
+
+    // ========================================================================
+    // ADMIN METHODS
+    // ========================================================================
+
+    pub async fn is_admin(&self, owner: &str) -> bool {
+        self.admins.contains_key(owner).await.unwrap_or(false)
+    }
+
+    pub fn add_admin(&mut self, owner: &str) -> Result<(), String> {
+        self.admins
+            .insert(owner, ())
+            .map_err(|e| format!("Failed to add admin: {}", e))
+    }
+
+    pub fn remove_admin(&mut self, owner: &str) -> Result<(), String> {
+        self.admins
+            .remove(owner)
+            .map_err(|e| format!("Failed to remove admin: {}", e))
+    }
+
+    pub async fn get_admins(&self) -> Vec<String> {
+        self.admins.indices().await.unwrap_or_default()
+    }
+
+    pub async fn is_banned(&self, player_id: &str) -> bool {
+        self.banned_players.contains_key(player_id).await.unwrap_or(false)
+    }
+
+    pub fn ban_player(&mut self, ban: PlayerBan) -> Result<(), String> {
+        self.banned_players
+            .insert(&ban.player_id.clone(), ban)
+            .map_err(|e| format!("Failed to ban player: {}", e))
+    }
+
+    pub fn unban_player(&mut self, player_id: &str) -> Result<(), String> {
+        self.banned_players
+            .remove(player_id)
+            .map_err(|e| format!("Failed to unban player: {}", e))
+    }
+
+    pub async fn get_banned_players(&self) -> Vec<PlayerBan> {
+        let mut bans = Vec::new();
+        let _ = self.banned_players
+            .for_each_index_value(|_, ban| {
+                bans.push(ban.into_owned());
+                Ok(())
+            })
+            .await;
+        bans
+    }
+
+    /// Add anti-cheat flags to a game, skipping ones it already carries
+    pub async fn flag_game(&mut self, game_id: &str, flags: Vec<SuspicionFlag>) -> Result<(), OperationError> {
+        if flags.is_empty() {
+            return Ok(());
+        }
+        let mut game = self.get_game(game_id)
+            .await
+            .ok_or_else(|| OperationError::new(ErrorCode::GameNotFound, "Game not found"))?;
+        for flag in flags {
+            let known = game.suspicion_flags.iter()
+                .any(|existing| existing.player_id == flag.player_id && existing.reason == flag.reason);
+            if !known {
+                game.suspicion_flags.push(flag);
+            }
+        }
+        self.save_game(game).await.map_err(OperationError::storage)?;
+        self.flagged_games
+            .insert(game_id, ())
+            .map_err(|e| OperationError::storage(format!("Failed to index flagged game: {}", e)))
+    }
+
+    /// Get up to `limit` flagged games, oldest first
+    pub async fn get_flagged_games(&self, limit: usize) -> Vec<CheckersGame> {
+        let game_ids = self.flagged_games.indices().await.unwrap_or_default();
+        let mut games = Vec::new();
+        for game_id in game_ids.into_iter().take(limit) {
+            if let Some(game) = self.get_game(&game_id).await {
+                games.push(game);
+            }
+        }
+        games
+    }
+
+    // ========================================================================
+    // REPORT METHODS
+    // ========================================================================
+
+    /// Generate a new unique report ID
+    pub fn generate_report_id(&mut self) -> String {
+        let id = *self.next_report_id.get() + 1;
+        self.next_report_id.set(id);
+        format!("report_{:06}", id)
+    }
+
+    pub async fn get_report(&self, report_id: &str) -> Option<Report> {
+        self.reports.get(report_id).await.ok().flatten()
+    }
+
+    /// Save a report, keeping the open report index in step with its status
+    pub fn save_report(&mut self, report: Report) -> Result<(), String> {
+        if report.status == ReportStatus::Open {
+            self.open_reports.insert(&report.id, ())
+        } else {
+            self.open_reports.remove(&report.id)
+        }
+        .map_err(|e| format!("Failed to index report: {}", e))?;
+        self.reports
+            .insert(&report.id.clone(), report)
+            .map_err(|e| format!("Failed to save report: {}", e))
+    }
+
+    /// Get up to `limit` open reports, oldest first
+    pub async fn get_open_reports(&self, limit: usize) -> Vec<Report> {
+        let report_ids = self.open_reports.indices().await.unwrap_or_default();
+        let mut reports = Vec::new();
+        for report_id in report_ids.into_iter().take(limit) {
+            if let Some(report) = self.get_report(&report_id).await {
+                reports.push(report);
+            }
+        }
+        reports
+    }
+
+    // ========================================================================
+    // PLAYER IDENTITY METHODS
+    // ========================================================================
+
+    pub async fn get_player_signer(&self, player_id: &str) -> Option<String> {
+        self.player_signers.get(player_id).await.ok().flatten()
+    }
+
+    pub fn bind_player_signer(&mut self, player_id: &str, signer: String) -> Result<(), String> {
+        self.player_signers
+            .insert(player_id, signer)
+            .map_err(|e| format!("Failed to bind player ID: {}", e))
+    }
+
+    // ========================================================================
+    // GAME SUBSCRIPTION METHODS
+    // ========================================================================
+
+    pub async fn is_subscribed_to_games(&self, chain_id: &str) -> bool {
+        self.game_subscriptions.contains_key(chain_id).await.unwrap_or(false)
+    }
+
+    pub fn add_game_subscription(&mut self, chain_id: &str) -> Result<(), String> {
+        self.game_subscriptions
+            .insert(chain_id, ())
+            .map_err(|e| format!("Failed to save subscription: {}", e))
+    }
+
+    pub fn remove_game_subscription(&mut self, chain_id: &str) -> Result<(), String> {
+        self.game_subscriptions
+            .remove(chain_id)
+            .map_err(|e| format!("Failed to remove subscription: {}", e))
+    }
+
+    pub async fn get_game_subscriptions(&self) -> Vec<String> {
+        self.game_subscriptions.indices().await.unwrap_or_default()
+    }
+
+    /// Update the followed game an event from another chain is about
+    pub async fn record_spectated_event(&mut self, chain_id: &str, event: &GameEvent, now: u64) -> Result<(), String> {
+        let Some(game_id) = event.game_id() else { return Ok(()) };
+        let key = (chain_id.to_string(), game_id.to_string());
+        let mut game = self.spectated_games
+            .get(&key)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| SpectatedGame::new(key.0.clone(), key.1.clone()));
+        game.apply(event, now);
+        self.spectated_games
+            .insert(&key, game)
+            .map_err(|e| format!("Failed to save spectated game: {}", e))
+    }
+
+    pub async fn get_spectated_game(&self, chain_id: &str, game_id: &str) -> Option<SpectatedGame> {
+        self.spectated_games
+            .get(&(chain_id.to_string(), game_id.to_string()))
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Get up to `limit` followed games, optionally from one chain, most recently updated first
+    pub async fn get_spectated_games(&self, chain_id: Option<&str>, limit: usize) -> Vec<SpectatedGame> {
+        let mut games = Vec::new();
+        let _ = self.spectated_games
+            .for_each_index_value(|(game_chain, _), game| {
+                if chain_id.is_none_or(|chain_id| chain_id == game_chain) {
+                    games.push(game.into_owned());
+                }
+                Ok(())
+            })
+            .await;
+        games.sort_by_key(|game| Reverse(game.updated_at));
+        games.truncate(limit);
+        games
+    }
+
+    // ========================================================================
+    // USERNAME METHODS
+    // ========================================================================
+
+    /// Give a player a username, releasing their previous one.
+    /// Usernames are unique regardless of case.
+    pub async fn register_username(&mut self, player_id: &str, username: &str) -> Result<(), OperationError> {
+        let key = username.to_lowercase();
+        let owner = self.usernames.get(&key).await.ok().flatten();
+        if owner.as_deref().is_some_and(|owner| owner != player_id) {
+            return Err(OperationError::new(ErrorCode::AlreadyDone, "Username already taken"));
+        }
+
+        let mut stats = self.get_player_stats(player_id).await;
+        if let Some(previous) = &stats.username {
+            let _ = self.usernames.remove(&previous.to_lowercase());
+        }
+        self.usernames
+            .insert(&key, player_id.to_string())
+            .map_err(|e| OperationError::storage(format!("Failed to register username: {}", e)))?;
+        stats.username = Some(username.to_string());
+        self.update_player_stats(stats).await.map_err(OperationError::storage)
+    }
+
+    /// The player who owns a username, ignoring case
+    pub async fn get_player_by_username(&self, username: &str) -> Option<String> {
+        self.usernames.get(&username.trim().to_lowercase()).await.ok().flatten()
+    }
+
+    pub async fn get_username(&self, player_id: &str) -> Option<String> {
+        self.player_stats.get(player_id).await.ok().flatten()?.username
+    }
+
+    /// Fill in the players' usernames on games about to be shown
+    pub async fn with_usernames(&self, mut games: Vec<CheckersGame>) -> Vec<CheckersGame> {
+        let mut names = std::collections::HashMap::new();
+        for game in &mut games {
+            for (player, username) in [
+                (&game.red_player, &mut game.red_username),
+                (&game.black_player, &mut game.black_username),
+            ] {
+                let Some(player) = player else { continue };
+                if !names.contains_key(player) {
+                    names.insert(player.clone(), self.get_username(player).await);
+                }
+                *username = names[player].clone();
+            }
+        }
+        games
+    }
+
+    /// A player's profile, empty if they never set one
+    pub async fn get_profile(&self, player_id: &str) -> PlayerProfile {
+        self.profiles.get(player_id).await.ok().flatten().unwrap_or_else(|| PlayerProfile {
+            player_id: player_id.to_string(),
+            ..Default::default()
+        })
+    }
+
+    pub fn save_profile(&mut self, profile: PlayerProfile) -> Result<(), String> {
+        self.profiles
+            .insert(&profile.player_id.clone(), profile)
+            .map_err(|e| format!("Failed to save profile: {}", e))
+    }
+
+    /// A player's settings, the defaults if they never changed them
+    pub async fn get_settings(&self, player_id: &str) -> PlayerSettings {
+        self.player_settings.get(player_id).await.ok().flatten().unwrap_or_default()
+    }
+
+    pub fn save_settings(&mut self, player_id: &str, settings: PlayerSettings) -> Result<(), String> {
+        self.player_settings
+            .insert(&player_id.to_string(), settings)
+            .map_err(|e| format!("Failed to save settings: {}", e))
+    }
+
+    // ========================================================================
+    // FRIEND METHODS
+    // ========================================================================
+
+    pub async fn get_friends(&self, player_id: &str) -> Vec<String> {
+        index_keys(&self.friends, &player_id.to_string()).await
+    }
+
+    pub async fn are_friends(&self, player_id: &str, friend_id: &str) -> bool {
+        match self.friends.try_load_entry(player_id).await {
+            Ok(Some(friends)) => friends.contains_key(friend_id).await.unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    /// Record a friendship on both sides
+    pub async fn add_friendship(&mut self, player_id: &str, friend_id: &str) -> Result<(), String> {
+        for (a, b) in [(player_id, friend_id), (friend_id, player_id)] {
+            self.friends
+                .load_entry_mut(a)
+                .await
+                .map_err(|e| format!("Failed to load friends: {}", e))?
+                .insert(b, ())
+                .map_err(|e| format!("Failed to add friend: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Remove a friendship from both sides
+    pub async fn remove_friendship(&mut self, player_id: &str, friend_id: &str) -> Result<(), String> {
+        for (a, b) in [(player_id, friend_id), (friend_id, player_id)] {
+            self.friends
+                .load_entry_mut(a)
+                .await
+                .map_err(|e| format!("Failed to load friends: {}", e))?
+                .remove(b)
+                .map_err(|e| format!("Failed to remove friend: {}", e))?;
+        }
+        Ok(())
+    }
+
+    pub async fn has_friend_request(&self, to: &str, from: &str) -> bool {
+        match self.friend_requests.try_load_entry(to).await {
+            Ok(Some(requests)) => requests.contains_key(from).await.unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    pub async fn add_friend_request(&mut self, to: &str, from: &str, sent_at: u64) -> Result<(), String> {
+        self.friend_requests
+            .load_entry_mut(to)
+            .await
+            .map_err(|e| format!("Failed to load friend requests: {}", e))?
+            .insert(from, sent_at)
+            .map_err(|e| format!("Failed to send friend request: {}", e))
+    }
+
+    pub async fn remove_friend_request(&mut self, to: &str, from: &str) -> Result<(), String> {
+        self.friend_requests
+            .load_entry_mut(to)
+            .await
+            .map_err(|e| format!("Failed to load friend requests: {}", e))?
+            .remove(from)
+            .map_err(|e| format!("Failed to remove friend request: {}", e))
+    }
+
+    /// Get the friend requests a player has received
+    pub async fn get_friend_requests(&self, player_id: &str) -> Vec<FriendRequest> {
+        let mut requests = Vec::new();
+        if let Ok(Some(entry)) = self.friend_requests.try_load_entry(player_id).await {
+            let _ = entry
+                .for_each_index_value(|from, sent_at| {
+                    requests.push(FriendRequest { from, sent_at: *sent_at });
+                    Ok(())
+                })
+                .await;
+        }
+        requests
+    }
+
+    /// Get the friends of a player who are in an active game, with that game
+    pub async fn get_friend_activity(&self, player_id: &str) -> Vec<FriendActivity> {
+        let mut activity = Vec::new();
+        for friend_id in self.get_friends(player_id).await {
+            let games = self.get_player_games(&friend_id, Some(GameStatus::Active), None, None, 1).await;
+            if let Some(game) = games.into_iter().next() {
+                activity.push(FriendActivity { friend_id, game });
+            }
+        }
+        activity
+    }
+
+    // ========================================================================
+    // BLOCK LIST METHODS
+    // ========================================================================
+
+    pub async fn get_blocked_players(&self, player_id: &str) -> Vec<String> {
+        index_keys(&self.blocked_players, &player_id.to_string()).await
+    }
+
+    pub async fn has_blocked(&self, player_id: &str, blocked_id: &str) -> bool {
+        match self.blocked_players.try_load_entry(player_id).await {
+            Ok(Some(blocked)) => blocked.contains_key(blocked_id).await.unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    /// Whether either player has blocked the other
+    pub async fn is_blocked_between(&self, a: &str, b: &str) -> bool {
+        self.has_blocked(a, b).await || self.has_blocked(b, a).await
+    }
+
+    pub async fn block_player(&mut self, player_id: &str, blocked_id: &str) -> Result<(), String> {
+        self.blocked_players
+            .load_entry_mut(player_id)
+            .await
+            .map_err(|e| format!("Failed to load block list: {}", e))?
+            .insert(blocked_id, ())
+            .map_err(|e| format!("Failed to block player: {}", e))
+    }
+
+    pub async fn unblock_player(&mut self, player_id: &str, blocked_id: &str) -> Result<(), String> {
+        self.blocked_players
+            .load_entry_mut(player_id)
+            .await
+            .map_err(|e| format!("Failed to load block list: {}", e))?
+            .remove(blocked_id)
+            .map_err(|e| format!("Failed to unblock player: {}", e))
+    }
+
+    // ========================================================================
+    // MATCH APP APPROVAL METHODS
+    // ========================================================================
+
+    pub async fn get_approved_match_apps(&self, player_id: &str) -> Vec<String> {
+        index_keys(&self.match_app_approvals, &player_id.to_string()).await
+    }
+
+    pub async fn has_approved_match_app(&self, player_id: &str, app_id: &str) -> bool {
+        match self.match_app_approvals.try_load_entry(player_id).await {
+            Ok(Some(apps)) => apps.contains_key(app_id).await.unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    pub async fn approve_match_app(&mut self, player_id: &str, app_id: &str) -> Result<(), String> {
+        self.match_app_approvals
+            .load_entry_mut(player_id)
+            .await
+            .map_err(|e| format!("Failed to load approved apps: {}", e))?
+            .insert(app_id, ())
+            .map_err(|e| format!("Failed to approve app: {}", e))
+    }
+
+    pub async fn revoke_match_app(&mut self, player_id: &str, app_id: &str) -> Result<(), String> {
+        self.match_app_approvals
+            .load_entry_mut(player_id)
+            .await
+            .map_err(|e| format!("Failed to load approved apps: {}", e))?
+            .remove(app_id)
+            .map_err(|e| format!("Failed to revoke app: {}", e))
+    }
+
+    // ========================================================================
+    // CHALLENGE METHODS
+    // ========================================================================
 
-        match result {
-            GameResult::RedWins => {
-                if !red_is_ai {
-                    red_stats.record_win_with_rating(black_rating, &time_control);
-                    self.update_player_stats(red_stats).await?;
-                }
-                if !black_is_ai {
-                    black_stats.record_loss_with_rating(red_rating, &time_control);
-                    self.update_player_stats(black_stats).await?;
-                }
-            }
-            GameResult::BlackWins => {
-                if !black_is_ai {
-                    black_stats.record_win_with_rating(red_rating, &time_control);
-                    self.update_player_stats(black_stats).await?;
-                }
-                if !red_is_ai {
-                    red_stats.record_loss_with_rating(black_rating, &time_control);
-                    self.update_player_stats(red_stats).await?;
-                }
-            }
-            GameResult::Draw => {
-                if !red_is_ai {
-                    red_stats.record_draw_with_rating(black_rating, &time_control);
-                    self.update_player_stats(red_stats).await?;
-                }
-                if !black_is_ai {
-                    black_stats.record_draw_with_rating(red_rating, &time_control);
-                    self.update_player_stats(black_stats).await?;
-                }
-            }
-            GameResult::InProgress => {
-                // Unreachable - we return early for InProgress
-            }
-        }
+    /// Generate a new unique challenge ID
+    pub fn generate_challenge_id(&mut self) -> String {
+        let id = *self.next_challenge_id.get() + 1;
+        self.next_challenge_id.set(id);
+        format!("challenge_{:06}", id)
+    }
 
-        Ok(())
+    pub async fn get_challenge(&self, challenge_id: &str) -> Option<Challenge> {
+        self.challenges.get(challenge_id).await.ok().flatten()
     }
 
-    /// Record game counts only (for casual games - no ELO updates)
-    async fn record_game_counts_only(
-        &mut self,
-        game: &CheckersGame,
-        result: GameResult,
-        red_is_ai: bool,
-        black_is_ai: bool,
-    ) -> Result<(), String> {
-        // Get current stats for both players
-        let mut red_stats = if !red_is_ai {
-            if let Some(chain) = game.red_player.as_deref() {
-                self.get_player_stats(chain).await
-            } else {
-                return Ok(());
-            }
+    /// Save a challenge and index it for both players
+    pub async fn save_challenge(&mut self, challenge: Challenge) -> Result<(), String> {
+        for player in [&challenge.challenger, &challenge.opponent] {
+            self.player_challenges
+                .load_entry_mut(player)
+                .await
+                .map_err(|e| format!("Failed to load player challenges: {}", e))?
+                .insert(&challenge.id, ())
+                .map_err(|e| format!("Failed to index challenge: {}", e))?;
+        }
+        let challenge_id = challenge.id.clone();
+        if challenge.status == ChallengeStatus::Pending {
+            self.pending_challenges
+                .insert(&challenge_id, ())
+                .map_err(|e| format!("Failed to update pending challenges: {}", e))?;
         } else {
-            return Ok(()); // AI-only scenario
-        };
+            let _ = self.pending_challenges.remove(&challenge_id);
+        }
+        self.challenges
+            .insert(&challenge_id, challenge)
+            .map_err(|e| format!("Failed to save challenge: {}", e))
+    }
 
-        let mut black_stats = if !black_is_ai {
-            if let Some(chain) = game.black_player.as_deref() {
-                self.get_player_stats(chain).await
-            } else {
-                PlayerStats::new("unknown".to_string())
-            }
-        } else {
-            PlayerStats::new("AI".to_string())
-        };
+    /// Get the pending challenges a player has sent or received, newest first
+    /// Expired challenges are included until something closes them.
+    pub async fn get_pending_challenges(&self, player_id: &str) -> Vec<Challenge> {
+        let mut challenge_ids = index_keys(&self.player_challenges, &player_id.to_string()).await;
+        challenge_ids.sort_by(|a, b| b.cmp(a));
 
-        match result {
-            GameResult::RedWins => {
-                if !red_is_ai {
-                    red_stats.record_win();
-                    self.update_player_stats(red_stats).await?;
-                }
-                if !black_is_ai {
-                    black_stats.record_loss();
-                    self.update_player_stats(black_stats).await?;
-                }
-            }
-            GameResult::BlackWins => {
-                if !black_is_ai {
-                    black_stats.record_win();
-                    self.update_player_stats(black_stats).await?;
-                }
-                if !red_is_ai {
-                    red_stats.record_loss();
-                    self.update_player_stats(red_stats).await?;
-                }
-            }
-            GameResult::Draw => {
-                if !red_is_ai {
-                    red_stats.record_draw();
-                    self.update_player_stats(red_stats).await?;
-                }
-                if !black_is_ai {
-                    black_stats.record_draw();
-                    self.update_player_stats(black_stats).await?;
+        let mut challenges = Vec::new();
+        for challenge_id in challenge_ids {
+            if let Some(challenge) = self.get_challenge(&challenge_id).await {
+                if challenge.status == ChallengeStatus::Pending {
+                    challenges.push(challenge);
                 }
             }
-            GameResult::InProgress => {}
         }
+        challenges
+    }
 
-        Ok(())
+    /// Get up to `limit` challenges that are still marked pending
+    pub async fn get_all_pending_challenges(&self, limit: usize) -> Vec<Challenge> {
+        let challenge_ids = self.pending_challenges.indices().await.unwrap_or_default();
+        let mut challenges = Vec::new();
+        for challenge_id in challenge_ids.into_iter().take(limit) {
+            if let Some(challenge) = self.get_challenge(&challenge_id).await {
+                challenges.push(challenge);
+            }
+        }
+        challenges
     }
 
     // ========================================================================
-    // MATCHMAKING QUEUE METHODS
+    // SEEK METHODS
     // ========================================================================
 
-    /// Join the matchmaking queue
-    /// Returns Some(opponent_chain_id) if a match was found, None if added to queue
-    pub async fn join_queue(
-        &mut self,
-        chain_id: &str,
-        time_control: TimeControl,
-        timestamp: u64,
-    ) -> Result<Option<String>, String> {
-        let _ = self.matchmaking_queue.remove(chain_id);
-
-        let mut matched_opponent: Option<String> = None;
-        let _ = self.matchmaking_queue
-            .for_each_index_value(|opponent_chain_id, entry| {
-                if entry.time_control == time_control
-                    && matched_opponent.is_none()
-                    && opponent_chain_id != chain_id
-                {
-                    matched_opponent = Some(opponent_chain_id.clone());
-                }
+    /// Generate a new unique seek ID
+    pub fn generate_seek_id(&mut self) -> String {
+        let id = *self.next_seek_id.get() + 1;
+        self.next_seek_id.set(id);
+        format!("seek_{:06}", id)
+    }
+
+    pub async fn get_seek(&self, seek_id: &str) -> Option<Seek> {
+        self.seeks.get(seek_id).await.ok().flatten()
+    }
+
+    /// Get every seek on the board, oldest first
+    pub async fn get_seeks(&self) -> Vec<Seek> {
+        let mut seeks = Vec::new();
+        let _ = self.seeks
+            .for_each_index_value(|_id, seek| {
+                seeks.push(seek.into_owned());
                 Ok(())
             })
             .await;
+        seeks
+    }
 
-        if let Some(opponent_chain_id) = matched_opponent {
-            // Match found: remove opponent from queue
-            let _ = self.matchmaking_queue.remove(&opponent_chain_id);
-            Ok(Some(opponent_chain_id))
-        } else {
-            // No match: add player to queue
-            let entry = QueueEntry::new(chain_id.to_string(), time_control, timestamp);
-            self.matchmaking_queue
-                .insert(&chain_id.to_string(), entry)
-                .map_err(|e| format!("Failed to join queue: {}", e))?;
-            Ok(None)
+    pub fn save_seek(&mut self, seek: Seek) -> Result<(), String> {
+        let seek_id = seek.id.clone();
+        self.seeks
+            .insert(&seek_id, seek)
+            .map_err(|e| format!("Failed to save seek: {}", e))
+    }
+
+    pub fn remove_seek(&mut self, seek_id: &str) -> Result<(), String> {
+        self.seeks
+            .remove(seek_id)
+            .map_err(|e| format!("Failed to remove seek: {}", e))
+    }
+
+    // ========================================================================
+    // STUDY METHODS
+    // ========================================================================
+
+    /// Generate a new unique study ID
+    pub fn generate_study_id(&mut self) -> String {
+        let id = *self.next_study_id.get() + 1;
+        self.next_study_id.set(id);
+        format!("study_{:06}", id)
+    }
+
+    pub async fn get_study(&self, study_id: &str) -> Option<Study> {
+        self.studies.get(study_id).await.ok().flatten()
+    }
+
+    /// Save a study and make sure each of its members can find it
+    pub async fn save_study(&mut self, study: Study) -> Result<(), String> {
+        for member in &study.members {
+            self.player_studies
+                .load_entry_mut(member)
+                .await
+                .map_err(|e| format!("Failed to load player studies: {}", e))?
+                .insert(&study.id, ())
+                .map_err(|e| format!("Failed to index study: {}", e))?;
         }
+        let study_id = study.id.clone();
+        self.studies
+            .insert(&study_id, study)
+            .map_err(|e| format!("Failed to save study: {}", e))
     }
 
-    /// Leave the matchmaking queue
-    /// Returns true if player was in queue, false otherwise
-    pub async fn leave_queue(&mut self, chain_id: &str) -> Result<bool, String> {
-        let was_in_queue = self.matchmaking_queue
-            .get(chain_id)
+    /// Drop a study from a former member's list
+    pub async fn remove_player_study(&mut self, player_id: &str, study_id: &str) -> Result<(), String> {
+        self.player_studies
+            .load_entry_mut(player_id)
             .await
-            .ok()
-            .flatten()
-            .is_some();
+            .map_err(|e| format!("Failed to load player studies: {}", e))?
+            .remove(study_id)
+            .map_err(|e| format!("Failed to unindex study: {}", e))
+    }
 
-        if was_in_queue {
-            let _ = self.matchmaking_queue.remove(chain_id);
+    /// Get the studies a player is a member of
+    pub async fn get_player_studies(&self, player_id: &str) -> Vec<Study> {
+        let mut studies = Vec::new();
+        for study_id in index_keys(&self.player_studies, &player_id.to_string()).await {
+            if let Some(study) = self.get_study(&study_id).await {
+                studies.push(study);
+            }
         }
+        studies
+    }
 
-        Ok(was_in_queue)
+    // ========================================================================
+    // CLUB METHODS
+    // ========================================================================
+
+    /// Generate a new unique club ID
+    pub fn generate_club_id(&mut self) -> String {
+        let id = *self.next_club_id.get() + 1;
+        self.next_club_id.set(id);
+        format!("club_{:06}", id)
     }
 
-    /// Get queue counts for each time control
-    pub async fn get_queue_counts(&self) -> Vec<QueueStatus> {
-        let mut counts = std::collections::HashMap::new();
+    pub async fn get_club(&self, club_id: &str) -> Option<Club> {
+        self.clubs.get(club_id).await.ok().flatten()
+    }
 
-        // Initialize counts for all time controls
-        for tc in TimeControl::all() {
-            counts.insert(tc, 0u32);
+    /// Save a club and point each of its members and applicants at it
+    pub async fn save_club(&mut self, club: Club) -> Result<(), String> {
+        for player_id in club.members.iter().chain(&club.join_requests) {
+            self.player_clubs
+                .insert(player_id, club.id.clone())
+                .map_err(|e| format!("Failed to index club: {}", e))?;
         }
+        let club_id = club.id.clone();
+        self.clubs
+            .insert(&club_id, club)
+            .map_err(|e| format!("Failed to save club: {}", e))
+    }
 
-        // Count players per time control
-        let _ = self.matchmaking_queue
-            .for_each_index_value(|_chain_id, entry| {
-                *counts.entry(entry.time_control).or_insert(0) += 1;
-                Ok(())
-            })
-            .await;
+    /// Delete a club that has no members left
+    pub fn remove_club(&mut self, club_id: &str) -> Result<(), String> {
+        self.clubs
+            .remove(club_id)
+            .map_err(|e| format!("Failed to remove club: {}", e))
+    }
 
-        // Convert to Vec<QueueStatus>
-        TimeControl::all()
-            .into_iter()
-            .map(|tc| QueueStatus {
-                time_control: tc,
-                player_count: *counts.get(&tc).unwrap_or(&0),
-            })
-            .collect()
+    /// The ID of the club a player belongs to or is waiting to join
+    pub async fn get_player_club_id(&self, player_id: &str) -> Option<String> {
+        self.player_clubs.get(player_id).await.ok().flatten()
     }
 
-    /// Get a player's queue entry if they're in the queue
-    pub async fn get_player_queue_entry(&self, chain_id: &str) -> Option<QueueEntry> {
-        self.matchmaking_queue
-            .get(chain_id)
-            .await
-            .ok()
-            .flatten()
+    /// The club a player belongs to, not counting one they are waiting to join
+    pub async fn get_player_club(&self, player_id: &str) -> Option<Club> {
+        let club_id = self.get_player_club_id(player_id).await?;
+        self.get_club(&club_id).await.filter(|club| club.is_member(player_id))
+    }
+
+    /// Forget which club a player belongs to
+    pub fn clear_player_club(&mut self, player_id: &str) -> Result<(), String> {
+        self.player_clubs
+            .remove(player_id)
+            .map_err(|e| format!("Failed to unindex club: {}", e))
+    }
+
+    /// Generate a new unique club match ID
+    pub fn generate_club_match_id(&mut self) -> String {
+        let id = *self.next_club_match_id.get() + 1;
+        self.next_club_match_id.set(id);
+        format!("club_match_{:06}", id)
+    }
+
+    pub async fn get_club_match(&self, match_id: &str) -> Option<ClubMatch> {
+        self.club_matches.get(match_id).await.ok().flatten()
+    }
+
+    /// Save a club match and list it under both clubs
+    pub async fn save_club_match(&mut self, club_match: ClubMatch) -> Result<(), String> {
+        for club_id in [&club_match.home_club, &club_match.away_club] {
+            self.club_match_index
+                .load_entry_mut(club_id)
+                .await
+                .map_err(|e| format!("Failed to load club matches: {}", e))?
+                .insert(&club_match.id, ())
+                .map_err(|e| format!("Failed to index club match: {}", e))?;
+        }
+        let match_id = club_match.id.clone();
+        self.club_matches
+            .insert(&match_id, club_match)
+            .map_err(|e| format!("Failed to save club match: {}", e))
+    }
+
+    /// Get the matches of a club, newest first
+    pub async fn get_club_matches(&self, club_id: &str) -> Vec<ClubMatch> {
+        let mut matches = Vec::new();
+        for match_id in index_keys(&self.club_match_index, &club_id.to_string()).await {
+            if let Some(club_match) = self.get_club_match(&match_id).await {
+                matches.push(club_match);
+            }
+        }
+        matches.sort_by_key(|club_match| std::cmp::Reverse(club_match.created_at));
+        matches
+    }
+
+    /// Get clubs, largest first
+    pub async fn get_clubs(&self, limit: usize) -> Vec<Club> {
+        let mut clubs = Vec::new();
+        let _ = self.clubs
+            .for_each_index_value(|_id, club| {
+                clubs.push(club.into_owned());
+                Ok(())
+            })
+            .await;
+        clubs.sort_by_key(|club| std::cmp::Reverse(club.members.len()));
+        clubs.truncate(limit);
+        clubs
     }
 
     // ========================================================================
@@ -421,11 +2655,103 @@ impl CheckersState {
     /// Save or update a tournament
     pub async fn save_tournament(&mut self, tournament: Tournament) -> Result<(), String> {
         let tournament_id = tournament.id.clone();
+        let previous = self.get_tournament(&tournament_id).await;
+        self.save_tournament_matches(previous.as_ref(), &tournament).await?;
+        let previous_status = previous.map(|t| t.status);
+        if previous_status.is_none() {
+            *self.tournament_count.get_mut() += 1;
+        }
+        if previous_status != Some(tournament.status) {
+            // Nobody can join once a tournament is over, so its invite code goes
+            if matches!(tournament.status, TournamentStatus::Finished | TournamentStatus::Cancelled) {
+                if let Some(code) = &tournament.invite_code {
+                    self.remove_invite_code_index(code).await?;
+                }
+            }
+            if let Some(status) = previous_status {
+                self.tournament_status_index
+                    .load_entry_mut(&status)
+                    .await
+                    .map_err(|e| format!("Failed to load tournament status index: {}", e))?
+                    .remove(&tournament_id)
+                    .map_err(|e| format!("Failed to index tournament: {}", e))?;
+            }
+            self.tournament_status_index
+                .load_entry_mut(&tournament.status)
+                .await
+                .map_err(|e| format!("Failed to load tournament status index: {}", e))?
+                .insert(&tournament_id, ())
+                .map_err(|e| format!("Failed to index tournament: {}", e))?;
+        }
         self.tournaments
             .insert(&tournament_id, tournament)
             .map_err(|e| format!("Failed to save tournament: {}", e))
     }
 
+    /// Store the matches that changed since the tournament was last saved
+    async fn save_tournament_matches(&mut self, previous: Option<&Tournament>, tournament: &Tournament) -> Result<(), String> {
+        let matches = self.tournament_matches
+            .load_entry_mut(&tournament.id)
+            .await
+            .map_err(|e| format!("Failed to load tournament matches: {}", e))?;
+        let saved: std::collections::HashMap<&str, &TournamentMatch> = previous
+            .map(|previous| previous.matches.iter().map(|m| (m.id.as_str(), m)).collect())
+            .unwrap_or_default();
+        for m in &tournament.matches {
+            if saved.get(m.id.as_str()).is_some_and(|saved| *saved == m) {
+                continue;
+            }
+            matches
+                .insert(&m.id, m.clone())
+                .map_err(|e| format!("Failed to save tournament match: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Mark tournaments cancelled before they started, which used to be saved as
+    /// Finished, as Cancelled
+    pub async fn mark_cancelled_tournaments(&mut self) -> Result<(), String> {
+        for mut tournament in self.get_all_tournaments().await {
+            if tournament.status == TournamentStatus::Finished && tournament.started_at.is_none() {
+                tournament.status = TournamentStatus::Cancelled;
+                self.save_tournament(tournament).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Store the matches of every tournament, for deployments from before they were kept apart
+    pub async fn index_tournament_matches(&mut self) -> Result<(), String> {
+        for tournament in self.get_all_tournaments().await {
+            self.save_tournament_matches(None, &tournament).await?;
+        }
+        Ok(())
+    }
+
+    /// Get a tournament match. Match IDs start with their tournament's ID.
+    pub async fn get_tournament_match(&self, match_id: &str) -> Option<TournamentMatch> {
+        let (tournament_id, _) = match_id.split_once('_')?;
+        let matches = self.tournament_matches.try_load_entry(tournament_id).await.ok()??;
+        matches.get(match_id).await.ok().flatten()
+    }
+
+    /// The matches of one round of a tournament, on every side of the bracket
+    pub async fn get_tournament_round(&self, tournament_id: &str, round: u32) -> Vec<TournamentMatch> {
+        let mut round_matches = Vec::new();
+        if let Ok(Some(matches)) = self.tournament_matches.try_load_entry(tournament_id).await {
+            let _ = matches
+                .for_each_index_value(|_id, m| {
+                    if m.round == round {
+                        round_matches.push(m.into_owned());
+                    }
+                    Ok(())
+                })
+                .await;
+        }
+        round_matches.sort_by_key(|m| (m.bracket as u8, m.match_number));
+        round_matches
+    }
+
     /// Get all tournaments
     pub async fn get_all_tournaments(&self) -> Vec<Tournament> {
         let mut tournaments = Vec::new();
@@ -440,12 +2766,57 @@ impl CheckersState {
 
     /// Get active tournaments (Registration or InProgress)
     pub async fn get_active_tournaments(&self) -> Vec<Tournament> {
-        use checkers_abi::TournamentStatus;
-        self.get_all_tournaments()
-            .await
-            .into_iter()
-            .filter(|t| t.status == TournamentStatus::Registration || t.status == TournamentStatus::InProgress)
-            .collect()
+        let mut tournament_ids = index_keys(&self.tournament_status_index, &TournamentStatus::Registration).await;
+        tournament_ids.extend(index_keys(&self.tournament_status_index, &TournamentStatus::InProgress).await);
+        tournament_ids.sort();
+        let mut tournaments = Vec::new();
+        for tournament_id in tournament_ids {
+            if let Some(tournament) = self.get_tournament(&tournament_id).await {
+                tournaments.push(tournament);
+            }
+        }
+        tournaments
+    }
+
+    /// Public tournaments matching the filter, newest first
+    /// Candidates come from the status index when the filter names a status.
+    /// `after` is the ID of the last tournament of the previous page.
+    pub async fn search_tournaments(&self, filter: &TournamentFilter, after: Option<String>, limit: usize) -> Vec<Tournament> {
+        let mut tournament_ids = match filter.status {
+            Some(status) => index_keys(&self.tournament_status_index, &status).await,
+            None => self.tournaments.indices().await.unwrap_or_default(),
+        };
+        // Tournament IDs are zero-padded counters, so they sort in creation order
+        tournament_ids.sort_by(|a, b| b.cmp(a));
+
+        let mut tournaments = Vec::new();
+        for tournament_id in tournament_ids {
+            if tournaments.len() >= limit {
+                break;
+            }
+            if after.as_ref().is_some_and(|after| &tournament_id >= after) {
+                continue;
+            }
+            if let Some(tournament) = self.get_tournament(&tournament_id).await {
+                if tournament.is_public && filter.matches(&tournament) {
+                    tournaments.push(tournament);
+                }
+            }
+        }
+        tournaments
+    }
+
+    /// Index every stored tournament by status, for deployments from before the index
+    pub async fn index_tournament_statuses(&mut self) -> Result<(), String> {
+        for tournament in self.get_all_tournaments().await {
+            self.tournament_status_index
+                .load_entry_mut(&tournament.status)
+                .await
+                .map_err(|e| format!("Failed to load tournament status index: {}", e))?
+                .insert(&tournament.id, ())
+                .map_err(|e| format!("Failed to index tournament: {}", e))?;
+        }
+        Ok(())
     }
 
     /// Get public tournaments (for browsing)
@@ -457,6 +2828,32 @@ impl CheckersState {
             .collect()
     }
 
+    /// Add a finished tournament to a player's history
+    pub async fn record_tournament_result(&mut self, player_id: &str, record: TournamentRecord) -> Result<(), String> {
+        self.tournament_history
+            .load_entry_mut(player_id)
+            .await
+            .map_err(|e| format!("Failed to load tournament history: {}", e))?
+            .insert(&record.tournament_id.clone(), record)
+            .map_err(|e| format!("Failed to record tournament result: {}", e))
+    }
+
+    /// A player's finished tournaments, most recent first
+    pub async fn get_tournament_history(&self, player_id: &str, limit: usize) -> Vec<TournamentRecord> {
+        let mut records = Vec::new();
+        if let Ok(Some(history)) = self.tournament_history.try_load_entry(player_id).await {
+            let _ = history
+                .for_each_index_value(|_id, record| {
+                    records.push(record.into_owned());
+                    Ok(())
+                })
+                .await;
+        }
+        records.sort_by(|a, b| b.finished_at.cmp(&a.finished_at).then_with(|| b.tournament_id.cmp(&a.tournament_id)));
+        records.truncate(limit);
+        records
+    }
+
     /// Get tournaments for a specific player (ones they're registered in or created)
     pub async fn get_player_tournaments(&self, player_id: &str) -> Vec<Tournament> {
         self.get_all_tournaments()
@@ -493,4 +2890,141 @@ impl CheckersState {
         let _ = self.invite_code_index.remove(&invite_code.to_uppercase());
         Ok(())
     }
+
+    /// Remove invite codes of tournaments and games that can no longer be joined.
+    /// Returns how many were removed.
+    pub async fn prune_invite_codes(&mut self) -> Result<u32, String> {
+        let mut stale = Vec::new();
+        for (code, tournament_id) in self.invite_code_index.index_values().await.unwrap_or_default() {
+            let open = self.get_tournament(&tournament_id).await
+                .is_some_and(|t| !matches!(t.status, TournamentStatus::Finished | TournamentStatus::Cancelled));
+            if !open {
+                stale.push(code);
+            }
+        }
+        let mut stale_games = Vec::new();
+        for (code, game_id) in self.game_invite_index.index_values().await.unwrap_or_default() {
+            if !self.get_game(&game_id).await.is_some_and(|g| g.status == GameStatus::Pending) {
+                stale_games.push(code);
+            }
+        }
+
+        let removed = (stale.len() + stale_games.len()) as u32;
+        for code in stale {
+            self.invite_code_index.remove(&code).map_err(|e| format!("Failed to remove invite code: {}", e))?;
+        }
+        for code in stale_games {
+            self.game_invite_index.remove(&code).map_err(|e| format!("Failed to remove invite code: {}", e))?;
+        }
+        Ok(removed)
+    }
+
+    /// Remove finished, cancelled and missing games from every player's watch list.
+    /// Returns how many entries were removed.
+    pub async fn prune_watched_games(&mut self) -> Result<u32, String> {
+        let mut stale = Vec::new();
+        for player_id in self.watched_games.indices().await.unwrap_or_default() {
+            for game_id in index_keys(&self.watched_games, &player_id).await {
+                if !self.get_game(&game_id).await.is_some_and(|g| !g.is_over()) {
+                    stale.push((player_id.clone(), game_id));
+                }
+            }
+        }
+        let removed = stale.len() as u32;
+        for (player_id, game_id) in stale {
+            self.remove_watched_game(&player_id, &game_id).await?;
+        }
+        Ok(removed)
+    }
+}
+
+/// Leaderboard ordering: best first, ties broken by chain ID for stable pages
+fn compare_leaderboard(a: &PlayerStats, b: &PlayerStats, sort: LeaderboardSort, time_control: &TimeControl) -> Ordering {
+    let ordering = match sort {
+        LeaderboardSort::Wins => b.games_won.cmp(&a.games_won),
+        LeaderboardSort::Rating => b.get_rating(time_control).cmp(&a.get_rating(time_control)),
+        LeaderboardSort::WinRate => b.win_rate_bps().cmp(&a.win_rate_bps()),
+        LeaderboardSort::Streak => b.win_streak
+            .cmp(&a.win_streak)
+            .then_with(|| b.best_streak.cmp(&a.best_streak)),
+        LeaderboardSort::PuzzleRush => b.puzzle_rush_best.cmp(&a.puzzle_rush_best),
+        LeaderboardSort::CategoryStreak => b.get_win_streak(time_control)
+            .cmp(&a.get_win_streak(time_control))
+            .then_with(|| b.get_best_streak(time_control).cmp(&a.get_best_streak(time_control))),
+    };
+    ordering.then_with(|| a.chain_id.cmp(&b.chain_id))
+}
+
+/// Decrement a counter, stopping at zero
+fn decrement(counter: &mut RegisterView<u64>) {
+    let value = counter.get_mut();
+    *value = value.saturating_sub(1);
+}
+
+/// Keys of one entry of a collection of game ID indexes
+async fn index_keys<K>(index: &CollectionView<K, MapView<String, ()>>, key: &K) -> Vec<String>
+where
+    K: serde::Serialize + serde::de::DeserializeOwned + Clone + std::fmt::Debug + Send + Sync,
+{
+    match index.try_load_entry(key).await {
+        Ok(Some(entry)) => entry.indices().await.unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Apply a rated result to both sides, using the ratings from before the game
+fn apply_rated_result(
+    red_stats: &mut PlayerStats,
+    black_stats: &mut PlayerStats,
+    red_rating: u32,
+    black_rating: u32,
+    result: GameResult,
+    time_control: &TimeControl,
+    config: &RatingConfig,
+) {
+    let red_outcome = match result {
+        GameResult::RedWins => 1.0,
+        GameResult::BlackWins => 0.0,
+        GameResult::Draw => 0.5,
+        GameResult::InProgress => return,
+    };
+    red_stats.record_result_with_config(black_rating, red_outcome, time_control, config);
+    black_stats.record_result_with_config(red_rating, 1.0 - red_outcome, time_control, config);
+}
+
+/// Scan a stats map and return one page of the best entries under `sort`
+/// At most MAX_LEADERBOARD_SCAN entries are scanned, and only the top `offset + limit`
+/// entries are kept in memory while scanning.
+async fn top_player_stats(
+    map: &MapView<String, PlayerStats>,
+    offset: usize,
+    limit: usize,
+    sort: LeaderboardSort,
+    time_control: TimeControl,
+    include: impl Fn(&PlayerStats) -> bool + Send + Sync,
+) -> Vec<PlayerStats> {
+    let keep = offset.saturating_add(limit);
+    let mut top: Vec<PlayerStats> = Vec::new();
+    let mut scanned = 0;
+
+    let _ = map
+        .for_each_index_value_while(|_id, stats| {
+            scanned += 1;
+            if include(&stats) {
+                rank_into(&mut top, &stats, keep, sort, &time_control);
+            }
+            Ok(scanned < MAX_LEADERBOARD_SCAN)
+        })
+        .await;
+
+    top.into_iter().skip(offset).collect()
+}
+
+/// Insert `stats` into `top`, a list sorted by `sort`, if it ranks among the first `keep`
+fn rank_into(top: &mut Vec<PlayerStats>, stats: &PlayerStats, keep: usize, sort: LeaderboardSort, time_control: &TimeControl) {
+    let pos = top.partition_point(|other| compare_leaderboard(other, stats, sort, time_control) != Ordering::Greater);
+    if pos < keep {
+        top.insert(pos, stats.clone());
+        top.truncate(keep);
+    }
 }
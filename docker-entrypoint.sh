@@ -19,7 +19,7 @@ if [ -z "$APP_ID" ]; then
     cd /app/contracts
 
     # Find and deploy the WASM files
-    APP_ID=$(linera project publish-and-create 2>&1 | grep "Application" | awk '{print $2}')
+    APP_ID=$(linera project publish-and-create --json-argument "{}" 2>&1 | grep "Application" | awk '{print $2}')
     export APP_ID
     echo "Application deployed: $APP_ID"
 fi
@@ -51,7 +51,7 @@ export async function POST(request: NextRequest) {
       return NextResponse.json({
         success: true,
         simulated: true,
-        result: { AiMoveMade: { game_id: operation.RequestAiMove.game_id, game_over: false } }
+        result: { AiMoveMade: { game_id: operation.RequestAiMove.game_id, game_over: false, search_depth: 0 } }
       });
     }
 
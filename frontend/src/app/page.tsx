@@ -214,7 +214,7 @@ function HomeContent() {
   // Poll for updates during active games AND pending games (waiting for opponent)
   useEffect(() => {
     // Poll for both Active games (for move updates) and Pending games (waiting for opponent to join)
-    if (!ready || !selectedGame || selectedGame.status === GameStatus.Finished) {
+    if (!ready || !selectedGame || selectedGame.status === GameStatus.Finished || selectedGame.status === GameStatus.Cancelled) {
       return;
     }
 
@@ -51,6 +51,12 @@ export default function GameList({
             Finished
           </span>
         );
+      case GameStatus.Cancelled:
+        return (
+          <span className="px-2 py-1 text-xs rounded-full bg-gray-100 text-gray-500">
+            Cancelled
+          </span>
+        );
       default:
         return null;
     }
@@ -66,6 +66,8 @@ const GET_GAME = `
         blackTimeMs
         lastMoveAt
       }
+      colorCommitment
+      colorSeed
     }
   }
 `
@@ -178,12 +180,42 @@ const LEAVE_QUEUE_MUTATION = `mutation LeaveQueue($playerId: String!) { leaveQue
 const GET_QUEUE_STATUS = `query GetQueueStatus { queueStatus { timeControl playerCount } }`
 
 // Draw mutations
-const OFFER_DRAW_MUTATION = `mutation OfferDraw($gameId: String!) { offerDraw(gameId: $gameId) }`
-const ACCEPT_DRAW_MUTATION = `mutation AcceptDraw($gameId: String!) { acceptDraw(gameId: $gameId) }`
-const DECLINE_DRAW_MUTATION = `mutation DeclineDraw($gameId: String!) { declineDraw(gameId: $gameId) }`
+const OFFER_DRAW_MUTATION = `mutation OfferDraw($gameId: String!, $playerId: String!) { offerDraw(gameId: $gameId, playerId: $playerId) }`
+const ACCEPT_DRAW_MUTATION = `mutation AcceptDraw($gameId: String!, $playerId: String!) { acceptDraw(gameId: $gameId, playerId: $playerId) }`
+const DECLINE_DRAW_MUTATION = `mutation DeclineDraw($gameId: String!, $playerId: String!) { declineDraw(gameId: $gameId, playerId: $playerId) }`
 
 // Time win mutation
-const CLAIM_TIME_WIN_MUTATION = `mutation ClaimTimeWin($gameId: String!) { claimTimeWin(gameId: $gameId) }`
+const CLAIM_TIME_WIN_MUTATION = `mutation ClaimTimeWin($gameId: String!, $playerId: String!) { claimTimeWin(gameId: $gameId, playerId: $playerId) }`
+
+// Random-color games commit to a secret when created and reveal it once someone joins
+const COLOR_COMMITMENT_QUERY = `query ColorCommitment($secret: String!) { colorCommitment(secret: $secret) }`
+
+function colorSecretKey(commitment: string): string {
+  return `checkers_color_secret_${commitment}`
+}
+
+// Reveal the color secret of a random-color game we created, once an opponent has joined it
+async function revealColorIfJoined(game: CheckersGame): Promise<void> {
+  const { requestAsync, incrementNotification, playerId } = useWalletStore.getState()
+  if (typeof window === 'undefined' || !playerId) return
+  if (game.status !== GameStatus.Pending || !game.colorSeed || !game.colorCommitment || game.redPlayer !== playerId) {
+    return
+  }
+
+  const key = colorSecretKey(game.colorCommitment)
+  const secret = localStorage.getItem(key)
+  if (!secret) return
+
+  try {
+    await requestAsync({
+      query: `mutation { revealColor(gameId: "${game.id}", secret: "${secret}", playerId: "${playerId}") }`,
+    })
+    localStorage.removeItem(key)
+    incrementNotification()
+  } catch (e) {
+    console.error('[GameStore] revealColor error:', e)
+  }
+}
 
 // Types for optimistic updates
 type MoveInfo = {
@@ -374,6 +406,7 @@ export const useGameStore = create<GameStore>((set, get) => ({
             g.id === id ? data.game : g
           ),
         }))
+        await revealColorIfJoined(data.game)
       } else {
         set({
           error: 'Game not found',
@@ -565,6 +598,16 @@ export const useGameStore = create<GameStore>((set, get) => ({
       if (isRated !== undefined) {
         mutationQuery += `, isRated: ${isRated}`
       }
+      // Commit to a color secret, kept in this browser until revealed; rated games require it
+      if (!vsAi && colorPreference === ColorPreference.Random) {
+        const secret = crypto.randomUUID()
+        const data = await requestAsync<{ colorCommitment: string }>({
+          query: COLOR_COMMITMENT_QUERY,
+          variables: { secret },
+        })
+        localStorage.setItem(colorSecretKey(data.colorCommitment), secret)
+        mutationQuery += `, colorCommitment: "${data.colorCommitment}"`
+      }
 
       mutationQuery += ') }'
 
@@ -874,12 +917,17 @@ export const useGameStore = create<GameStore>((set, get) => ({
 
   // Offer a draw to opponent
   offerDraw: async (gameId: string) => {
-    const { requestAsync, incrementNotification } = useWalletStore.getState()
+    const { requestAsync, incrementNotification, playerId } = useWalletStore.getState()
+
+    if (!playerId) {
+      set({ error: 'Player ID not available' })
+      return false
+    }
 
     try {
       await requestAsync({
         query: OFFER_DRAW_MUTATION,
-        variables: { gameId },
+        variables: { gameId, playerId },
       })
 
       incrementNotification()
@@ -894,12 +942,17 @@ export const useGameStore = create<GameStore>((set, get) => ({
 
   // Accept a draw offer
   acceptDraw: async (gameId: string) => {
-    const { requestAsync, incrementNotification } = useWalletStore.getState()
+    const { requestAsync, incrementNotification, playerId } = useWalletStore.getState()
+
+    if (!playerId) {
+      set({ error: 'Player ID not available' })
+      return false
+    }
 
     try {
       await requestAsync({
         query: ACCEPT_DRAW_MUTATION,
-        variables: { gameId },
+        variables: { gameId, playerId },
       })
 
       incrementNotification()
@@ -914,12 +967,17 @@ export const useGameStore = create<GameStore>((set, get) => ({
 
   // Decline a draw offer
   declineDraw: async (gameId: string) => {
-    const { requestAsync, incrementNotification } = useWalletStore.getState()
+    const { requestAsync, incrementNotification, playerId } = useWalletStore.getState()
+
+    if (!playerId) {
+      set({ error: 'Player ID not available' })
+      return false
+    }
 
     try {
       await requestAsync({
         query: DECLINE_DRAW_MUTATION,
-        variables: { gameId },
+        variables: { gameId, playerId },
       })
 
       incrementNotification()
@@ -934,12 +992,17 @@ export const useGameStore = create<GameStore>((set, get) => ({
 
   // Claim time win when opponent's clock expires
   claimTimeWin: async (gameId: string) => {
-    const { requestAsync, incrementNotification } = useWalletStore.getState()
+    const { requestAsync, incrementNotification, playerId } = useWalletStore.getState()
+
+    if (!playerId) {
+      set({ error: 'Player ID not available' })
+      return false
+    }
 
     try {
       await requestAsync({
         query: CLAIM_TIME_WIN_MUTATION,
-        variables: { gameId },
+        variables: { gameId, playerId },
       })
 
       incrementNotification()
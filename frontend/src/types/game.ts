@@ -12,6 +12,7 @@ export enum GameStatus {
   Pending = "PENDING",
   Active = "ACTIVE",
   Finished = "FINISHED",
+  Cancelled = "CANCELLED",
 }
 
 export enum GameResult {
@@ -62,6 +63,10 @@ export interface CheckersGame {
   createdAt: number;
   updatedAt: number;
   clock?: Clock;
+  // Random-color games settled by commit-reveal: the creator's commitment, and
+  // the joiner's seed while the game waits for the creator to reveal
+  colorCommitment?: string;
+  colorSeed?: string;
 }
 
 export interface PlayerStats {
@@ -46,7 +46,7 @@ eval $(grep "export LINERA_STORAGE" /tmp/linera_net.log)
 # 4. Deploy contract
 echo "Deploying contract..."
 cd "$SCRIPT_DIR/contracts/checkers"
-DEPLOY_OUTPUT=$(linera project publish-and-create 2>&1)
+DEPLOY_OUTPUT=$(linera project publish-and-create --json-argument "{}" 2>&1)
 APP_ID=$(echo "$DEPLOY_OUTPUT" | grep -E "^[a-f0-9]{64}$" | tail -1)
 CHAIN_ID=$(echo "$DEPLOY_OUTPUT" | grep "Creating application on chain" | awk '{print $NF}')
 
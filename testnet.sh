@@ -71,7 +71,7 @@ echo -e "${GREEN}✓ Contract built${NC}"
 # 4. Deploy to testnet
 echo -e "${YELLOW}Deploying to testnet...${NC}"
 cd "$SCRIPT_DIR/contracts/checkers"
-DEPLOY_OUTPUT=$(linera project publish-and-create --json-parameters "null" 2>&1)
+DEPLOY_OUTPUT=$(linera project publish-and-create --json-parameters "null" --json-argument "{}" 2>&1)
 
 # Extract App ID and Chain ID
 APP_ID=$(echo "$DEPLOY_OUTPUT" | grep -E "^[a-f0-9]{64}$" | tail -1)